@@ -2,7 +2,12 @@
  * Copyright (c) 2023-2024 Paul Sobolik
  * Created 2024-03-18
  */
+use std::collections::{HashMap, HashSet};
 use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use crossterm::{
@@ -11,69 +16,623 @@ use crossterm::{
 };
 use ratatui::{prelude::*, widgets::*};
 use tokio::sync::mpsc::UnboundedSender;
+use tracing::{debug, info, trace};
 
+use crate::app::bookmarks::Bookmarks;
+use crate::app::clipboard::Clipboard;
+use crate::app::event_output::EventOutput;
+use crate::app::frecency::Frecency;
+use crate::app::fs_error::{Context, ErrorQueue, FsError, Operation};
+use crate::app::opener::{OpenFile, Opener, Opening};
+use crate::app::plugins::{PluginContext, PluginRequest, Plugins};
+use crate::app::previewer::Previewer;
+use crate::app::search::SearchResult;
+use crate::app::session::Session;
+use crate::app::settings::Settings;
+use crate::app::tab::{History, OtherPane, Tab};
+use crate::app::view_memory::{SharedViewMemory, ViewMemory};
+use crate::app::watcher::Watcher;
 use crate::app::{
-    components::directory::Directory, components::head::Head, components::preview::Preview,
-    components::Component,
+    action::Action, command::Command, components::bookmark_list::BookmarkList,
+    components::bookmark_list::BookmarkListEvent, components::checksum_report::ChecksumReport,
+    components::checksum_report::ChecksumReportEvent, components::command_line::CommandLine,
+    components::command_line::CommandLineEvent, components::confirm_popup::ConfirmEvent,
+    components::confirm_popup::ConfirmPopup, components::debug_overlay::DebugOverlay,
+    components::directory::Directory, components::disk_usage_view::DiskUsageEvent,
+    components::disk_usage_view::DiskUsageView, components::error_list::ErrorList,
+    components::error_list::ErrorListEvent, components::finder::Finder,
+    components::finder::FinderEvent, components::head::Head, components::head::HeadEvent,
+    components::info::Info, components::jump_list::JumpList, components::jump_list::JumpListEvent,
+    components::key_hints::KeyHint, components::key_hints::KeyHints,
+    components::mount_list::MountList, components::mount_list::MountListEvent,
+    components::open_with_menu::OpenWithMenu, components::open_with_menu::OpenWithMenuEvent,
+    components::palette::Palette, components::palette::PaletteEvent,
+    components::parent_list::ParentList, components::preview::Preview,
+    components::results::Results, components::status::Status, components::trash_list::TrashList,
+    components::trash_list::TrashListEvent, components::Component, components::ListingOptions,
 };
+use crate::constants;
+use crate::control::ControlCommand;
+use crate::graphics::{GraphicsUpdate, Protocol};
 use crate::tui::Event;
+use crate::util;
 
-mod components;
-mod styles;
+mod action;
+pub(crate) mod archive;
+#[cfg(unix)]
+pub(crate) mod attributes;
+pub(crate) mod audio;
+mod bookmarks;
+pub(crate) mod checksum;
+mod clipboard;
+mod command;
+/// The panes the app is made of
+pub mod components;
+mod dir_compare;
+pub(crate) mod disk_usage;
+pub(crate) mod event_output;
+pub(crate) mod exif;
+mod file_mask;
+mod file_ops;
+mod frecency;
+pub(crate) mod fs_error;
+pub(crate) mod fs_provider;
+mod mounts;
+mod opener;
+#[cfg(unix)]
+mod permissions;
+mod plugins;
+mod previewer;
+pub(crate) mod remote;
+#[cfg(feature = "s3")]
+mod s3;
+pub(crate) mod search;
+mod session;
+mod settings;
+mod sftp;
+pub(crate) mod styles;
+mod tab;
+mod tasks;
+mod trash;
+mod view_memory;
+mod watcher;
+mod zoxide;
 
 struct FrameSet {
     head: Rect,
+    tabs: Rect,
+    main: Rect,
+    parent_list: Rect,
     directory: Rect,
     preview: Rect,
+    other_directory: Rect,
+    results: Rect,
+    status: Rect,
+    key_hints: Rect,
 }
 
+// How the main area is shared between the directory and the preview
+#[derive(Default, Clone, Copy, PartialEq)]
+enum PreviewLayout {
+    #[default]
+    Split,
+    Hidden,
+    Maximized,
+}
+
+// What about the selected entry is copied to the clipboard
+#[derive(Clone, Copy)]
+enum CopyTarget {
+    Path,
+    Name,
+    Directory,
+}
+
+// Whether a key is a letter with Alt, which the app's own commands are on, so letters on their
+// own can be typed ahead in the directory pane
+fn is_command_key(key_event: KeyEvent) -> bool {
+    matches!(key_event.code, Char(_))
+        && key_event.modifiers.contains(KeyModifiers::ALT)
+        && !key_event.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+// What's done once it's confirmed
+enum Confirmation {
+    // Moving the entries to the trash
+    Trash(Vec<PathBuf>),
+    // Deleting everything in the trash for good
+    EmptyTrash,
+}
+
+/// The file browser: a directory pane, a preview pane, and the popups, tabs and status bar around
+/// them.
+///
+/// It's given every [`Event`] with [`App::handle_event`], and drawn with [`App::render`]. Events
+/// it starts in the background, like directory reads and preview loads, come back through the
+/// sender given to [`App::set_event_tx`], so an application that embeds it has to send what it
+/// receives there back to `handle_event`, and start it with [`Event::Init`].
 #[derive(Default)]
 pub struct App<'a> {
+    /// Set when the app has been asked to quit
     pub should_quit: bool,
-    fs_error: Option<io::Error>,
+    /// Set to have the app suspended, as Ctrl+Z does to other programs
+    pub should_suspend: bool,
+    /// A program, like an editor, for the terminal to be given to until it's done
+    pub program_to_run: Option<std::process::Command>,
+    /// A location on another computer to connect to while the terminal's given to the connection,
+    /// so it can ask for a password, with [`connect_to_location`](App::connect_to_location)
+    pub location_to_connect: Option<remote::Location>,
+    /// Set while the mouse is left to the terminal, so text on the screen can be selected with it
+    pub is_mouse_released: bool,
+    fs_errors: ErrorQueue,
+
+    // The directory given on the command line, which the app goes to when it starts, the file
+    // given, which is selected there, and the other paths given, which are opened in tabs
+    initial_directory: Option<PathBuf>,
+    initial_selection: Option<PathBuf>,
+    initial_tabs: Vec<PathBuf>,
+    // Whether to start where the last session left off, or None to go by the settings
+    restore_session: Option<bool>,
+
+    // What new tabs' panes are set up with
+    event_tx: Option<UnboundedSender<Event>>,
+    max_text_size: Option<u64>,
+    view_memory: SharedViewMemory,
+
+    // The tabs, if more than one has been opened, and which one is showing
+    tabs: Vec<Tab<'a>>,
+    tab_index: usize,
+
+    // In dual-pane mode, a second directory pane takes the preview's place
+    other_pane: Option<OtherPane>,
+
+    // The directories the directory pane has been in, to go back and forward through
+    history: History,
+
+    preview_layout: PreviewLayout,
+
+    // The settings remembered between runs, like where the divider between the directory and the
+    // preview is. The divider can be dragged with the mouse within the main area.
+    settings: Settings,
+    main_area: Rect,
+    is_stacked_layout: bool,
+    is_dragging_divider: bool,
+
+    // In the Miller columns layout, the parent directory's entries are shown left of the
+    // directory's
+    is_miller_columns: bool,
+    parent_list: ParentList,
+
+    // Whether the terminal was too small to draw the panes in the last time it was drawn
+    is_too_small: bool,
+
+    // How the terminal draws images, and the image it's drawn and where
+    graphics_protocol: Protocol,
+    drawn_image: Option<(PathBuf, Rect)>,
+
+    // Watches the current directory and the previewed folder, if it can
+    watcher: Option<Watcher>,
+
+    // While the terminal doesn't have focus, changes to the watched directories are only
+    // collected, and followed files aren't read, until it gets focus back
+    is_unfocused: bool,
+    unfocused_changes: HashSet<PathBuf>,
+
+    // The bookmarked directories, which are read when they're first needed
+    bookmarks: Option<Bookmarks>,
+
+    // Marks set during the session, as the directory and the entry selected in it, and the key
+    // waiting for a mark's letter: m to set it or ' to go to it
+    marks: HashMap<char, (PathBuf, Option<PathBuf>)>,
+    pending_mark: Option<char>,
+
+    // The system clipboard, and whether the key after y says what to copy to it
+    clipboard: Clipboard,
+    is_copy_pending: bool,
+
+    // The scripts in the config directory that add commands, keys and previewers
+    plugins: Arc<Plugins>,
+
+    // Where the directory and selection changes and the files that are opened are written, for
+    // --events-json
+    event_output: Option<EventOutput>,
+
+    // The control socket the app listens on, for --listen, which the programs it opens files
+    // with are told about
+    control_socket: Option<PathBuf>,
+
+    // The visited directories, which are read when they're first needed, and the last directory
+    // whose visit was recorded
+    frecency: Option<Frecency>,
+    visited: Option<PathBuf>,
 
     // Components
     head: Head,
     directory: Directory,
     preview: Preview<'a>,
+    status: Status,
+    key_hints: KeyHints,
+    palette: Palette,
+    command_line: CommandLine,
+    finder: Finder,
+    info: Info,
+    bookmark_list: BookmarkList,
+    jump_list: JumpList,
+    mount_list: MountList,
+    open_with_menu: OpenWithMenu,
+    disk_usage_view: DiskUsageView,
+    checksum_report: ChecksumReport,
+    trash_list: TrashList,
+    error_list: ErrorList,
+    confirm_popup: ConfirmPopup<Confirmation>,
+    debug_overlay: DebugOverlay,
+    results: Results,
 }
 
 impl<'a> App<'a> {
+    /// Where the app and its panes send the events they start in the background, and the file
+    /// watcher sends changes. Without one, nothing's read in the background.
     pub fn set_event_tx(&mut self, event_tx: Option<UnboundedSender<Event>>) {
-        self.directory.set_event_tx(event_tx);
+        self.event_tx = event_tx.clone();
+        self.directory.set_event_tx(event_tx.clone());
+        self.disk_usage_view.set_event_tx(event_tx.clone());
+        self.checksum_report.set_event_tx(event_tx.clone());
+        self.finder.set_event_tx(event_tx.clone());
+        self.info.set_event_tx(event_tx.clone());
+        self.preview.set_event_tx(event_tx.clone());
+        self.results.set_event_tx(event_tx.clone());
+        // Without a watcher, changes made outside the app only show up on a refresh
+        self.watcher = event_tx.and_then(|event_tx| Watcher::new(event_tx).ok());
+    }
+
+    /// How previewed images are drawn
+    pub fn set_graphics_protocol(&mut self, protocol: Protocol) {
+        self.graphics_protocol = protocol;
+        self.preview.set_graphics_protocol(protocol);
+    }
+
+    /// Text files at least this many bytes long aren't read all at once
+    pub fn set_max_text_size(&mut self, max_text_size: u64) {
+        self.max_text_size = Some(max_text_size);
+        self.preview.set_max_text_size(max_text_size);
+    }
+
+    /// The directory to go to when the app starts, instead of staying in the current directory.
+    /// The app keeps the directory it's in as the process's current directory, so that's changed
+    /// then, and not before.
+    pub fn set_initial_directory(&mut self, initial_directory: PathBuf) {
+        self.initial_directory = Some(initial_directory);
+    }
+
+    /// The entry to select in the starting directory when the app starts
+    pub fn set_initial_selection(&mut self, initial_selection: PathBuf) {
+        self.initial_selection = Some(initial_selection);
+    }
+
+    /// Directories to open in tabs when the app starts, after the current directory's
+    pub fn set_initial_tabs(&mut self, initial_tabs: Vec<PathBuf>) {
+        self.initial_tabs = initial_tabs;
+    }
+
+    /// Whether the last session is restored when the app starts, or None to leave it to the
+    /// settings
+    pub fn set_restore_session(&mut self, restore_session: Option<bool>) {
+        self.restore_session = restore_session;
+    }
+
+    /// How long the last frame took to draw, which the debug overlay averages
+    pub fn record_frame_time(&mut self, frame_time: Duration) {
+        self.debug_overlay.record_frame_time(frame_time);
+    }
+
+    /// How previewed images are drawn, which the terminal's told with the graphics updates
+    pub fn graphics_protocol(&self) -> Protocol {
+        self.graphics_protocol
+    }
+
+    /// Returns what has to be written to the terminal, after the frame is drawn, to get the
+    /// previewed image on the screen where it belongs. An image that's been drawn is erased before
+    /// a new one is, and the image is kept off the screen while a popup is over it.
+    pub fn graphics_update(&mut self) -> Option<GraphicsUpdate> {
+        let popup_open = self.fs_errors.is_showing()
+            || self.palette.is_open()
+            || self.finder.is_open()
+            || self.info.is_open()
+            || self.bookmark_list.is_open()
+            || self.jump_list.is_open()
+            || self.mount_list.is_open()
+            || self.open_with_menu.is_open()
+            || self.disk_usage_view.is_open()
+            || self.checksum_report.is_open()
+            || self.trash_list.is_open()
+            || self.error_list.is_open()
+            || self.confirm_popup.is_open()
+            || self.debug_overlay.is_open();
+        let wanted = if popup_open || self.is_too_small || !self.shows_preview() {
+            None
+        } else {
+            self.preview.image_placement()
+        };
+        let wanted_key = wanted.map(|(entry, _, area)| (entry.to_path_buf(), area));
+        if wanted_key == self.drawn_image {
+            return None;
+        }
+        if self.drawn_image.take().is_some() {
+            return Some(GraphicsUpdate::Erase);
+        }
+        let (_, image, area) = wanted?;
+        let payload = crate::graphics::encode(self.graphics_protocol, image, area)?;
+        self.drawn_image = wanted_key;
+        Some(GraphicsUpdate::Draw(payload))
     }
 
+    /// Handles a terminal event, or one the app started. Afterwards
+    /// [`should_quit`](App::should_quit), [`should_suspend`](App::should_suspend),
+    /// [`program_to_run`](App::program_to_run) and
+    /// [`location_to_connect`](App::location_to_connect) say whether the app wants anything of the
+    /// terminal.
     pub async fn handle_event(&mut self, event: Event) {
+        Self::log_event(&event);
+        // Ticks and renders come at a steady rate, so they'd only hide the events that vary
+        if !matches!(event, Event::Tick | Event::Render) {
+            self.debug_overlay.count_event();
+        }
         match event {
             Event::Key(key_event) => self.handle_key_event(key_event).await,
             Event::Init(width, height) => self.handle_init_event(width, height).await,
             Event::Mouse(mouse_event) => self.handle_mouse_event(mouse_event).await,
             Event::Resize(width, height) => self.handle_resize_event(width, height),
-            Event::SelectionChanged => self.load_selected_item().await,
-            Event::DirectoryChanged => self.handle_directory_changed(),
+            Event::Paste(text) => self.handle_paste_event(text).await,
+            Event::SelectionChanged => self.preview_selected_item(),
+            Event::OpenFile(file) => self.open_file(&file).await,
+            Event::Control(command, answer_tx) => {
+                let answer = self.handle_control_command(command).await;
+                let _ = answer_tx.send(answer);
+            }
+            Event::DirectoryBatch(read_id, batch) => {
+                let directory = match self.other_pane.as_mut() {
+                    Some(other_pane) if other_pane.directory.is_read(read_id) => {
+                        &mut other_pane.directory
+                    }
+                    _ => &mut self.directory,
+                };
+                if let Err(error) = directory.add_batch(read_id, batch).await {
+                    self.fs_errors.push(error);
+                }
+            }
+            Event::PreviewLoaded(load_id, entry, content) => {
+                self.preview.set_loaded(load_id, entry, content)
+            }
+            Event::DirectoryChanged => {
+                self.handle_directory_changed();
+                self.load_parent_list().await;
+            }
+            Event::FinderResults(walk_id, paths) => self.finder.add_paths(walk_id, paths),
+            Event::FinderFinished(walk_id) => self.finder.set_finished(walk_id),
+            Event::SearchResults(search_id, results) => {
+                self.results.add_results(search_id, results)
+            }
+            Event::SearchFinished(search_id) => self.results.set_finished(search_id),
+            Event::OpenSearchResult(result) => self.open_search_result(result).await,
+            Event::Tick => self.preview.handle_tick(!self.is_unfocused),
+            Event::FocusLost => self.is_unfocused = true,
+            Event::FocusGained => self.handle_focus_gained().await,
+            Event::Suspend => self.should_suspend = true,
+            // The screen was cleared, so the image has to be drawn again
+            Event::Resume => self.drawn_image = None,
+            Event::FsChanged(paths) if self.is_unfocused => self.unfocused_changes.extend(paths),
+            Event::FsChanged(paths) => self.handle_fs_changed(paths).await,
+            Event::ChecksumProgress(checksum_id, progress) => {
+                self.info.set_checksum_progress(checksum_id, progress)
+            }
+            Event::ChecksumsFinished(checksum_id, checksums) => {
+                self.info.set_checksums(checksum_id, checksums)
+            }
+            Event::DirectorySizeProgress(size_id, file_count, byte_count) => self
+                .info
+                .set_directory_size(size_id, file_count, byte_count),
+            Event::DirectorySizeFinished(size_id, file_count, byte_count) => self
+                .info
+                .set_directory_size_final(size_id, file_count, byte_count),
+            // Only the pane that started the computation takes it, wherever it is now
+            Event::MarkedSizeFinished(size_id, size) => {
+                self.directory.set_marked_size(size_id, size);
+                if let Some(other_pane) = self.other_pane.as_mut() {
+                    other_pane.directory.set_marked_size(size_id, size);
+                }
+                for tab in self.tabs.iter_mut() {
+                    tab.directory.set_marked_size(size_id, size);
+                    if let Some(other_pane) = tab.other_pane.as_mut() {
+                        other_pane.directory.set_marked_size(size_id, size);
+                    }
+                }
+            }
+            Event::DirectorySizeFound(size_id, directory, size) => {
+                self.directory
+                    .set_directory_size(size_id, directory.clone(), size);
+                if let Some(other_pane) = self.other_pane.as_mut() {
+                    other_pane
+                        .directory
+                        .set_directory_size(size_id, directory.clone(), size);
+                }
+                for tab in self.tabs.iter_mut() {
+                    tab.directory
+                        .set_directory_size(size_id, directory.clone(), size);
+                    if let Some(other_pane) = tab.other_pane.as_mut() {
+                        other_pane
+                            .directory
+                            .set_directory_size(size_id, directory.clone(), size);
+                    }
+                }
+            }
+            Event::ChecksumVerified(verify_id, index, result) => {
+                self.checksum_report.set_result(verify_id, index, result)
+            }
+            Event::DiskUsageProgress(scan_id, file_count, byte_count) => self
+                .disk_usage_view
+                .set_progress(scan_id, file_count, byte_count),
+            Event::DiskUsageFinished(scan_id, disk_usage) => {
+                self.disk_usage_view.set_disk_usage(scan_id, disk_usage)
+            }
             _ => {}
         }
+        self.update_event_output();
+    }
+
+    // Where the directory and selection changes and the files that are opened are written
+    pub(crate) fn set_event_output(&mut self, event_output: EventOutput) {
+        self.event_output = Some(event_output);
+    }
+
+    // Where the app's control socket is, which the programs it opens files with are told
+    pub(crate) fn set_control_socket(&mut self, control_socket: PathBuf) {
+        self.control_socket = Some(control_socket);
+    }
+
+    fn update_event_output(&mut self) {
+        if let Some(event_output) = &mut self.event_output {
+            let selection = self
+                .directory
+                .selected_item()
+                .filter(|selected| util::entry_name(selected) != constants::PARENT_DIRECTORY);
+            event_output.update(std::env::current_dir().ok(), selection);
+        }
+    }
+
+    // Events that come many times a second, or carry a lot, are only logged at the trace level,
+    // and only what they're for
+    fn log_event(event: &Event) {
+        match event {
+            Event::Tick | Event::Render => {}
+            Event::DirectoryBatch(read_id, _) => trace!(read_id, "directory batch"),
+            Event::PreviewLoaded(load_id, entry, _) => {
+                trace!(load_id, entry = %entry.display(), "preview loaded")
+            }
+            Event::FinderResults(walk_id, paths) => {
+                trace!(walk_id, count = paths.len(), "finder results")
+            }
+            Event::SearchResults(search_id, results) => {
+                trace!(search_id, count = results.len(), "search results")
+            }
+            Event::ChecksumProgress(..)
+            | Event::DirectorySizeProgress(..)
+            | Event::DirectorySizeFound(..)
+            | Event::DiskUsageProgress(..) => trace!(?event),
+            Event::DiskUsageFinished(scan_id, _) => debug!(scan_id, "disk usage finished"),
+            _ => debug!(?event),
+        }
     }
 
     async fn handle_init_event(&mut self, width: u16, height: u16) {
+        match Settings::load() {
+            Ok(settings) => self.settings = settings,
+            Err(error) => self.fs_errors.push(error),
+        }
+        self.load_plugins();
+        self.directory
+            .set_wheel_scroll(self.settings.wheel_scroll());
+        self.preview.set_wheel_scroll(self.settings.wheel_scroll());
+        self.preview
+            .set_previewers(self.previewers(), self.settings.previewer_timeout);
+        if self
+            .restore_session
+            .unwrap_or(self.settings.restore_session)
+        {
+            self.restore_session();
+        }
+        if let Some(initial_directory) = self.initial_directory.take() {
+            if let Err(error) = std::env::set_current_dir(&initial_directory)
+                .context(Operation::Entering, &initial_directory)
+            {
+                self.fs_errors.push(error);
+            }
+        }
+        if self.settings.remember_views {
+            match ViewMemory::load() {
+                Ok(view_memory) => *self.view_memory.lock().unwrap() = view_memory,
+                Err(error) => self.fs_errors.push(error),
+            }
+        }
+        self.directory.set_view_memory(self.view_memory.clone());
         let area = Rect::new(0, 0, width, height);
-        let frame_set = Self::calculate_frames(area);
+        let frame_set = self.calculate_frames(area);
 
         self.directory.set_area(frame_set.directory);
         self.preview.set_area(frame_set.preview);
+        self.results.set_area(frame_set.results);
 
         if let Err(error) = self.directory.load_cwd().await {
-            self.fs_error = Some(error);
+            self.fs_errors.push(error);
+        }
+        if let Some(initial_selection) = self.initial_selection.take() {
+            self.directory.select_entry(&initial_selection);
         }
         self.load_selected_item().await;
         self.directory.set_focus(true);
         self.preview.set_focus(false);
+
+        // The first tab is shown once the others have been opened
+        let initial_tabs = std::mem::take(&mut self.initial_tabs);
+        if !initial_tabs.is_empty() {
+            for path in initial_tabs {
+                self.new_tab().await;
+                self.open_location(&path).await;
+            }
+            self.show_tab(0).await;
+        }
+    }
+
+    // Whether a popup or the command line is open, or the path bar is being edited
+    fn is_popup_open(&self) -> bool {
+        self.palette.is_open()
+            || self.finder.is_open()
+            || self.info.is_open()
+            || self.bookmark_list.is_open()
+            || self.jump_list.is_open()
+            || self.mount_list.is_open()
+            || self.open_with_menu.is_open()
+            || self.disk_usage_view.is_open()
+            || self.checksum_report.is_open()
+            || self.trash_list.is_open()
+            || self.error_list.is_open()
+            || self.confirm_popup.is_open()
+            || self.command_line.is_open()
+            || self.head.is_editing()
+    }
+
+    // Pasted text goes into whatever's being typed, all at once, so it isn't taken as keys.
+    // Otherwise it goes to the path it names, if it names one. It's ignored while any other popup
+    // is open.
+    async fn handle_paste_event(&mut self, text: String) {
+        if self.fs_errors.is_showing() {
+            return;
+        }
+        let line = util::pasted_line(&text);
+        if self.palette.is_open() {
+            self.palette.paste(&line);
+        } else if self.finder.is_open() {
+            self.finder.paste(&line);
+        } else if self.jump_list.is_open() {
+            self.jump_list.paste(&line);
+        } else if self.head.is_editing() {
+            self.head.paste(&line);
+        } else if self.command_line.is_open() {
+            self.command_line.paste(&line);
+        } else if self.is_popup_open() {
+            // The other popups don't take text
+        } else if self.directory.has_focus() && self.directory.is_input() {
+            self.directory.paste(&line);
+        } else if self.preview.has_focus() && self.preview.is_input() {
+            self.preview.paste(&line);
+        } else if let Some(path) = Self::pasted_path(&text) {
+            self.open_location(&path).await;
+        }
     }
 
     async fn maybe_clear_error(&mut self) -> bool {
-        if self.fs_error.is_some() {
+        if self.fs_errors.is_showing() {
             // If there's an error pending, clear it.
-            self.fs_error = None;
+            self.fs_errors.dismiss();
             // If the current item is not valid anymore, reload the current folder and selected item
             if let Some(path) = self.directory.selected_item() {
                 if path.metadata().is_err() {
@@ -90,24 +649,44 @@ impl<'a> App<'a> {
     async fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
         // If there's an error showing, any mouse down will clear it and quit processing the event.
         // Any other mouse event will be ignored.
-        if self.fs_error.is_some() {
+        if self.fs_errors.is_showing() {
             if let MouseEventKind::Down(..) = mouse_event.kind {
                 self.maybe_clear_error().await;
             }
             return;
         }
+        // Mouse events are ignored while a popup or the command line is open, or while the
+        // terminal is too small for the panes to be drawn.
+        if self.is_too_small || self.is_popup_open() {
+            return;
+        }
 
+        // Clicking a component of the path in the header goes to it
+        if let MouseEventKind::Down(MouseButton::Left) = mouse_event.kind {
+            if let Some(path) = self.head.breadcrumb_at(mouse_event.column, mouse_event.row) {
+                self.go_to_breadcrumb(&path).await;
+                return;
+            }
+        }
+        // Dragging the divider between the directory and the preview resizes them
+        if self.handle_divider_mouse_event(mouse_event) {
+            return;
+        }
         // A left mouse click may change focused pane, but won't quit processing the event.
         if let MouseEventKind::Down(mouse_button) = mouse_event.kind {
             if mouse_button == MouseButton::Left {
-                if self.directory.has_focus()
-                    && self.preview.hit_test(mouse_event.column, mouse_event.row)
-                {
-                    self.focus_preview();
-                } else if self.preview.has_focus()
-                    && self.directory.hit_test(mouse_event.column, mouse_event.row)
-                {
+                if self.other_pane.as_ref().is_some_and(|other_pane| {
+                    other_pane
+                        .directory
+                        .hit_test(mouse_event.column, mouse_event.row)
+                }) {
+                    self.swap_panes().await;
+                } else if self.directory.hit_test(mouse_event.column, mouse_event.row) {
                     self.focus_directory();
+                } else if self.preview.hit_test(mouse_event.column, mouse_event.row) {
+                    self.focus_preview();
+                } else if self.results.hit_test(mouse_event.column, mouse_event.row) {
+                    self.focus_results();
                 }
             }
         }
@@ -116,13 +695,19 @@ impl<'a> App<'a> {
             && self.directory.hit_test(mouse_event.column, mouse_event.row)
         {
             if let Err(error) = self.directory.handle_mouse_event(mouse_event).await {
-                self.fs_error = Some(error);
+                self.fs_errors.push(error);
             }
         } else if self.preview.has_focus()
             && self.preview.hit_test(mouse_event.column, mouse_event.row)
         {
             if let Err(error) = self.preview.handle_mouse_event(mouse_event).await {
-                self.fs_error = Some(error);
+                self.fs_errors.push(error);
+            }
+        } else if self.results.has_focus()
+            && self.results.hit_test(mouse_event.column, mouse_event.row)
+        {
+            if let Err(error) = self.results.handle_mouse_event(mouse_event).await {
+                self.fs_errors.push(error);
             }
         }
     }
@@ -134,140 +719,2356 @@ impl<'a> App<'a> {
             self.quit();
             return;
         }
-        // If there is an error showing, clear it and don't process the event.
+        // If there is an error showing, clear it and don't process the event. Enter shows all the
+        // errors in full.
         if self.maybe_clear_error().await {
+            if key_event.code == KeyCode::Enter {
+                self.error_list.open(self.fs_errors.errors());
+            }
+            return;
+        }
+        // If something's waiting to be confirmed, the key answers whether to go ahead with it
+        if self.confirm_popup.is_open() {
+            if let Some(ConfirmEvent::Confirmed(confirmation)) =
+                self.confirm_popup.handle_key_event(key_event)
+            {
+                match confirmation {
+                    Confirmation::Trash(entries) => self.trash_items(entries).await,
+                    Confirmation::EmptyTrash => self.empty_trash().await,
+                }
+            }
+            return;
+        }
+        // If the command palette is open, it gets the key
+        if self.palette.is_open() {
+            if let Some(PaletteEvent::Selected(action)) = self.palette.handle_key_event(key_event) {
+                self.perform_action(action).await;
+            }
+            return;
+        }
+        // If the fuzzy finder is open, it gets the key
+        if self.finder.is_open() {
+            if let Some(FinderEvent::Selected(path)) = self.finder.handle_key_event(key_event) {
+                self.go_to_path(&path).await;
+            }
+            return;
+        }
+        // If the details popup is open, it gets the key
+        if self.info.is_open() {
+            self.info.handle_key_event(key_event);
+            return;
+        }
+        // If the bookmark list is open, it gets the key
+        if self.bookmark_list.is_open() {
+            match self.bookmark_list.handle_key_event(key_event) {
+                Some(BookmarkListEvent::Selected(path)) => self.change_directory(&path).await,
+                Some(BookmarkListEvent::Removed(path)) => self.remove_bookmark(&path),
+                _ => {}
+            }
+            return;
+        }
+        // If the jump list is open, it gets the key
+        if self.jump_list.is_open() {
+            if let Some(JumpListEvent::Selected(path)) = self.jump_list.handle_key_event(key_event)
+            {
+                self.change_directory(&path).await;
+            }
+            return;
+        }
+        // If the drive list is open, it gets the key
+        if self.mount_list.is_open() {
+            if let Some(MountListEvent::Selected(path)) =
+                self.mount_list.handle_key_event(key_event)
+            {
+                self.change_directory(&path).await;
+            }
+            return;
+        }
+        // If the open with menu is open, it gets the key
+        if self.open_with_menu.is_open() {
+            if let Some(OpenWithMenuEvent::Selected(opener, file)) =
+                self.open_with_menu.handle_key_event(key_event)
+            {
+                self.open_file_with(&opener, &file).await;
+            }
+            return;
+        }
+        // If the disk usage popup is open, it gets the key
+        if self.disk_usage_view.is_open() {
+            if let Some(DiskUsageEvent::Selected(path)) =
+                self.disk_usage_view.handle_key_event(key_event)
+            {
+                self.go_to_path(&path).await;
+            }
+            return;
+        }
+        // If the checksum report is open, it gets the key
+        if self.checksum_report.is_open() {
+            if let Some(ChecksumReportEvent::Selected(path)) =
+                self.checksum_report.handle_key_event(key_event)
+            {
+                self.go_to_path(&path).await;
+            }
+            return;
+        }
+        // If the trash list is open, it gets the key
+        if self.trash_list.is_open() {
+            match self.trash_list.handle_key_event(key_event) {
+                Some(TrashListEvent::Restore(item)) => self.restore_trashed_item(item).await,
+                Some(TrashListEvent::Empty) => self.confirm_popup.open(
+                    "Delete everything in the trash for good?".to_string(),
+                    Confirmation::EmptyTrash,
+                ),
+                _ => {}
+            }
+            return;
+        }
+        // If the error list is open, it gets the key
+        if self.error_list.is_open() {
+            if let Some(ErrorListEvent::Cleared) = self.error_list.handle_key_event(key_event) {
+                self.fs_errors.clear();
+            }
+            return;
+        }
+        // If the path bar is being edited, it gets the key
+        if self.head.is_editing() {
+            if let Some(HeadEvent::Submitted(input)) = self.head.handle_key_event(key_event) {
+                self.go_to_typed_path(&input).await;
+            }
+            return;
+        }
+        // If the command line is open, it gets the key
+        if self.command_line.is_open() {
+            if let Some(CommandLineEvent::Submitted(input)) =
+                self.command_line.handle_key_event(key_event)
+            {
+                self.run_command(&input).await;
+            }
+            return;
+        }
+        // While the directory filter is being typed, the directory pane gets every key
+        if self.directory.has_focus() && self.directory.is_input() {
+            if let Err(error) = self.directory.handle_key_event(key_event).await {
+                self.fs_errors.push(error);
+            }
+            return;
+        }
+        // Likewise the preview pane while its search or a line number is being typed, or lines are
+        // being selected in it. Selected lines that are copied go on the clipboard.
+        if self.preview.has_focus() && (self.preview.is_input() || self.preview.is_selecting()) {
+            if let Err(error) = self.preview.handle_key_event(key_event).await {
+                self.fs_errors.push(error);
+            }
+            if let Some(text) = self.preview.take_copied_text() {
+                if let Err(error) = self.clipboard.copy(&text) {
+                    self.fs_errors.push(error);
+                }
+            }
+            return;
+        }
+        // Once a name is being typed ahead in the directory pane, or a count that may be the start
+        // of one, the characters that follow go on with it instead of doing what they do alone.
+        // Any other key ends it.
+        let is_character = matches!(key_event.code, Char(_))
+            && (key_event.modifiers - KeyModifiers::SHIFT).is_empty();
+        if !is_character {
+            self.directory.end_type_ahead();
+        } else if self.directory.has_focus() && self.directory.is_typing_ahead() {
+            if let Err(error) = self.directory.handle_key_event(key_event).await {
+                self.fs_errors.push(error);
+            }
+            return;
+        }
+        // The key after m or ' is the mark's letter
+        if let Some(pending_mark) = self.pending_mark.take() {
+            if let Char(letter) = key_event.code {
+                if letter.is_ascii_alphabetic() {
+                    if pending_mark == 'm' {
+                        self.set_mark(letter);
+                    } else {
+                        self.go_to_mark(letter).await;
+                    }
+                }
+            }
+            return;
+        }
+        // The key after y says what to copy
+        if std::mem::take(&mut self.is_copy_pending) {
+            match key_event.code {
+                Char('p') => self.copy_selected(CopyTarget::Path),
+                Char('n') => self.copy_selected(CopyTarget::Name),
+                Char('d') => self.copy_selected(CopyTarget::Directory),
+                _ => {}
+            }
+            return;
+        }
+        if self.plugins.has_key(key_event) {
+            let result = self.plugins.run_key(key_event, &self.plugin_context());
+            self.apply_plugin_result(result).await;
             return;
         }
         match key_event.code {
-            KeyCode::Esc => self.quit(),
+            // Esc closes the results pane if it has focus
+            KeyCode::Esc if self.results.has_focus() => self.close_results(),
+            // Esc clears the directory filter, mask or marks, or the preview search before it quits
+            KeyCode::Esc
+                if !(self.directory.has_focus()
+                    && (self.directory.is_filtered() || self.directory.has_marks())
+                    || self.preview.has_focus() && self.preview.is_searched()) =>
+            {
+                self.quit()
+            }
+            KeyCode::Tab if self.other_pane.is_some() => self.swap_panes().await,
             KeyCode::Tab => self.toggle_focus(),
+            KeyCode::F(5) if self.other_pane.is_some() => self.copy_to_other_pane(false).await,
+            KeyCode::F(6) if self.other_pane.is_some() => self.copy_to_other_pane(true).await,
+            Char(':') => self.command_line.open(),
+            Char('i') if is_command_key(key_event) => self.open_info(),
+            Char('b') if is_command_key(key_event) => self.add_bookmark(),
+            Char('z') if is_command_key(key_event) => self.open_frecent(),
+            Char('Z') if is_command_key(key_event) => self.open_zoxide().await,
+            Char(c @ ('m' | '\'')) if is_command_key(key_event) => self.pending_mark = Some(c),
+            Char('y') if is_command_key(key_event) => self.is_copy_pending = true,
+            Char('P') if is_command_key(key_event) => self.go_to_clipboard_path().await,
+            Char('B') if is_command_key(key_event) => self.open_bookmark_list(),
+            KeyCode::Delete if self.directory.has_focus() => self.trash_selected_items(),
+            Char('X') if is_command_key(key_event) => self.open_trash_list().await,
+            Char('E') if is_command_key(key_event) => self.error_list.open(self.fs_errors.errors()),
+            Char('U') if is_command_key(key_event) => self.open_disk_usage(),
+            Char('A') if is_command_key(key_event) => self.command_line.open_with("chmod "),
+            Char('O') if is_command_key(key_event) => self.command_line.open_with("chown "),
+            Char('W') if is_command_key(key_event) => self.open_selected_file_with().await,
+            Char('V') if is_command_key(key_event) => self.open_checksum_report(),
+            // In dual-pane mode, C compares the panes' directories instead of two files
+            Char('C') if is_command_key(key_event) => {
+                if self.other_pane.is_some() {
+                    self.compare_directories()
+                } else {
+                    self.compare_marked_files().await
+                }
+            }
+            Char('D') if is_command_key(key_event) => self.open_mount_list().await,
+            Char('T') if is_command_key(key_event) => self.directory.toggle_tree_view(),
+            Char('S') if is_command_key(key_event) => self.directory.toggle_sizes(),
+            Char('M') if is_command_key(key_event) => self.toggle_miller_columns().await,
+            // In the Miller columns layout, Left goes up to the parent and Right opens the
+            // selected directory, unless the tree view uses them
+            KeyCode::Left | KeyCode::Right
+                if self.is_miller_columns
+                    && self.directory.has_focus()
+                    && !self.directory.is_tree_view()
+                    && key_event.modifiers == KeyModifiers::NONE =>
+            {
+                let key_code = if key_event.code == KeyCode::Left {
+                    KeyCode::Backspace
+                } else {
+                    KeyCode::Enter
+                };
+                let key_event = KeyEvent::new(key_code, KeyModifiers::NONE);
+                if let Err(error) = self.directory.handle_key_event(key_event).await {
+                    self.fs_errors.push(error);
+                }
+            }
+            Char('l') if key_event.modifiers == KeyModifiers::CONTROL => self.head.start_editing(),
+            Char('z') if key_event.modifiers == KeyModifiers::CONTROL => self.should_suspend = true,
+            Char('t') if key_event.modifiers == KeyModifiers::CONTROL => self.new_tab().await,
+            Char('d') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.toggle_dual_pane().await
+            }
+            Char('w') if key_event.modifiers == KeyModifiers::CONTROL => self.close_tab().await,
+            KeyCode::PageUp if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.previous_tab().await
+            }
+            KeyCode::PageDown if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.next_tab().await
+            }
+            Char('f') if key_event.modifiers == KeyModifiers::CONTROL => self.open_finder(),
+            Char('x') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.toggle_execute().await
+            }
+            Char('o') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.set_preview_layout(PreviewLayout::Hidden).await
+            }
+            // Not Ctrl+P, which moves up like it does in Emacs
+            KeyCode::F(1) => self.palette.open(),
+            KeyCode::F(3) => self.set_preview_layout(PreviewLayout::Maximized).await,
+            KeyCode::F(9) => self.toggle_mouse_capture(),
+            KeyCode::F(12) => self.debug_overlay.toggle(),
+            KeyCode::Left if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.move_divider(-(constants::SPLIT_PERCENT_STEP as i32))
+            }
+            KeyCode::Left if key_event.modifiers == KeyModifiers::ALT => self.go_back().await,
+            KeyCode::Right if key_event.modifiers == KeyModifiers::ALT => self.go_forward().await,
+            KeyCode::Right if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.move_divider(constants::SPLIT_PERCENT_STEP as i32)
+            }
             _ => {
                 if self.directory.has_focus() {
                     if let Err(error) = self.directory.handle_key_event(key_event).await {
-                        self.fs_error = Some(error);
+                        self.fs_errors.push(error);
                     }
                 } else if self.preview.has_focus() {
                     if let Err(error) = self.preview.handle_key_event(key_event).await {
-                        self.fs_error = Some(error);
+                        self.fs_errors.push(error);
+                    }
+                } else if self.results.has_focus() {
+                    if let Err(error) = self.results.handle_key_event(key_event).await {
+                        self.fs_errors.push(error);
                     }
                 }
             }
         }
     }
 
-    fn handle_resize_event(&mut self, width: u16, height: u16) {
-        let area = Rect::new(0, 0, width, height);
-        let frame_set = Self::calculate_frames(area);
-        self.directory.set_area(frame_set.directory);
-        self.preview.set_area(frame_set.preview);
+    async fn perform_action(&mut self, action: Action) {
+        match action {
+            Action::Quit => self.quit(),
+            Action::Suspend => self.should_suspend = true,
+            Action::ToggleFocus => self.toggle_focus(),
+            Action::FocusDirectory => self.focus_directory(),
+            Action::FocusPreview => self.focus_preview(),
+            Action::OpenSelected => self.send_directory_key(KeyCode::Enter).await,
+            Action::ParentDirectory => self.send_directory_key(KeyCode::Backspace).await,
+            Action::FirstEntry => self.send_directory_key(KeyCode::Home).await,
+            Action::LastEntry => self.send_directory_key(KeyCode::End).await,
+            Action::Refresh => self.reload_directory().await,
+            Action::ToggleHidden => {
+                let mut listing_options = self.directory.listing_options();
+                listing_options.show_hidden = !listing_options.show_hidden;
+                self.set_listing_options(listing_options).await;
+            }
+            Action::CommandLine => self.command_line.open(),
+            Action::EditPath => self.head.start_editing(),
+            Action::FindFile => self.open_finder(),
+            Action::FindByName => self.command_line.open_with("find "),
+            Action::SearchContents => self.command_line.open_with("grep "),
+            Action::ChangePermissions => self.command_line.open_with("chmod "),
+            Action::ChangeOwner => self.command_line.open_with("chown "),
+            Action::ToggleExecute => self.toggle_execute().await,
+            Action::OpenWith => self.open_selected_file_with().await,
+            Action::ShowDetails => self.open_info(),
+            Action::VerifyChecksums => self.open_checksum_report(),
+            Action::AddBookmark => self.add_bookmark(),
+            Action::ShowBookmarks => self.open_bookmark_list(),
+            Action::JumpToFrecent => self.open_frecent(),
+            Action::JumpToZoxide => self.open_zoxide().await,
+            Action::GoBack => self.go_back().await,
+            Action::GoForward => self.go_forward().await,
+            Action::NewTab => self.new_tab().await,
+            Action::CloseTab => self.close_tab().await,
+            Action::NextTab => self.next_tab().await,
+            Action::PreviousTab => self.previous_tab().await,
+            Action::ToggleDualPane => self.toggle_dual_pane().await,
+            Action::ToggleTreeView => self.directory.toggle_tree_view(),
+            Action::ToggleSizes => self.directory.toggle_sizes(),
+            Action::ShowDrives => self.open_mount_list().await,
+            Action::ShowDiskUsage => self.open_disk_usage(),
+            Action::MoveToTrash => self.trash_selected_items(),
+            Action::ShowTrash => self.open_trash_list().await,
+            Action::ShowErrors => self.error_list.open(self.fs_errors.errors()),
+            Action::ToggleDebugOverlay => self.debug_overlay.toggle(),
+            Action::ToggleMouseCapture => self.toggle_mouse_capture(),
+            // The pattern is typed in the directory pane
+            Action::MarkGroup | Action::UnmarkGroup => {
+                self.focus_directory();
+                let key_code = if action == Action::MarkGroup {
+                    Char('=')
+                } else {
+                    Char('-')
+                };
+                self.send_directory_key(key_code).await;
+            }
+            Action::MarkAll => {
+                let key_event = KeyEvent::new(Char('a'), KeyModifiers::CONTROL);
+                if let Err(error) = self.directory.handle_key_event(key_event).await {
+                    self.fs_errors.push(error);
+                }
+            }
+            Action::InvertMarks => self.send_directory_key(Char('*')).await,
+            Action::CompareMarked => self.compare_marked_files().await,
+            Action::CopyPath => self.copy_selected(CopyTarget::Path),
+            Action::CopyName => self.copy_selected(CopyTarget::Name),
+            Action::CopyDirectory => self.copy_selected(CopyTarget::Directory),
+            Action::GoToClipboardPath => self.go_to_clipboard_path().await,
+            Action::ToggleMillerColumns => self.toggle_miller_columns().await,
+            Action::HidePreview => self.set_preview_layout(PreviewLayout::Hidden).await,
+            Action::MaximizePreview => self.set_preview_layout(PreviewLayout::Maximized).await,
+            Action::WidenDirectory => self.move_divider(constants::SPLIT_PERCENT_STEP as i32),
+            Action::NarrowDirectory => self.move_divider(-(constants::SPLIT_PERCENT_STEP as i32)),
+            Action::CopyToOtherPane => self.copy_to_other_pane(false).await,
+            Action::CompareDirectories => self.compare_directories(),
+            Action::MoveToOtherPane => self.copy_to_other_pane(true).await,
+        }
     }
 
-    fn quit(&mut self) {
-        self.should_quit = true;
+    // The visited directories, read from the frecency file the first time they're needed
+    fn frecency(&mut self) -> io::Result<&mut Frecency> {
+        if self.frecency.is_none() {
+            self.frecency = Some(Frecency::load()?);
+        }
+        Ok(self.frecency.as_mut().unwrap())
     }
 
-    fn toggle_focus(&mut self) {
-        if self.directory.has_focus() {
-            self.focus_preview()
-        } else {
-            self.focus_directory()
+    // Records a visit to the current directory, here and in zoxide's database, unless it's already
+    // been recorded or it's inside an archive
+    fn record_visit(&mut self, cwd: &Path) {
+        if self.visited.as_deref() == Some(cwd) || self.directory.display_path(cwd) != cwd {
+            return;
         }
+        self.visited = Some(cwd.to_path_buf());
+        // Not being able to record visits isn't worth interrupting the user about
+        let _ = self.frecency().and_then(|frecency| frecency.visit(cwd));
+        zoxide::add(cwd);
     }
 
-    fn focus_directory(&mut self) {
-        if !self.directory.has_focus() {
-            self.directory.set_focus(true);
-            self.preview.set_focus(false);
+    // Opens the jump list with the directories in zoxide's database, highest ranked first
+    async fn open_zoxide(&mut self) {
+        match zoxide::query().await {
+            Ok(directories) => self.jump_list.open("zoxide", directories),
+            Err(error) => self.fs_errors.push(error),
         }
     }
 
-    fn focus_preview(&mut self) {
-        if !self.preview.has_focus() {
-            self.directory.set_focus(false);
-            self.preview.set_focus(true);
+    // Opens the jump list with the visited directories, highest ranked first
+    fn open_frecent(&mut self) {
+        match self.frecency() {
+            Ok(frecency) => {
+                let directories = frecency.ranked();
+                self.jump_list.open("Frequent directories", directories);
+            }
+            Err(error) => self.fs_errors.push(error),
         }
     }
 
-    fn handle_directory_changed(&mut self) {
+    // Opens the disk usage popup on the current directory
+    fn open_disk_usage(&mut self) {
         match std::env::current_dir() {
-            Ok(cwd) => self.head.set_path(Some(cwd)),
-            Err(error) => {
-                self.head.set_path(None);
-                self.fs_error = Some(error);
-            }
+            Ok(cwd) => self.disk_usage_view.open(&cwd),
+            Err(error) => self.fs_errors.push(error),
         }
     }
 
-    async fn load_selected_item(&mut self) {
-        self.preview
-            .load_entry(self.directory.selected_item())
-            .await;
+    // Opens the list of drives or mount points. Finding their free space can block, so it's done
+    // on a blocking thread.
+    async fn open_mount_list(&mut self) {
+        let result = tokio::task::spawn_blocking(mounts::mounts)
+            .await
+            .map_err(io::Error::from)
+            .and_then(|result| result);
+        match result {
+            Ok(mounts) => self.mount_list.open(mounts),
+            Err(error) => self.fs_errors.push(error),
+        }
     }
 
-    pub fn render(&mut self, frame: &mut Frame<'_>) {
-        let area = frame.size();
-        let frame_set = Self::calculate_frames(area);
-
-        self.head.render(frame_set.head, frame);
-        if let Err(error) = self.directory.render(frame_set.directory, frame) {
-            self.fs_error = Some(error);
+    // Shows how the two marked files differ in the preview pane, and focuses it to scroll through
+    // the diff. It's shown until another entry is previewed.
+    async fn compare_marked_files(&mut self) {
+        let marked_items = self.directory.marked_items();
+        let [left, right] = marked_items.as_slice() else {
+            self.fs_errors.push(FsError::Other(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Mark two files to compare them",
+            )));
+            return;
+        };
+        // The other pane takes the preview's place in dual-pane mode
+        if self.other_pane.is_some() {
+            self.fs_errors.push(FsError::Other(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Files can't be compared in dual-pane mode",
+            )));
+            return;
         }
-        if let Err(error) = self.preview.render(frame_set.preview, frame) {
-            self.fs_error = Some(error);
+        if self.preview_layout == PreviewLayout::Hidden {
+            self.preview_layout = PreviewLayout::Split;
         }
-        if let Some(fs_error) = &self.fs_error {
-            self.render_error_popup(&fs_error.to_string(), frame, area);
+        match self.preview.load_diff(left, right).await {
+            Ok(()) => self.focus_preview(),
+            Err(error) => self.fs_errors.push(error),
         }
     }
 
-    fn render_error_popup(&self, error: &str, frame: &mut Frame, frame_size: Rect) {
-        let text = Paragraph::new(Text::from(error)).style(styles::ERROR_STYLE);
-        let block = Block::bordered().title("Error");
-
-        let error_len = error.len() as u16;
-        let area = Self::centered_rect(error_len + 4, 3, frame_size);
-        let error_area = Self::centered_rect(error_len, 1, area);
-
-        frame.render_widget(Clear, area); // This clears the background underneath the popup
-        frame.render_widget(block, area);
-        frame.render_widget(text, error_area);
+    // The marked entries, or the selected entry if none are marked
+    fn selected_items(&self) -> Vec<PathBuf> {
+        if self.directory.has_marks() {
+            return self.directory.marked_items();
+        }
+        self.directory
+            .selected_item()
+            .filter(|selected| selected.file_name().is_some())
+            .into_iter()
+            .collect()
     }
 
-    fn centered_rect(width: u16, height: u16, rect: Rect) -> Rect {
-        let vert_margin = (rect.height - height) / 2;
-        let horiz_margin = (rect.width - width) / 2;
-        let vert_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(vert_margin),
-                Constraint::Length(height),
-                Constraint::Length(vert_margin),
-            ])
-            .split(rect);
+    // Whether the entries can be changed, which those in archives and on other computers can't.
+    // The first that can't be is an error.
+    fn can_change(&mut self, operation: Operation, entries: &[PathBuf]) -> bool {
+        for entry in entries {
+            if let Err(error) = fs_provider::check_local(entry).context(operation, entry) {
+                self.fs_errors.push(error);
+                return false;
+            }
+        }
+        true
+    }
 
-        Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Length(horiz_margin),
-                Constraint::Length(width),
-                Constraint::Length(horiz_margin),
-            ])
-            .split(vert_layout[1])[1]
+    // Asks before moving the marked entries, or the selected one, to the trash
+    fn trash_selected_items(&mut self) {
+        let selected_items = self.selected_items();
+        if !self.can_change(Operation::Trashing, &selected_items) {
+            return;
+        }
+        let question = match selected_items.as_slice() {
+            [] => return,
+            [selected] => format!("Move {} to the trash?", util::entry_name(selected)),
+            selected_items => format!("Move {} entries to the trash?", selected_items.len()),
+        };
+        self.confirm_popup
+            .open(question, Confirmation::Trash(selected_items));
     }
 
-    fn calculate_frames(frame_rect: Rect) -> FrameSet {
-        let root = Layout::default()
-            .constraints([Constraint::Length(1), Constraint::Min(1)])
-            .split(frame_rect);
-        let main = Layout::default()
+    async fn trash_items(&mut self, entries: Vec<PathBuf>) {
+        if !self.can_change(Operation::Trashing, &entries) {
+            return;
+        }
+        for selected in entries {
+            info!(path = %selected.display(), "trashing");
+            let result = {
+                let selected = selected.clone();
+                tokio::task::spawn_blocking(move || trash::trash(&selected))
+                    .await
+                    .map_err(io::Error::from)
+                    .and_then(|result| result)
+            }
+            .context(Operation::Trashing, &selected);
+            if let Err(error) = result {
+                self.fs_errors.push(error);
+            }
+        }
+        self.directory.clear_marks();
+        self.reload_directory().await;
+    }
+
+    async fn open_trash_list(&mut self) {
+        let result = tokio::task::spawn_blocking(trash::list)
+            .await
+            .map_err(io::Error::from)
+            .and_then(|result| result);
+        match result {
+            Ok(items) => self.trash_list.open(items),
+            Err(error) => self.fs_errors.push(error),
+        }
+    }
+
+    // Puts the item back where it was deleted from, and selects it there
+    async fn restore_trashed_item(&mut self, item: trash::TrashedItem) {
+        let original_path = item.original_path.clone();
+        info!(path = %original_path.display(), "restoring from the trash");
+        let result = tokio::task::spawn_blocking(move || trash::restore(&item))
+            .await
+            .map_err(io::Error::from)
+            .and_then(|result| result)
+            .context(Operation::Restoring, &original_path);
+        match result {
+            Ok(()) => self.go_to_path(&original_path).await,
+            Err(error) => self.fs_errors.push(error),
+        }
+    }
+
+    // Deletes what it can, and says what it couldn't
+    async fn empty_trash(&mut self) {
+        info!("emptying the trash");
+        let result = tokio::task::spawn_blocking(trash::empty)
+            .await
+            .map_err(io::Error::from)
+            .and_then(|result| result);
+        match result {
+            Ok(failures) => {
+                for (path, error) in failures {
+                    self.fs_errors
+                        .push(FsError::io(Operation::Deleting, &path, error));
+                }
+            }
+            Err(error) => self.fs_errors.push(error),
+        }
+    }
+
+    fn tab_count(&self) -> usize {
+        self.tabs.len().max(1)
+    }
+
+    // Opens a tab in the current directory, and shows it
+    async fn new_tab(&mut self) {
+        let cwd = match std::env::current_dir() {
+            Ok(cwd) => cwd,
+            Err(error) => {
+                self.fs_errors.push(error);
+                return;
+            }
+        };
+        let mut tab = Tab {
+            cwd,
+            ..Default::default()
+        };
+        tab.directory.set_event_tx(self.event_tx.clone());
+        tab.directory.set_view_memory(self.view_memory.clone());
+        tab.directory
+            .set_listing_options(self.directory.default_listing_options());
+        tab.preview.set_event_tx(self.event_tx.clone());
+        tab.preview
+            .set_listing_options(self.directory.listing_options());
+        tab.preview.set_graphics_protocol(self.graphics_protocol);
+        if let Some(max_text_size) = self.max_text_size {
+            tab.preview.set_max_text_size(max_text_size);
+        }
+        tab.directory.set_wheel_scroll(self.settings.wheel_scroll());
+        tab.preview.set_wheel_scroll(self.settings.wheel_scroll());
+        tab.preview
+            .set_previewers(self.previewers(), self.settings.previewer_timeout);
+        // The first tab gets its place in the list when the second one is opened
+        if self.tabs.is_empty() {
+            self.tabs.push(Tab::default());
+        }
+        self.tabs.insert(self.tab_index + 1, tab);
+        self.show_tab(self.tab_index + 1).await;
+    }
+
+    // Closes the showing tab and shows the next one, unless it's the only one
+    async fn close_tab(&mut self) {
+        if self.tab_count() < 2 {
+            return;
+        }
+        self.cancel_tab_reads();
+        self.tabs.remove(self.tab_index);
+        let index = self.tab_index.min(self.tabs.len() - 1);
+        self.swap_tab(index);
+        self.tab_index = index;
+        let cwd = self.tabs[index].cwd.clone();
+        self.enter_tab(&cwd).await;
+    }
+
+    async fn previous_tab(&mut self) {
+        let count = self.tab_count();
+        self.show_tab((self.tab_index + count - 1) % count).await;
+    }
+
+    async fn next_tab(&mut self) {
+        self.show_tab((self.tab_index + 1) % self.tab_count()).await;
+    }
+
+    // Puts the showing tab's panes away in its tab, and shows the other tab's panes. What the hidden
+    // tab was doing in the background is stopped, and it's reloaded when it's shown again.
+    async fn show_tab(&mut self, index: usize) {
+        if index == self.tab_index || index >= self.tabs.len() {
+            return;
+        }
+        self.cancel_tab_reads();
+        if let Ok(cwd) = std::env::current_dir() {
+            self.tabs[self.tab_index].cwd = cwd;
+        }
+        self.swap_tab(self.tab_index);
+        self.swap_tab(index);
+        self.tab_index = index;
+        let cwd = self.tabs[index].cwd.clone();
+        self.enter_tab(&cwd).await;
+    }
+
+    // Trades the showing panes and history for the ones a tab holds
+    fn swap_tab(&mut self, index: usize) {
+        let tab = &mut self.tabs[index];
+        std::mem::swap(&mut self.directory, &mut tab.directory);
+        std::mem::swap(&mut self.preview, &mut tab.preview);
+        std::mem::swap(&mut self.other_pane, &mut tab.other_pane);
+        std::mem::swap(&mut self.history, &mut tab.history);
+    }
+
+    // Stops what the showing tab's panes are reading in the background
+    fn cancel_tab_reads(&mut self) {
+        self.directory.cancel_read();
+        self.preview.cancel_load();
+        if let Some(other_pane) = self.other_pane.as_mut() {
+            other_pane.directory.cancel_read();
+        }
+    }
+
+    // Goes to the directory of the tab that's just been shown
+    async fn enter_tab(&mut self, cwd: &Path) {
+        if let Err(error) = std::env::set_current_dir(cwd) {
+            self.fs_errors.push(error);
+        }
+        self.focus_directory();
+        self.reload_directory().await;
+        self.reload_other_pane().await;
+    }
+
+    // Goes back to the directory the directory pane was in before this one
+    async fn go_back(&mut self) {
+        if let Some(directory) = self.history.back() {
+            self.go_through_history(&directory).await;
+            // It's still the way forward if it couldn't be gone to
+            if std::env::current_dir().is_ok_and(|cwd| cwd != directory) {
+                self.history.forward();
+            }
+        }
+    }
+
+    // Goes forward to the directory that going back came from
+    async fn go_forward(&mut self) {
+        if let Some(directory) = self.history.forward() {
+            self.go_through_history(&directory).await;
+            if std::env::current_dir().is_ok_and(|cwd| cwd != directory) {
+                self.history.back();
+            }
+        }
+    }
+
+    async fn go_through_history(&mut self, directory: &Path) {
+        info!(directory = %directory.display(), "going through the history");
+        self.change_directory(directory).await;
+    }
+
+    // Switches the preview to the given layout, or back to splitting the width with the directory
+    async fn set_preview_layout(&mut self, layout: PreviewLayout) {
+        if self.other_pane.is_some() {
+            return;
+        }
+        let was_hidden = self.preview_layout == PreviewLayout::Hidden;
+        self.preview_layout = if self.preview_layout == layout {
+            PreviewLayout::Split
+        } else {
+            layout
+        };
+        match self.preview_layout {
+            PreviewLayout::Hidden => {
+                self.preview.load_entry(None).await;
+                self.focus_directory();
+            }
+            PreviewLayout::Maximized => self.focus_preview(),
+            PreviewLayout::Split => {}
+        }
+        if was_hidden && self.shows_preview() {
+            self.load_selected_item().await;
+        }
+    }
+
+    async fn toggle_miller_columns(&mut self) {
+        self.is_miller_columns = !self.is_miller_columns;
+        self.load_parent_list().await;
+    }
+
+    // Reads the parent directory's entries, if they're shown
+    async fn load_parent_list(&mut self) {
+        if !self.is_miller_columns {
+            return;
+        }
+        let result = match std::env::current_dir() {
+            Ok(cwd) => {
+                let listing_options = self.directory.listing_options();
+                self.parent_list.load(&cwd, &listing_options).await
+            }
+            Err(error) => Err(error.into()),
+        };
+        if let Err(error) = result {
+            self.fs_errors.push(error);
+        }
+    }
+
+    // Whether the directory and the preview are side by side, with a divider between them
+    fn is_split(&self) -> bool {
+        self.other_pane.is_none() && self.preview_layout == PreviewLayout::Split
+    }
+
+    // Moves the divider between the directory and the preview by some percent of the width
+    fn move_divider(&mut self, delta: i32) {
+        if self.is_split() {
+            let split_percent = (self.settings.split_percent as i32 + delta).max(0) as u16;
+            self.set_split_percent(split_percent);
+            self.save_settings();
+        }
+    }
+
+    fn set_split_percent(&mut self, split_percent: u16) {
+        self.settings.split_percent = Settings::clamp_split_percent(split_percent);
+    }
+
+    fn save_settings(&mut self) {
+        if let Err(error) = self.settings.save() {
+            self.fs_errors.push(error);
+        }
+    }
+
+    // Pressing the mouse on the divider starts dragging it, and releasing it saves where it ended
+    // up. Returns true if the event was used.
+    fn handle_divider_mouse_event(&mut self, mouse_event: MouseEvent) -> bool {
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) if self.is_split() => {
+                // The divider is the directory's border next to the preview's, and runs across
+                // the main area when the panes are stacked
+                let directory_area = self.directory.area();
+                let main_area = self.main_area;
+                let (divider, position, across, extent) = if self.is_stacked_layout {
+                    (
+                        directory_area.bottom().saturating_sub(1),
+                        mouse_event.row,
+                        mouse_event.column,
+                        main_area.left()..main_area.right(),
+                    )
+                } else {
+                    (
+                        directory_area.right().saturating_sub(1),
+                        mouse_event.column,
+                        mouse_event.row,
+                        main_area.top()..main_area.bottom(),
+                    )
+                };
+                let on_divider =
+                    (divider..=divider + 1).contains(&position) && extent.contains(&across);
+                self.is_dragging_divider = on_divider;
+                on_divider
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.is_dragging_divider => {
+                let (position, start, length) = if self.is_stacked_layout {
+                    (mouse_event.row, self.main_area.y, self.main_area.height)
+                } else {
+                    (mouse_event.column, self.main_area.x, self.main_area.width)
+                };
+                if length > 0 {
+                    let offset = position.saturating_sub(start) as u32;
+                    let split_percent = (offset + 1) * 100 / length as u32;
+                    self.set_split_percent(split_percent as u16);
+                }
+                true
+            }
+            MouseEventKind::Up(MouseButton::Left) if self.is_dragging_divider => {
+                self.is_dragging_divider = false;
+                self.save_settings();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn toggle_mouse_capture(&mut self) {
+        self.is_mouse_released = !self.is_mouse_released;
+        // The mouse button won't be seen going up
+        self.is_dragging_divider = false;
+    }
+
+    fn shows_preview(&self) -> bool {
+        self.other_pane.is_none() && self.preview_layout != PreviewLayout::Hidden
+    }
+
+    fn shows_directory(&self) -> bool {
+        self.other_pane.is_some() || self.preview_layout != PreviewLayout::Maximized
+    }
+
+    // Turns dual-pane mode on, with the other pane in the same directory, or off
+    async fn toggle_dual_pane(&mut self) {
+        if let Some(mut other_pane) = self.other_pane.take() {
+            other_pane.directory.cancel_read();
+            self.focus_directory();
+            self.load_selected_item().await;
+            return;
+        }
+        let cwd = match std::env::current_dir() {
+            Ok(cwd) => cwd,
+            Err(error) => {
+                self.fs_errors.push(error);
+                return;
+            }
+        };
+        let mut directory = Directory::default();
+        directory.set_event_tx(self.event_tx.clone());
+        directory.set_view_memory(self.view_memory.clone());
+        directory.set_listing_options(self.directory.default_listing_options());
+        directory.set_wheel_scroll(self.settings.wheel_scroll());
+        self.preview.load_entry(None).await;
+        self.preview_layout = PreviewLayout::Split;
+        self.focus_directory();
+        self.other_pane = Some(OtherPane {
+            directory,
+            cwd,
+            history: History::default(),
+            is_left: false,
+        });
+        self.reload_other_pane().await;
+    }
+
+    // Makes the other pane the active one, in its directory
+    async fn swap_panes(&mut self) {
+        let Some(other_pane) = self.other_pane.as_mut() else {
+            return;
+        };
+        if let Err(error) = std::env::set_current_dir(&other_pane.cwd) {
+            self.fs_errors.push(error);
+            return;
+        }
+        let cwd = self.directory.loaded_directory().unwrap_or_default();
+        std::mem::swap(&mut self.directory, &mut other_pane.directory);
+        std::mem::swap(&mut self.history, &mut other_pane.history);
+        other_pane.cwd = cwd;
+        other_pane.is_left = !other_pane.is_left;
+        other_pane.directory.set_focus(false);
+        self.focus_directory();
+        self.handle_directory_changed();
+        self.select_item();
+    }
+
+    // Reloads the other pane's directory, which means going there for a moment
+    async fn reload_other_pane(&mut self) {
+        let Some(other_pane) = self.other_pane.as_mut() else {
+            return;
+        };
+        let cwd = std::env::current_dir();
+        let result = match std::env::set_current_dir(&other_pane.cwd)
+            .context(Operation::Entering, &other_pane.cwd)
+        {
+            Ok(()) => {
+                let selected = other_pane.directory.selected_item();
+                let result = other_pane.directory.load_cwd().await;
+                if let Some(selected) = selected {
+                    other_pane.directory.select_entry(&selected);
+                }
+                result
+            }
+            Err(error) => Err(error),
+        };
+        if let Err(error) = cwd.and_then(std::env::set_current_dir) {
+            self.fs_errors.push(error);
+        } else if let Err(error) = result {
+            self.fs_errors.push(error);
+        }
+    }
+
+    // Marks the entries in each pane that aren't in the other one, or are newer or a different
+    // size, so that copying the marked entries makes the directories the same
+    fn compare_directories(&mut self) {
+        let Some(other_pane) = self.other_pane.as_mut() else {
+            return;
+        };
+        let (differing, other_differing) = dir_compare::differing_entries(
+            &self.directory.markable_items(),
+            &other_pane.directory.markable_items(),
+        );
+        self.directory.set_marks(differing);
+        other_pane.directory.set_marks(other_differing);
+    }
+
+    // Copies or moves the marked entries, or the selected entry, to the other pane's directory,
+    // and selects the last one there
+    async fn copy_to_other_pane(&mut self, is_move: bool) {
+        let Some(other_pane) = self.other_pane.as_ref() else {
+            return;
+        };
+        let selected_items = self.selected_items();
+        if selected_items.is_empty() {
+            return;
+        }
+        let directory = other_pane.cwd.clone();
+        let operation = if is_move {
+            Operation::Moving
+        } else {
+            Operation::Copying
+        };
+        // What's in an archive can be copied out of it, but not moved
+        if !self.can_change(operation, std::slice::from_ref(&directory))
+            || is_move && !self.can_change(operation, &selected_items)
+        {
+            return;
+        }
+        let mut last_destination = None;
+        for selected in selected_items {
+            info!(
+                from = %selected.display(),
+                to = %directory.display(),
+                ?operation
+            );
+            // What's in an archive is extracted to be copied
+            let result = match fs_provider::provider(&selected).local_path(&selected).await {
+                Ok(selected) => {
+                    let directory = directory.clone();
+                    tokio::task::spawn_blocking(move || {
+                        if is_move {
+                            file_ops::move_entry(&selected, &directory)
+                        } else {
+                            file_ops::copy_entry(&selected, &directory)
+                        }
+                    })
+                    .await
+                    .map_err(io::Error::from)
+                    .and_then(|result| result)
+                }
+                Err(error) => Err(error),
+            }
+            .context(operation, &selected);
+            match result {
+                Ok(destination) => last_destination = Some(destination),
+                Err(error) => self.fs_errors.push(error),
+            }
+        }
+        self.directory.clear_marks();
+        self.reload_other_pane().await;
+        if let (Some(other_pane), Some(destination)) = (self.other_pane.as_mut(), last_destination)
+        {
+            other_pane.directory.select_entry(&destination);
+        }
+        if is_move {
+            self.reload_directory().await;
+        }
+    }
+
+    // The bookmarks, read from the bookmarks file the first time they're needed
+    fn bookmarks(&mut self) -> io::Result<&mut Bookmarks> {
+        if self.bookmarks.is_none() {
+            self.bookmarks = Some(Bookmarks::load()?);
+        }
+        Ok(self.bookmarks.as_mut().unwrap())
+    }
+
+    // Bookmarks the current directory
+    fn add_bookmark(&mut self) {
+        let result = std::env::current_dir().and_then(|cwd| {
+            let cwd = self.directory.display_path(&cwd);
+            self.bookmarks()?.add(&cwd)
+        });
+        if let Err(error) = result {
+            self.fs_errors.push(error);
+        }
+    }
+
+    fn remove_bookmark(&mut self, path: &Path) {
+        if let Err(error) = self
+            .bookmarks()
+            .and_then(|bookmarks| bookmarks.remove(path))
+        {
+            self.fs_errors.push(error);
+        }
+    }
+
+    fn open_bookmark_list(&mut self) {
+        match self.bookmarks() {
+            Ok(bookmarks) => {
+                let paths = bookmarks.paths().to_vec();
+                self.bookmark_list.open(&paths);
+            }
+            Err(error) => self.fs_errors.push(error),
+        }
+    }
+
+    // Opens the details popup for the selected entry
+    fn open_info(&mut self) {
+        if let Some(mut entry) = self.directory.selected_item() {
+            // The parent directory entry is shown by its real path
+            if entry.file_name().is_none() {
+                match entry.canonicalize() {
+                    Ok(canonical) => entry = canonical,
+                    Err(error) => {
+                        self.fs_errors.push(error);
+                        return;
+                    }
+                }
+            }
+            let display_path = self.directory.display_path(&entry);
+            if let Err(error) = self.info.open(&entry, &display_path) {
+                self.fs_errors.push(error);
+            }
+        }
+    }
+
+    // Verifies the checksums in the selected manifest, or the selected file's checksum in the
+    // manifest beside it
+    fn open_checksum_report(&mut self) {
+        let Some(selected) = self.directory.selected_item() else {
+            return;
+        };
+        let result = if checksum::is_manifest(&selected) {
+            self.checksum_report.open(&selected, None)
+        } else if let Some(manifest) = checksum::sibling_manifest(&selected) {
+            self.checksum_report.open(&manifest, Some(&selected))
+        } else {
+            Err(FsError::Other(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} isn't a checksum manifest, and doesn't have one beside it",
+                    util::entry_name(&selected)
+                ),
+            )))
+        };
+        if let Err(error) = result {
+            self.fs_errors.push(error);
+        }
+    }
+
+    fn open_finder(&mut self) {
+        match std::env::current_dir() {
+            Ok(cwd) => {
+                let show_hidden = self.directory.listing_options().show_hidden;
+                self.finder.open(cwd, show_hidden);
+            }
+            Err(error) => self.fs_errors.push(error),
+        }
+    }
+
+    // Opens the directory containing the path and selects it
+    async fn go_to_path(&mut self, path: &Path) {
+        if let Some(parent) = path.parent() {
+            if let Err(error) =
+                std::env::set_current_dir(parent).context(Operation::Entering, parent)
+            {
+                self.fs_errors.push(error);
+                return;
+            }
+        }
+        if let Err(error) = self.directory.load_cwd().await {
+            self.fs_errors.push(error);
+        }
+        self.directory.select_entry(&path.to_path_buf());
+        self.load_selected_item().await;
+    }
+
+    fn set_mark(&mut self, letter: char) {
+        match std::env::current_dir() {
+            Ok(cwd) => {
+                let selected = self.directory.selected_item();
+                self.marks.insert(letter, (cwd, selected));
+            }
+            Err(error) => self.fs_errors.push(error),
+        }
+    }
+
+    // Copies the selected entry's absolute path, its name, or the directory it's in, to the
+    // clipboard
+    fn copy_selected(&mut self, target: CopyTarget) {
+        let Some(selected) = self
+            .directory
+            .selected_item()
+            .filter(|selected| selected.file_name().is_some())
+        else {
+            return;
+        };
+        let path = match std::path::absolute(&selected) {
+            Ok(path) => path,
+            Err(error) => {
+                self.fs_errors.push(error);
+                return;
+            }
+        };
+        let text = match target {
+            CopyTarget::Path => path.to_string_lossy().to_string(),
+            CopyTarget::Name => util::entry_name(&path),
+            CopyTarget::Directory => path
+                .parent()
+                .map(|parent| parent.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+        if let Err(error) = self.clipboard.copy(&text) {
+            self.fs_errors.push(error);
+        }
+    }
+
+    // Goes back to the directory the mark was set in, and selects the entry that was selected
+    async fn go_to_mark(&mut self, letter: char) {
+        let Some((directory, selected)) = self.marks.get(&letter).cloned() else {
+            self.fs_errors.push(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Mark {letter} isn't set"),
+            ));
+            return;
+        };
+        if let Err(error) =
+            std::env::set_current_dir(&directory).context(Operation::Entering, &directory)
+        {
+            self.fs_errors.push(error);
+            return;
+        }
+        if let Err(error) = self.directory.load_cwd().await {
+            self.fs_errors.push(error);
+        }
+        if let Some(selected) = selected {
+            self.directory.select_entry(&selected);
+        }
+        self.load_selected_item().await;
+    }
+
+    fn load_plugins(&mut self) {
+        let (plugins, errors) = Plugins::load();
+        for error in errors {
+            self.fs_errors
+                .push(io::Error::new(io::ErrorKind::InvalidData, error));
+        }
+        self.plugins = Arc::new(plugins);
+    }
+
+    // The previewers in the settings, then the plugins' previewers
+    fn previewers(&self) -> Vec<Previewer> {
+        let mut previewers = self.settings.previewers.clone();
+        previewers.extend(self.plugins.previewers().filter_map(|(mask, index)| {
+            Previewer::from_script(mask, self.plugins.clone(), index).ok()
+        }));
+        previewers
+    }
+
+    // What a plugin's command or key is told about the app
+    fn plugin_context(&self) -> PluginContext {
+        PluginContext {
+            cwd: std::env::current_dir().unwrap_or_default(),
+            selected: self
+                .directory
+                .selected_item()
+                .filter(|selected| util::entry_name(selected) != constants::PARENT_DIRECTORY),
+            marked: self.directory.marked_items(),
+        }
+    }
+
+    // Does what a plugin's command or key asked for, in order, or shows why it failed. Paths are
+    // relative to the current directory when it's asked for.
+    async fn apply_plugin_result(&mut self, result: Result<Vec<PluginRequest>, String>) {
+        let requests = match result {
+            Ok(requests) => requests,
+            Err(error) => {
+                self.fs_errors.push(io::Error::other(error));
+                return;
+            }
+        };
+        for request in requests {
+            let cwd = std::env::current_dir().unwrap_or_default();
+            match request {
+                PluginRequest::Cd(path) => self.change_directory(&cwd.join(path)).await,
+                PluginRequest::Select(path) => self.go_to_path(&cwd.join(path)).await,
+                PluginRequest::Mark(paths) => {
+                    let mut marked = self.directory.marked_items();
+                    marked.extend(paths.into_iter().map(|path| cwd.join(path)));
+                    self.directory.set_marks(marked);
+                }
+                PluginRequest::ClearMarks => self.directory.clear_marks(),
+                PluginRequest::Message(message) => self.status.set_message(message),
+                PluginRequest::Command(command) => self.run_builtin_command(&command).await,
+            }
+        }
+    }
+
+    // Opens the directory
+    async fn change_directory(&mut self, path: &Path) {
+        if let Err(error) = std::env::set_current_dir(path).context(Operation::Entering, path) {
+            self.fs_errors.push(error);
+            return;
+        }
+        if let Err(error) = self.directory.load_cwd().await {
+            self.fs_errors.push(error);
+        }
+        self.load_selected_item().await;
+    }
+
+    // Goes to a path typed in the path bar: into it if it's a directory, or to the directory it's
+    // in with it selected if it isn't. Relative paths are relative to the current directory.
+    async fn go_to_typed_path(&mut self, input: &str) {
+        let input = input.trim();
+        if input.is_empty() {
+            return;
+        }
+        if let Some(location) = remote::Location::parse(input) {
+            self.go_to_remote_location(location).await;
+            return;
+        }
+        let path = util::expand_tilde(input);
+        let path = match std::env::current_dir() {
+            Ok(cwd) => cwd.join(path),
+            Err(error) => {
+                self.fs_errors.push(error);
+                return;
+            }
+        };
+        if path.is_dir() {
+            self.change_directory(&path).await;
+        } else if path.exists() {
+            self.go_to_path(&path).await;
+        } else {
+            self.fs_errors.push(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{input} doesn't exist"),
+            ));
+        }
+    }
+
+    // Goes to the path on the clipboard, if there is one
+    async fn go_to_clipboard_path(&mut self) {
+        let path = self
+            .clipboard
+            .text()
+            .and_then(|text| Self::pasted_path(&text));
+        match path {
+            Some(path) => self.open_location(&path).await,
+            None => self.fs_errors.push(io::Error::new(
+                io::ErrorKind::NotFound,
+                "The clipboard doesn't have the path of anything that exists",
+            )),
+        }
+    }
+
+    // Goes into a directory, or to the directory anything else is in, with it selected
+    async fn open_location(&mut self, path: &Path) {
+        if path.is_dir() {
+            self.change_directory(path).await;
+        } else {
+            self.go_to_path(path).await;
+        }
+    }
+
+    // The path in pasted text, if it's the path of something that exists. Quotes around it, a
+    // file URL's scheme and a leading tilde are allowed, and a relative path is taken to be in the
+    // current directory.
+    fn pasted_path(text: &str) -> Option<PathBuf> {
+        let text = text.lines().next()?.trim();
+        let text = text
+            .strip_prefix('"')
+            .and_then(|text| text.strip_suffix('"'))
+            .or_else(|| {
+                text.strip_prefix('\'')
+                    .and_then(|text| text.strip_suffix('\''))
+            })
+            .unwrap_or(text);
+        let text = text.strip_prefix("file://").unwrap_or(text);
+        if text.is_empty() {
+            return None;
+        }
+        let path = std::env::current_dir().ok()?.join(util::expand_tilde(text));
+        path.exists().then_some(path)
+    }
+
+    // Goes to a location on another computer or in an S3 bucket, connecting to it first if it
+    // isn't already. Connecting to another computer can ask for a password, so that's left for
+    // when the terminal's been given to it.
+    async fn go_to_remote_location(&mut self, location: remote::Location) {
+        let root = location.root();
+        if self.directory.real_path(&root) == root {
+            if location.needs_terminal() {
+                self.location_to_connect = Some(location);
+            } else {
+                self.connect_to_location(location).await;
+            }
+            return;
+        }
+        self.go_to_connected_location(&location).await;
+    }
+
+    /// Connects to a location on another computer or in an S3 bucket, and goes there
+    pub async fn connect_to_location(&mut self, location: remote::Location) {
+        let root = location.root();
+        info!(root = %root.display(), "connecting");
+        match remote::connect(&location).await {
+            Ok(mount) => {
+                self.directory.add_remote_mount(mount, root);
+                self.go_to_connected_location(&location).await;
+            }
+            Err(error) => self.fs_errors.push(FsError::Network {
+                location: root.to_string_lossy().to_string(),
+                message: error.to_string(),
+            }),
+        }
+    }
+
+    async fn go_to_connected_location(&mut self, location: &remote::Location) {
+        let path = self.directory.real_path(&location.display_path());
+        match fs_provider::provider(&path).metadata(&path).await {
+            Ok(metadata) if metadata.is_dir => self.change_directory(&path).await,
+            Ok(_) => self.go_to_path(&path).await,
+            Err(_) => self.fs_errors.push(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} doesn't exist", location.display_path().display()),
+            )),
+        }
+    }
+
+    // Goes to a directory in the header's path. Inside an archive, the archive file itself is
+    // selected in the directory it's in.
+    async fn go_to_breadcrumb(&mut self, path: &Path) {
+        let real_path = self.directory.real_path(path);
+        if real_path.is_dir() {
+            self.change_directory(&real_path).await;
+        } else {
+            self.go_to_path(&real_path).await;
+        }
+    }
+
+    // Goes to the path a search found, and if it found a line in it, scrolls the preview to the line
+    async fn open_search_result(&mut self, result: SearchResult) {
+        self.go_to_path(&result.path).await;
+        if let Some(line) = result.line {
+            self.preview.scroll_to_line(line);
+        }
+    }
+
+    // Runs a plugin's command if the first word is one, and a built-in command if it isn't
+    async fn run_command(&mut self, input: &str) {
+        let input = input.trim();
+        let (name, argument) = input.split_once(' ').unwrap_or((input, ""));
+        if self.plugins.has_command(name) {
+            let result = self
+                .plugins
+                .run_command(name, argument.trim(), &self.plugin_context());
+            self.apply_plugin_result(result).await;
+        } else {
+            self.run_builtin_command(input).await;
+        }
+    }
+
+    async fn run_builtin_command(&mut self, input: &str) {
+        let command = match Command::parse(input) {
+            Ok(command) => command,
+            Err(message) => {
+                self.fs_errors
+                    .push(io::Error::new(io::ErrorKind::InvalidInput, message));
+                return;
+            }
+        };
+        match command {
+            Command::Cd(path) => self.change_directory(&path).await,
+            Command::Sort(sort_key) => {
+                let mut listing_options = self.directory.listing_options();
+                listing_options.sort_key = sort_key;
+                self.set_listing_options(listing_options).await;
+            }
+            Command::Hidden(show_hidden) => {
+                let mut listing_options = self.directory.listing_options();
+                listing_options.show_hidden = show_hidden;
+                self.set_listing_options(listing_options).await;
+            }
+            Command::Mkdir(path) => {
+                info!(path = %path.display(), "creating directory");
+                let absolute_path = std::path::absolute(&path).unwrap_or_else(|_| path.clone());
+                if !self.can_change(Operation::Creating, &[absolute_path]) {
+                    return;
+                }
+                if let Err(error) = tokio::fs::create_dir(&path)
+                    .await
+                    .context(Operation::Creating, &path)
+                {
+                    self.fs_errors.push(error);
+                    return;
+                }
+                if let Err(error) = self.directory.load_cwd().await {
+                    self.fs_errors.push(error);
+                }
+                if let Ok(cwd) = std::env::current_dir() {
+                    self.directory.select_entry(&cwd.join(path));
+                }
+                self.load_selected_item().await;
+            }
+            Command::Chmod(mode, is_recursive) => {
+                self.change_permissions(&mode, is_recursive).await
+            }
+            Command::Chown(owner, is_recursive) => self.change_owners(&owner, is_recursive).await,
+            Command::Find(pattern) => self.start_search(|results, cwd, show_hidden| {
+                results.start_find(cwd, &pattern, show_hidden)
+            }),
+            Command::Grep(pattern) => self.start_search(|results, cwd, show_hidden| {
+                results.start_grep(cwd, &pattern, show_hidden)
+            }),
+            Command::GotoLine(line) => {
+                if self.preview.scroll_to_line(line) {
+                    self.focus_preview();
+                } else {
+                    self.fs_errors.push(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "The preview isn't a text file",
+                    ));
+                }
+            }
+            Command::Quit => self.quit(),
+        }
+    }
+
+    // Gives the marked entries, or the selected entry, the mode, and everything in them if it's
+    // recursive
+    #[cfg(unix)]
+    async fn change_permissions(&mut self, mode: &str, is_recursive: bool) {
+        let mode = match permissions::Mode::parse(mode) {
+            Ok(mode) => mode,
+            Err(message) => {
+                self.fs_errors
+                    .push(io::Error::new(io::ErrorKind::InvalidInput, message));
+                return;
+            }
+        };
+        info!(?mode, is_recursive, "changing permissions");
+        self.change_selected_items(Operation::ChangingPermissions, is_recursive, move |path| {
+            permissions::change_mode(path, &mode)
+        })
+        .await;
+    }
+
+    #[cfg(not(unix))]
+    async fn change_permissions(&mut self, _mode: &str, _is_recursive: bool) {
+        self.fs_errors.push(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Permissions can't be changed on this system",
+        ));
+    }
+
+    // Gives the marked entries, or the selected entry, the owner or group, and everything in them
+    // if it's recursive
+    #[cfg(unix)]
+    async fn change_owners(&mut self, owner: &str, is_recursive: bool) {
+        let owner = match permissions::Owner::parse(owner) {
+            Ok(owner) => owner,
+            Err(message) => {
+                self.fs_errors
+                    .push(io::Error::new(io::ErrorKind::InvalidInput, message));
+                return;
+            }
+        };
+        info!(?owner, is_recursive, "changing owners");
+        self.change_selected_items(Operation::ChangingOwner, is_recursive, move |path| {
+            permissions::change_owner(path, &owner)
+        })
+        .await;
+    }
+
+    #[cfg(not(unix))]
+    async fn change_owners(&mut self, _owner: &str, _is_recursive: bool) {
+        self.fs_errors.push(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Owners can't be changed on this system",
+        ));
+    }
+
+    // Opens a file that Enter was pressed on however the settings say to for its extension
+    async fn open_file(&mut self, file: &Path) {
+        match self.settings.open_file_for(file) {
+            OpenFile::Nothing => {}
+            OpenFile::Editor => self.open_file_with(&Opener::Editor, file).await,
+            OpenFile::Default => self.open_file_with(&Opener::Default, file).await,
+            OpenFile::Menu => self.open_with_menu.open(file, &self.settings.open_with),
+        }
+    }
+
+    // Asks what to open the selected file with
+    async fn open_selected_file_with(&mut self) {
+        let Some(selected) = self.directory.selected_item() else {
+            return;
+        };
+        let metadata = fs_provider::provider(&selected).metadata(&selected).await;
+        if metadata.is_ok_and(|metadata| metadata.is_file) {
+            self.open_with_menu
+                .open(&selected, &self.settings.open_with);
+        }
+    }
+
+    // A file in an archive is extracted to be opened
+    async fn open_file_with(&mut self, opener: &Opener, file: &Path) {
+        info!(?opener, path = %file.display(), "opening file");
+        let result = match fs_provider::provider(file).local_path(file).await {
+            Ok(local_path) => opener::open(opener, &local_path, self.control_socket.as_deref()),
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok(opening) => {
+                if let Some(event_output) = &mut self.event_output {
+                    event_output.file_opened(file, &opener.label());
+                }
+                if let Opening::InTerminal(program) = opening {
+                    self.program_to_run = Some(program);
+                }
+            }
+            Err(error) => self.fs_errors.push(error),
+        }
+    }
+
+    /// A program that had the terminal is done. What it did to the file is shown.
+    pub async fn program_finished(&mut self, name: &str, status: io::Result<ExitStatus>) {
+        match status {
+            Ok(status) if !status.success() => self
+                .fs_errors
+                .push(io::Error::other(format!("{name} exited with {status}"))),
+            Ok(_) => {}
+            Err(error) => self.fs_errors.push(io::Error::new(
+                error.kind(),
+                format!("Can't run {name}: {error}"),
+            )),
+        }
+        self.reload_directory().await;
+    }
+
+    // Makes the selected file executable, or not, like a script that's just been downloaded
+    #[cfg(unix)]
+    async fn toggle_execute(&mut self) {
+        let Some(selected) = self.directory.selected_item() else {
+            return;
+        };
+        if !selected.is_file() {
+            self.fs_errors.push(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} isn't a file", util::entry_name(&selected)),
+            ));
+            return;
+        }
+        if !self.can_change(
+            Operation::ChangingPermissions,
+            std::slice::from_ref(&selected),
+        ) {
+            return;
+        }
+        match permissions::toggle_execute(&selected) {
+            Ok(is_executable) => {
+                info!(path = %selected.display(), is_executable, "toggled execute");
+                self.reload_directory().await;
+                self.status.set_message(format!(
+                    "{} is {}executable",
+                    util::entry_name(&selected),
+                    if is_executable { "" } else { "no longer " }
+                ));
+            }
+            Err(error) => self.fs_errors.push(FsError::io(
+                Operation::ChangingPermissions,
+                &selected,
+                error,
+            )),
+        }
+    }
+
+    #[cfg(not(unix))]
+    async fn toggle_execute(&mut self) {
+        self.fs_errors.push(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Permissions can't be changed on this system",
+        ));
+    }
+
+    // Changes the marked entries, or the selected entry, in the background, and says how many were
+    // changed. The ones that couldn't be are errors.
+    #[cfg(unix)]
+    async fn change_selected_items(
+        &mut self,
+        operation: Operation,
+        is_recursive: bool,
+        change: impl Fn(&Path) -> io::Result<()> + Send + 'static,
+    ) {
+        let selected_items = self.selected_items();
+        if selected_items.is_empty() || !self.can_change(operation, &selected_items) {
+            return;
+        }
+        let result = tokio::task::spawn_blocking(move || {
+            permissions::change_all(&selected_items, is_recursive, change)
+        })
+        .await;
+        let (changed_count, failures) = match result {
+            Ok(result) => result,
+            Err(error) => {
+                self.fs_errors.push(io::Error::from(error));
+                return;
+            }
+        };
+        let mut message = format!(
+            "Changed {changed_count} entr{}",
+            if changed_count != 1 { "ies" } else { "y" }
+        );
+        if !failures.is_empty() {
+            message.push_str(&format!(", {} couldn't be changed", failures.len()));
+        }
+        for (path, error) in failures {
+            self.fs_errors.push(FsError::io(operation, &path, error));
+        }
+        self.reload_directory().await;
+        self.status.set_message(message);
+    }
+
+    // Starts a search of the current directory, and focuses the results pane
+    fn start_search(
+        &mut self,
+        start: impl FnOnce(&mut Results, PathBuf, bool) -> Result<(), String>,
+    ) {
+        let cwd = match std::env::current_dir() {
+            Ok(cwd) => cwd,
+            Err(error) => {
+                self.fs_errors.push(error);
+                return;
+            }
+        };
+        let show_hidden = self.directory.listing_options().show_hidden;
+        match start(&mut self.results, cwd, show_hidden) {
+            Ok(()) => self.focus_results(),
+            Err(message) => self
+                .fs_errors
+                .push(io::Error::new(io::ErrorKind::InvalidInput, message)),
+        }
+    }
+
+    // Changes how the current directory is listed, which it's remembered for
+    async fn set_listing_options(&mut self, listing_options: ListingOptions) {
+        if let Err(error) = self.directory.change_listing_options(listing_options) {
+            self.fs_errors.push(error);
+        }
+        self.preview.set_listing_options(listing_options);
+        self.reload_directory().await;
+    }
+
+    // Reloads the current directory, keeping the selection on the same entry if it's still there
+    async fn reload_directory(&mut self) {
+        let selected = self.directory.selected_item();
+        if let Err(error) = self.directory.load_cwd().await {
+            self.fs_errors.push(error);
+        }
+        if let Some(selected) = selected {
+            self.directory.select_entry(&selected);
+        }
+        self.load_selected_item().await;
+    }
+
+    // Actions on the directory pane are converted into the key events that do the same thing.
+    async fn send_directory_key(&mut self, key_code: KeyCode) {
+        let key_event = KeyEvent::new(key_code, KeyModifiers::NONE);
+        if let Err(error) = self.directory.handle_key_event(key_event).await {
+            self.fs_errors.push(error);
+        }
+    }
+
+    fn handle_resize_event(&mut self, width: u16, height: u16) {
+        let area = Rect::new(0, 0, width, height);
+        let frame_set = self.calculate_frames(area);
+        self.directory.set_area(frame_set.directory);
+        self.preview.set_area(frame_set.preview);
+        if let Some(other_pane) = self.other_pane.as_mut() {
+            other_pane.directory.set_area(frame_set.other_directory);
+        }
+        self.results.set_area(frame_set.results);
+    }
+
+    // Does what another program asked through the control socket, and returns the answer
+    async fn handle_control_command(&mut self, command: ControlCommand) -> String {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        match command {
+            ControlCommand::Navigate(path) => {
+                let path = cwd.join(path);
+                if !path.is_dir() {
+                    return format!("error: {} isn't a directory", path.display());
+                }
+                self.change_directory(&path).await;
+            }
+            ControlCommand::Select(path) => {
+                let path = cwd.join(path);
+                if !path.exists() {
+                    return format!("error: {} doesn't exist", path.display());
+                }
+                self.go_to_path(&path).await;
+            }
+            ControlCommand::Quit => {
+                let mut chosen = self.directory.marked_items();
+                if chosen.is_empty() {
+                    chosen.extend(self.directory.selected_item().filter(|selected| {
+                        util::entry_name(selected) != constants::PARENT_DIRECTORY
+                    }));
+                }
+                self.quit();
+                return chosen
+                    .iter()
+                    .map(|path| path.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+            }
+        }
+        "ok".to_string()
+    }
+
+    fn quit(&mut self) {
+        // There's nowhere to show an error once the app's quitting
+        if let Some(session) = self.session() {
+            let _ = session.save();
+        }
+        self.should_quit = true;
+    }
+
+    // Where the app is now, to start from next time
+    fn session(&self) -> Option<Session> {
+        Some(Session {
+            directory: std::env::current_dir().ok()?,
+            // The parent directory's entry isn't worth remembering
+            selection: self
+                .directory
+                .selected_item()
+                .filter(|selection| selection.file_name().is_some()),
+            listing_options: self.directory.listing_options(),
+            split_percent: self.settings.split_percent,
+        })
+    }
+
+    // Goes back to where the last session left off, before the directory's loaded. A directory
+    // that isn't there anymore, like one in a mount that's gone, is left alone.
+    fn restore_session(&mut self) {
+        let session = match Session::load() {
+            Ok(Some(session)) if session.directory.is_dir() => session,
+            Ok(_) => return,
+            Err(error) => {
+                self.fs_errors.push(error);
+                return;
+            }
+        };
+        if let Err(error) = std::env::set_current_dir(&session.directory) {
+            self.fs_errors.push(error);
+            return;
+        }
+        self.directory.set_listing_options(session.listing_options);
+        self.preview.set_listing_options(session.listing_options);
+        self.settings.split_percent = session.split_percent;
+        self.initial_selection = session.selection;
+    }
+
+    // Focus moves from the directory to the preview to the results, skipping the ones not shown
+    fn toggle_focus(&mut self) {
+        if self.directory.has_focus() && self.shows_preview() {
+            self.focus_preview()
+        } else if !self.results.has_focus() && self.results.is_open() {
+            self.focus_results()
+        } else if self.shows_directory() {
+            self.focus_directory()
+        } else {
+            self.focus_preview()
+        }
+    }
+
+    fn focus_directory(&mut self) {
+        self.directory.set_focus(true);
+        self.preview.set_focus(false);
+        self.results.set_focus(false);
+    }
+
+    fn focus_preview(&mut self) {
+        self.directory.set_focus(false);
+        self.preview.set_focus(true);
+        self.results.set_focus(false);
+    }
+
+    fn focus_results(&mut self) {
+        self.directory.set_focus(false);
+        self.preview.set_focus(false);
+        self.results.set_focus(true);
+    }
+
+    fn close_results(&mut self) {
+        self.results.close();
+        self.focus_directory();
+    }
+
+    fn handle_directory_changed(&mut self) {
+        // The directory may be listed differently from the last one
+        self.preview
+            .set_listing_options(self.directory.listing_options());
+        match std::env::current_dir() {
+            Ok(cwd) => {
+                self.head.set_path(Some(self.directory.display_path(&cwd)));
+                self.history.visit(&cwd);
+                self.record_visit(&cwd);
+            }
+            Err(error) => {
+                self.head.set_path(None);
+                self.fs_errors.push(error);
+            }
+        }
+    }
+
+    // Catches up with the changes that were made while the terminal didn't have focus
+    async fn handle_focus_gained(&mut self) {
+        self.is_unfocused = false;
+        let paths: Vec<PathBuf> = self.unfocused_changes.drain().collect();
+        if !paths.is_empty() {
+            self.handle_fs_changed(paths).await;
+        }
+    }
+
+    // Refreshes the directory and the previewed folder if anything in them was created, deleted or
+    // renamed, keeping the selection on the same entry if it's still there
+    async fn handle_fs_changed(&mut self, paths: Vec<PathBuf>) {
+        let changed = |dir: &Path| {
+            paths
+                .iter()
+                .any(|path| path == dir || path.parent() == Some(dir))
+        };
+        let selected = self.directory.selected_item();
+        if std::env::current_dir().is_ok_and(|cwd| changed(&cwd)) {
+            if let Err(error) = self.directory.load_cwd().await {
+                self.fs_errors.push(error);
+            }
+            if let Some(selected) = &selected {
+                self.directory.select_entry(selected);
+            }
+            // The preview is only reloaded if the selected entry went away
+            if self.directory.selected_item() != selected {
+                self.load_selected_item().await;
+                return;
+            }
+            self.status
+                .set_entry(selected.as_deref(), self.directory.selected_position());
+        }
+        if let Some(selected) = selected.filter(|selected| selected.is_dir() && changed(selected)) {
+            self.preview.load_entry(Some(selected)).await;
+        }
+    }
+
+    // Watches the current directory, and the selected entry if it's a folder
+    fn update_watched(&mut self) {
+        if let Some(watcher) = self.watcher.as_mut() {
+            let mut directories: Vec<PathBuf> = std::env::current_dir().into_iter().collect();
+            directories.extend(
+                self.directory
+                    .selected_item()
+                    .filter(|selected| selected.is_dir()),
+            );
+            watcher.watch(directories);
+        }
+    }
+
+    async fn load_selected_item(&mut self) {
+        let selected_item = self.select_item();
+        // There's no preview in dual-pane mode, or while it's hidden
+        if self.shows_preview() {
+            self.preview.load_entry(selected_item).await;
+        }
+    }
+
+    // Like load_selected_item, but the preview is loaded in the background after a moment
+    fn preview_selected_item(&mut self) {
+        let selected_item = self.select_item();
+        if self.shows_preview() {
+            self.preview.request_entry(selected_item);
+        }
+    }
+
+    // Updates everything but the preview for the selected entry, and returns the entry
+    fn select_item(&mut self) -> Option<PathBuf> {
+        self.update_watched();
+        let selected_item = self.directory.selected_item();
+        self.status
+            .set_entry(selected_item.as_deref(), self.directory.selected_position());
+        selected_item
+    }
+
+    /// Draws the app in an area of the frame, which is usually all of it
+    pub fn render(&mut self, area: Rect, frame: &mut Frame<'_>) {
+        self.is_too_small = area.width < constants::MIN_TERMINAL_WIDTH
+            || area.height < constants::MIN_TERMINAL_HEIGHT;
+        if self.is_too_small {
+            Self::render_too_small(area, frame);
+            return;
+        }
+        let frame_set = self.calculate_frames(area);
+        self.main_area = frame_set.main;
+        self.is_stacked_layout = Self::is_stacked(area);
+
+        self.head.render(frame_set.head, frame);
+        if self.tab_count() > 1 {
+            self.render_tab_bar(frame_set.tabs, frame);
+        }
+        // The command line takes the status bar's place while it's open
+        if self.command_line.is_open() {
+            self.command_line.render(frame_set.status, frame);
+        } else {
+            self.status.render(frame_set.status, frame);
+        }
+        self.key_hints.set_hints(self.current_key_hints());
+        self.key_hints.render(frame_set.key_hints, frame);
+        if !frame_set.parent_list.is_empty() {
+            self.parent_list.render(frame_set.parent_list, frame);
+        }
+        // Panes that aren't shown lose their areas, so they don't take mouse clicks
+        let result = if self.shows_directory() {
+            self.directory.render(frame_set.directory, frame)
+        } else {
+            self.directory.set_area(Rect::default());
+            Ok(())
+        };
+        if let Err(error) = result {
+            self.fs_errors.push(error);
+        }
+        let shows_preview = self.shows_preview();
+        let result = match self.other_pane.as_mut() {
+            Some(other_pane) => other_pane
+                .directory
+                .render(frame_set.other_directory, frame),
+            None if shows_preview => self.preview.render(frame_set.preview, frame),
+            None => {
+                self.preview.set_area(Rect::default());
+                Ok(())
+            }
+        };
+        if let Err(error) = result {
+            self.fs_errors.push(error);
+        }
+        if self.results.is_open() {
+            if let Err(error) = self.results.render(frame_set.results, frame) {
+                self.fs_errors.push(error);
+            }
+        }
+        if self.palette.is_open() {
+            let palette_area = Self::centered_rect(
+                area.width.saturating_sub(4).min(60),
+                area.height
+                    .saturating_sub(4)
+                    .min(Action::ALL.len() as u16 + 3),
+                area,
+            );
+            self.palette.render(palette_area, frame);
+        }
+        if self.finder.is_open() {
+            let finder_area = Self::centered_rect(area.width * 4 / 5, area.height * 4 / 5, area);
+            self.finder.render(finder_area, frame);
+        }
+        if self.info.is_open() {
+            let info_area = Self::centered_rect(
+                area.width.saturating_sub(4).min(100),
+                area.height.saturating_sub(2).min(self.info.height()),
+                area,
+            );
+            self.info.render(info_area, frame);
+        }
+        if self.bookmark_list.is_open() {
+            let bookmark_list_area = Self::centered_rect(
+                area.width.saturating_sub(4).min(80),
+                area.height
+                    .saturating_sub(4)
+                    .min(self.bookmark_list.height()),
+                area,
+            );
+            self.bookmark_list.render(bookmark_list_area, frame);
+        }
+        if self.jump_list.is_open() {
+            let jump_list_area = Self::centered_rect(
+                area.width.saturating_sub(4).min(80),
+                area.height * 4 / 5,
+                area,
+            );
+            self.jump_list.render(jump_list_area, frame);
+        }
+        if self.mount_list.is_open() {
+            let mount_list_area = Self::centered_rect(
+                area.width.saturating_sub(4).min(100),
+                area.height.saturating_sub(4).min(self.mount_list.height()),
+                area,
+            );
+            self.mount_list.render(mount_list_area, frame);
+        }
+        if self.open_with_menu.is_open() {
+            let open_with_menu_area = Self::centered_rect(
+                area.width.saturating_sub(4).min(60),
+                area.height
+                    .saturating_sub(4)
+                    .min(self.open_with_menu.height()),
+                area,
+            );
+            self.open_with_menu.render(open_with_menu_area, frame);
+        }
+        if self.disk_usage_view.is_open() {
+            let disk_usage_area =
+                Self::centered_rect(area.width * 4 / 5, area.height * 4 / 5, area);
+            self.disk_usage_view.render(disk_usage_area, frame);
+        }
+        if self.checksum_report.is_open() {
+            let checksum_report_area = Self::centered_rect(
+                area.width.saturating_sub(4).min(100),
+                area.height
+                    .saturating_sub(4)
+                    .min(self.checksum_report.height()),
+                area,
+            );
+            self.checksum_report.render(checksum_report_area, frame);
+        }
+        if self.trash_list.is_open() {
+            let trash_list_area = Self::centered_rect(
+                area.width.saturating_sub(4).min(100),
+                area.height.saturating_sub(4).min(self.trash_list.height()),
+                area,
+            );
+            self.trash_list.render(trash_list_area, frame);
+        }
+        if self.error_list.is_open() {
+            let error_list_area = Self::centered_rect(
+                area.width.saturating_sub(4).min(100),
+                area.height.saturating_sub(4).min(self.error_list.height()),
+                area,
+            );
+            self.error_list.render(error_list_area, frame);
+        }
+        if self.confirm_popup.is_open() {
+            let confirm_area = Self::centered_rect(self.confirm_popup.width(), 3, area);
+            self.confirm_popup.render(confirm_area, frame);
+        }
+        if self.fs_errors.is_showing() {
+            self.render_error_popup(frame, area);
+        }
+        if self.debug_overlay.is_open() {
+            self.debug_overlay
+                .render(frame_set.main, frame, self.preview_cache_usage());
+        }
+    }
+
+    // How many previews are cached in every tab, and roughly how many bytes they take up
+    fn preview_cache_usage(&self) -> (usize, usize) {
+        self.tabs
+            .iter()
+            .map(|tab| &tab.preview)
+            .chain(std::iter::once(&self.preview))
+            .map(Preview::cache_usage)
+            .fold((0, 0), |(count, size), (preview_count, preview_size)| {
+                (count + preview_count, size + preview_size)
+            })
+    }
+
+    // Returns hints for the keys that apply to the focused pane, plus the global keys
+    fn current_key_hints(&self) -> Vec<KeyHint> {
+        if self.fs_errors.is_showing() {
+            return vec![
+                KeyHint::new("Enter", "Show all errors"),
+                KeyHint::new("Any key", "Dismiss"),
+            ];
+        }
+        if self.confirm_popup.is_open() {
+            return self.confirm_popup.key_hints();
+        }
+        if self.palette.is_open() {
+            return self.palette.key_hints();
+        }
+        if self.finder.is_open() {
+            return self.finder.key_hints();
+        }
+        if self.info.is_open() {
+            return self.info.key_hints();
+        }
+        if self.bookmark_list.is_open() {
+            return self.bookmark_list.key_hints();
+        }
+        if self.jump_list.is_open() {
+            return self.jump_list.key_hints();
+        }
+        if self.mount_list.is_open() {
+            return self.mount_list.key_hints();
+        }
+        if self.open_with_menu.is_open() {
+            return self.open_with_menu.key_hints();
+        }
+        if self.disk_usage_view.is_open() {
+            return self.disk_usage_view.key_hints();
+        }
+        if self.checksum_report.is_open() {
+            return self.checksum_report.key_hints();
+        }
+        if self.trash_list.is_open() {
+            return self.trash_list.key_hints();
+        }
+        if self.error_list.is_open() {
+            return self.error_list.key_hints();
+        }
+        if self.command_line.is_open() {
+            return self.command_line.key_hints();
+        }
+        if self.head.is_editing() {
+            return self.head.key_hints();
+        }
+        match self.pending_mark {
+            Some('m') => return vec![KeyHint::new("a-z", "Set mark")],
+            Some(_) => return vec![KeyHint::new("a-z", "Go to mark")],
+            None => {}
+        }
+        if self.is_copy_pending {
+            return vec![
+                KeyHint::new("p", "Copy path"),
+                KeyHint::new("n", "Copy name"),
+                KeyHint::new("d", "Copy directory"),
+            ];
+        }
+        let mut hints = if self.directory.has_focus() {
+            self.directory.key_hints()
+        } else if self.preview.has_focus() {
+            self.preview.key_hints()
+        } else if self.results.has_focus() {
+            self.results.key_hints()
+        } else {
+            vec![]
+        };
+        // Without the mouse, it might not be obvious how to get it back
+        if self.is_mouse_released {
+            hints.insert(0, KeyHint::new("F9", "Mouse on"));
+        }
+        hints.push(KeyHint::new("Tab", "Switch pane"));
+        hints.push(KeyHint::new("F1", "Commands"));
+        hints.push(KeyHint::new("^F", "Find"));
+        hints.push(KeyHint::new("Esc", "Quit"));
+        hints
+    }
+
+    fn render_tab_bar(&self, area: Rect, frame: &mut Frame) {
+        let titles: Vec<String> = self
+            .tabs
+            .iter()
+            .enumerate()
+            .map(|(index, tab)| {
+                // The showing tab's directory is the current one
+                let cwd = if index == self.tab_index {
+                    std::env::current_dir().unwrap_or_default()
+                } else {
+                    tab.cwd.clone()
+                };
+                format!(
+                    "{}:{}",
+                    index + 1,
+                    Tab::name(&self.directory.display_path(&cwd))
+                )
+            })
+            .collect();
+        let tabs = Tabs::new(titles)
+            .select(self.tab_index)
+            .highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+        frame.render_widget(tabs, area);
+    }
+
+    // Shows the latest error, and how many others haven't been dismissed
+    fn render_error_popup(&self, frame: &mut Frame, frame_size: Rect) {
+        let Some(latest) = self.fs_errors.latest() else {
+            return;
+        };
+        let error = latest.error.to_string();
+        let text = Paragraph::new(Text::from(error.as_str())).style(styles::ERROR_STYLE);
+        let title = match self.fs_errors.undismissed() {
+            1 => "Error".to_string(),
+            undismissed => format!("Error (+{} more)", undismissed - 1),
+        };
+        let error_len = util::display_width(&error).max(util::display_width(&title)) as u16;
+        let block = Block::bordered().title(title);
+
+        let area = Self::centered_rect(error_len + 4, 3, frame_size);
+        let error_area = Self::centered_rect(error_len, 1, area);
+
+        frame.render_widget(Clear, area); // This clears the background underneath the popup
+        frame.render_widget(block, area);
+        frame.render_widget(text, error_area);
+    }
+
+    fn render_too_small(area: Rect, frame: &mut Frame) {
+        let message = format!(
+            "Terminal too small ({}×{}). Make it at least {}×{}.",
+            area.width,
+            area.height,
+            constants::MIN_TERMINAL_WIDTH,
+            constants::MIN_TERMINAL_HEIGHT
+        );
+        let text = Paragraph::new(message)
+            .style(styles::ERROR_STYLE)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        let height = area.height.min(3);
+        frame.render_widget(text, Self::centered_rect(area.width, height, area));
+    }
+
+    // A rectangle of the given size centered in another, shrunk to fit if it's bigger
+    fn centered_rect(width: u16, height: u16, rect: Rect) -> Rect {
+        let width = width.min(rect.width);
+        let height = height.min(rect.height);
+        let vert_margin = (rect.height - height) / 2;
+        let horiz_margin = (rect.width - width) / 2;
+        let vert_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(vert_margin),
+                Constraint::Length(height),
+                Constraint::Length(vert_margin),
+            ])
+            .split(rect);
+
+        Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
-            .split(root[1]);
+            .constraints([
+                Constraint::Length(horiz_margin),
+                Constraint::Length(width),
+                Constraint::Length(horiz_margin),
+            ])
+            .split(vert_layout[1])[1]
+    }
+
+    fn is_stacked(frame_rect: Rect) -> bool {
+        frame_rect.width < constants::STACKED_LAYOUT_WIDTH
+    }
+
+    fn calculate_frames(&self, frame_rect: Rect) -> FrameSet {
+        // The tab bar is only shown if there's more than one tab
+        let tabs_height = if self.tab_count() > 1 { 1 } else { 0 };
+        let root = Layout::default()
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(tabs_height),
+                Constraint::Min(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(frame_rect);
+        // The results pane takes the bottom of the main area while it's open
+        let results_height = if self.results.is_open() { 35 } else { 0 };
+        let body = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(100 - results_height),
+                Constraint::Percentage(results_height),
+            ])
+            .split(root[2]);
+        let directory_width = match self.preview_layout {
+            PreviewLayout::Split => self.settings.split_percent,
+            PreviewLayout::Hidden => 100,
+            PreviewLayout::Maximized => 0,
+        };
+        // Narrow terminals stack the panes instead of squeezing them side by side
+        let direction = if Self::is_stacked(frame_rect) {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        };
+        // The Miller columns layout puts the parent directory's entries in a column of their own,
+        // when there's room for it
+        let columns =
+            if self.is_miller_columns && self.is_split() && direction == Direction::Horizontal {
+                Layout::default()
+                    .direction(direction)
+                    .constraints([
+                        Constraint::Percentage(constants::PARENT_LIST_PERCENT),
+                        Constraint::Percentage(100 - constants::PARENT_LIST_PERCENT),
+                    ])
+                    .split(body[0])
+            } else {
+                Layout::default()
+                    .direction(direction)
+                    .constraints([Constraint::Length(0), Constraint::Min(0)])
+                    .split(body[0])
+            };
+        let main = Layout::default()
+            .direction(direction)
+            .constraints([
+                Constraint::Percentage(directory_width),
+                Constraint::Percentage(100 - directory_width),
+            ])
+            .split(columns[1]);
+        // In dual-pane mode, the directory panes split the area evenly
+        let (directory, preview, other_directory) = match &self.other_pane {
+            Some(other_pane) => {
+                let halves = Layout::default()
+                    .direction(direction)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(body[0]);
+                if other_pane.is_left {
+                    (halves[1], Rect::default(), halves[0])
+                } else {
+                    (halves[0], Rect::default(), halves[1])
+                }
+            }
+            None => (main[0], main[1], Rect::default()),
+        };
 
         FrameSet {
             head: root[0],
-            directory: main[0],
-            preview: main[1],
+            tabs: root[1],
+            main: columns[1],
+            parent_list: columns[0],
+            directory,
+            preview,
+            other_directory,
+            results: body[1],
+            status: root[3],
+            key_hints: root[4],
         }
     }
 }
@@ -0,0 +1,256 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+// Something the user can ask the app to do, independent of how they asked
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Quit,
+    Suspend,
+    ToggleFocus,
+    FocusDirectory,
+    FocusPreview,
+    OpenSelected,
+    ParentDirectory,
+    FirstEntry,
+    LastEntry,
+    Refresh,
+    ToggleHidden,
+    CommandLine,
+    EditPath,
+    FindFile,
+    FindByName,
+    SearchContents,
+    ShowDetails,
+    VerifyChecksums,
+    AddBookmark,
+    ShowBookmarks,
+    JumpToFrecent,
+    JumpToZoxide,
+    GoBack,
+    GoForward,
+    NewTab,
+    CloseTab,
+    NextTab,
+    PreviousTab,
+    ToggleDualPane,
+    CopyToOtherPane,
+    MoveToOtherPane,
+    CompareDirectories,
+    MarkGroup,
+    UnmarkGroup,
+    MarkAll,
+    InvertMarks,
+    CompareMarked,
+    HidePreview,
+    MaximizePreview,
+    WidenDirectory,
+    NarrowDirectory,
+    ToggleTreeView,
+    ToggleSizes,
+    ToggleMillerColumns,
+    ShowDrives,
+    ShowDiskUsage,
+    MoveToTrash,
+    ChangePermissions,
+    ChangeOwner,
+    ToggleExecute,
+    OpenWith,
+    ShowTrash,
+    ShowErrors,
+    ToggleDebugOverlay,
+    ToggleMouseCapture,
+    CopyPath,
+    CopyName,
+    CopyDirectory,
+    GoToClipboardPath,
+}
+
+impl Action {
+    pub const ALL: &'static [Action] = &[
+        Action::Quit,
+        Action::Suspend,
+        Action::ToggleFocus,
+        Action::FocusDirectory,
+        Action::FocusPreview,
+        Action::OpenSelected,
+        Action::ParentDirectory,
+        Action::FirstEntry,
+        Action::LastEntry,
+        Action::Refresh,
+        Action::ToggleHidden,
+        Action::CommandLine,
+        Action::EditPath,
+        Action::FindFile,
+        Action::FindByName,
+        Action::SearchContents,
+        Action::ShowDetails,
+        Action::VerifyChecksums,
+        Action::AddBookmark,
+        Action::ShowBookmarks,
+        Action::JumpToFrecent,
+        Action::JumpToZoxide,
+        Action::GoBack,
+        Action::GoForward,
+        Action::NewTab,
+        Action::CloseTab,
+        Action::NextTab,
+        Action::PreviousTab,
+        Action::ToggleDualPane,
+        Action::CopyToOtherPane,
+        Action::MoveToOtherPane,
+        Action::CompareDirectories,
+        Action::MarkGroup,
+        Action::UnmarkGroup,
+        Action::MarkAll,
+        Action::InvertMarks,
+        Action::CompareMarked,
+        Action::HidePreview,
+        Action::MaximizePreview,
+        Action::WidenDirectory,
+        Action::NarrowDirectory,
+        Action::ToggleTreeView,
+        Action::ToggleSizes,
+        Action::ToggleMillerColumns,
+        Action::ShowDrives,
+        Action::ShowDiskUsage,
+        Action::MoveToTrash,
+        Action::ChangePermissions,
+        Action::ChangeOwner,
+        Action::ToggleExecute,
+        Action::OpenWith,
+        Action::ShowTrash,
+        Action::ShowErrors,
+        Action::ToggleDebugOverlay,
+        Action::ToggleMouseCapture,
+        Action::CopyPath,
+        Action::CopyName,
+        Action::CopyDirectory,
+        Action::GoToClipboardPath,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Suspend => "Suspend to the shell",
+            Action::ToggleFocus => "Switch pane",
+            Action::FocusDirectory => "Focus directory pane",
+            Action::FocusPreview => "Focus preview pane",
+            Action::OpenSelected => "Open selected entry",
+            Action::ParentDirectory => "Go to parent directory",
+            Action::FirstEntry => "Select first entry",
+            Action::LastEntry => "Select last entry",
+            Action::Refresh => "Refresh directory",
+            Action::ToggleHidden => "Show/hide hidden files",
+            Action::CommandLine => "Command line",
+            Action::EditPath => "Type a path to go to",
+            Action::FindFile => "Find file",
+            Action::FindByName => "Find files by name",
+            Action::SearchContents => "Search file contents",
+            Action::ShowDetails => "Show details of selected entry",
+            Action::VerifyChecksums => "Verify the checksums in the selected manifest",
+            Action::AddBookmark => "Bookmark current directory",
+            Action::ShowBookmarks => "Go to bookmark",
+            Action::JumpToFrecent => "Jump to frequently visited directory",
+            Action::JumpToZoxide => "Jump to zoxide directory",
+            Action::GoBack => "Go back to previous directory",
+            Action::GoForward => "Go forward to next directory",
+            Action::NewTab => "Open new tab",
+            Action::CloseTab => "Close tab",
+            Action::NextTab => "Next tab",
+            Action::PreviousTab => "Previous tab",
+            Action::ToggleDualPane => "Dual-pane mode on/off",
+            Action::CopyToOtherPane => "Copy selected or marked entries to other pane",
+            Action::MoveToOtherPane => "Move selected or marked entries to other pane",
+            Action::CompareDirectories => "Mark the entries that differ from the other pane's",
+            Action::MarkGroup => "Mark entries matching a pattern",
+            Action::UnmarkGroup => "Unmark entries matching a pattern",
+            Action::MarkAll => "Mark every entry",
+            Action::InvertMarks => "Invert the marks",
+            Action::CompareMarked => "Show how the two marked files differ",
+            Action::HidePreview => "Hide/show preview pane",
+            Action::MaximizePreview => "Maximize/restore preview pane",
+            Action::WidenDirectory => "Widen directory pane",
+            Action::NarrowDirectory => "Narrow directory pane",
+            Action::ToggleTreeView => "Tree view on/off",
+            Action::ToggleSizes => "Sizes, with directories added up, on/off",
+            Action::ToggleMillerColumns => "Miller columns on/off",
+            Action::ShowDrives => "Go to a drive or mount point",
+            Action::ShowDiskUsage => "Show what takes up space in this directory",
+            Action::MoveToTrash => "Move selected or marked entries to the trash",
+            Action::ChangePermissions => "Change the permissions of selected or marked entries",
+            Action::ChangeOwner => "Change the owner of selected or marked entries",
+            Action::ToggleExecute => "Make selected file executable or not",
+            Action::OpenWith => "Open selected file with a program",
+            Action::ShowTrash => "Show the trash, to restore or empty it",
+            Action::ShowErrors => "Show the errors that have happened",
+            Action::ToggleDebugOverlay => "Debug overlay on/off",
+            Action::ToggleMouseCapture => "Mouse on/off, to select text on the screen",
+            Action::CopyPath => "Copy selected entry's path to the clipboard",
+            Action::CopyName => "Copy selected entry's name to the clipboard",
+            Action::CopyDirectory => "Copy selected entry's directory to the clipboard",
+            Action::GoToClipboardPath => "Go to the path on the clipboard",
+        }
+    }
+
+    // The key that performs the action directly, if there is one
+    pub fn key(&self) -> Option<&'static str> {
+        match self {
+            Action::Quit => Some("Esc"),
+            Action::Suspend => Some("^Z"),
+            Action::ToggleFocus => Some("Tab"),
+            Action::OpenSelected => Some("Enter"),
+            Action::ParentDirectory => Some("Bksp"),
+            Action::FirstEntry => Some("Home"),
+            Action::LastEntry => Some("End"),
+            Action::CommandLine => Some(":"),
+            Action::EditPath => Some("^L"),
+            Action::FindFile => Some("^F"),
+            Action::ShowDetails => Some("M-i"),
+            Action::VerifyChecksums => Some("M-V"),
+            Action::AddBookmark => Some("M-b"),
+            Action::ShowBookmarks => Some("M-B"),
+            Action::JumpToFrecent => Some("M-z"),
+            Action::JumpToZoxide => Some("M-Z"),
+            Action::GoBack => Some("M-Left"),
+            Action::GoForward => Some("M-Right"),
+            Action::NewTab => Some("^T"),
+            Action::CloseTab => Some("^W"),
+            Action::NextTab => Some("^PgDn"),
+            Action::PreviousTab => Some("^PgUp"),
+            Action::ToggleDualPane => Some("^D"),
+            Action::CopyToOtherPane => Some("F5"),
+            Action::MoveToOtherPane => Some("F6"),
+            Action::CompareDirectories => Some("M-C"),
+            Action::MarkGroup => Some("="),
+            Action::UnmarkGroup => Some("-"),
+            Action::MarkAll => Some("^A"),
+            Action::InvertMarks => Some("*"),
+            Action::CompareMarked => Some("M-C"),
+            Action::HidePreview => Some("^O"),
+            Action::MaximizePreview => Some("F3"),
+            Action::WidenDirectory => Some("^Right"),
+            Action::NarrowDirectory => Some("^Left"),
+            Action::ToggleTreeView => Some("M-T"),
+            Action::ToggleSizes => Some("M-S"),
+            Action::ToggleMillerColumns => Some("M-M"),
+            Action::ShowDrives => Some("M-D"),
+            Action::ShowDiskUsage => Some("M-U"),
+            Action::MoveToTrash => Some("Del"),
+            Action::ChangePermissions => Some("M-A"),
+            Action::ChangeOwner => Some("M-O"),
+            Action::ToggleExecute => Some("^X"),
+            Action::OpenWith => Some("M-W"),
+            Action::ShowTrash => Some("M-X"),
+            Action::ShowErrors => Some("M-E"),
+            Action::ToggleDebugOverlay => Some("F12"),
+            Action::ToggleMouseCapture => Some("F9"),
+            Action::CopyPath => Some("M-y p"),
+            Action::CopyName => Some("M-y n"),
+            Action::CopyDirectory => Some("M-y d"),
+            Action::GoToClipboardPath => Some("M-P"),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,631 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use flate2::read::GzDecoder;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+
+use crate::app::fs_provider::{self, EntryMetadata, FsProvider, ListingEntry};
+use crate::util;
+
+// The kinds of archive whose contents can be listed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+    SevenZip,
+}
+
+impl ArchiveKind {
+    // Works out the kind of archive from the file's name
+    pub fn from_path(path: &Path) -> Option<ArchiveKind> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") || name.ends_with(".jar") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveKind::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else if name.ends_with(".7z") {
+            Some(ArchiveKind::SevenZip)
+        } else {
+            None
+        }
+    }
+}
+
+// Something in an archive. The compressed size is only known for some kinds of archive.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub compressed_size: Option<u64>,
+}
+
+// Lists the entries in an archive, in the order they're stored
+pub fn list_entries(path: &Path, kind: ArchiveKind) -> io::Result<Vec<ArchiveEntry>> {
+    match kind {
+        ArchiveKind::Zip => list_zip(path),
+        ArchiveKind::Tar => list_tar(BufReader::new(File::open(path)?)),
+        ArchiveKind::TarGz => list_tar(GzDecoder::new(BufReader::new(File::open(path)?))),
+        ArchiveKind::SevenZip => list_seven_zip(path),
+    }
+}
+
+fn list_zip(path: &Path) -> io::Result<Vec<ArchiveEntry>> {
+    let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
+    let mut entries = vec![];
+    for index in 0..archive.len() {
+        let file = archive.by_index_raw(index)?;
+        entries.push(ArchiveEntry {
+            name: file.name().to_string(),
+            is_dir: file.is_dir(),
+            size: file.size(),
+            compressed_size: Some(file.compressed_size()),
+        });
+    }
+    Ok(entries)
+}
+
+fn list_tar(reader: impl Read) -> io::Result<Vec<ArchiveEntry>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = vec![];
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let header = entry.header();
+        entries.push(ArchiveEntry {
+            name: entry.path()?.to_string_lossy().to_string(),
+            is_dir: header.entry_type().is_dir(),
+            size: header.size()?,
+            compressed_size: None,
+        });
+    }
+    Ok(entries)
+}
+
+// Opens an archive in a new directory under the temporary directory, and returns the directory.
+// Only the archive's directories are made there. Its files are read from the archive, and only
+// extracted when something needs one on disk.
+pub fn open_in_temp(path: &Path, kind: ArchiveKind) -> io::Result<PathBuf> {
+    // Numbered so archives that are open in either pane are kept apart
+    static OPENED_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let root = extraction_root().join(OPENED_COUNT.fetch_add(1, Ordering::Relaxed).to_string());
+    let archive_fs = ArchiveFs::new(path, kind, &root, list_entries(path, kind)?);
+    if root.exists() {
+        fs::remove_dir_all(&root)?;
+    }
+    let made = fs::create_dir_all(&root).and_then(|_| {
+        archive_fs
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.metadata.is_dir)
+            .try_for_each(|(directory, _)| fs::create_dir_all(directory))
+    });
+    if let Err(error) = made {
+        let _ = fs::remove_dir_all(&root);
+        return Err(error);
+    }
+    fs_provider::mount(root.clone(), Arc::new(archive_fs));
+    Ok(root)
+}
+
+// Removes everything that's been extracted
+pub fn remove_extracted() {
+    let _ = fs::remove_dir_all(extraction_root());
+}
+
+// Where archives are extracted to, which is different for each running instance
+fn extraction_root() -> PathBuf {
+    std::env::temp_dir().join(format!("rfb-{}", std::process::id()))
+}
+
+// Where an entry goes in the directory an archive is opened in. Entries whose paths would put them
+// outside it, like absolute ones and ones with "..", are skipped.
+fn enclosed_path(name: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(name) => path.push(name),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!path.as_os_str().is_empty()).then_some(path)
+}
+
+// Passes a reader of an entry's contents to read, looking the entry up by its name in the archive
+fn read_entry<T>(
+    path: &Path,
+    kind: ArchiveKind,
+    name: &str,
+    read: impl FnOnce(&mut dyn Read) -> io::Result<T>,
+) -> io::Result<T> {
+    match kind {
+        ArchiveKind::Zip => {
+            let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
+            let mut file = archive.by_name(name)?;
+            read(&mut file)
+        }
+        ArchiveKind::Tar => read_tar_entry(BufReader::new(File::open(path)?), name, read),
+        ArchiveKind::TarGz => read_tar_entry(
+            GzDecoder::new(BufReader::new(File::open(path)?)),
+            name,
+            read,
+        ),
+        ArchiveKind::SevenZip => {
+            let contents = run_seven_zip(&[
+                "e".as_ref(),
+                "-so".as_ref(),
+                "-spd".as_ref(),
+                "--".as_ref(),
+                path.as_os_str(),
+                name.as_ref(),
+            ])?;
+            read(&mut contents.as_slice())
+        }
+    }
+}
+
+// A tar file can only be read from the start, so it's read up to the entry
+fn read_tar_entry<T>(
+    reader: impl Read,
+    name: &str,
+    read: impl FnOnce(&mut dyn Read) -> io::Result<T>,
+) -> io::Result<T> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.to_string_lossy() == name {
+            return read(&mut entry);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{name} isn't in the archive"),
+    ))
+}
+
+// Extracts the entries with the names to the paths they're paired with. A tar file is only read
+// through once for all of them.
+fn extract_entries(
+    path: &Path,
+    kind: ArchiveKind,
+    mut destinations: HashMap<String, PathBuf>,
+) -> io::Result<()> {
+    let reader: Box<dyn Read> = match kind {
+        ArchiveKind::Tar => Box::new(BufReader::new(File::open(path)?)),
+        ArchiveKind::TarGz => Box::new(GzDecoder::new(BufReader::new(File::open(path)?))),
+        ArchiveKind::Zip | ArchiveKind::SevenZip => {
+            return destinations.iter().try_for_each(|(name, destination)| {
+                read_entry(path, kind, name, |reader| write_entry(reader, destination))
+            });
+        }
+    };
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        if destinations.is_empty() {
+            break;
+        }
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().to_string();
+        if let Some(destination) = destinations.remove(&name) {
+            write_entry(&mut entry, &destination)?;
+        }
+    }
+    Ok(())
+}
+
+// An entry is written somewhere else first, and moved where it goes once it's all there, so one
+// that's half-written is never taken for one that's been extracted
+fn write_entry(reader: &mut dyn Read, destination: &Path) -> io::Result<()> {
+    static PARTIAL_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let partial = extraction_root().join(format!(
+        "partial-{}",
+        PARTIAL_COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = File::create(&partial)
+        .and_then(|mut file| io::copy(reader, &mut file))
+        .and_then(|_| fs::rename(&partial, destination));
+    if result.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    result
+}
+
+// An entry that's been opened, with its name in the archive. Directories that are only there
+// because something's in them have no name.
+struct OpenEntry {
+    name: Option<String>,
+    metadata: EntryMetadata,
+}
+
+// An open archive, whose entries are shown under the directory it was opened in
+struct ArchiveFs {
+    archive: PathBuf,
+    kind: ArchiveKind,
+    root: PathBuf,
+    entries: HashMap<PathBuf, OpenEntry>,
+}
+
+impl ArchiveFs {
+    fn new(
+        archive: &Path,
+        kind: ArchiveKind,
+        root: &Path,
+        listing: Vec<ArchiveEntry>,
+    ) -> ArchiveFs {
+        let mut entries = HashMap::new();
+        for entry in listing {
+            let Some(relative) = enclosed_path(&entry.name) else {
+                continue;
+            };
+            let path = root.join(relative);
+            for directory in path.ancestors().skip(1) {
+                if directory == root {
+                    break;
+                }
+                entries
+                    .entry(directory.to_path_buf())
+                    .or_insert_with(|| OpenEntry {
+                        name: None,
+                        metadata: Self::directory_metadata(directory),
+                    });
+            }
+            let metadata = EntryMetadata {
+                is_dir: entry.is_dir,
+                is_file: !entry.is_dir,
+                is_hidden: util::is_hidden(&path, None),
+                len: entry.size,
+                ..EntryMetadata::default()
+            };
+            entries.insert(
+                path,
+                OpenEntry {
+                    name: Some(entry.name),
+                    metadata,
+                },
+            );
+        }
+        ArchiveFs {
+            archive: archive.to_path_buf(),
+            kind,
+            root: root.to_path_buf(),
+            entries,
+        }
+    }
+
+    fn directory_metadata(directory: &Path) -> EntryMetadata {
+        EntryMetadata {
+            is_dir: true,
+            is_hidden: util::is_hidden(directory, None),
+            ..EntryMetadata::default()
+        }
+    }
+
+    fn entry(&self, path: &Path) -> io::Result<&OpenEntry> {
+        self.entries.get(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} isn't in the archive", util::entry_name(path)),
+            )
+        })
+    }
+}
+
+impl FsProvider for ArchiveFs {
+    fn read_dir<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<Vec<ListingEntry>>> {
+        async move {
+            if path != self.root {
+                self.entry(path)?;
+            }
+            Ok(self
+                .entries
+                .iter()
+                .filter(|(entry, _)| entry.parent() == Some(path))
+                .map(|(entry, open_entry)| (entry.clone(), Some(open_entry.metadata)))
+                .collect())
+        }
+        .boxed()
+    }
+
+    fn metadata<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<EntryMetadata>> {
+        async move {
+            if path == self.root {
+                return Ok(Self::directory_metadata(path));
+            }
+            Ok(self.entry(path)?.metadata)
+        }
+        .boxed()
+    }
+
+    fn read<'a>(
+        &'a self,
+        path: &'a Path,
+        max_size: Option<u64>,
+    ) -> BoxFuture<'a, io::Result<Vec<u8>>> {
+        async move {
+            let Some(name) = self.entry(path)?.name.clone() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::IsADirectory,
+                    format!("{} is a directory", util::entry_name(path)),
+                ));
+            };
+            let (archive, kind) = (self.archive.clone(), self.kind);
+            tokio::task::spawn_blocking(move || {
+                read_entry(&archive, kind, &name, |reader| {
+                    let mut contents = vec![];
+                    match max_size {
+                        Some(max_size) => reader.take(max_size).read_to_end(&mut contents)?,
+                        None => reader.read_to_end(&mut contents)?,
+                    };
+                    Ok(contents)
+                })
+            })
+            .await?
+        }
+        .boxed()
+    }
+
+    // Extracts the file, or the files in the directory, that haven't been already
+    fn local_path<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<PathBuf>> {
+        async move {
+            if path != self.root {
+                self.entry(path)?;
+            }
+            let destinations: HashMap<String, PathBuf> = self
+                .entries
+                .iter()
+                .filter(|(entry, open_entry)| {
+                    open_entry.metadata.is_file
+                        && entry.starts_with(path)
+                        && entry.symlink_metadata().is_err()
+                })
+                .filter_map(|(entry, open_entry)| Some((open_entry.name.clone()?, entry.clone())))
+                .collect();
+            if !destinations.is_empty() {
+                let (archive, kind) = (self.archive.clone(), self.kind);
+                tokio::task::spawn_blocking(move || extract_entries(&archive, kind, destinations))
+                    .await??;
+            }
+            Ok(path.to_path_buf())
+        }
+        .boxed()
+    }
+}
+
+// Runs whichever 7-Zip program is installed, and returns what it printed
+fn run_seven_zip(args: &[&OsStr]) -> io::Result<Vec<u8>> {
+    let output = ["7z", "7zz", "7za"]
+        .iter()
+        .find_map(|program| Command::new(program).args(args).output().ok())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "7z archives need 7-Zip's 7z, 7zz or 7za program, and none of them is installed",
+            )
+        })?;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+    }
+    Ok(output.stdout)
+}
+
+// There's no 7z reader here, so 7-Zip itself lists the archive, in its technical format:
+// a block of "Key = Value" lines for each entry
+fn list_seven_zip(path: &Path) -> io::Result<Vec<ArchiveEntry>> {
+    let output = run_seven_zip(&[
+        "l".as_ref(),
+        "-slt".as_ref(),
+        "-ba".as_ref(),
+        path.as_os_str(),
+    ])?;
+    Ok(parse_seven_zip_listing(&String::from_utf8_lossy(&output)))
+}
+
+fn parse_seven_zip_listing(output: &str) -> Vec<ArchiveEntry> {
+    let mut entries = vec![];
+    let mut entry: Option<ArchiveEntry> = None;
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(" = ") else {
+            continue;
+        };
+        match key {
+            "Path" => {
+                entries.extend(entry.take());
+                entry = Some(ArchiveEntry {
+                    name: value.to_string(),
+                    is_dir: false,
+                    size: 0,
+                    compressed_size: None,
+                });
+            }
+            "Size" => {
+                if let Some(entry) = entry.as_mut() {
+                    entry.size = value.parse().unwrap_or(0);
+                }
+            }
+            "Packed Size" => {
+                if let Some(entry) = entry.as_mut() {
+                    entry.compressed_size = value.parse().ok();
+                }
+            }
+            "Folder" | "Attributes" => {
+                if let Some(entry) = entry.as_mut() {
+                    entry.is_dir |= value == "+" || value.starts_with('D');
+                }
+            }
+            _ => {}
+        }
+    }
+    entries.extend(entry);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // What "7z l -slt -ba" prints for a solid archive of a directory and two files. Only the
+    // first file in a solid block has a packed size.
+    const SEVEN_ZIP_LISTING: &str = "\
+Path = docs
+Size = 0
+Packed Size = 0
+Modified = 2024-05-01 10:00:00
+Attributes = D_ drwxr-xr-x
+CRC = 
+Encrypted = -
+Method = 
+Block = 
+
+Path = docs/readme.txt
+Size = 1234
+Packed Size = 567
+Modified = 2024-05-01 10:00:00
+Attributes = A_ -rw-r--r--
+CRC = 3610A686
+Encrypted = -
+Method = LZMA2:12
+Block = 0
+
+Path = docs/notes = draft.txt
+Size = 42
+Packed Size = 
+Modified = 2024-05-01 10:01:00
+Attributes = A_ -rw-r--r--
+CRC = 8587D865
+Encrypted = -
+Method = LZMA2:12
+Block = 0
+";
+
+    #[test]
+    fn parses_seven_zip_listing() {
+        assert_eq!(
+            parse_seven_zip_listing(SEVEN_ZIP_LISTING),
+            vec![
+                ArchiveEntry {
+                    name: "docs".to_string(),
+                    is_dir: true,
+                    size: 0,
+                    compressed_size: Some(0),
+                },
+                ArchiveEntry {
+                    name: "docs/readme.txt".to_string(),
+                    is_dir: false,
+                    size: 1234,
+                    compressed_size: Some(567),
+                },
+                ArchiveEntry {
+                    name: "docs/notes = draft.txt".to_string(),
+                    is_dir: false,
+                    size: 42,
+                    compressed_size: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_folder_lines() {
+        // Older versions of 7-Zip, and other kinds of archive, say whether it's a folder instead
+        let listing =
+            "Path = photos\nFolder = +\nSize = 0\n\nPath = photos/a.jpg\nFolder = -\nSize = 9\n";
+        let entries = parse_seven_zip_listing(listing);
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].is_dir);
+        assert!(!entries[1].is_dir);
+        assert_eq!(entries[1].size, 9);
+    }
+
+    #[test]
+    fn parses_nothing() {
+        assert!(parse_seven_zip_listing("").is_empty());
+    }
+
+    #[test]
+    fn keeps_entries_inside_the_archive() {
+        assert_eq!(
+            enclosed_path("./docs/notes.txt"),
+            Some(PathBuf::from("docs/notes.txt"))
+        );
+        assert_eq!(enclosed_path("../notes.txt"), None);
+        assert_eq!(enclosed_path("docs/../../notes.txt"), None);
+        assert_eq!(enclosed_path("/etc/passwd"), None);
+        assert_eq!(enclosed_path("./"), None);
+    }
+
+    #[tokio::test]
+    async fn extracts_only_what_is_asked_for() {
+        use std::io::Write;
+
+        let directory =
+            std::env::temp_dir().join(format!("rfb-archive-test-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let archive = directory.join("test.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&archive).unwrap());
+        let options =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        for (name, contents) in [
+            ("docs/notes.txt", "notes"),
+            ("docs/old/todo.txt", "todo"),
+            ("../escaped.txt", "escaped"),
+        ] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let root = open_in_temp(&archive, ArchiveKind::Zip).unwrap();
+        let provider = fs_provider::provider(&root);
+        let docs = root.join("docs");
+        let notes = docs.join("notes.txt");
+        let todo = docs.join("old/todo.txt");
+        // Only the directories are made when it's opened, and the entry that would be outside
+        // isn't there at all
+        assert!(docs.join("old").is_dir());
+        assert!(!notes.exists());
+        assert_eq!(provider.read_dir(&root).await.unwrap().len(), 1);
+        let mut listing: Vec<(String, bool)> = provider
+            .read_dir(&docs)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(entry, metadata)| (util::entry_name(&entry), metadata.unwrap().is_dir))
+            .collect();
+        listing.sort();
+        assert_eq!(
+            listing,
+            [("notes.txt".to_string(), false), ("old".to_string(), true)]
+        );
+
+        assert_eq!(provider.read(&notes, Some(3)).await.unwrap(), b"not");
+        assert!(!notes.exists());
+        assert_eq!(provider.local_path(&notes).await.unwrap(), notes);
+        assert_eq!(fs::read_to_string(&notes).unwrap(), "notes");
+        assert!(!todo.exists());
+        provider.local_path(&docs).await.unwrap();
+        assert_eq!(fs::read_to_string(&todo).unwrap(), "todo");
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&directory);
+    }
+}
@@ -0,0 +1,123 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::ffi::OsStr;
+use std::io;
+use std::path::Path;
+
+// The attributes Linux keeps POSIX ACLs in. They're shown decoded rather than as attributes.
+const ACL_ACCESS: &str = "system.posix_acl_access";
+const ACL_DEFAULT: &str = "system.posix_acl_default";
+
+// The most of an attribute's value that's shown
+const MAX_VALUE_LENGTH: usize = 64;
+
+// An entry's extended attributes, as names and displayable values, and its ACL entries, in the
+// form getfacl uses
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Attributes {
+    pub xattrs: Vec<(String, String)>,
+    pub acl: Vec<String>,
+}
+
+// Reads the entry's attributes, without following it if it's a link. Platforms without extended
+// attributes have none.
+pub fn read(path: &Path) -> io::Result<Attributes> {
+    let mut attributes = Attributes::default();
+    if !xattr::SUPPORTED_PLATFORM {
+        return Ok(attributes);
+    }
+    let mut names: Vec<_> = match xattr::list(path) {
+        Ok(names) => names.collect(),
+        // File systems without extended attributes don't have any to show
+        Err(error) if error.raw_os_error() == Some(libc::ENOTSUP) => return Ok(attributes),
+        Err(error) => return Err(error),
+    };
+    names.sort();
+    for name in names {
+        let Some(value) = xattr::get(path, &name)? else {
+            continue;
+        };
+        match name.to_str() {
+            Some(ACL_ACCESS) => attributes.acl.extend(acl_entries(&value, "")),
+            Some(ACL_DEFAULT) => attributes.acl.extend(acl_entries(&value, "default:")),
+            _ => attributes
+                .xattrs
+                .push((display_name(&name), display_value(&value))),
+        }
+    }
+    Ok(attributes)
+}
+
+fn display_name(name: &OsStr) -> String {
+    name.to_string_lossy().to_string()
+}
+
+// Text values are shown as text, without the terminating NUL some have, and anything else as hex
+fn display_value(value: &[u8]) -> String {
+    let text = value.strip_suffix(&[0]).unwrap_or(value);
+    let display = match std::str::from_utf8(text) {
+        Ok(text) if !text.chars().any(char::is_control) => text.to_string(),
+        _ => format!(
+            "0x{}",
+            value
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<String>()
+        ),
+    };
+    if display.chars().count() > MAX_VALUE_LENGTH {
+        format!(
+            "{}… ({} bytes)",
+            display.chars().take(MAX_VALUE_LENGTH).collect::<String>(),
+            value.len()
+        )
+    } else {
+        display
+    }
+}
+
+// Decodes a Linux POSIX ACL attribute: a 4-byte version, then 8-byte entries of a 2-byte tag,
+// 2-byte permissions and 4-byte user or group id, all little-endian
+fn acl_entries(value: &[u8], prefix: &str) -> Vec<String> {
+    if value.len() < 4 || u32::from_le_bytes([value[0], value[1], value[2], value[3]]) != 2 {
+        return vec![format!("{prefix}(unreadable)")];
+    }
+    value[4..]
+        .chunks_exact(8)
+        .map(|entry| {
+            let tag = u16::from_le_bytes([entry[0], entry[1]]);
+            let permissions = u16::from_le_bytes([entry[2], entry[3]]);
+            let id = u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]);
+            let qualifier = match tag {
+                0x01 => "user:".to_string(),
+                0x02 => format!("user:{}", user_name(id)),
+                0x04 => "group:".to_string(),
+                0x08 => format!("group:{}", group_name(id)),
+                0x10 => "mask:".to_string(),
+                0x20 => "other:".to_string(),
+                _ => format!("{tag:#x}:{id}"),
+            };
+            format!(
+                "{prefix}{qualifier}:{}{}{}",
+                if permissions & 0o4 != 0 { 'r' } else { '-' },
+                if permissions & 0o2 != 0 { 'w' } else { '-' },
+                if permissions & 0o1 != 0 { 'x' } else { '-' },
+            )
+        })
+        .collect()
+}
+
+pub fn user_name(uid: u32) -> String {
+    uzers::get_user_by_uid(uid)
+        .map(|user| user.name().to_string_lossy().to_string())
+        .unwrap_or_else(|| uid.to_string())
+}
+
+pub fn group_name(gid: u32) -> String {
+    uzers::get_group_by_gid(gid)
+        .map(|group| group.name().to_string_lossy().to_string())
+        .unwrap_or_else(|| gid.to_string())
+}
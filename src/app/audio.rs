@@ -0,0 +1,667 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+use id3::TagLike;
+
+// How much of the end of an Ogg file is searched for its last page
+const OGG_TAIL_SIZE: u64 = 65_536;
+
+// What's known about an audio file from its tags and headers
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AudioInfo {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration: Option<Duration>,
+    // In bits per second; an average for variable bitrate files
+    pub bitrate: Option<u64>,
+    pub has_artwork: bool,
+}
+
+// Whether the file's extension is one of the audio formats that can be read
+pub fn is_audio_file(path: &Path) -> bool {
+    matches!(
+        extension(path).as_deref(),
+        Some("mp3" | "flac" | "ogg" | "oga" | "opus" | "m4a")
+    )
+}
+
+pub fn read_info(path: &Path) -> io::Result<AudioInfo> {
+    let file_size = path.metadata()?.len();
+    let mut file = BufReader::new(File::open(path)?);
+    let mut info = match extension(path).as_deref() {
+        Some("mp3") => read_mp3(path, &mut file, file_size)?,
+        Some("flac") => read_flac(&mut file)?,
+        Some("ogg" | "oga" | "opus") => read_ogg(&mut file, file_size)?,
+        Some("m4a") => read_m4a(&mut file, file_size)?,
+        _ => return Err(invalid_data("Not an audio file")),
+    };
+    // Without a better figure, the average bitrate is the file's size over its length
+    if info.bitrate.is_none() {
+        info.bitrate = info
+            .duration
+            .filter(|duration| !duration.is_zero())
+            .map(|duration| (file_size as f64 * 8.0 / duration.as_secs_f64()) as u64);
+    }
+    Ok(info)
+}
+
+fn extension(path: &Path) -> Option<String> {
+    Some(path.extension()?.to_string_lossy().to_lowercase())
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// A length in seconds, unless it's too long, or not a number, which a corrupt file can make it
+fn duration_of(seconds: f64) -> Option<Duration> {
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+fn u32_be(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn u32_le(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn u64_be(bytes: &[u8]) -> u64 {
+    u64::from_be_bytes(bytes[..8].try_into().unwrap_or_default())
+}
+
+// MP3: the tags are ID3v2, and the length comes from the first frame: either its Xing or VBRI
+// header's frame count, or for constant bitrate files, the size of the audio and its bitrate
+fn read_mp3(path: &Path, file: &mut BufReader<File>, file_size: u64) -> io::Result<AudioInfo> {
+    let mut info = AudioInfo::default();
+    if let Ok(tag) = id3::Tag::read_from_path(path) {
+        info.title = tag.title().map(String::from);
+        info.artist = tag.artist().map(String::from);
+        info.album = tag.album().map(String::from);
+        // Pictures aren't decoded, so look for their frames
+        info.has_artwork = tag
+            .frames()
+            .any(|frame| frame.id() == "APIC" || frame.id() == "PIC");
+    }
+
+    // Skip the ID3v2 tag, whose size is stored 7 bits to a byte
+    let mut header = [0u8; 10];
+    file.read_exact(&mut header)?;
+    let mut audio_start = 0;
+    if &header[0..3] == b"ID3" {
+        let size = header[6..10]
+            .iter()
+            .fold(0u64, |size, byte| (size << 7) | (*byte & 0x7f) as u64);
+        let footer = if header[5] & 0x10 != 0 { 10 } else { 0 };
+        audio_start = 10 + size + footer;
+    }
+    let mut audio_end = file_size;
+    if file_size >= 128 {
+        // An ID3v1 tag is the last 128 bytes
+        let mut tag = [0u8; 3];
+        file.seek(SeekFrom::Start(file_size - 128))?;
+        file.read_exact(&mut tag)?;
+        if &tag == b"TAG" {
+            audio_end -= 128;
+        }
+    }
+
+    let mut buffer = vec![];
+    file.seek(SeekFrom::Start(audio_start))?;
+    file.by_ref().take(65_536).read_to_end(&mut buffer)?;
+    let Some((offset, frame)) = (0..buffer.len().saturating_sub(4))
+        .find_map(|offset| Some((offset, MpegFrame::parse(&buffer[offset..])?)))
+    else {
+        return Ok(info);
+    };
+    let audio_size = audio_end.saturating_sub(audio_start + offset as u64);
+    let frame_data = &buffer[offset..];
+    match frame.vbr_frame_count(frame_data) {
+        Some(frame_count) => {
+            let samples = frame_count as u64 * frame.samples_per_frame as u64;
+            info.duration = duration_of(samples as f64 / frame.sample_rate as f64);
+        }
+        None => {
+            info.bitrate = Some(frame.bitrate);
+            info.duration = duration_of(audio_size as f64 * 8.0 / frame.bitrate as f64);
+        }
+    }
+    Ok(info)
+}
+
+// The parts of an MPEG audio frame header that matter here
+struct MpegFrame {
+    is_mpeg1: bool,
+    is_mono: bool,
+    bitrate: u64,
+    sample_rate: u32,
+    samples_per_frame: u32,
+}
+
+impl MpegFrame {
+    fn parse(bytes: &[u8]) -> Option<MpegFrame> {
+        if bytes.len() < 4 || bytes[0] != 0xff || bytes[1] & 0xe0 != 0xe0 {
+            return None;
+        }
+        // 3 is MPEG 1, 2 is MPEG 2, 0 is MPEG 2.5; 3 is layer I, 2 is layer II, 1 is layer III
+        let version = (bytes[1] >> 3) & 3;
+        let layer = (bytes[1] >> 1) & 3;
+        let bitrate_index = (bytes[2] >> 4) as usize;
+        let sample_rate_index = ((bytes[2] >> 2) & 3) as usize;
+        if version == 1 || layer == 0 || bitrate_index == 0 || bitrate_index == 15 {
+            return None;
+        }
+        let is_mpeg1 = version == 3;
+        const MPEG1_LAYER1: [u64; 15] = [
+            0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+        ];
+        const MPEG1_LAYER2: [u64; 15] = [
+            0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+        ];
+        const MPEG1_LAYER3: [u64; 15] = [
+            0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+        ];
+        const MPEG2_LAYER1: [u64; 15] = [
+            0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+        ];
+        const MPEG2_LAYER2_3: [u64; 15] =
+            [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+        let bitrates = match (is_mpeg1, layer) {
+            (true, 3) => &MPEG1_LAYER1,
+            (true, 2) => &MPEG1_LAYER2,
+            (true, _) => &MPEG1_LAYER3,
+            (false, 3) => &MPEG2_LAYER1,
+            (false, _) => &MPEG2_LAYER2_3,
+        };
+        let sample_rate = *[44_100, 48_000, 32_000].get(sample_rate_index)?;
+        let sample_rate = match version {
+            3 => sample_rate,
+            2 => sample_rate / 2,
+            _ => sample_rate / 4,
+        };
+        let samples_per_frame = match layer {
+            3 => 384,
+            1 if !is_mpeg1 => 576,
+            _ => 1152,
+        };
+        Some(MpegFrame {
+            is_mpeg1,
+            is_mono: bytes[3] >> 6 == 3,
+            bitrate: bitrates[bitrate_index] * 1000,
+            sample_rate,
+            samples_per_frame,
+        })
+    }
+
+    // The number of frames in the file, if the first frame says; variable bitrate files do
+    fn vbr_frame_count(&self, frame: &[u8]) -> Option<u32> {
+        // A Xing (or Info) header comes after the side information
+        let side_info_size = match (self.is_mpeg1, self.is_mono) {
+            (true, false) => 32,
+            (true, true) | (false, false) => 17,
+            (false, true) => 9,
+        };
+        let xing = frame.get(4 + side_info_size..)?;
+        if xing.len() >= 12 && (&xing[0..4] == b"Xing" || &xing[0..4] == b"Info") {
+            // The frame count is there if the first flag is set
+            return (u32_be(&xing[4..8]) & 1 != 0).then(|| u32_be(&xing[8..12]));
+        }
+        // A VBRI header is always 32 bytes after the frame header
+        let vbri = frame.get(36..)?;
+        if vbri.len() >= 18 && &vbri[0..4] == b"VBRI" {
+            return Some(u32_be(&vbri[14..18]));
+        }
+        None
+    }
+}
+
+// FLAC: metadata blocks follow the marker. The stream info has the length, the tags are a
+// Vorbis comment, and artwork has a block of its own.
+fn read_flac(file: &mut impl Read) -> io::Result<AudioInfo> {
+    let mut marker = [0u8; 4];
+    file.read_exact(&mut marker)?;
+    if &marker != b"fLaC" {
+        return Err(invalid_data("Not a FLAC file"));
+    }
+    let mut info = AudioInfo::default();
+    loop {
+        let mut header = [0u8; 4];
+        file.read_exact(&mut header)?;
+        let is_last = header[0] & 0x80 != 0;
+        let block_type = header[0] & 0x7f;
+        let length = u32_be(&[0, header[1], header[2], header[3]]) as u64;
+        let mut block = vec![];
+        file.by_ref().take(length).read_to_end(&mut block)?;
+        match block_type {
+            0 if block.len() >= 18 => {
+                // 20 bits of sample rate, 3 of channels, 5 of bits per sample, 36 of samples
+                let sample_rate = ((block[10] as u32) << 12)
+                    | ((block[11] as u32) << 4)
+                    | (block[12] as u32 >> 4);
+                let samples = (((block[13] & 0x0f) as u64) << 32) | u32_be(&block[14..18]) as u64;
+                if sample_rate > 0 && samples > 0 {
+                    info.duration = duration_of(samples as f64 / sample_rate as f64);
+                }
+            }
+            4 => read_vorbis_comment(&block, &mut info),
+            6 => info.has_artwork = true,
+            _ => {}
+        }
+        if is_last {
+            break;
+        }
+    }
+    Ok(info)
+}
+
+// Ogg Vorbis and Opus: the first packet says what the stream is, the second has the tags, and the
+// last page's granule position is the number of samples
+fn read_ogg(file: &mut BufReader<File>, file_size: u64) -> io::Result<AudioInfo> {
+    let mut packets = OggPackets::new(file);
+    let identification = packets
+        .next()
+        .ok_or_else(|| invalid_data("Not an Ogg file"))??;
+    let comment = packets.next().unwrap_or_else(|| Ok(vec![]))?;
+
+    let mut info = AudioInfo::default();
+    let (sample_rate, pre_skip) =
+        if identification.starts_with(b"\x01vorbis") && identification.len() >= 24 {
+            let nominal_bitrate = u32_le(&identification[20..24]) as i32;
+            if nominal_bitrate > 0 {
+                info.bitrate = Some(nominal_bitrate as u64);
+            }
+            if comment.starts_with(b"\x03vorbis") {
+                read_vorbis_comment(&comment[7..], &mut info);
+            }
+            (u32_le(&identification[12..16]) as u64, 0)
+        } else if identification.starts_with(b"OpusHead") && identification.len() >= 12 {
+            if comment.starts_with(b"OpusTags") {
+                read_vorbis_comment(&comment[8..], &mut info);
+            }
+            // Opus granule positions are always counted at 48 kHz
+            let pre_skip = u16::from_le_bytes([identification[10], identification[11]]);
+            (48_000, pre_skip as u64)
+        } else {
+            return Err(invalid_data("Not a Vorbis or Opus stream"));
+        };
+
+    // The last page is somewhere near the end
+    let tail_start = file_size.saturating_sub(OGG_TAIL_SIZE);
+    let mut tail = vec![];
+    file.seek(SeekFrom::Start(tail_start))?;
+    file.read_to_end(&mut tail)?;
+    let granule_position = tail
+        .windows(4)
+        .rposition(|window| window == b"OggS")
+        .and_then(|index| tail.get(index + 6..index + 14))
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap_or_default()));
+    if let Some(granule_position) = granule_position {
+        if sample_rate > 0 && granule_position != u64::MAX {
+            let samples = granule_position.saturating_sub(pre_skip);
+            info.duration = duration_of(samples as f64 / sample_rate as f64);
+        }
+    }
+    Ok(info)
+}
+
+// Reassembles the packets of an Ogg stream from its pages
+struct OggPackets<'a, R: Read> {
+    reader: &'a mut R,
+    // Segment lengths from the current page that haven't been read yet
+    segments: Vec<u8>,
+}
+
+impl<'a, R: Read> OggPackets<'a, R> {
+    fn new(reader: &'a mut R) -> OggPackets<'a, R> {
+        OggPackets {
+            reader,
+            segments: vec![],
+        }
+    }
+
+    fn next_page(&mut self) -> io::Result<()> {
+        let mut header = [0u8; 27];
+        self.reader.read_exact(&mut header)?;
+        if &header[0..4] != b"OggS" {
+            return Err(invalid_data("Not an Ogg file"));
+        }
+        self.segments = vec![0; header[26] as usize];
+        self.reader.read_exact(&mut self.segments)?;
+        self.segments.reverse();
+        Ok(())
+    }
+
+    fn read_packet(&mut self) -> io::Result<Vec<u8>> {
+        let mut packet = vec![];
+        loop {
+            while self.segments.is_empty() {
+                self.next_page()?;
+            }
+            let length = self.segments.pop().unwrap_or_default();
+            let start = packet.len();
+            packet.resize(start + length as usize, 0);
+            self.reader.read_exact(&mut packet[start..])?;
+            // Only a full segment continues the packet
+            if length < 255 {
+                return Ok(packet);
+            }
+        }
+    }
+}
+
+impl<'a, R: Read> Iterator for OggPackets<'a, R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.read_packet())
+    }
+}
+
+// A Vorbis comment: a vendor string, then a count of "NAME=value" fields. The strings are
+// prefixed with their lengths.
+fn read_vorbis_comment(block: &[u8], info: &mut AudioInfo) {
+    let mut position = 0;
+    let Some(vendor_length) = next_u32_le(block, &mut position) else {
+        return;
+    };
+    position += vendor_length as usize;
+    let Some(count) = next_u32_le(block, &mut position) else {
+        return;
+    };
+    for _ in 0..count {
+        let Some(length) = next_u32_le(block, &mut position).map(|length| length as usize) else {
+            return;
+        };
+        let Some(field) = block.get(position..position + length) else {
+            return;
+        };
+        position += length;
+        let field = String::from_utf8_lossy(field);
+        let Some((name, value)) = field.split_once('=') else {
+            continue;
+        };
+        let value = Some(value.to_string());
+        match name.to_uppercase().as_str() {
+            "TITLE" => info.title = info.title.take().or(value),
+            "ARTIST" => info.artist = info.artist.take().or(value),
+            "ALBUM" => info.album = info.album.take().or(value),
+            "METADATA_BLOCK_PICTURE" => info.has_artwork = true,
+            _ => {}
+        }
+    }
+}
+
+// Reads a number, and moves past it
+fn next_u32_le(block: &[u8], position: &mut usize) -> Option<u32> {
+    let value = u32_le(block.get(*position..*position + 4)?);
+    *position += 4;
+    Some(value)
+}
+
+// M4A: the length is in the movie header, and the tags are in the item list of the movie's
+// metadata. Each tag is an atom holding a data atom.
+fn read_m4a(file: &mut BufReader<File>, file_size: u64) -> io::Result<AudioInfo> {
+    // Find the movie atom, which can be anywhere in the file
+    let mut position = 0;
+    let moov = loop {
+        if position + 8 > file_size {
+            return Err(invalid_data("No movie atom"));
+        }
+        let mut header = [0u8; 16];
+        file.seek(SeekFrom::Start(position))?;
+        file.read_exact(&mut header[..8])?;
+        let (mut size, mut header_size) = (u32_be(&header[0..4]) as u64, 8);
+        if size == 1 {
+            file.read_exact(&mut header[8..16])?;
+            size = u64_be(&header[8..16]);
+            header_size = 16;
+        } else if size == 0 {
+            size = file_size - position;
+        }
+        if size < header_size {
+            return Err(invalid_data("Not an M4A file"));
+        }
+        if &header[4..8] == b"moov" {
+            let mut moov = vec![];
+            file.by_ref()
+                .take(size - header_size)
+                .read_to_end(&mut moov)?;
+            break moov;
+        }
+        position = position
+            .checked_add(size)
+            .ok_or_else(|| invalid_data("No movie atom"))?;
+    };
+
+    let mut info = AudioInfo {
+        duration: find_atom(&moov, b"mvhd").and_then(movie_duration),
+        ..Default::default()
+    };
+    // The metadata atom has a version and flags before its children
+    let ilst = find_atom(&moov, b"udta")
+        .and_then(|udta| find_atom(udta, b"meta"))
+        .and_then(|meta| find_atom(meta.get(4..)?, b"ilst"));
+    if let Some(ilst) = ilst {
+        // The data atom has a type and a locale before the value
+        let text = |name: &[u8; 4]| {
+            let data = find_atom(find_atom(ilst, name)?, b"data")?;
+            Some(String::from_utf8_lossy(data.get(8..)?).to_string())
+        };
+        info.title = text(b"\xa9nam");
+        info.artist = text(b"\xa9ART");
+        info.album = text(b"\xa9alb");
+        info.has_artwork = find_atom(ilst, b"covr").is_some();
+    }
+    Ok(info)
+}
+
+// The length in a movie header. Version 1 has 64-bit times and duration, and a duration that's all
+// ones is unknown.
+fn movie_duration(mvhd: &[u8]) -> Option<Duration> {
+    let (timescale, duration) = match mvhd.first() {
+        Some(1) if mvhd.len() >= 32 => (u32_be(&mvhd[20..24]), u64_be(&mvhd[24..32])),
+        Some(0) if mvhd.len() >= 20 => match u32_be(&mvhd[16..20]) {
+            u32::MAX => return None,
+            duration => (u32_be(&mvhd[12..16]), duration as u64),
+        },
+        _ => return None,
+    };
+    if timescale == 0 || duration == u64::MAX {
+        return None;
+    }
+    duration_of(duration as f64 / timescale as f64)
+}
+
+// Finds the contents of an atom amongst the atoms in some data
+fn find_atom<'a>(mut data: &'a [u8], name: &[u8; 4]) -> Option<&'a [u8]> {
+    while data.len() >= 8 {
+        let size = (u32_be(&data[0..4]) as usize).clamp(8, data.len());
+        if &data[4..8] == name {
+            return Some(&data[8..size]);
+        }
+        data = &data[size..];
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // MPEG 1 layer III, 128 kbit/s, 44.1 kHz, joint stereo
+    const MP3_FRAME_HEADER: [u8; 4] = [0xff, 0xfb, 0x90, 0x40];
+
+    // The block's header: whether it's the last, its type, and its 24-bit length
+    fn flac_block(is_last: bool, block_type: u8, block: &[u8]) -> Vec<u8> {
+        let length = (block.len() as u32).to_be_bytes();
+        let mut bytes = vec![block_type | if is_last { 0x80 } else { 0 }];
+        bytes.extend_from_slice(&length[1..]);
+        bytes.extend_from_slice(block);
+        bytes
+    }
+
+    fn vorbis_comment(fields: &[&str]) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&6u32.to_le_bytes());
+        bytes.extend_from_slice(b"vendor");
+        bytes.extend_from_slice(&(fields.len() as u32).to_le_bytes());
+        for field in fields {
+            bytes.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(field.as_bytes());
+        }
+        bytes
+    }
+
+    fn atom(name: &[u8; 4], contents: &[u8]) -> Vec<u8> {
+        let mut bytes = ((contents.len() + 8) as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(name);
+        bytes.extend_from_slice(contents);
+        bytes
+    }
+
+    #[test]
+    fn parses_an_mpeg_frame_header() {
+        let frame = MpegFrame::parse(&MP3_FRAME_HEADER).unwrap();
+        assert!(frame.is_mpeg1);
+        assert!(!frame.is_mono);
+        assert_eq!(frame.bitrate, 128_000);
+        assert_eq!(frame.sample_rate, 44_100);
+        assert_eq!(frame.samples_per_frame, 1152);
+    }
+
+    #[test]
+    fn rejects_what_isnt_an_mpeg_frame_header() {
+        assert!(MpegFrame::parse(&[0xff, 0xfb, 0x90]).is_none());
+        assert!(MpegFrame::parse(&[0x49, 0x44, 0x33, 0x04]).is_none());
+        // A bad bitrate, and the reserved version
+        assert!(MpegFrame::parse(&[0xff, 0xfb, 0xf0, 0x40]).is_none());
+        assert!(MpegFrame::parse(&[0xff, 0xeb, 0x90, 0x40]).is_none());
+    }
+
+    #[test]
+    fn reads_the_frame_count_from_a_xing_header() {
+        let mut bytes = MP3_FRAME_HEADER.to_vec();
+        bytes.resize(4 + 32, 0);
+        bytes.extend_from_slice(b"Xing");
+        bytes.extend_from_slice(&1u32.to_be_bytes());
+        bytes.extend_from_slice(&1000u32.to_be_bytes());
+        let frame = MpegFrame::parse(&bytes).unwrap();
+        assert_eq!(frame.vbr_frame_count(&bytes), Some(1000));
+        assert_eq!(frame.vbr_frame_count(&MP3_FRAME_HEADER), None);
+    }
+
+    #[test]
+    fn reads_a_flac_files_length_and_tags() {
+        // 44.1 kHz, 2 channels, 16 bits, 441,000 samples
+        let mut stream_info = vec![0u8; 10];
+        stream_info.extend_from_slice(&[0x0a, 0xc4, 0x42, 0xf0]);
+        stream_info.extend_from_slice(&441_000u32.to_be_bytes());
+        stream_info.resize(34, 0);
+        let mut bytes = b"fLaC".to_vec();
+        bytes.extend(flac_block(false, 0, &stream_info));
+        bytes.extend(flac_block(
+            false,
+            4,
+            &vorbis_comment(&["TITLE=Song", "artist=Band"]),
+        ));
+        bytes.extend(flac_block(true, 6, &[0; 8]));
+
+        let info = read_flac(&mut bytes.as_slice()).unwrap();
+        assert_eq!(info.duration, Some(Duration::from_secs(10)));
+        assert_eq!(info.title.as_deref(), Some("Song"));
+        assert_eq!(info.artist.as_deref(), Some("Band"));
+        assert!(info.has_artwork);
+    }
+
+    #[test]
+    fn a_flac_file_has_to_start_with_its_marker() {
+        let error = read_flac(&mut b"ID3\x04".as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        // A block that's cut short is an error too, rather than a panic
+        let mut bytes = b"fLaC".to_vec();
+        bytes.extend_from_slice(&[0x00, 0x00, 0x00]);
+        assert!(read_flac(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn reads_a_vorbis_comment() {
+        let mut info = AudioInfo::default();
+        read_vorbis_comment(
+            &vorbis_comment(&["ALBUM=Record", "no equals sign", "METADATA_BLOCK_PICTURE=x"]),
+            &mut info,
+        );
+        assert_eq!(info.album.as_deref(), Some("Record"));
+        assert!(info.has_artwork);
+    }
+
+    #[test]
+    fn stops_at_the_end_of_a_truncated_vorbis_comment() {
+        let mut bytes = vorbis_comment(&["TITLE=Song"]);
+        bytes.truncate(bytes.len() - 2);
+        let mut info = AudioInfo::default();
+        read_vorbis_comment(&bytes, &mut info);
+        assert_eq!(info.title, None);
+        // A field count far bigger than the fields there are
+        let mut bytes = vorbis_comment(&[]);
+        let count = bytes.len() - 4;
+        bytes[count..].copy_from_slice(&u32::MAX.to_le_bytes());
+        read_vorbis_comment(&bytes, &mut info);
+    }
+
+    #[test]
+    fn finds_an_atom_amongst_others() {
+        let mut data = atom(b"free", &[0; 4]);
+        data.extend(atom(b"mvhd", &[1, 2, 3]));
+        assert_eq!(find_atom(&data, b"mvhd"), Some([1, 2, 3].as_slice()));
+        assert_eq!(find_atom(&data, b"udta"), None);
+    }
+
+    #[test]
+    fn finds_atoms_with_sizes_that_dont_fit() {
+        // A size too small to move past the header, and one bigger than what's left
+        let mut data = vec![0, 0, 0, 0];
+        data.extend_from_slice(b"free");
+        data.extend_from_slice(&[0, 0, 1, 0]);
+        data.extend_from_slice(b"mvhd");
+        data.extend_from_slice(&[7; 4]);
+        assert_eq!(find_atom(&data, b"mvhd"), Some([7; 4].as_slice()));
+    }
+
+    #[test]
+    fn reads_a_movie_headers_duration() {
+        // Version 0: flags, created, modified, timescale, duration
+        let mut mvhd = vec![0u8; 12];
+        mvhd.extend_from_slice(&1000u32.to_be_bytes());
+        mvhd.extend_from_slice(&90_000u32.to_be_bytes());
+        assert_eq!(movie_duration(&mvhd), Some(Duration::from_secs(90)));
+
+        mvhd[16..20].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(movie_duration(&mvhd), None);
+    }
+
+    #[test]
+    fn a_movie_header_with_an_unknown_or_huge_duration_has_none() {
+        // Version 1 has 64-bit times and duration
+        let mut mvhd = vec![1u8];
+        mvhd.resize(20, 0);
+        mvhd.extend_from_slice(&1u32.to_be_bytes());
+        mvhd.extend_from_slice(&u64::MAX.to_be_bytes());
+        assert_eq!(movie_duration(&mvhd), None);
+
+        mvhd[24..32].copy_from_slice(&(u64::MAX - 1).to_be_bytes());
+        assert_eq!(movie_duration(&mvhd), None);
+        // No timescale, or not enough of a header
+        assert_eq!(movie_duration(&mvhd[..20]), None);
+        mvhd[20..24].copy_from_slice(&0u32.to_be_bytes());
+        assert_eq!(movie_duration(&mvhd), None);
+    }
+}
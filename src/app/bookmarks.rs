@@ -0,0 +1,78 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::constants;
+
+const BOOKMARKS_FILE: &str = "bookmarks";
+
+// Directories the user has bookmarked, kept in a file in the config directory, one per line
+#[derive(Default)]
+pub struct Bookmarks {
+    paths: Vec<PathBuf>,
+}
+
+impl Bookmarks {
+    // Reads the bookmarks file. There are no bookmarks if it doesn't exist yet.
+    pub fn load() -> io::Result<Bookmarks> {
+        let contents = match std::fs::read_to_string(Self::file()?) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+        let paths = contents
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        Ok(Bookmarks { paths })
+    }
+
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    // Adds the directory and saves the bookmarks, unless it's already bookmarked
+    pub fn add(&mut self, path: &Path) -> io::Result<()> {
+        if !self.paths.iter().any(|bookmark| bookmark == path) {
+            self.paths.push(path.to_path_buf());
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, path: &Path) -> io::Result<()> {
+        self.paths.retain(|bookmark| bookmark != path);
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let file = Self::file()?;
+        if let Some(directory) = file.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        let mut contents = String::new();
+        for path in &self.paths {
+            contents.push_str(&path.to_string_lossy());
+            contents.push('\n');
+        }
+        std::fs::write(file, contents)
+    }
+
+    fn file() -> io::Result<PathBuf> {
+        dirs::config_dir()
+            .map(|config| {
+                config
+                    .join(constants::CONFIG_DIRECTORY)
+                    .join(BOOKMARKS_FILE)
+            })
+            .ok_or(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Can't find config directory",
+            ))
+    }
+}
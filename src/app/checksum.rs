@@ -0,0 +1,152 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use tokio_util::sync::CancellationToken;
+
+// How much of the file is read at a time, and how often progress is reported
+const CHUNK_SIZE: usize = 64 * 1024;
+const PROGRESS_INTERVAL: u64 = 4 * 1024 * 1024;
+
+// A file's checksums, as lowercase hex
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Checksums {
+    pub md5: String,
+    pub sha256: String,
+}
+
+// The extensions of checksum manifests, like the files sha256sum and md5sum write, and the names
+// they're often given instead
+const MANIFEST_EXTENSIONS: &[&str] = &["sha256", "sha256sum", "md5", "md5sum"];
+const MANIFEST_NAMES: &[&str] = &["SHA256SUMS", "MD5SUMS"];
+
+// A file listed in a checksum manifest, as it's named there and where it is, and the checksum it
+// should have
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub checksum: String,
+}
+
+pub fn is_manifest(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| MANIFEST_NAMES.iter().any(|manifest| name == *manifest))
+        || path.extension().is_some_and(|extension| {
+            let extension = extension.to_string_lossy().to_lowercase();
+            MANIFEST_EXTENSIONS.contains(&extension.as_str())
+        })
+}
+
+// The manifest beside a file that has the file's checksum, like foo.iso.sha256 for foo.iso
+pub fn sibling_manifest(path: &Path) -> Option<PathBuf> {
+    MANIFEST_EXTENSIONS.iter().find_map(|extension| {
+        let mut manifest = path.as_os_str().to_os_string();
+        manifest.push(format!(".{extension}"));
+        Some(PathBuf::from(manifest)).filter(|manifest| manifest.is_file())
+    })
+}
+
+// Reads the files and checksums listed in a manifest, in the format sha256sum and md5sum write,
+// "checksum  name" or "checksum *name", or the BSD format, "SHA256 (name) = checksum". Names are
+// relative to the manifest's directory. Lines that aren't either are skipped.
+pub fn read_manifest(path: &Path) -> io::Result<Vec<ManifestEntry>> {
+    let text = std::fs::read_to_string(path)?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+    Ok(text
+        .lines()
+        .filter_map(|line| {
+            let (name, checksum) = match line.split_once(" (") {
+                Some((_, rest)) if line.starts_with(['S', 'M']) => rest.rsplit_once(") = ")?,
+                _ => {
+                    let (checksum, rest) = line.split_once(' ')?;
+                    (rest.strip_prefix([' ', '*']).unwrap_or(rest), checksum)
+                }
+            };
+            if name.is_empty()
+                || checksum.is_empty()
+                || !checksum.chars().all(|c| c.is_ascii_hexdigit())
+            {
+                return None;
+            }
+            Some(ManifestEntry {
+                name: name.to_string(),
+                path: directory.join(name),
+                checksum: checksum.to_lowercase(),
+            })
+        })
+        .collect())
+}
+
+// Whether a file has the checksum, which can be an MD5 or a SHA-256 one. Returns None if it was
+// cancelled.
+pub fn verify(
+    path: &Path,
+    checksum: &str,
+    cancellation_token: &CancellationToken,
+) -> io::Result<Option<bool>> {
+    if checksum.len() != 32 && checksum.len() != 64 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "it isn't an MD5 or SHA-256 checksum",
+        ));
+    }
+    let Some(checksums) = compute(path, cancellation_token, |_| {})? else {
+        return Ok(None);
+    };
+    let actual = if checksum.len() == 32 {
+        checksums.md5
+    } else {
+        checksums.sha256
+    };
+    Ok(Some(actual == checksum))
+}
+
+// Reads the file once to compute all of its checksums, calling on_progress with the number of
+// bytes read so far every few megabytes. Returns None if it was cancelled.
+pub fn compute(
+    path: &Path,
+    cancellation_token: &CancellationToken,
+    mut on_progress: impl FnMut(u64),
+) -> io::Result<Option<Checksums>> {
+    let mut file = File::open(path)?;
+    let mut md5 = Md5::new();
+    let mut sha256 = Sha256::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut total = 0u64;
+    let mut next_progress = PROGRESS_INTERVAL;
+    loop {
+        if cancellation_token.is_cancelled() {
+            return Ok(None);
+        }
+        let count = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(count) => count,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+        md5.update(&buffer[..count]);
+        sha256.update(&buffer[..count]);
+        total += count as u64;
+        if total >= next_progress {
+            on_progress(total);
+            next_progress = total + PROGRESS_INTERVAL;
+        }
+    }
+    Ok(Some(Checksums {
+        md5: hex(&md5.finalize()),
+        sha256: hex(&sha256.finalize()),
+    }))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -0,0 +1,51 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io;
+use std::io::Write;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+// The system clipboard. Text is copied with the OSC 52 escape sequence, which has the terminal
+// put it on its clipboard, so it works over SSH. When the app isn't running over SSH, the text is
+// also put on the native clipboard, for terminals that don't understand OSC 52.
+#[derive(Default)]
+pub struct Clipboard {
+    // Kept open, because on X11 what's copied is only there as long as whoever copied it is
+    native: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    pub fn copy(&mut self, text: &str) -> io::Result<()> {
+        let mut stderr = io::stderr();
+        write!(stderr, "\x1b]52;c;{}\x07", BASE64.encode(text))?;
+        stderr.flush()?;
+        if !is_remote() {
+            if let Some(native) = self.native() {
+                // The terminal may still have got it
+                let _ = native.set_text(text);
+            }
+        }
+        Ok(())
+    }
+
+    // What's on the native clipboard, if it's text. The terminal's clipboard can't be read.
+    pub fn text(&mut self) -> Option<String> {
+        self.native()?.get_text().ok()
+    }
+
+    fn native(&mut self) -> Option<&mut arboard::Clipboard> {
+        if self.native.is_none() {
+            self.native = arboard::Clipboard::new().ok();
+        }
+        self.native.as_mut()
+    }
+}
+
+// Whether the app is running over SSH, where the native clipboard isn't the user's
+fn is_remote() -> bool {
+    std::env::var_os("SSH_CONNECTION").is_some() || std::env::var_os("SSH_TTY").is_some()
+}
@@ -0,0 +1,133 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::PathBuf;
+
+use crate::app::components::SortKey;
+use crate::util;
+
+// A command entered on the command line
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Cd(PathBuf),
+    Sort(SortKey),
+    Hidden(bool),
+    Mkdir(PathBuf),
+    // The mode, and whether to change everything in the directories too
+    Chmod(String, bool),
+    // The owner and group, and whether to change everything in the directories too
+    Chown(String, bool),
+    Find(String),
+    Grep(String),
+    GotoLine(usize),
+    Quit,
+}
+
+// The kind of argument a command takes, for tab completion
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArgumentKind {
+    None,
+    Path,
+    Text,
+    Choice(&'static [&'static str]),
+}
+
+pub const ON_OFF: &[&str] = &["on", "off"];
+
+pub const COMMANDS: &[(&str, ArgumentKind)] = &[
+    ("cd", ArgumentKind::Path),
+    ("chmod", ArgumentKind::Text),
+    ("chown", ArgumentKind::Text),
+    ("find", ArgumentKind::Text),
+    ("grep", ArgumentKind::Text),
+    ("hidden", ArgumentKind::Choice(ON_OFF)),
+    ("mkdir", ArgumentKind::Path),
+    ("q", ArgumentKind::None),
+    ("quit", ArgumentKind::None),
+    ("sort", ArgumentKind::Choice(SortKey::NAMES)),
+];
+
+impl Command {
+    pub fn parse(input: &str) -> Result<Command, String> {
+        let input = input.trim();
+        let (name, argument) = match input.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (input, ""),
+        };
+        match name {
+            "cd" => {
+                if argument.is_empty() {
+                    dirs::home_dir()
+                        .map(Command::Cd)
+                        .ok_or("Can't find home directory".to_string())
+                } else {
+                    Ok(Command::Cd(util::expand_tilde(argument)))
+                }
+            }
+            "sort" => SortKey::from_name(argument)
+                .map(Command::Sort)
+                .ok_or(format!("Usage: sort {}", SortKey::NAMES.join("|"))),
+            "hidden" => match argument {
+                "on" => Ok(Command::Hidden(true)),
+                "off" => Ok(Command::Hidden(false)),
+                _ => Err("Usage: hidden on|off".to_string()),
+            },
+            "mkdir" => {
+                if argument.is_empty() {
+                    Err("Usage: mkdir <directory>".to_string())
+                } else {
+                    Ok(Command::Mkdir(util::expand_tilde(argument)))
+                }
+            }
+            "chmod" => match recursive_argument(argument) {
+                (_, "") => Err("Usage: chmod [-R] <mode>".to_string()),
+                (is_recursive, mode) => Ok(Command::Chmod(mode.to_string(), is_recursive)),
+            },
+            "chown" => match recursive_argument(argument) {
+                (_, "") => Err("Usage: chown [-R] <owner>[:<group>]".to_string()),
+                (is_recursive, owner) => Ok(Command::Chown(owner.to_string(), is_recursive)),
+            },
+            "find" => {
+                if argument.is_empty() {
+                    Err("Usage: find <pattern>".to_string())
+                } else {
+                    Ok(Command::Find(argument.to_string()))
+                }
+            }
+            "grep" => {
+                if argument.is_empty() {
+                    Err("Usage: grep <regex>".to_string())
+                } else {
+                    Ok(Command::Grep(argument.to_string()))
+                }
+            }
+            "q" | "quit" => Ok(Command::Quit),
+            "" => Err("No command".to_string()),
+            // A number by itself goes to that line in the text preview, like vi
+            _ if name.chars().all(|c| c.is_ascii_digit()) => name
+                .parse()
+                .map(Command::GotoLine)
+                .map_err(|_| format!("Bad line number: {name}")),
+            _ => Err(format!("Unknown command: {name}")),
+        }
+    }
+
+    pub fn argument_kind(name: &str) -> Option<ArgumentKind> {
+        COMMANDS
+            .iter()
+            .find(|(command, _)| *command == name)
+            .map(|(_, kind)| *kind)
+    }
+}
+
+// Splits -R off the front of an argument: whether it was there, and the rest
+fn recursive_argument(argument: &str) -> (bool, &str) {
+    match argument.strip_prefix("-R") {
+        Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => {
+            (true, rest.trim())
+        }
+        _ => (false, argument),
+    }
+}
@@ -5,54 +5,369 @@
 
 use std::cmp::Ordering;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use crossterm::event::{KeyEvent, MouseEvent};
-use ratatui::layout::Rect;
-use ratatui::prelude::{Color, Modifier, Style};
-use ratatui::widgets::{Block, BorderType, Padding};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::prelude::{Color, Line, Modifier, Span, Style};
+use ratatui::widgets::{Block, BorderType, Padding, Paragraph};
 use ratatui::Frame;
-use tokio::fs;
+use tokio_util::sync::CancellationToken;
+use walkdir::WalkDir;
 
-pub(crate) mod directory;
+use key_hints::KeyHint;
+
+use crate::app::fs_error::{Context, FsError, Operation};
+use crate::app::fs_provider::{self, EntryMetadata, ListingEntry};
+use crate::app::styles;
+use crate::util;
+
+pub(crate) mod bookmark_list;
+pub(crate) mod checksum_report;
+pub(crate) mod command_line;
+pub(crate) mod confirm_popup;
+pub(crate) mod debug_overlay;
+/// The directory pane
+pub mod directory;
+pub(crate) mod disk_usage_view;
+pub(crate) mod error_list;
+pub(crate) mod finder;
 pub(crate) mod head;
-pub(crate) mod preview;
+pub(crate) mod info;
+pub(crate) mod jump_list;
+/// The hints at the bottom of the screen, saying what the keys do
+pub mod key_hints;
+pub(crate) mod mount_list;
+pub(crate) mod open_with_menu;
+pub(crate) mod palette;
+pub(crate) mod parent_list;
+/// The preview pane
+pub mod preview;
+pub(crate) mod results;
+pub(crate) mod status;
+pub(crate) mod trash_list;
+pub(crate) mod wheel;
 
-pub(crate) trait Component {
+/// A pane of the app, like the directory list or the preview, which takes the keys and mouse
+/// events when it has focus and draws itself into its area.
+// The futures aren't Send, since the app handles its events on one task
+#[allow(async_fn_in_trait)]
+pub trait Component {
+    /// Where the pane was last drawn, which mouse events are checked against
     fn set_area(&mut self, area: Rect);
+    /// Whether the pane takes the keys
     fn has_focus(&self) -> bool;
+    /// Gives the pane the keys, or takes them away
     fn set_focus(&mut self, focus: bool);
+    /// Whether the screen position is in the pane
     fn hit_test(&self, x: u16, y: u16) -> bool;
-    async fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> Result<(), std::io::Error>;
-    async fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<(), std::io::Error>;
-    fn render(&mut self, area: Rect, frame: &mut Frame<'_>) -> Result<(), std::io::Error>;
+    /// Handles a mouse event in the pane. An error is something that couldn't be done, like
+    /// going into a directory that can't be read.
+    async fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> Result<(), FsError>;
+    /// Handles a key while the pane has focus. Errors are like the mouse's.
+    async fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<(), FsError>;
+    /// Draws the pane in the area, which it takes as its own
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>) -> Result<(), FsError>;
+    /// The keys the pane takes, for the hint line at the bottom of the screen
+    fn key_hints(&self) -> Vec<KeyHint>;
 }
 
-async fn read_file(path: &Path) -> std::io::Result<Vec<String>> {
-    let contents = fs::read_to_string(path).await?;
+// Reads the lines of a text file, which has to be UTF-8
+async fn read_file(path: &Path) -> Result<Vec<String>, FsError> {
+    let bytes = fs_provider::provider(path)
+        .read(path, None)
+        .await
+        .context(Operation::Reading, path)?;
+    let contents = String::from_utf8(bytes).map_err(|_| FsError::Decode {
+        path: path.to_path_buf(),
+        message: "it isn't valid UTF-8".to_string(),
+    })?;
+    Ok(contents.lines().map(|f| f.to_string()).collect())
+}
+
+// Reads the lines in the first max_size bytes of a file. A line that's cut off isn't included.
+async fn read_file_head(path: &Path, max_size: u64) -> Result<Vec<String>, FsError> {
+    let mut bytes = fs_provider::provider(path)
+        .read(path, Some(max_size))
+        .await
+        .context(Operation::Reading, path)?;
+    if bytes.len() as u64 == max_size {
+        if let Some(last_newline) = bytes.iter().rposition(|byte| *byte == b'\n') {
+            bytes.truncate(last_newline);
+        }
+    }
+    let contents = String::from_utf8_lossy(&bytes);
     Ok(contents.lines().map(|f| f.to_string()).collect())
 }
 
-async fn read_directory(path: &Path) -> std::io::Result<Vec<PathBuf>> {
-    let mut paths: Vec<(bool, PathBuf)> = vec![];
-    let mut entries = fs::read_dir(&path).await?;
-    while let Some(dir_entry) = entries.next_entry().await? {
-        let entry = dir_entry.path();
-        paths.push((entry.is_dir(), entry));
+/// How directory listings are sorted. Directories always come first.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SortKey {
+    /// By name
+    #[default]
+    Name,
+    /// Largest first
+    Size,
+    /// Newest first
+    Modified,
+    /// By extension, then by name
+    Extension,
+}
+
+impl SortKey {
+    /// What the sort keys are called in the sort command
+    pub const NAMES: &'static [&'static str] = &["name", "size", "time", "ext"];
+
+    /// The sort key with the name, if there is one
+    pub fn from_name(name: &str) -> Option<SortKey> {
+        match name {
+            "name" => Some(SortKey::Name),
+            "size" => Some(SortKey::Size),
+            "time" => Some(SortKey::Modified),
+            "ext" => Some(SortKey::Extension),
+            _ => None,
+        }
+    }
+
+    /// What the sort key is called, which is one of [`NAMES`](SortKey::NAMES)
+    pub fn name(self) -> &'static str {
+        match self {
+            SortKey::Name => "name",
+            SortKey::Size => "size",
+            SortKey::Modified => "time",
+            SortKey::Extension => "ext",
+        }
+    }
+}
+
+/// How a directory is listed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ListingOptions {
+    /// How the entries are sorted
+    pub sort_key: SortKey,
+    /// Whether hidden entries are listed, which they are by default
+    pub show_hidden: bool,
+}
+
+impl Default for ListingOptions {
+    fn default() -> Self {
+        ListingOptions {
+            sort_key: SortKey::default(),
+            show_hidden: true,
+        }
     }
-    // Sort by name, directories first
-    paths.sort_unstable_by(|(lhs_is_dir, lhs_path), (rhs_is_dir, rhs_path)| {
-        if *lhs_is_dir && !*rhs_is_dir {
+}
+
+/// What a background read of a directory sends: the entries it's found so far, unsorted, and then
+/// all of them, sorted, with their metadata
+#[derive(Clone, Debug)]
+pub enum ListingBatch {
+    /// Entries found since the last batch
+    Entries(Vec<ListingEntry>),
+    /// All the entries, or why the directory couldn't be read
+    Finished(Result<Vec<ListingEntry>, String>),
+}
+
+// Reads a directory's entries, from whichever file system it's on, sorted
+async fn read_directory(
+    path: &Path,
+    options: &ListingOptions,
+) -> Result<Vec<ListingEntry>, FsError> {
+    let listing: Vec<ListingEntry> = fs_provider::provider(path)
+        .read_dir(path)
+        .await
+        .context(Operation::Listing, path)?
+        .into_iter()
+        .filter(|entry| options.show_hidden || !is_hidden(entry))
+        .collect();
+    Ok(sort_listing(listing, options.sort_key))
+}
+
+// Reads a directory that's been opened, passing the entries it finds to on_batch every so often,
+// and returns all of them, sorted, or None if it's cancelled
+pub(crate) async fn stream_directory(
+    mut entries: BoxStream<'static, std::io::Result<ListingEntry>>,
+    options: &ListingOptions,
+    cancellation_token: &CancellationToken,
+    mut on_batch: impl FnMut(Vec<ListingEntry>),
+) -> Option<std::io::Result<Vec<ListingEntry>>> {
+    const BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+    let mut listing: Vec<ListingEntry> = vec![];
+    let mut batch = vec![];
+    let mut batch_time = Instant::now();
+    loop {
+        let entry = tokio::select! {
+            _ = cancellation_token.cancelled() => return None,
+            entry = entries.next() => match entry {
+                Some(Ok(entry)) => entry,
+                Some(Err(error)) => return Some(Err(error)),
+                None => break,
+            },
+        };
+        if options.show_hidden || !is_hidden(&entry) {
+            batch.push(entry.clone());
+            listing.push(entry);
+        }
+        if batch_time.elapsed() >= BATCH_INTERVAL && !batch.is_empty() {
+            on_batch(std::mem::take(&mut batch));
+            batch_time = Instant::now();
+        }
+    }
+    Some(Ok(sort_listing(listing, options.sort_key)))
+}
+
+// An entry whose metadata can't be read can still be a dot file
+fn is_hidden((entry, metadata): &ListingEntry) -> bool {
+    metadata.map_or_else(
+        || util::is_hidden(entry, None),
+        |metadata| metadata.is_hidden,
+    )
+}
+
+fn sort_listing(mut listing: Vec<ListingEntry>, sort_key: SortKey) -> Vec<ListingEntry> {
+    // Sort by the sort key, directories first
+    let is_dir = |metadata: &Option<EntryMetadata>| metadata.is_some_and(|m| m.is_dir);
+    listing.sort_unstable_by(|(lhs_path, lhs_metadata), (rhs_path, rhs_metadata)| {
+        let (lhs_is_dir, rhs_is_dir) = (is_dir(lhs_metadata), is_dir(rhs_metadata));
+        if lhs_is_dir && !rhs_is_dir {
             Ordering::Less
-        } else if !*lhs_is_dir && *rhs_is_dir {
+        } else if !lhs_is_dir && rhs_is_dir {
             Ordering::Greater
         } else {
-            lhs_path.file_name().cmp(&rhs_path.file_name())
+            let ordering = match sort_key {
+                SortKey::Name => Ordering::Equal,
+                SortKey::Size => {
+                    let lhs_len = lhs_metadata.map(|m| m.len);
+                    let rhs_len = rhs_metadata.map(|m| m.len);
+                    rhs_len.cmp(&lhs_len)
+                }
+                SortKey::Modified => {
+                    let lhs_modified = lhs_metadata.and_then(|m| m.modified);
+                    let rhs_modified = rhs_metadata.and_then(|m| m.modified);
+                    rhs_modified.cmp(&lhs_modified)
+                }
+                SortKey::Extension => lhs_path.extension().cmp(&rhs_path.extension()),
+            };
+            ordering.then_with(|| lhs_path.file_name().cmp(&rhs_path.file_name()))
         }
     });
-    Ok(paths.iter().map(|(_, path)| path.clone()).collect())
+    listing
+}
+
+// Walks the directory tree under the root, passing the paths it finds to on_batch a batch at a time,
+// until it's finished or cancelled. This blocks, so it should be run with spawn_blocking.
+pub(crate) fn walk_directory(
+    root: &Path,
+    show_hidden: bool,
+    cancellation_token: &CancellationToken,
+    mut on_batch: impl FnMut(Vec<PathBuf>),
+) {
+    const BATCH_SIZE: usize = 1000;
+
+    if !fs_provider::is_local(root) {
+        walk_provider_directory(root, show_hidden, cancellation_token, on_batch);
+        return;
+    }
+    let mut batch = vec![];
+    let walker = WalkDir::new(root).min_depth(1).into_iter();
+    for entry in walker.filter_entry(|entry| show_hidden || !util::is_hidden(entry.path(), None)) {
+        if cancellation_token.is_cancelled() {
+            return;
+        }
+        // Entries that can't be read are skipped
+        if let Ok(entry) = entry {
+            batch.push(entry.into_path());
+            if batch.len() == BATCH_SIZE {
+                on_batch(std::mem::take(&mut batch));
+            }
+        }
+    }
+    if !batch.is_empty() {
+        on_batch(batch);
+    }
 }
 
-pub fn component_block<'a>(has_focus: bool) -> Block<'a> {
+// Walks a tree that isn't on this computer, reading it a directory at a time from its file system.
+// Each directory's entries are a batch. This blocks too.
+fn walk_provider_directory(
+    root: &Path,
+    show_hidden: bool,
+    cancellation_token: &CancellationToken,
+    mut on_batch: impl FnMut(Vec<PathBuf>),
+) {
+    let runtime = tokio::runtime::Handle::current();
+    let provider = fs_provider::provider(root);
+    let mut directories = vec![root.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        if cancellation_token.is_cancelled() {
+            return;
+        }
+        // Directories that can't be read are skipped
+        let Ok(mut entries) = runtime.block_on(provider.read_dir(&directory)) else {
+            continue;
+        };
+        entries.retain(|entry| show_hidden || !is_hidden(entry));
+        entries.sort_unstable_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        let mut batch = vec![];
+        for (entry, metadata) in entries {
+            if metadata.is_some_and(|metadata| metadata.is_dir) {
+                directories.push(entry.clone());
+            }
+            batch.push(entry);
+        }
+        if !batch.is_empty() {
+            on_batch(batch);
+        }
+    }
+}
+
+// Lines of names and values, with the names right-aligned to the longest one
+pub(crate) fn property_lines(properties: Vec<(&str, String)>) -> Vec<Line<'static>> {
+    let width = properties
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    properties
+        .into_iter()
+        .map(|(name, value)| {
+            Line::from(vec![
+                Span::styled(format!("{name:>width$}  "), styles::PROPERTY_NAME_STYLE),
+                Span::raw(value),
+            ])
+        })
+        .collect()
+}
+
+// What's shown in a pane while what goes in it is slow to load, animated by the ticks
+pub(crate) fn render_loading(
+    title: String,
+    tick: usize,
+    has_focus: bool,
+    area: Rect,
+    frame: &mut Frame,
+) {
+    const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+    let block = component_block(has_focus).title(title);
+    let inner_area = block.inner(area);
+    let text_area = Rect {
+        y: inner_area.y + inner_area.height / 2,
+        height: inner_area.height.min(1),
+        ..inner_area
+    };
+    frame.render_widget(block, area);
+    frame.render_widget(
+        Paragraph::new(format!("{} Loading…", SPINNER[tick % SPINNER.len()]))
+            .alignment(Alignment::Center),
+        text_area,
+    );
+}
+
+pub(crate) fn component_block<'a>(has_focus: bool) -> Block<'a> {
     if has_focus {
         focused_block()
     } else {
@@ -71,6 +386,5 @@ fn focused_block<'a>() -> Block<'a> {
 }
 
 fn default_block<'a>() -> Block<'a> {
-    Block::bordered()
-        .padding(Padding::horizontal(1))
+    Block::bordered().padding(Padding::horizontal(1))
 }
@@ -0,0 +1,122 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::widgets::{Clear, List, ListItem};
+use ratatui::{layout::Rect, Frame};
+
+use crate::app::{components, styles};
+use crate::stateful_list::StatefulList;
+use crate::util;
+
+use super::key_hints::KeyHint;
+
+pub enum BookmarkListEvent {
+    Cancelled,
+    Selected(PathBuf),
+    Removed(PathBuf),
+}
+
+// A popup list of the bookmarked directories
+#[derive(Default)]
+pub struct BookmarkList {
+    is_open: bool,
+    bookmarks: StatefulList<PathBuf>,
+}
+
+impl BookmarkList {
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn open(&mut self, bookmarks: &[PathBuf]) {
+        self.is_open = true;
+        self.bookmarks = StatefulList::with_items(bookmarks.to_vec());
+        self.bookmarks.first();
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<BookmarkListEvent> {
+        if util::is_up_key(key_event) {
+            self.bookmarks.previous();
+        } else if util::is_down_key(key_event) {
+            self.bookmarks.next();
+        } else {
+            match key_event.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.close();
+                    return Some(BookmarkListEvent::Cancelled);
+                }
+                KeyCode::Enter => {
+                    if let Some(selected) = self.selected_bookmark() {
+                        self.close();
+                        return Some(BookmarkListEvent::Selected(selected));
+                    }
+                }
+                KeyCode::Delete | KeyCode::Char('d') => {
+                    if let Some(selected) = self.selected_bookmark() {
+                        let index = self.bookmarks.selected().unwrap_or(0);
+                        let mut bookmarks: Vec<PathBuf> = self.bookmarks.iter().cloned().collect();
+                        bookmarks.remove(index);
+                        self.bookmarks = StatefulList::with_items(bookmarks);
+                        if !self.bookmarks.is_empty() {
+                            self.bookmarks
+                                .set_selected(Some(index.min(self.bookmarks.upper_bound())));
+                        }
+                        return Some(BookmarkListEvent::Removed(selected));
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // How tall the popup has to be to show every bookmark
+    pub fn height(&self) -> u16 {
+        self.bookmarks.len().max(1) as u16 + 2
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let block = components::component_block(true).title("[Bookmarks]");
+        let items: Vec<ListItem> = if self.bookmarks.is_empty() {
+            vec![ListItem::new(
+                "No bookmarks. Press Alt+B to bookmark a directory.",
+            )]
+        } else {
+            self.bookmarks
+                .iter()
+                .map(|bookmark| ListItem::new(bookmark.to_string_lossy().to_string()))
+                .collect()
+        };
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.bookmarks.state);
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Select"),
+            KeyHint::new("Enter", "Go"),
+            KeyHint::new("d", "Delete"),
+            KeyHint::new("Esc", "Close"),
+        ]
+    }
+
+    fn selected_bookmark(&self) -> Option<PathBuf> {
+        self.bookmarks
+            .selected()
+            .filter(|selected| *selected < self.bookmarks.len())
+            .map(|selected| self.bookmarks[selected].clone())
+    }
+}
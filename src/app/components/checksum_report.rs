@@ -0,0 +1,219 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::widgets::{Clear, List, ListItem};
+use ratatui::{layout::Rect, Frame};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+use crate::app::checksum::{self, ManifestEntry};
+use crate::app::fs_error::{Context, FsError, Operation};
+use crate::app::{components, styles, tasks};
+use crate::stateful_list::StatefulList;
+use crate::tui::Event;
+use crate::util;
+
+use super::key_hints::KeyHint;
+
+pub enum ChecksumReportEvent {
+    Cancelled,
+    Selected(PathBuf),
+}
+
+// A popup listing the files in a checksum manifest and whether each one has the checksum it
+// should, as they're verified in the background
+#[derive(Default)]
+pub struct ChecksumReport {
+    is_open: bool,
+    event_tx: Option<UnboundedSender<Event>>,
+
+    // The verification, and the manifest being verified
+    verify_id: u32,
+    cancellation_token: Option<CancellationToken>,
+    manifest: PathBuf,
+
+    // The files, and whether each one passed, or why it couldn't be verified, once it's been
+    files: StatefulList<ManifestEntry>,
+    results: Vec<Option<Result<bool, String>>>,
+}
+
+impl ChecksumReport {
+    pub fn set_event_tx(&mut self, event_tx: Option<UnboundedSender<Event>>) {
+        self.event_tx = event_tx;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    // Opens the popup and starts verifying the files in the manifest, or just the one file if
+    // it's given
+    pub fn open(&mut self, manifest: &Path, file: Option<&Path>) -> Result<(), FsError> {
+        let mut entries =
+            checksum::read_manifest(manifest).context(Operation::Reading, manifest)?;
+        if let Some(file) = file {
+            entries.retain(|entry| entry.path == file);
+        }
+        if entries.is_empty() {
+            let message = match file {
+                Some(file) => format!(
+                    "{} doesn't have a checksum for {}",
+                    manifest.display(),
+                    util::entry_name(file)
+                ),
+                None => format!("{} doesn't have any checksums", manifest.display()),
+            };
+            return Err(FsError::Other(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                message,
+            )));
+        }
+        self.cancel_verify();
+        self.is_open = true;
+        self.manifest = manifest.to_path_buf();
+        self.results = vec![None; entries.len()];
+        self.files = StatefulList::with_items(entries);
+        self.files.first();
+        self.start_verify();
+        Ok(())
+    }
+
+    pub fn close(&mut self) {
+        self.cancel_verify();
+        self.is_open = false;
+        self.files = StatefulList::default();
+        self.results.clear();
+    }
+
+    pub fn set_result(&mut self, verify_id: u32, index: usize, result: Result<bool, String>) {
+        if self.is_open && verify_id == self.verify_id && index < self.results.len() {
+            self.results[index] = Some(result);
+            if index + 1 == self.results.len() {
+                self.cancellation_token = None;
+            }
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<ChecksumReportEvent> {
+        if util::is_up_key(key_event) {
+            self.files.previous();
+        } else if util::is_down_key(key_event) {
+            self.files.next();
+        } else {
+            match key_event.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.close();
+                    return Some(ChecksumReportEvent::Cancelled);
+                }
+                KeyCode::Home => {
+                    self.files.first();
+                }
+                KeyCode::End => {
+                    self.files.last();
+                }
+                // Show the selected file in the directory pane
+                KeyCode::Char('o') => {
+                    if let Some(selected) = self
+                        .files
+                        .selected()
+                        .filter(|selected| *selected < self.files.len())
+                    {
+                        let path = self.files[selected].path.clone();
+                        self.close();
+                        return Some(ChecksumReportEvent::Selected(path));
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    pub fn height(&self) -> u16 {
+        self.files.len().max(1) as u16 + 2
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let (mut passed, mut failed, mut pending) = (0, 0, 0);
+        for result in &self.results {
+            match result {
+                Some(Ok(true)) => passed += 1,
+                Some(_) => failed += 1,
+                None => pending += 1,
+            }
+        }
+        let mut title = format!(
+            "[{}] {passed} passed, {failed} failed",
+            util::entry_name(&self.manifest)
+        );
+        if pending > 0 {
+            title.push_str(&format!(", {pending} to go…"));
+        }
+        let block = components::component_block(true).title(title);
+        let items: Vec<ListItem> =
+            self.files
+                .iter()
+                .zip(&self.results)
+                .map(|(entry, result)| match result {
+                    Some(Ok(true)) => ListItem::new(format!("✓ {}", entry.name))
+                        .style(styles::CHECKSUM_PASSED_STYLE),
+                    Some(Ok(false)) => ListItem::new(format!("✗ {} doesn't match", entry.name))
+                        .style(styles::CHECKSUM_FAILED_STYLE),
+                    Some(Err(error)) => ListItem::new(format!("✗ {}: {error}", entry.name))
+                        .style(styles::CHECKSUM_FAILED_STYLE),
+                    None => ListItem::new(format!("  {}", entry.name)),
+                })
+                .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.files.state);
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Select"),
+            KeyHint::new("o", "Show in directory"),
+            KeyHint::new("Esc", "Close"),
+        ]
+    }
+
+    // Verifies the files one after another, sending each one's result as it's found
+    fn start_verify(&mut self) {
+        let cancellation_token = CancellationToken::new();
+        self.cancellation_token = Some(cancellation_token.clone());
+        self.verify_id = self.verify_id.wrapping_add(1);
+
+        let verify_id = self.verify_id;
+        let entries: Vec<ManifestEntry> = self.files.iter().cloned().collect();
+        let event_tx = self.event_tx.clone().unwrap();
+        debug!(verify_id, manifest = %self.manifest.display(), "verifying checksums");
+        let task = tasks::start();
+        tokio::task::spawn_blocking(move || {
+            let _task = task;
+            for (index, entry) in entries.iter().enumerate() {
+                let result =
+                    match checksum::verify(&entry.path, &entry.checksum, &cancellation_token) {
+                        Ok(Some(is_match)) => Ok(is_match),
+                        Ok(None) => return, // Cancelled
+                        Err(error) => Err(error.to_string()),
+                    };
+                // The receiver is only gone if the app is shutting down
+                let _ = event_tx.send(Event::ChecksumVerified(verify_id, index, result));
+            }
+        });
+    }
+
+    fn cancel_verify(&mut self) {
+        if let Some(cancellation_token) = self.cancellation_token.take() {
+            cancellation_token.cancel();
+        }
+    }
+}
@@ -0,0 +1,196 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::MAIN_SEPARATOR;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::widgets::Paragraph;
+use ratatui::{layout::Rect, Frame};
+
+use crate::app::command::{ArgumentKind, Command, COMMANDS};
+use crate::util;
+
+use super::key_hints::KeyHint;
+
+pub enum CommandLineEvent {
+    Cancelled,
+    Submitted(String),
+}
+
+#[derive(Default)]
+pub struct CommandLine {
+    is_open: bool,
+    input: String,
+    completer: Completer,
+}
+
+// Tab completion: the first Tab completes the input to the longest prefix its candidates share, or
+// if it's already that long, each Tab shows the next candidate
+#[derive(Default)]
+pub struct Completer {
+    // Candidates for the input being completed, and which one is showing
+    completions: Vec<String>,
+    completion_index: Option<usize>,
+}
+
+impl Completer {
+    pub fn reset(&mut self) {
+        self.completions.clear();
+        self.completion_index = None;
+    }
+
+    pub fn complete(
+        &mut self,
+        input: &mut String,
+        find_completions: impl FnOnce(&str) -> Vec<String>,
+    ) {
+        if self.completions.is_empty() {
+            self.completions = find_completions(input);
+            let common_prefix = util::common_prefix(&self.completions);
+            if common_prefix.len() > input.len() {
+                *input = common_prefix;
+                self.reset();
+                return;
+            }
+        }
+        if !self.completions.is_empty() {
+            let index = match self.completion_index {
+                Some(index) => (index + 1) % self.completions.len(),
+                None => 0,
+            };
+            *input = self.completions[index].clone();
+            self.completion_index = Some(index);
+        }
+    }
+}
+
+impl CommandLine {
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn open(&mut self) {
+        self.is_open = true;
+        self.input.clear();
+        self.completer.reset();
+    }
+
+    // Opens the command line with some input already typed
+    pub fn open_with(&mut self, input: &str) {
+        self.open();
+        self.input.push_str(input);
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<CommandLineEvent> {
+        if key_event.code != KeyCode::Tab {
+            self.completer.reset();
+        }
+        match key_event.code {
+            KeyCode::Esc => {
+                self.close();
+                return Some(CommandLineEvent::Cancelled);
+            }
+            KeyCode::Enter => {
+                self.close();
+                return Some(CommandLineEvent::Submitted(self.input.clone()));
+            }
+            KeyCode::Tab => self
+                .completer
+                .complete(&mut self.input, Self::find_completions),
+            KeyCode::Backspace => {
+                // Backspace on an empty command line closes it, like vi
+                if self.input.pop().is_none() {
+                    self.close();
+                    return Some(CommandLineEvent::Cancelled);
+                }
+            }
+            KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input.push(c);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    // Adds pasted text to the command being typed
+    pub fn paste(&mut self, text: &str) {
+        self.completer.reset();
+        self.input.push_str(text);
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let text = format!(":{}", self.input);
+        let text = util::clip_string(&text, area.width as usize);
+        let cursor_x =
+            area.x + (util::display_width(&text) as u16).min(area.width.saturating_sub(1));
+        frame.render_widget(Paragraph::new(text), area);
+        frame.set_cursor(cursor_x, area.y);
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("Tab", "Complete"),
+            KeyHint::new("Enter", "Run"),
+            KeyHint::new("Esc", "Cancel"),
+        ]
+    }
+
+    fn find_completions(input: &str) -> Vec<String> {
+        match input.split_once(' ') {
+            None => COMMANDS
+                .iter()
+                .filter(|(name, _)| name.starts_with(input))
+                .map(|(name, kind)| match kind {
+                    ArgumentKind::None => name.to_string(),
+                    _ => format!("{name} "),
+                })
+                .collect(),
+            Some((name, argument)) => match Command::argument_kind(name) {
+                Some(ArgumentKind::Path) => path_completions(argument)
+                    .into_iter()
+                    .map(|path| format!("{name} {path}"))
+                    .collect(),
+                Some(ArgumentKind::Choice(choices)) => choices
+                    .iter()
+                    .filter(|choice| choice.starts_with(argument))
+                    .map(|choice| format!("{name} {choice}"))
+                    .collect(),
+                _ => vec![],
+            },
+        }
+    }
+}
+
+// Returns the subdirectories that complete the last component of a partial path
+pub fn path_completions(partial: &str) -> Vec<String> {
+    let (directory, prefix) = match partial.rfind(['/', MAIN_SEPARATOR]) {
+        Some(index) => partial.split_at(index + 1),
+        None => ("", partial),
+    };
+    let read_path = if directory.is_empty() {
+        ".".into()
+    } else {
+        util::expand_tilde(directory)
+    };
+    let Ok(entries) = std::fs::read_dir(read_path) else {
+        return vec![];
+    };
+    let mut completions: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(|name| name.to_string()))
+        // Hidden directories are only offered if they're asked for
+        .filter(|name| {
+            name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.'))
+        })
+        .map(|name| format!("{directory}{name}{MAIN_SEPARATOR}"))
+        .collect();
+    completions.sort();
+    completions
+}
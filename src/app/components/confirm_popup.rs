@@ -0,0 +1,77 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::text::Text;
+use ratatui::widgets::{Clear, Padding, Paragraph};
+use ratatui::{layout::Rect, Frame};
+
+use crate::app::components;
+use crate::util;
+
+use super::key_hints::KeyHint;
+
+pub enum ConfirmEvent<T> {
+    Cancelled,
+    Confirmed(T),
+}
+
+// A popup asking whether to go ahead with something that can't be undone, and what to do if it's
+// answered yes
+pub struct ConfirmPopup<T> {
+    question: String,
+    action: Option<T>,
+}
+
+impl<T> Default for ConfirmPopup<T> {
+    fn default() -> Self {
+        ConfirmPopup {
+            question: String::new(),
+            action: None,
+        }
+    }
+}
+
+impl<T> ConfirmPopup<T> {
+    pub fn is_open(&self) -> bool {
+        self.action.is_some()
+    }
+
+    pub fn open(&mut self, question: String, action: T) {
+        self.question = question;
+        self.action = Some(action);
+    }
+
+    // Y or Enter goes ahead, and any other key doesn't
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<ConfirmEvent<T>> {
+        let action = self.action.take()?;
+        match key_event.code {
+            KeyCode::Char('y' | 'Y') | KeyCode::Enter => Some(ConfirmEvent::Confirmed(action)),
+            _ => Some(ConfirmEvent::Cancelled),
+        }
+    }
+
+    // How wide the popup has to be to show the question
+    pub fn width(&self) -> u16 {
+        util::display_width(&self.question_text()) as u16 + 4
+    }
+
+    pub fn render(&self, area: Rect, frame: &mut Frame) {
+        let block = components::component_block(true)
+            .title("[Confirm]")
+            .padding(Padding::horizontal(1));
+        let paragraph = Paragraph::new(Text::from(self.question_text())).block(block);
+        frame.render_widget(Clear, area); // This clears the background underneath the popup
+        frame.render_widget(paragraph, area);
+    }
+
+    fn question_text(&self) -> String {
+        format!("{} (y/n)", self.question)
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![KeyHint::new("y", "Yes"), KeyHint::new("n", "No")]
+    }
+}
@@ -0,0 +1,109 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Clear, Paragraph};
+use ratatui::{layout::Rect, Frame};
+
+use crate::app::{components, styles, tasks};
+use crate::util;
+
+// How many frames the frame time is averaged over
+const FRAME_SAMPLES: usize = 30;
+
+const WIDTH: u16 = 34;
+const HEIGHT: u16 = 6;
+
+// A panel in the top right corner with numbers that help show why the app is slow: how long
+// frames take to draw, how many events it's handling, how many background tasks haven't finished
+// and how much the cached previews take up. It doesn't take keys, so the app can be used with it
+// open.
+#[derive(Default)]
+pub struct DebugOverlay {
+    is_open: bool,
+    frame_times: VecDeque<Duration>,
+    // Events are counted for a second at a time, and the last full second's count is shown
+    event_count: u32,
+    events_per_second: u32,
+    counting_since: Option<Instant>,
+}
+
+impl DebugOverlay {
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn toggle(&mut self) {
+        self.is_open = !self.is_open;
+    }
+
+    pub fn record_frame_time(&mut self, frame_time: Duration) {
+        if self.frame_times.len() >= FRAME_SAMPLES {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    pub fn count_event(&mut self) {
+        let now = Instant::now();
+        let counting_since = *self.counting_since.get_or_insert(now);
+        let elapsed = now.duration_since(counting_since);
+        if elapsed >= Duration::from_secs(1) {
+            self.events_per_second = (self.event_count as f64 / elapsed.as_secs_f64()) as u32;
+            self.event_count = 0;
+            self.counting_since = Some(now);
+        }
+        self.event_count += 1;
+    }
+
+    // Draws the panel in the area's top right corner. The previews' cache usage is how many
+    // previews are cached and roughly how many bytes they take up.
+    pub fn render(&self, area: Rect, frame: &mut Frame, preview_cache_usage: (usize, usize)) {
+        let width = WIDTH.min(area.width);
+        let area = Rect::new(area.right() - width, area.y, width, HEIGHT.min(area.height));
+
+        let average = self
+            .frame_times
+            .iter()
+            .sum::<Duration>()
+            .checked_div(self.frame_times.len() as u32)
+            .unwrap_or_default();
+        let slowest = self.frame_times.iter().max().copied().unwrap_or_default();
+        let (preview_count, preview_size) = preview_cache_usage;
+        let lines = vec![
+            Self::line(
+                "Frame",
+                format!(
+                    "{:.1} ms (max {:.1})",
+                    average.as_secs_f64() * 1000.0,
+                    slowest.as_secs_f64() * 1000.0
+                ),
+            ),
+            Self::line("Events", format!("{}/s", self.events_per_second)),
+            Self::line("Tasks", tasks::pending().to_string()),
+            Self::line(
+                "Previews",
+                format!(
+                    "{} cached, {}",
+                    preview_count,
+                    util::size_string(preview_size as u64)
+                ),
+            ),
+        ];
+        let block = components::component_block(false).title("[Debug]");
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn line(name: &str, value: String) -> Line<'static> {
+        Line::from(vec![
+            Span::styled(format!("{:<10}", name), styles::PROPERTY_NAME_STYLE),
+            Span::raw(value),
+        ])
+    }
+}
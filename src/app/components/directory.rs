@@ -3,7 +3,10 @@
  * Created 2024-03-17
  */
 
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Instant;
 
 use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use crossterm::{
@@ -11,21 +14,117 @@ use crossterm::{
     event::{KeyCode, KeyEvent},
 };
 use ratatui::layout::Position;
-use ratatui::{layout::Rect, widgets::List, Frame};
-use tokio::sync::mpsc::UnboundedSender;
+use ratatui::{
+    layout::Rect,
+    widgets::{List, ListItem},
+    Frame,
+};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
 
-use crate::app::{components, styles};
+use crate::app::archive;
+use crate::app::archive::ArchiveKind;
+use crate::app::components::{ListingBatch, ListingOptions};
+use crate::app::file_mask::FileMask;
+use crate::app::fs_error::{Context, FsError, Operation};
+use crate::app::fs_provider::{self, EntryMetadata, ListingEntry, LocalFs};
+use crate::app::view_memory::{SharedViewMemory, View};
+use crate::app::{components, styles, tasks};
 use crate::tui::Event;
 use crate::{constants, stateful_list::StatefulList, util};
 
+use super::key_hints::KeyHint;
+use super::wheel::{WheelDirection, WheelScroll};
 use super::Component;
 
+// Reads are numbered across every tab's directory pane, so one pane can't take another's batches
+static NEXT_READ_ID: AtomicU32 = AtomicU32::new(0);
+
+// Likewise the computations of the marked entries' sizes, and of the listed directories' sizes
+static NEXT_SIZE_ID: AtomicU32 = AtomicU32::new(0);
+
+/// The directory pane, which lists the current directory's entries, or their tree, and keeps the
+/// selection, the marks, the filter and how the entries are sorted
 #[derive(Default)]
 pub struct Directory {
     items: StatefulList<PathBuf>,
     has_focus: bool,
     area: Rect,
     event_tx: Option<UnboundedSender<Event>>,
+
+    // Count typed before a movement key, e.g. the 5 in 5j
+    pending_count: Option<usize>,
+
+    // How the directory is listed, how directories whose view hasn't been changed are, and the
+    // views of the ones that have
+    listing_options: ListingOptions,
+    default_listing_options: ListingOptions,
+    view_memory: SharedViewMemory,
+
+    // The directory that was loaded, all of its entries before they're filtered, and what was
+    // listed about them and the entries of the expanded directories
+    loaded_directory: Option<PathBuf>,
+    entries: Vec<PathBuf>,
+    metadata: util::KnownMetadata,
+
+    // The read of a big directory's entries that's finishing in the background, and the entry to
+    // select when it's found
+    read_id: u32,
+    reading: Option<CancellationToken>,
+    pending_selection: Option<PathBuf>,
+
+    // Text the entries are filtered by, and whether it's still being typed
+    filter: Option<String>,
+    is_filter_input: bool,
+
+    // The mask the files are restricted to, in every directory until it's cleared, and the mask
+    // being typed, if it is
+    mask: Option<FileMask>,
+    mask_input: Option<String>,
+
+    // The entries that are marked, to copy, move or trash together, and while a pattern to mark
+    // or unmark entries by is being typed, whether it marks them, and the pattern
+    marked: HashSet<PathBuf>,
+    group_input: Option<(bool, String)>,
+
+    // The marked entries' total size, once it's been added up, and the computation adding it up
+    // in the background, if it hasn't finished
+    marked_size: Option<u64>,
+    marked_size_id: u32,
+    sizing: Option<CancellationToken>,
+
+    // Whether each entry's size is shown after its name, the sizes of everything in the listed
+    // directories that have been added up, and the computation adding up the rest in the
+    // background, if it hasn't finished
+    show_sizes: bool,
+    directory_sizes: HashMap<PathBuf, u64>,
+    directory_sizes_id: u32,
+    sizing_directories: Option<CancellationToken>,
+
+    // Characters typed in quick succession to select an entry, and when the last one was typed
+    type_ahead: String,
+    type_ahead_time: Option<Instant>,
+
+    // The archives that have been opened, as the directories they were opened in and the archive
+    // files themselves
+    archive_mounts: Vec<(PathBuf, PathBuf)>,
+
+    // The other computers and S3 buckets that have been mounted, as the directories they were
+    // mounted on and the sftp:// or s3:// URLs of their roots
+    remote_mounts: Vec<(PathBuf, PathBuf)>,
+
+    // Whether the entries are shown as a tree, and the directories in it that have been expanded,
+    // with their entries
+    is_tree_view: bool,
+    expanded: HashMap<PathBuf, Vec<PathBuf>>,
+
+    // How far the mouse wheel moves the selection
+    wheel_scroll: WheelScroll,
+
+    // The entry that was selected in each directory when it was left, which is selected again
+    // when it's come back to
+    cursors: HashMap<PathBuf, PathBuf>,
 }
 
 impl Component for Directory {
@@ -45,7 +144,7 @@ impl Component for Directory {
         self.area.contains(Position { x, y })
     }
 
-    async fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> Result<(), std::io::Error> {
+    async fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> Result<(), FsError> {
         match mouse_event.kind {
             MouseEventKind::Down(mouse_button) => {
                 // A left click on the selected item is converted into an Enter key event.
@@ -57,6 +156,7 @@ impl Component for Directory {
                             self.handle_key_event(key_event).await?;
                         } else {
                             self.set_selected(index);
+                            self.pending_selection = None;
                             self.event_tx
                                 .as_ref()
                                 .unwrap()
@@ -66,20 +166,36 @@ impl Component for Directory {
                     }
                 }
             }
-            MouseEventKind::ScrollUp => {
-                let key_event = KeyEvent::new(KeyCode::Up, KeyModifiers::NONE);
-                self.handle_key_event(key_event).await?;
-            }
-            MouseEventKind::ScrollDown => {
-                let key_event = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
-                self.handle_key_event(key_event).await?;
-            }
+            MouseEventKind::ScrollUp => self.wheel_selection(WheelDirection::Up),
+            MouseEventKind::ScrollDown => self.wheel_selection(WheelDirection::Down),
             _ => { /* ignore */ }
         }
         Ok(())
     }
 
-    async fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<(), std::io::Error> {
+    async fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<(), FsError> {
+        if self.handle_mask_key_event(key_event)?
+            || self.handle_filter_key_event(key_event)
+            || self.handle_mark_key_event(key_event)?
+        {
+            return Ok(());
+        }
+        // Digits accumulate a count for the next movement key, unless they're typed ahead after
+        // other characters
+        if let Some(digit) = util::count_digit(key_event, self.pending_count.is_some())
+            .filter(|_| !self.is_type_ahead_active())
+        {
+            self.pending_count = util::push_count_digit(self.pending_count, digit);
+            return Ok(());
+        }
+        if self.is_tree_view && self.handle_tree_key_event(key_event).await? {
+            return Ok(());
+        }
+        let pending_count = self.pending_count.take();
+        let count = pending_count.unwrap_or(1);
+        // An entry waiting to be found isn't selected once the selection's been moved
+        self.pending_selection = None;
+
         // If nothing is selected, select the first item before processing the key
         if self.items.selected().is_none() {
             self.items.set_selected(Some(0));
@@ -92,12 +208,17 @@ impl Component for Directory {
         let mut directory_changed = false;
         let current = self.get_cwd();
 
-        if util::is_up_key(key_event) {
-            // Move selection up one entry
-            selection_changed = self.items.previous();
-        } else if util::is_down_key(key_event) {
-            // Move selection down one entry
-            selection_changed = self.items.next();
+        if util::is_up_key(key_event) || (pending_count.is_some() && key_event.code == Char('k')) {
+            // Move selection up count entries
+            selection_changed = self.items.retreat(count);
+        } else if util::is_down_key(key_event)
+            || (pending_count.is_some() && key_event.code == Char('j'))
+        {
+            // Move selection down count entries
+            selection_changed = self.items.advance(count);
+        } else if pending_count.is_some() && key_event.code == Char('G') {
+            // Move selection to the count-th entry
+            selection_changed = self.set_selected((count - 1).min(self.items.upper_bound()));
         } else {
             match key_event.code {
                 KeyCode::Home => {
@@ -109,33 +230,53 @@ impl Component for Directory {
                     selection_changed = self.items.last();
                 }
                 KeyCode::PageUp => {
-                    // Move selection up one page
-                    selection_changed = self.items.retreat(self.area.height as usize);
+                    // Move selection up count pages
+                    let distance = (self.area.height as usize).saturating_mul(count);
+                    selection_changed = self.items.retreat(distance);
                 }
                 KeyCode::PageDown => {
-                    // Move selection down one page
-                    selection_changed = self.items.advance(self.area.height as usize)
+                    // Move selection down count pages
+                    let distance = (self.area.height as usize).saturating_mul(count);
+                    selection_changed = self.items.advance(distance)
                 }
-                // Open selected item if it's a folder
+                // Open selected item if it's a folder or an archive. Anything else is a file for the
+                // app to open.
                 KeyCode::Enter => {
-                    if self.cd()? {
+                    if self.cd().await? {
                         selection_changed = true;
                         directory_changed = true;
+                    } else if let Some(selected) = self.selected_item().filter(|selected| {
+                        self.entry_metadata(selected)
+                            .is_some_and(|metadata| metadata.is_file)
+                    }) {
+                        self.event_tx
+                            .as_ref()
+                            .unwrap()
+                            .send(Event::OpenFile(selected))
+                            .expect("Panic sending open file event");
                     }
                 }
                 // If there's a parent directory open it
                 KeyCode::Backspace => {
                     if self.has_parent_directory() {
+                        // Remembered before the parent directory entry is selected to open it
+                        self.remember_cursor();
                         self.set_selected(0);
-                        if self.cd()? {
+                        if self.cd().await? {
                             selection_changed = true;
                             directory_changed = true;
                         }
                     }
                 }
                 key_code => {
-                    // Move selection to item starting with character
+                    // Move selection to item starting with character. Digits that weren't
+                    // followed by a movement key were the start of a name, like 2024-report.
                     if let Char(c) = key_code {
+                        if let Some(count) = pending_count {
+                            for digit in count.to_string().chars() {
+                                self.select_by_char(digit);
+                            }
+                        }
                         self.select_by_char(c);
                         selection_changed = true;
                     }
@@ -144,10 +285,10 @@ impl Component for Directory {
         }
         if directory_changed {
             self.load_cwd().await?;
+            // Coming out of an archive selects the archive file
+            let current = current.map(|current| self.archive_of(&current).unwrap_or(current));
             if let Ok(current) = current {
-                if let Some(selected) = self.items.index_of(&current) {
-                    self.set_selected(selected);
-                }
+                self.select_entry(&current);
             }
         }
         if selection_changed {
@@ -160,45 +301,254 @@ impl Component for Directory {
         Ok(())
     }
 
-    fn render(&mut self, area: Rect, frame: &mut Frame) -> Result<(), std::io::Error> {
+    fn render(&mut self, area: Rect, frame: &mut Frame) -> Result<(), FsError> {
         self.set_area(area);
 
-        let items = util::list_items(&self.items, frame.size().height as usize);
+        let height = self.area.height.saturating_sub(2) as usize; // Inside the borders
+        let width = self.area.width.saturating_sub(4) as usize; // And the padding
+
+        // Files' sizes are shown right away, and directories' when they've been added up
+        let show_sizes = self.show_sizes;
+        let directory_sizes = &self.directory_sizes;
+        let size_column = |entry: &Path, metadata: Option<&EntryMetadata>| match metadata {
+            _ if !show_sizes || util::entry_name(entry) == constants::PARENT_DIRECTORY => {
+                String::new()
+            }
+            Some(metadata) if metadata.is_dir => directory_sizes
+                .get(entry)
+                .map_or("…".to_string(), |size| util::size_string(*size)),
+            Some(metadata) => util::size_string(metadata.len),
+            None => String::new(),
+        };
+        let (items, mut state) = if self.is_tree_view {
+            // Entries are indented by their depth, and directories show whether they're expanded
+            let root = self.loaded_directory.clone().unwrap_or_default();
+            let expanded = &self.expanded;
+            util::list_items_with_columns(
+                &mut self.items,
+                height,
+                &mut self.metadata,
+                width,
+                |entry, icon| {
+                    let depth = entry.strip_prefix(&root).map_or(0, |relative| {
+                        relative.components().count().saturating_sub(1)
+                    });
+                    let marker = if icon != constants::DIRECTORY_ICON
+                        || util::entry_name(entry) == constants::PARENT_DIRECTORY
+                    {
+                        ' '
+                    } else if expanded.contains_key(entry) {
+                        '▾'
+                    } else {
+                        '▸'
+                    };
+                    format!("{}{marker} ", "  ".repeat(depth))
+                },
+                size_column,
+            )
+        } else {
+            util::list_items_with_columns(
+                &mut self.items,
+                height,
+                &mut self.metadata,
+                width,
+                |_, _| String::new(),
+                size_column,
+            )
+        };
         // Don't include parent directory in count
         let mut item_count = self.items.len();
         if self.has_parent_directory() {
             item_count -= 1;
         }
-        let item_count_string = format!("[{item_count} items]");
+        // The count isn't final while the directory is still being read
+        let more = if self.is_reading() { " …" } else { "" };
+        let mut item_count_string = if self.filter.is_some() || self.mask.is_some() {
+            let mut entry_count = self.entries.len();
+            if self.has_parent_directory() {
+                entry_count -= 1;
+            }
+            format!("[{item_count} of {entry_count} items{more}]")
+        } else {
+            format!("[{item_count} items{more}]")
+        };
+        if let Some(filter) = &self.filter {
+            item_count_string.push_str(&format!(" /{filter}"));
+        }
+        // The mask being typed is shown instead of the one it replaces
+        if let Some(mask) = self
+            .mask_input
+            .as_deref()
+            .or(self.mask.as_ref().map(FileMask::pattern))
+        {
+            item_count_string.push_str(&format!(" +{mask}"));
+        }
+        if let Some((is_marking, pattern)) = &self.group_input {
+            let sign = if *is_marking { '=' } else { '-' };
+            item_count_string.push_str(&format!(" {sign}{pattern}"));
+        }
+        if !self.marked.is_empty() {
+            let size = self.marked_size.map_or("…".to_string(), util::size_string);
+            item_count_string.push_str(&format!(" {} selected, {size}", self.marked.len()));
+        }
+        // Marked entries stand out
+        let items: Vec<ListItem> = if self.marked.is_empty() {
+            items
+        } else {
+            let offset = self.items.offset();
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    if self.marked.contains(&self.items[offset + index]) {
+                        item.style(styles::MARKED_STYLE)
+                    } else {
+                        item
+                    }
+                })
+                .collect()
+        };
         let block = components::component_block(self.has_focus).title(item_count_string);
         let list = List::new(items)
             .block(block)
             .highlight_style(styles::LIST_HIGHLIGHT_STYLE);
-        frame.render_stateful_widget(list, self.area, &mut self.items.state);
+        frame.render_stateful_widget(list, self.area, &mut state);
 
         Ok(())
     }
+
+    fn key_hints(&self) -> Vec<KeyHint> {
+        if let Some((is_marking, _)) = &self.group_input {
+            let action = if *is_marking {
+                "Mark entries like *.rs or /regex/"
+            } else {
+                "Unmark entries like *.rs or /regex/"
+            };
+            return vec![
+                KeyHint::new("Type", action),
+                KeyHint::new("Enter", "Accept"),
+                KeyHint::new("Esc", "Cancel"),
+            ];
+        }
+        if self.mask_input.is_some() {
+            return vec![
+                KeyHint::new("Type", "Mask, like *.rs or /regex/"),
+                KeyHint::new("Enter", "Accept"),
+                KeyHint::new("Esc", "Clear"),
+            ];
+        }
+        if self.is_filter_input {
+            return vec![
+                KeyHint::new("Type", "Filter"),
+                KeyHint::new("Enter", "Accept"),
+                KeyHint::new("Esc", "Clear"),
+            ];
+        }
+        match self.pending_count {
+            Some(count) => vec![
+                KeyHint::new(count.to_string(), "Count"),
+                KeyHint::new("j/k", "Move"),
+                KeyHint::new("G", "Go to entry"),
+            ],
+            None if self.is_tree_view => vec![
+                KeyHint::new("↑/↓", "Move"),
+                KeyHint::new("←/→", "Collapse/Expand"),
+                KeyHint::new("Enter", "Open"),
+                KeyHint::new("Bksp", "Parent"),
+                KeyHint::new("/", "Filter"),
+            ],
+            None => {
+                let mut hints = vec![
+                    KeyHint::new("↑/↓", "Move"),
+                    KeyHint::new("Enter", "Open"),
+                    KeyHint::new("Bksp", "Parent"),
+                    KeyHint::new("a-z", "Jump"),
+                    KeyHint::new("/", "Filter"),
+                    KeyHint::new("+", "Mask"),
+                    KeyHint::new("Space", "Mark"),
+                ];
+                // Two marked files can be compared
+                if self.marked.len() == 2 {
+                    hints.push(KeyHint::new("M-C", "Compare"));
+                }
+                hints
+            }
+        }
+    }
 }
 
 impl Directory {
+    /// Where the pane sends what it reads in the background, and its selection and directory
+    /// changes. Without one, nothing's read in the background.
     pub fn set_event_tx(&mut self, event_tx: Option<UnboundedSender<Event>>) {
         self.event_tx = event_tx;
     }
 
-    pub fn set_items(&mut self, items: Vec<PathBuf>) -> &mut Directory {
+    /// How the current directory is listed
+    pub fn listing_options(&self) -> ListingOptions {
+        self.listing_options
+    }
+
+    pub(crate) fn default_listing_options(&self) -> ListingOptions {
+        self.default_listing_options
+    }
+
+    /// Sets how directories are listed, unless their view has been changed
+    pub fn set_listing_options(&mut self, listing_options: ListingOptions) {
+        self.listing_options = listing_options;
+        self.default_listing_options = listing_options;
+    }
+
+    // Changes how this directory is listed, and remembers it for when it's loaded again
+    pub(crate) fn change_listing_options(
+        &mut self,
+        listing_options: ListingOptions,
+    ) -> Result<(), FsError> {
+        self.listing_options = listing_options;
+        self.remember_view()
+    }
+
+    pub(crate) fn set_wheel_scroll(&mut self, wheel_scroll: WheelScroll) {
+        self.wheel_scroll = wheel_scroll;
+    }
+
+    pub(crate) fn set_view_memory(&mut self, view_memory: SharedViewMemory) {
+        self.view_memory = view_memory;
+    }
+
+    fn remember_view(&mut self) -> Result<(), FsError> {
+        let Some(loaded_directory) = &self.loaded_directory else {
+            return Ok(());
+        };
+        let view = View {
+            listing_options: self.listing_options,
+            filter: self.filter.clone(),
+        };
+        let default_view = View {
+            listing_options: self.default_listing_options,
+            filter: None,
+        };
+        self.view_memory
+            .lock()
+            .unwrap()
+            .remember(loaded_directory, view, &default_view)
+            .map_err(FsError::from)
+    }
+
+    pub(crate) fn set_items(&mut self, items: Vec<PathBuf>) -> &mut Directory {
         self.items = StatefulList::with_items(items);
         self.items.first(); // Because no line is selected by default
         self
     }
 
-    pub fn is_selected(&self, index: usize) -> bool {
+    pub(crate) fn is_selected(&self, index: usize) -> bool {
         match self.items.state.selected() {
             Some(selected) => selected == index,
             None => false,
         }
     }
 
-    pub fn index_from_row(&self, row: u16) -> Option<usize> {
+    pub(crate) fn index_from_row(&self, row: u16) -> Option<usize> {
         let index = (row - self.area.y) as usize + self.items.state.offset();
         if (index > self.items.lower_bound()) && (index <= self.items.len()) {
             Some(index - 1)
@@ -207,18 +557,118 @@ impl Directory {
         }
     }
 
-    pub async fn load_cwd(&mut self) -> Result<(), std::io::Error> {
+    /// Loads the current directory. A directory that takes a while to read is shown as its entries
+    /// are found, and its entries are sorted when they've all been found.
+    pub async fn load_cwd(&mut self) -> Result<(), FsError> {
         let cwd = self.get_cwd()?;
-        let entries = components::read_directory(&cwd).await?;
+        debug!(cwd = %cwd.display(), "loading directory");
+        let entries = fs_provider::provider(&cwd)
+            .open_dir(&cwd)
+            .await
+            .context(Operation::Listing, &cwd)?;
+        self.cancel_read();
+        self.cancel_directory_sizes();
         let mut result = vec![];
-        // Prepend parent directory entry if there is one
-        if cwd.parent().is_some() {
+        // Prepend parent directory entry if there is one. The root of a mounted computer has none.
+        if cwd.parent().is_some() && self.remote_root(&cwd).is_none() {
             let mut p = cwd.clone();
             p.push(constants::PARENT_DIRECTORY);
             result.push(p);
         }
-        result.extend(entries);
-        self.set_items(result);
+        // A different directory is listed the way it was last time its view was changed, and the
+        // expanded directories are cleared. Reloading the same one rereads the directories that
+        // are expanded.
+        let mut expanded_metadata = vec![];
+        if self.loaded_directory.as_ref() != Some(&cwd) {
+            self.remember_cursor();
+            // Found when it's read, unless the caller selects something else
+            self.pending_selection = self.cursors.get(&cwd).cloned();
+            let view = self.view_memory.lock().unwrap().get(&cwd);
+            (self.listing_options, self.filter) = match view {
+                Some(view) => (view.listing_options, view.filter),
+                None => (self.default_listing_options, None),
+            };
+            self.is_filter_input = false;
+            self.expanded.clear();
+            self.clear_marks();
+            self.directory_sizes.clear();
+        } else {
+            for (directory, entries) in std::mem::take(&mut self.expanded) {
+                let provider = fs_provider::provider(&directory);
+                match components::read_directory(&directory, &self.listing_options).await {
+                    Ok(listing) => {
+                        let entries = listing.iter().map(|(entry, _)| entry.clone()).collect();
+                        expanded_metadata.extend(listing);
+                        self.expanded.insert(directory, entries);
+                    }
+                    Err(_) => {
+                        // One that's still there but can't be read is left as it was
+                        let metadata = provider.metadata(&directory).await;
+                        if metadata.is_ok_and(|metadata| metadata.is_dir) {
+                            self.expanded.insert(directory, entries);
+                        }
+                    }
+                }
+            }
+        }
+        self.loaded_directory = Some(cwd);
+        self.entries = result;
+        self.metadata.clear();
+        self.metadata.extend(expanded_metadata);
+        self.apply_filter();
+
+        let cancellation_token = CancellationToken::new();
+        self.reading = Some(cancellation_token.clone());
+        self.read_id = NEXT_READ_ID.fetch_add(1, Ordering::Relaxed);
+        let read_id = self.read_id;
+        let listing_options = self.listing_options;
+        let (batch_tx, mut batch_rx) = mpsc::unbounded_channel();
+        let task = tasks::start();
+        tokio::spawn(async move {
+            let _task = task;
+            let result = components::stream_directory(
+                entries,
+                &listing_options,
+                &cancellation_token,
+                |batch| {
+                    let _ = batch_tx.send(ListingBatch::Entries(batch));
+                },
+            )
+            .await;
+            if let Some(result) = result {
+                let result = result.map_err(|error| error.to_string());
+                let _ = batch_tx.send(ListingBatch::Finished(result));
+            }
+        });
+        // A directory that's quick to read is loaded before this returns, as if it weren't read
+        // in the background. What's read after that is sent as events.
+        let deadline = tokio::time::Instant::now() + constants::DIRECTORY_READ_WAIT;
+        loop {
+            match tokio::time::timeout_at(deadline, batch_rx.recv()).await {
+                Ok(Some(batch)) => {
+                    let is_finished = matches!(batch, ListingBatch::Finished(_));
+                    self.add_batch(read_id, batch).await?;
+                    if is_finished {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    debug!(
+                        read_id,
+                        "directory is still being read, finishing in the background"
+                    );
+                    let event_tx = self.event_tx.clone().unwrap();
+                    tokio::spawn(async move {
+                        while let Some(batch) = batch_rx.recv().await {
+                            // The receiver is only gone if the app is shutting down
+                            let _ = event_tx.send(Event::DirectoryBatch(read_id, batch));
+                        }
+                    });
+                    break;
+                }
+            }
+        }
         self.event_tx
             .as_ref()
             .unwrap()
@@ -227,7 +677,122 @@ impl Directory {
         Ok(())
     }
 
-    fn get_cwd(&self) -> Result<PathBuf, std::io::Error> {
+    /// Adds entries found by the directory read, or replaces them with all of them, sorted, when
+    /// it's finished, keeping the selection where it was unless the entry waiting to be selected
+    /// turns up
+    pub async fn add_batch(&mut self, read_id: u32, batch: ListingBatch) -> Result<(), FsError> {
+        // Ignore a read that's been replaced or cancelled
+        if read_id != self.read_id || self.reading.is_none() {
+            return Ok(());
+        }
+        let selected = self.selected_item();
+        match batch {
+            ListingBatch::Entries(entries) => self.add_entries(entries),
+            ListingBatch::Finished(result) => {
+                self.reading = None;
+                let entries = result.map_err(|error| FsError::Io {
+                    operation: Operation::Listing,
+                    path: self.loaded_directory.clone().unwrap_or_default(),
+                    source: std::io::Error::other(error),
+                })?;
+                let is_parent_directory =
+                    |entry: &PathBuf| util::entry_name(entry) == constants::PARENT_DIRECTORY;
+                self.entries.truncate(usize::from(
+                    self.entries.first().is_some_and(is_parent_directory),
+                ));
+                self.add_entries(entries);
+                // Entries that are gone can't be marked
+                let marked_count = self.marked.len();
+                let mut gone = vec![];
+                for marked in &self.marked {
+                    if fs_provider::provider(marked)
+                        .metadata(marked)
+                        .await
+                        .is_err()
+                    {
+                        gone.push(marked.clone());
+                    }
+                }
+                for marked in gone {
+                    self.marked.remove(&marked);
+                }
+                if self.marked.len() != marked_count {
+                    self.update_marked_size();
+                }
+                self.update_directory_sizes(true);
+            }
+        }
+        let offset = self.items.offset();
+        self.apply_filter();
+        *self.items.state.offset_mut() = offset;
+        let pending_selection = self.pending_selection.take();
+        if let Some(index) = pending_selection
+            .as_ref()
+            .and_then(|pending_selection| self.items.index_of(pending_selection))
+        {
+            self.set_selected(index);
+            self.send_selection_changed();
+        } else {
+            if let Some(index) = selected.and_then(|selected| self.items.index_of(&selected)) {
+                self.set_selected(index);
+            }
+            // Keep waiting for it while there's more to read
+            if self.reading.is_some() {
+                self.pending_selection = pending_selection;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_entries(&mut self, entries: Vec<ListingEntry>) {
+        for (entry, metadata) in entries {
+            self.metadata.insert(entry.clone(), metadata);
+            self.entries.push(entry);
+        }
+    }
+
+    // What was listed about an entry, or if it wasn't, what can be found out about it here
+    fn entry_metadata(&self, entry: &Path) -> Option<EntryMetadata> {
+        match self.metadata.get(entry) {
+            Some(metadata) => *metadata,
+            None => LocalFs::entry_metadata(entry),
+        }
+    }
+
+    // Whether an entry is a directory. The parent directory entry always is.
+    fn is_dir(&self, entry: &Path) -> bool {
+        util::entry_name(entry) == constants::PARENT_DIRECTORY
+            || self
+                .entry_metadata(entry)
+                .is_some_and(|metadata| metadata.is_dir)
+    }
+
+    pub(crate) fn is_reading(&self) -> bool {
+        self.reading.is_some()
+    }
+
+    /// Whether the read is this pane's, and it's still going
+    pub fn is_read(&self, read_id: u32) -> bool {
+        self.reading.is_some() && read_id == self.read_id
+    }
+
+    pub(crate) fn area(&self) -> Rect {
+        self.area
+    }
+
+    pub(crate) fn loaded_directory(&self) -> Option<PathBuf> {
+        self.loaded_directory.clone()
+    }
+
+    /// Stops reading the directory in the background
+    pub fn cancel_read(&mut self) {
+        if let Some(cancellation_token) = self.reading.take() {
+            cancellation_token.cancel();
+        }
+        self.pending_selection = None;
+    }
+
+    fn get_cwd(&self) -> Result<PathBuf, FsError> {
         // Gets the current directory, unless it doesn't exist (because it was deleted?)
         // Then gets the current directory's first valid parent instead.
         let mut cwd: Option<PathBuf> = None;
@@ -241,24 +806,104 @@ impl Directory {
         if let Some(cwd) = cwd {
             Ok(cwd)
         } else {
-            Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
+            Err(FsError::Other(std::io::Error::other(
                 "Can't find valid directory",
-            ))
+            )))
         }
     }
 
-    fn cd(&mut self) -> Result<bool, std::io::Error> {
+    async fn cd(&mut self) -> Result<bool, FsError> {
         if let Some(selected) = self.selected_item() {
-            if selected.is_dir() {
-                std::env::set_current_dir(selected)?;
+            if selected.ends_with(constants::PARENT_DIRECTORY) {
+                // The parent of an archive's top level is the directory the archive is in
+                let archive = selected.parent().and_then(|cwd| self.archive_of(cwd));
+                if let Some(directory) = archive.as_deref().and_then(Path::parent) {
+                    std::env::set_current_dir(directory).context(Operation::Entering, directory)?;
+                    return Ok(true);
+                }
+            }
+            if self.is_dir(&selected) {
+                std::env::set_current_dir(&selected).context(Operation::Entering, &selected)?;
+                return Ok(true);
+            }
+            if let Some(kind) = ArchiveKind::from_path(&selected) {
+                let mount = match self.archive_mounts.iter().find(|(_, a)| *a == selected) {
+                    Some((mount, _)) => mount.clone(),
+                    None => {
+                        // An archive in an archive is extracted before it's opened
+                        let mount = fs_provider::provider(&selected)
+                            .local_path(&selected)
+                            .await
+                            .and_then(|archive| archive::open_in_temp(&archive, kind))
+                            .map_err(|error| FsError::archive(&selected, error))?;
+                        self.archive_mounts.push((mount.clone(), selected));
+                        mount
+                    }
+                };
+                std::env::set_current_dir(&mount).context(Operation::Entering, &mount)?;
                 return Ok(true);
             }
         }
         Ok(false)
     }
 
-    pub fn set_selected(&mut self, selected: usize) -> bool {
+    // The archive file a directory was opened from, if it's the top level of an open archive
+    fn archive_of(&self, directory: &Path) -> Option<PathBuf> {
+        self.archive_mounts
+            .iter()
+            .find(|(mount, _)| mount == directory)
+            .map(|(_, archive)| archive.clone())
+    }
+
+    // The URL of a directory a computer or bucket is mounted on
+    fn remote_root(&self, directory: &Path) -> Option<PathBuf> {
+        self.remote_mounts
+            .iter()
+            .find(|(mount, _)| mount == directory)
+            .map(|(_, root)| root.clone())
+    }
+
+    // Shows the directory a computer or bucket was mounted on as its URL
+    pub(crate) fn add_remote_mount(&mut self, mount: PathBuf, root: PathBuf) {
+        self.remote_mounts.push((mount, root));
+    }
+
+    // The path to show for a directory: inside an archive, that's the path of the archive file
+    // followed by the path in the archive, and on a mounted computer or bucket it's a URL
+    pub(crate) fn display_path(&self, path: &Path) -> PathBuf {
+        let mut path = path.to_path_buf();
+        // Archives can be opened from inside other archives
+        while let Some((relative, archive)) = self
+            .archive_mounts
+            .iter()
+            .chain(&self.remote_mounts)
+            .find_map(|(mount, archive)| Some((path.strip_prefix(mount).ok()?, archive)))
+        {
+            path = if relative.as_os_str().is_empty() {
+                archive.clone()
+            } else {
+                archive.join(relative)
+            };
+        }
+        path
+    }
+
+    // The real path of a path as it's shown, which inside an archive is in the directory the
+    // archive was opened in. The archive whose shown path matches the most of it is the innermost one.
+    pub(crate) fn real_path(&self, path: &Path) -> PathBuf {
+        self.archive_mounts
+            .iter()
+            .chain(&self.remote_mounts)
+            .filter_map(|(mount, archive)| {
+                let archive = self.display_path(archive);
+                let relative = path.strip_prefix(&archive).ok()?;
+                Some((archive.components().count(), mount.join(relative)))
+            })
+            .max_by_key(|(length, _)| *length)
+            .map_or_else(|| path.to_path_buf(), |(_, real_path)| real_path)
+    }
+
+    pub(crate) fn set_selected(&mut self, selected: usize) -> bool {
         if Some(selected) == self.items.selected() {
             false
         } else {
@@ -267,22 +912,54 @@ impl Directory {
         }
     }
 
+    // Whether characters are being typed to select an entry, or digits that may be a count or the
+    // start of a name, so the keys that follow go on with them
+    pub(crate) fn is_typing_ahead(&self) -> bool {
+        self.pending_count.is_some() || self.is_type_ahead_active()
+    }
+
+    pub(crate) fn end_type_ahead(&mut self) {
+        self.type_ahead.clear();
+    }
+
+    fn is_type_ahead_active(&self) -> bool {
+        !self.type_ahead.is_empty()
+            && self
+                .type_ahead_time
+                .is_some_and(|time| time.elapsed() <= constants::TYPE_AHEAD_TIMEOUT)
+    }
+
+    // Selects the next entry whose name, or failing that a word in it, starts with the characters
+    // typed in quick succession. Typing the same character repeatedly cycles through the entries
+    // that start with it.
     fn select_by_char(&mut self, ch: char) -> bool {
-        let selected = self.items.selected().unwrap_or(0);
+        let now = Instant::now();
+        let timed_out = match self.type_ahead_time {
+            Some(time) => now.duration_since(time) > constants::TYPE_AHEAD_TIMEOUT,
+            None => true,
+        };
+        if timed_out {
+            self.type_ahead.clear();
+        }
+        self.type_ahead.push(ch);
+        self.type_ahead_time = Some(now);
 
-        let index =
-            util::find_match_by_char(self.items.iter().as_slice(), ch, selected, |path_buf| {
-                // This returns the first character of the path's file name if it can
-                if let Some(file_name) = path_buf.file_name() {
-                    if let Some(file_name) = file_name.to_str() {
-                        file_name.chars().next()
+        let selected = self.items.selected().unwrap_or(0);
+        let is_repeated_char = self.type_ahead.chars().all(|c| c == ch);
+        let index = if self.type_ahead.chars().count() == 1 {
+            // A new prefix starts searching after the selected entry
+            self.find_match_by_prefix(&ch.to_string(), selected + 1)
+        } else {
+            // A longer prefix keeps the selected entry if it still matches
+            self.find_match_by_prefix(&self.type_ahead, selected)
+                .or_else(|| {
+                    if is_repeated_char {
+                        self.find_match_by_prefix(&ch.to_string(), selected + 1)
                     } else {
                         None
                     }
-                } else {
-                    None
-                }
-            });
+                })
+        };
         // Don't change the selection unless a match was made
         if let Some(index) = index {
             self.set_selected(index)
@@ -291,13 +968,646 @@ impl Directory {
         }
     }
 
+    fn find_match_by_prefix(&self, prefix: &str, from: usize) -> Option<usize> {
+        util::find_match_by_prefix(self.items.iter().as_slice(), prefix, from, |path_buf| {
+            path_buf
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+        })
+    }
+
+    /// Selects the given entry, if it's in the list. If it isn't, but the directory is still
+    /// being read, it's selected when it's found.
+    pub fn select_entry(&mut self, entry: &PathBuf) -> bool {
+        match self.items.index_of(entry) {
+            Some(index) => self.set_selected(index),
+            None => {
+                if self.reading.is_some() {
+                    self.pending_selection = Some(entry.clone());
+                }
+                false
+            }
+        }
+    }
+
+    // Remembers the entry that's selected in the directory that's loaded, to select it again
+    // when the directory is come back to. The parent directory entry is only selected to leave
+    // the directory by it, so it isn't remembered.
+    fn remember_cursor(&mut self) {
+        if let (Some(directory), Some(selected)) =
+            (self.loaded_directory.clone(), self.selected_item())
+        {
+            if util::entry_name(&selected) != constants::PARENT_DIRECTORY {
+                self.cursors.insert(directory, selected);
+            }
+        }
+    }
+
+    /// The selected entry, which may be the parent directory entry
     pub fn selected_item(&self) -> Option<PathBuf> {
+        // A directory with no parent has no entries until the first of them are read
         self.items
             .selected()
+            .filter(|selected| *selected < self.items.len())
             .map(|selected| self.items[selected].clone())
     }
-    
+
+    // Returns the selected entry's one-based position and the number of entries,
+    // not counting the parent directory entry, or None if the parent directory entry is selected.
+    pub(crate) fn selected_position(&self) -> Option<(usize, usize)> {
+        let selected = self.items.selected()?;
+        if self.has_parent_directory() {
+            if selected == 0 {
+                None
+            } else {
+                Some((selected, self.items.len() - 1))
+            }
+        } else {
+            Some((selected + 1, self.items.len()))
+        }
+    }
+
     fn has_parent_directory(&self) -> bool {
-        util::entry_name(&self.items[0]) == constants::PARENT_DIRECTORY && self.items.len() > 0
+        !self.items.is_empty() && util::entry_name(&self.items[0]) == constants::PARENT_DIRECTORY
+    }
+
+    // Whether the filter, the mask or a pattern to mark entries by is being typed
+    pub(crate) fn is_input(&self) -> bool {
+        self.is_filter_input || self.mask_input.is_some() || self.group_input.is_some()
+    }
+
+    /// Whether any entries are marked
+    pub fn has_marks(&self) -> bool {
+        !self.marked.is_empty()
+    }
+
+    /// The marked entries, in the order they're listed, followed by the ones that aren't listed,
+    /// because the filter hides them or they're in a tree directory that was collapsed, by path
+    pub fn marked_items(&self) -> Vec<PathBuf> {
+        let mut items: Vec<PathBuf> = self
+            .items
+            .iter()
+            .filter(|item| self.marked.contains(*item))
+            .cloned()
+            .collect();
+        let listed: HashSet<&PathBuf> = self.items.iter().collect();
+        let mut unlisted: Vec<PathBuf> = self
+            .marked
+            .iter()
+            .filter(|marked| !listed.contains(marked))
+            .cloned()
+            .collect();
+        unlisted.sort();
+        items.append(&mut unlisted);
+        items
+    }
+
+    // Marks the entries, and only them
+    pub(crate) fn set_marks(&mut self, entries: Vec<PathBuf>) {
+        self.marked = entries.into_iter().collect();
+        self.update_marked_size();
+    }
+
+    /// Unmarks the marked entries
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+        self.update_marked_size();
+    }
+
+    // Adds up the marked entries' sizes, including everything in the marked directories. A few
+    // files are added up right away, and anything more in the background, until the marks change
+    // again.
+    fn update_marked_size(&mut self) {
+        if let Some(cancellation_token) = self.sizing.take() {
+            cancellation_token.cancel();
+        }
+        self.marked_size = None;
+        if self.marked.is_empty() {
+            return;
+        }
+        let entries: Vec<ListingEntry> = self
+            .marked
+            .iter()
+            .map(|entry| (entry.clone(), self.entry_metadata(entry)))
+            .collect();
+        let is_dir = |metadata: &Option<EntryMetadata>| metadata.is_some_and(|m| m.is_dir);
+        let file_size = |metadata: &Option<EntryMetadata>| metadata.map_or(0, |m| m.len);
+        if entries.len() <= constants::MAX_MARKED_SIZE_WAIT
+            && entries.iter().all(|(_, metadata)| !is_dir(metadata))
+        {
+            let size = entries
+                .iter()
+                .map(|(_, metadata)| file_size(metadata))
+                .sum();
+            self.marked_size = Some(size);
+            return;
+        }
+        let cancellation_token = CancellationToken::new();
+        self.sizing = Some(cancellation_token.clone());
+        self.marked_size_id = NEXT_SIZE_ID.fetch_add(1, Ordering::Relaxed);
+        let size_id = self.marked_size_id;
+        let event_tx = self.event_tx.clone().unwrap();
+        let task = tasks::start();
+        tokio::spawn(async move {
+            let _task = task;
+            let mut size = 0;
+            for (entry, metadata) in entries {
+                if is_dir(&metadata) {
+                    size += fs_provider::total_size(&entry, &cancellation_token).await?;
+                } else {
+                    size += file_size(&metadata);
+                }
+            }
+            if !cancellation_token.is_cancelled() {
+                let _ = event_tx.send(Event::MarkedSizeFinished(size_id, size));
+            }
+            Some(())
+        });
+    }
+
+    /// Shows or hides the entries' sizes. The listed directories' sizes are added up while they're
+    /// shown.
+    pub fn toggle_sizes(&mut self) {
+        self.show_sizes = !self.show_sizes;
+        if self.show_sizes {
+            self.update_directory_sizes(false);
+        } else {
+            self.cancel_directory_sizes();
+            self.directory_sizes.clear();
+        }
+    }
+
+    // Adds up the sizes of everything in the listed directories, and in the tree's expanded ones,
+    // in the background, one directory at a time. Each is shown as it's found, until the directory
+    // is left. A directory that's been reread is added up again, and its old size is shown until
+    // the new one is found.
+    fn update_directory_sizes(&mut self, is_reread: bool) {
+        if !self.show_sizes || self.is_reading() {
+            return;
+        }
+        self.cancel_directory_sizes();
+        let directories: Vec<PathBuf> = self
+            .entries
+            .iter()
+            .chain(self.expanded.values().flatten())
+            .filter(|entry| util::entry_name(entry) != constants::PARENT_DIRECTORY)
+            .filter(|entry| is_reread || !self.directory_sizes.contains_key(*entry))
+            .filter(|entry| self.is_dir(entry))
+            .cloned()
+            .collect();
+        if directories.is_empty() {
+            return;
+        }
+        let cancellation_token = CancellationToken::new();
+        self.sizing_directories = Some(cancellation_token.clone());
+        self.directory_sizes_id = NEXT_SIZE_ID.fetch_add(1, Ordering::Relaxed);
+        let size_id = self.directory_sizes_id;
+        let event_tx = self.event_tx.clone().unwrap();
+        let task = tasks::start();
+        tokio::spawn(async move {
+            let _task = task;
+            for directory in directories {
+                let size = fs_provider::total_size(&directory, &cancellation_token).await?;
+                let _ = event_tx.send(Event::DirectorySizeFound(size_id, directory, size));
+            }
+            Some(())
+        });
+    }
+
+    fn cancel_directory_sizes(&mut self) {
+        if let Some(cancellation_token) = self.sizing_directories.take() {
+            cancellation_token.cancel();
+        }
+    }
+
+    /// Takes a listed directory's size, if it's from this pane's latest computation
+    pub fn set_directory_size(&mut self, size_id: u32, directory: PathBuf, size: u64) {
+        if self.sizing_directories.is_some() && size_id == self.directory_sizes_id {
+            self.directory_sizes.insert(directory, size);
+        }
+    }
+
+    /// Takes the marked entries' total size, if it's from this pane's latest computation
+    pub fn set_marked_size(&mut self, size_id: u32, size: u64) {
+        if self.sizing.is_some() && size_id == self.marked_size_id {
+            self.sizing = None;
+            self.marked_size = Some(size);
+        }
+    }
+
+    pub(crate) fn is_filtered(&self) -> bool {
+        self.filter.is_some() || self.mask.is_some()
+    }
+
+    // Adds pasted text to the filter, the mask or the pattern to mark entries by, if it's being
+    // typed
+    pub(crate) fn paste(&mut self, text: &str) {
+        if let Some((_, pattern)) = self.group_input.as_mut() {
+            pattern.push_str(text);
+            return;
+        }
+        if let Some(mask_input) = self.mask_input.as_mut() {
+            mask_input.push_str(text);
+            self.apply_mask_input();
+            return;
+        }
+        if !self.is_filter_input {
+            return;
+        }
+        if let Some(filter) = self.filter.as_mut() {
+            filter.push_str(text);
+        }
+        self.apply_filter();
+        self.send_selection_changed();
+    }
+
+    // Handles the keys that start, edit and clear the filter. Returns true if the key was handled.
+    fn handle_filter_key_event(&mut self, key_event: KeyEvent) -> bool {
+        if self.is_filter_input {
+            match key_event.code {
+                Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    if let Some(filter) = self.filter.as_mut() {
+                        filter.push(c);
+                    }
+                    self.apply_filter();
+                    self.send_selection_changed();
+                    true
+                }
+                KeyCode::Backspace => {
+                    match self.filter.as_mut() {
+                        Some(filter) if !filter.is_empty() => {
+                            filter.pop();
+                            self.apply_filter();
+                            self.send_selection_changed();
+                        }
+                        _ => self.clear_filter(),
+                    }
+                    true
+                }
+                KeyCode::Enter => {
+                    // Stop typing, but keep the filter unless it's empty
+                    self.is_filter_input = false;
+                    if self.filter.as_ref().is_some_and(|filter| filter.is_empty()) {
+                        self.clear_filter();
+                    } else {
+                        // The filter's still applied if it can't be saved
+                        let _ = self.remember_view();
+                    }
+                    true
+                }
+                KeyCode::Esc => {
+                    self.clear_filter();
+                    true
+                }
+                _ => false,
+            }
+        } else {
+            match key_event.code {
+                Char('/') if self.pending_count.is_none() => {
+                    self.filter = Some(self.filter.take().unwrap_or_default());
+                    self.is_filter_input = true;
+                    true
+                }
+                KeyCode::Esc if self.filter.is_some() => {
+                    self.clear_filter();
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    // Handles the keys that start, edit and clear the mask. The mask is applied as it's typed,
+    // whenever it's one that can be used. Returns true if the key was handled, or an error if the
+    // mask that was typed can't be used.
+    fn handle_mask_key_event(&mut self, key_event: KeyEvent) -> Result<bool, FsError> {
+        let Some(mask_input) = self.mask_input.as_mut() else {
+            return Ok(match key_event.code {
+                Char('+') if self.pending_count.is_none() && !self.is_filter_input => {
+                    let pattern = self.mask.as_ref().map(FileMask::pattern);
+                    self.mask_input = Some(pattern.unwrap_or_default().to_string());
+                    true
+                }
+                // The filter is cleared first
+                KeyCode::Esc if self.filter.is_none() && self.mask.is_some() => {
+                    self.set_mask(None);
+                    true
+                }
+                _ => false,
+            });
+        };
+        match key_event.code {
+            Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                mask_input.push(c);
+                self.apply_mask_input();
+            }
+            KeyCode::Backspace => {
+                if mask_input.pop().is_none() {
+                    self.mask_input = None;
+                }
+                self.apply_mask_input();
+            }
+            KeyCode::Enter => {
+                let mask_input = self.mask_input.take().unwrap_or_default();
+                if mask_input.is_empty() {
+                    self.set_mask(None);
+                } else {
+                    match FileMask::new(&mask_input) {
+                        Ok(mask) => self.set_mask(Some(mask)),
+                        Err(message) => {
+                            self.set_mask(None);
+                            return Err(FsError::Other(std::io::Error::new(
+                                std::io::ErrorKind::InvalidInput,
+                                format!("Can't use mask {mask_input}: {message}"),
+                            )));
+                        }
+                    }
+                }
+            }
+            KeyCode::Esc => {
+                self.mask_input = None;
+                self.set_mask(None);
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    // Handles the keys that mark and unmark entries: Space marks or unmarks the selected entry and
+    // moves on to the next one, = and - mark or unmark the entries that match a pattern, like a
+    // mask, Ctrl+A marks every entry and * inverts the marks. Returns true if the key was handled,
+    // or an error if the pattern can't be used.
+    fn handle_mark_key_event(&mut self, key_event: KeyEvent) -> Result<bool, FsError> {
+        let Some((is_marking, pattern)) = self.group_input.as_mut() else {
+            match key_event.code {
+                Char(' ') | KeyCode::Insert => {
+                    if let Some(selected) = self.selected_item().filter(|selected| {
+                        util::entry_name(selected) != constants::PARENT_DIRECTORY
+                    }) {
+                        if !self.marked.remove(&selected) {
+                            self.marked.insert(selected);
+                        }
+                        self.update_marked_size();
+                        if self.items.advance(1) {
+                            self.send_selection_changed();
+                        }
+                    }
+                }
+                Char(c @ ('=' | '-')) if self.pending_count.is_none() => {
+                    self.group_input = Some((c == '=', String::new()));
+                }
+                Char('a') if key_event.modifiers == KeyModifiers::CONTROL => {
+                    self.marked.extend(self.markable_items());
+                    self.update_marked_size();
+                }
+                Char('*') if self.pending_count.is_none() => {
+                    for entry in self.markable_items() {
+                        if !self.marked.remove(&entry) {
+                            self.marked.insert(entry);
+                        }
+                    }
+                    self.update_marked_size();
+                }
+                // The filter and the mask are cleared first
+                KeyCode::Esc if !self.marked.is_empty() => self.clear_marks(),
+                _ => return Ok(false),
+            }
+            return Ok(true);
+        };
+        match key_event.code {
+            Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => pattern.push(c),
+            KeyCode::Backspace => {
+                if pattern.pop().is_none() {
+                    self.group_input = None;
+                }
+            }
+            KeyCode::Enter => {
+                let is_marking = *is_marking;
+                let pattern = std::mem::take(pattern);
+                self.group_input = None;
+                if !pattern.is_empty() {
+                    let mask = FileMask::new(&pattern).map_err(|message| {
+                        FsError::Other(std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            format!("Can't mark entries like {pattern}: {message}"),
+                        ))
+                    })?;
+                    self.mark_matches(&mask, is_marking);
+                }
+            }
+            KeyCode::Esc => self.group_input = None,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    // The listed entries that can be marked, which is all of them but the parent directory entry
+    pub(crate) fn markable_items(&self) -> Vec<PathBuf> {
+        self.items
+            .iter()
+            .filter(|item| util::entry_name(item) != constants::PARENT_DIRECTORY)
+            .cloned()
+            .collect()
+    }
+
+    // Marks or unmarks the listed entries whose names match
+    fn mark_matches(&mut self, mask: &FileMask, is_marking: bool) {
+        let matches = self
+            .markable_items()
+            .into_iter()
+            .filter(|item| mask.is_match(&util::entry_name(item)));
+        if is_marking {
+            self.marked.extend(matches);
+        } else {
+            for item in matches {
+                self.marked.remove(&item);
+            }
+        }
+        self.update_marked_size();
+    }
+
+    // Applies the mask being typed, if it can be used yet
+    fn apply_mask_input(&mut self) {
+        match self.mask_input.as_deref() {
+            Some("") | None => self.set_mask(None),
+            Some(mask_input) => {
+                if let Ok(mask) = FileMask::new(mask_input) {
+                    self.set_mask(Some(mask));
+                }
+            }
+        }
+    }
+
+    // Restricts the files to the mask, or shows them all, keeping the selected entry selected if
+    // it's still shown
+    fn set_mask(&mut self, mask: Option<FileMask>) {
+        let selected = self.selected_item();
+        self.mask = mask;
+        self.apply_filter();
+        if let Some(selected) = selected {
+            self.select_entry(&selected);
+        }
+        self.send_selection_changed();
+    }
+
+    // Restores the full listing, keeping the selected entry selected
+    fn clear_filter(&mut self) {
+        let selected = self.selected_item();
+        self.filter = None;
+        self.is_filter_input = false;
+        self.apply_filter();
+        if let Some(selected) = selected {
+            self.select_entry(&selected);
+        }
+        let _ = self.remember_view();
+        self.send_selection_changed();
+    }
+
+    // Shows the entries whose names contain the filter text, ignoring case, and the files whose
+    // names match the mask. The parent directory entry, and with a mask, the directories, are
+    // always shown.
+    fn apply_filter(&mut self) {
+        let mut entries = if self.is_tree_view {
+            self.tree_entries()
+        } else {
+            self.entries.clone()
+        };
+        if let Some(mask) = &self.mask {
+            entries.retain(|entry| {
+                let name = util::entry_name(entry);
+                name == constants::PARENT_DIRECTORY || self.is_dir(entry) || mask.is_match(&name)
+            });
+        }
+        let items = match &self.filter {
+            Some(filter) if !filter.is_empty() => {
+                let filter = filter.to_lowercase();
+                entries
+                    .iter()
+                    .filter(|entry| {
+                        let name = util::entry_name(entry);
+                        name == constants::PARENT_DIRECTORY || name.to_lowercase().contains(&filter)
+                    })
+                    .cloned()
+                    .collect()
+            }
+            _ => entries,
+        };
+        self.set_items(items);
+        // Select the first match rather than the parent directory entry
+        if self.filter.is_some() && self.has_parent_directory() && self.items.len() > 1 {
+            self.set_selected(1);
+        }
+    }
+
+    pub(crate) fn is_tree_view(&self) -> bool {
+        self.is_tree_view
+    }
+
+    /// Switches between showing the entries as a list and as a tree, keeping the selection if it's
+    /// still shown
+    pub fn toggle_tree_view(&mut self) {
+        self.is_tree_view = !self.is_tree_view;
+        self.expanded.clear();
+        self.refresh_items();
+    }
+
+    // The entries in tree order, with the entries of each expanded directory after it
+    fn tree_entries(&self) -> Vec<PathBuf> {
+        fn push_entry(
+            entry: &PathBuf,
+            expanded: &HashMap<PathBuf, Vec<PathBuf>>,
+            entries: &mut Vec<PathBuf>,
+        ) {
+            entries.push(entry.clone());
+            for child in expanded.get(entry).into_iter().flatten() {
+                push_entry(child, expanded, entries);
+            }
+        }
+        let mut entries = vec![];
+        for entry in &self.entries {
+            push_entry(entry, &self.expanded, &mut entries);
+        }
+        entries
+    }
+
+    // Handles the keys that expand and collapse directories in the tree view. Returns true if the
+    // key was handled.
+    async fn handle_tree_key_event(&mut self, key_event: KeyEvent) -> Result<bool, FsError> {
+        let Some(selected) = self
+            .selected_item()
+            .filter(|selected| util::entry_name(selected) != constants::PARENT_DIRECTORY)
+        else {
+            return Ok(false);
+        };
+        match key_event.code {
+            KeyCode::Right => {
+                // Expanding a directory reads its entries the first time
+                if self.is_dir(&selected) && !self.expanded.contains_key(&selected) {
+                    let listing =
+                        components::read_directory(&selected, &self.listing_options).await?;
+                    let entries = listing.iter().map(|(entry, _)| entry.clone()).collect();
+                    self.metadata.extend(listing);
+                    self.expanded.insert(selected, entries);
+                    self.refresh_items();
+                    self.update_directory_sizes(false);
+                }
+                Ok(true)
+            }
+            KeyCode::Left => {
+                if self.expanded.contains_key(&selected) {
+                    // Collapsing a directory collapses the ones in it, too
+                    self.expanded
+                        .retain(|directory, _| !directory.starts_with(&selected));
+                    self.refresh_items();
+                } else if let Some(parent) = selected
+                    .parent()
+                    .filter(|parent| self.loaded_directory.as_deref() != Some(*parent))
+                {
+                    if self.select_entry(&parent.to_path_buf()) {
+                        self.send_selection_changed();
+                    }
+                }
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    // Rebuilds the items after the tree's changed, keeping the selected entry and the scroll
+    // position
+    fn refresh_items(&mut self) {
+        let selected = self.selected_item();
+        let offset = self.items.offset();
+        self.apply_filter();
+        *self.items.state.offset_mut() = offset;
+        if let Some(selected) = selected {
+            self.select_entry(&selected);
+        }
+        self.send_selection_changed();
+    }
+
+    // Moves the selection as far as the wheel scrolls, or selects the first entry if nothing is
+    // selected, like the Up and Down keys
+    fn wheel_selection(&mut self, direction: WheelDirection) {
+        let lines = self.wheel_scroll.lines(direction);
+        self.pending_selection = None;
+        let selection_changed = if self.items.selected().is_none() {
+            self.set_selected(0)
+        } else if direction == WheelDirection::Up {
+            self.items.retreat(lines)
+        } else {
+            self.items.advance(lines)
+        };
+        if selection_changed {
+            self.send_selection_changed();
+        }
+    }
+
+    fn send_selection_changed(&self) {
+        self.event_tx
+            .as_ref()
+            .unwrap()
+            .send(Event::SelectionChanged)
+            .expect("Panic sending selection changed event");
     }
 }
@@ -0,0 +1,253 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::widgets::{Clear, List, ListItem, Paragraph};
+use ratatui::{layout::Rect, Frame};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+use crate::app::disk_usage::{self, DiskUsage};
+use crate::app::{components, styles, tasks};
+use crate::stateful_list::StatefulList;
+use crate::tui::Event;
+use crate::{constants, util};
+
+use super::key_hints::KeyHint;
+
+// How many characters wide the bars showing each entry's share of the directory are
+const BAR_WIDTH: usize = 20;
+
+pub enum DiskUsageEvent {
+    Cancelled,
+    Selected(PathBuf),
+}
+
+// A popup showing how much space everything under a directory takes, biggest first, computed in
+// the background. The directories in it can be gone into, like in the directory pane.
+#[derive(Default)]
+pub struct DiskUsageView {
+    is_open: bool,
+    event_tx: Option<UnboundedSender<Event>>,
+
+    // The scan, how far it's got, and what it came up with
+    scan_id: u32,
+    file_count: u64,
+    byte_count: u64,
+    disk_usage: Option<DiskUsage>,
+    cancellation_token: Option<CancellationToken>,
+
+    // The directory that was scanned, the one being shown, and what's in it
+    root: PathBuf,
+    directory: PathBuf,
+    entries: StatefulList<(PathBuf, u64)>,
+}
+
+impl DiskUsageView {
+    pub fn set_event_tx(&mut self, event_tx: Option<UnboundedSender<Event>>) {
+        self.event_tx = event_tx;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    // Opens the popup and starts scanning the directory
+    pub fn open(&mut self, root: &Path) {
+        self.cancel_scan();
+        self.is_open = true;
+        self.root = root.to_path_buf();
+        self.directory = root.to_path_buf();
+        self.entries = StatefulList::default();
+        self.file_count = 0;
+        self.byte_count = 0;
+        self.disk_usage = None;
+        self.start_scan();
+    }
+
+    pub fn close(&mut self) {
+        self.cancel_scan();
+        self.is_open = false;
+        self.disk_usage = None;
+        self.entries = StatefulList::default();
+    }
+
+    pub fn set_progress(&mut self, scan_id: u32, file_count: u64, byte_count: u64) {
+        if self.is_open && scan_id == self.scan_id {
+            self.file_count = file_count;
+            self.byte_count = byte_count;
+        }
+    }
+
+    pub fn set_disk_usage(&mut self, scan_id: u32, disk_usage: DiskUsage) {
+        if self.is_open && scan_id == self.scan_id {
+            self.disk_usage = Some(disk_usage);
+            self.cancellation_token = None;
+            self.show_directory(self.directory.clone(), None);
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<DiskUsageEvent> {
+        if util::is_up_key(key_event) {
+            self.entries.previous();
+        } else if util::is_down_key(key_event) {
+            self.entries.next();
+        } else {
+            match key_event.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.close();
+                    return Some(DiskUsageEvent::Cancelled);
+                }
+                KeyCode::Home => {
+                    self.entries.first();
+                }
+                KeyCode::End => {
+                    self.entries.last();
+                }
+                // Go into the selected directory
+                KeyCode::Enter | KeyCode::Right => {
+                    if let Some(selected) = self.selected_entry().filter(|selected| {
+                        self.disk_usage
+                            .as_ref()
+                            .is_some_and(|disk_usage| disk_usage.is_directory(selected))
+                    }) {
+                        self.show_directory(selected, None);
+                    }
+                }
+                // Go back up, but not above the directory that was scanned
+                KeyCode::Backspace | KeyCode::Left => {
+                    if self.directory != self.root {
+                        if let Some(parent) = self.directory.parent().map(Path::to_path_buf) {
+                            self.show_directory(parent, Some(self.directory.clone()));
+                        }
+                    }
+                }
+                // Show the selected entry in the directory pane
+                KeyCode::Char('o') => {
+                    if let Some(selected) = self.selected_entry() {
+                        self.close();
+                        return Some(DiskUsageEvent::Selected(selected));
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        frame.render_widget(Clear, area);
+        let Some(disk_usage) = &self.disk_usage else {
+            let message = format!(
+                "Scanning… {} files, {}",
+                self.file_count,
+                util::size_string(self.byte_count)
+            );
+            let block = components::component_block(true).title("[Disk usage]");
+            frame.render_widget(Paragraph::new(message).block(block), area);
+            return;
+        };
+        let total = disk_usage.size(&self.directory);
+        let title = format!(
+            "[{} - {}]",
+            self.directory.to_string_lossy(),
+            util::size_string(total)
+        );
+        let block = components::component_block(true).title(title);
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|(path, size)| {
+                let share = if total == 0 {
+                    0.0
+                } else {
+                    *size as f64 / total as f64
+                };
+                let filled = (share * BAR_WIDTH as f64).round() as usize;
+                let icon = if disk_usage.is_directory(path) {
+                    constants::DIRECTORY_ICON
+                } else {
+                    constants::DOCUMENT_ICON
+                };
+                ListItem::new(format!(
+                    "{:>10} [{}{}] {:>5.1}% {icon} {}",
+                    util::size_string(*size),
+                    "█".repeat(filled),
+                    " ".repeat(BAR_WIDTH - filled),
+                    share * 100.0,
+                    util::entry_name(path)
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+        frame.render_stateful_widget(list, area, &mut self.entries.state);
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Select"),
+            KeyHint::new("Enter", "Open"),
+            KeyHint::new("Bksp", "Parent"),
+            KeyHint::new("o", "Show in directory"),
+            KeyHint::new("Esc", "Close"),
+        ]
+    }
+
+    // Lists what's in the directory, selecting the given entry, or the first one
+    fn show_directory(&mut self, directory: PathBuf, selected: Option<PathBuf>) {
+        let Some(disk_usage) = &self.disk_usage else {
+            return;
+        };
+        let entries = disk_usage.children(&directory);
+        let index = selected
+            .and_then(|selected| entries.iter().position(|(path, _)| *path == selected))
+            .unwrap_or(0);
+        self.entries = StatefulList::with_items(entries);
+        if !self.entries.is_empty() {
+            self.entries.set_selected(Some(index));
+        }
+        self.directory = directory;
+    }
+
+    fn selected_entry(&self) -> Option<PathBuf> {
+        self.entries
+            .selected()
+            .filter(|selected| *selected < self.entries.len())
+            .map(|selected| self.entries[selected].0.clone())
+    }
+
+    fn start_scan(&mut self) {
+        let cancellation_token = CancellationToken::new();
+        self.cancellation_token = Some(cancellation_token.clone());
+        self.scan_id = self.scan_id.wrapping_add(1);
+
+        let scan_id = self.scan_id;
+        let root = self.root.clone();
+        let event_tx = self.event_tx.clone().unwrap();
+        debug!(scan_id, root = %root.display(), "disk usage scan started");
+        let task = tasks::start();
+        tokio::task::spawn_blocking(move || {
+            let _task = task;
+            let result = disk_usage::scan(&root, &cancellation_token, |file_count, byte_count| {
+                // The receiver is only gone if the app is shutting down
+                let _ = event_tx.send(Event::DiskUsageProgress(scan_id, file_count, byte_count));
+            });
+            if let Some(disk_usage) = result {
+                let _ = event_tx.send(Event::DiskUsageFinished(scan_id, disk_usage));
+            }
+        });
+    }
+
+    fn cancel_scan(&mut self) {
+        if let Some(cancellation_token) = self.cancellation_token.take() {
+            cancellation_token.cancel();
+        }
+    }
+}
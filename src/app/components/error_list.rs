@@ -0,0 +1,119 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Clear, Paragraph, Wrap};
+use ratatui::{layout::Rect, Frame};
+
+use crate::app::fs_error::QueuedError;
+use crate::app::{components, styles};
+use crate::util;
+
+use super::key_hints::KeyHint;
+
+pub enum ErrorListEvent {
+    Cleared,
+}
+
+// A popup with the errors that have happened, newest first, with their full messages and the
+// directories they happened in, scrolled a line or a page at a time
+#[derive(Default)]
+pub struct ErrorList {
+    is_open: bool,
+    text: Text<'static>,
+    scroll: u16,
+    page_height: u16,
+}
+
+impl ErrorList {
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn open(&mut self, errors: &[QueuedError]) {
+        self.is_open = true;
+        self.scroll = 0;
+        self.text = Self::text(errors);
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<ErrorListEvent> {
+        if util::is_up_key(key_event) {
+            self.scroll = self.scroll.saturating_sub(1);
+        } else if util::is_down_key(key_event) {
+            self.scroll = self.scroll.saturating_add(1);
+        } else {
+            match key_event.code {
+                KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(self.page_height),
+                KeyCode::PageDown => self.scroll = self.scroll.saturating_add(self.page_height),
+                KeyCode::Home => self.scroll = 0,
+                // Scrolled back into range when it's drawn
+                KeyCode::End => self.scroll = u16::MAX,
+                KeyCode::Char('C') => {
+                    self.close();
+                    return Some(ErrorListEvent::Cleared);
+                }
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.close(),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // How tall the popup has to be to show every error without wrapping
+    pub fn height(&self) -> u16 {
+        self.text.height().max(1) as u16 + 2
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let block = components::component_block(true).title("[Errors]");
+        let inner_area = block.inner(area);
+        let paragraph = Paragraph::new(self.text.clone()).wrap(Wrap { trim: false });
+        let line_count = paragraph.line_count(inner_area.width) as u16;
+        self.page_height = inner_area.height.max(1);
+        self.scroll = self
+            .scroll
+            .min(line_count.saturating_sub(inner_area.height));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph.block(block).scroll((self.scroll, 0)), area);
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Scroll"),
+            KeyHint::new("C", "Clear errors"),
+            KeyHint::new("Esc", "Close"),
+        ]
+    }
+
+    fn text(errors: &[QueuedError]) -> Text<'static> {
+        if errors.is_empty() {
+            return Text::from("There haven't been any errors.");
+        }
+        let mut lines = vec![];
+        for (index, error) in errors.iter().rev().enumerate() {
+            if index > 0 {
+                lines.push(Line::default());
+            }
+            lines.push(Line::from(vec![
+                Span::styled(error.time.clone(), styles::LINE_NUMBER_STYLE),
+                Span::raw("  "),
+                Span::styled(error.error.to_string(), styles::ERROR_STYLE),
+            ]));
+            if let Some(directory) = &error.directory {
+                lines.push(Line::styled(
+                    format!("In {}", directory.to_string_lossy()),
+                    styles::LINE_NUMBER_STYLE,
+                ));
+            }
+        }
+        Text::from(lines)
+    }
+}
@@ -0,0 +1,273 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::prelude::{Line, Span};
+use ratatui::widgets::{Clear, List, ListItem, Paragraph};
+use ratatui::{layout::Rect, Frame};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+use crate::app::{components, styles, tasks};
+use crate::stateful_list::StatefulList;
+use crate::tui::Event;
+use crate::util;
+
+use super::key_hints::KeyHint;
+
+// The most matches that are kept and shown
+const MAX_MATCHES: usize = 1000;
+
+pub enum FinderEvent {
+    Cancelled,
+    Selected(PathBuf),
+}
+
+#[derive(Default)]
+pub struct Finder {
+    is_open: bool,
+    input: String,
+    event_tx: Option<UnboundedSender<Event>>,
+
+    // The directory being searched, and the paths under it, relative to it
+    root: PathBuf,
+    paths: Vec<String>,
+
+    // Indexes into paths of the best matches for the input, best first
+    matches: StatefulList<usize>,
+
+    // The walk that's finding paths
+    walk_id: u32,
+    is_walking: bool,
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl Finder {
+    pub fn set_event_tx(&mut self, event_tx: Option<UnboundedSender<Event>>) {
+        self.event_tx = event_tx;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    // Opens the finder and starts walking the directory tree under root in the background
+    pub fn open(&mut self, root: PathBuf, show_hidden: bool) {
+        self.cancel_walk();
+        self.is_open = true;
+        self.input.clear();
+        self.paths.clear();
+        self.matches = StatefulList::default();
+        self.root = root.clone();
+
+        let cancellation_token = CancellationToken::new();
+        self.cancellation_token = Some(cancellation_token.clone());
+        self.walk_id = self.walk_id.wrapping_add(1);
+        self.is_walking = true;
+
+        let walk_id = self.walk_id;
+        let event_tx = self.event_tx.clone().unwrap();
+        debug!(walk_id, root = %root.display(), "finder walk started");
+        let task = tasks::start();
+        tokio::task::spawn_blocking(move || {
+            let _task = task;
+            components::walk_directory(&root, show_hidden, &cancellation_token, |batch| {
+                // The receiver is only gone if the app is shutting down
+                let _ = event_tx.send(Event::FinderResults(walk_id, batch));
+            });
+            let _ = event_tx.send(Event::FinderFinished(walk_id));
+        });
+    }
+
+    pub fn close(&mut self) {
+        self.cancel_walk();
+        self.is_open = false;
+        self.paths.clear();
+        self.matches = StatefulList::default();
+    }
+
+    pub fn add_paths(&mut self, walk_id: u32, paths: Vec<PathBuf>) {
+        // Ignore paths from a walk that's been replaced or cancelled
+        if !self.is_open || walk_id != self.walk_id {
+            return;
+        }
+        self.paths.extend(paths.iter().map(|path| {
+            let relative = path.strip_prefix(&self.root).unwrap_or(path);
+            relative.to_string_lossy().to_string()
+        }));
+        self.update_matches();
+    }
+
+    pub fn set_finished(&mut self, walk_id: u32) {
+        if walk_id == self.walk_id {
+            self.is_walking = false;
+            self.cancellation_token = None;
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<FinderEvent> {
+        if util::is_up_key(key_event) {
+            self.matches.previous();
+        } else if util::is_down_key(key_event) {
+            self.matches.next();
+        } else {
+            match key_event.code {
+                KeyCode::Esc => {
+                    self.close();
+                    return Some(FinderEvent::Cancelled);
+                }
+                KeyCode::Enter => {
+                    if let Some(path) = self.selected_path() {
+                        self.close();
+                        return Some(FinderEvent::Selected(path));
+                    }
+                }
+                KeyCode::Backspace => {
+                    if self.input.pop().is_some() {
+                        self.update_matches();
+                    }
+                }
+                KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.input.push(c);
+                    self.update_matches();
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        self.input.push_str(text);
+        self.update_matches();
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let title = format!(
+            "[Find: {}/{}{}]",
+            self.matches.len(),
+            self.paths.len(),
+            if self.is_walking { " …" } else { "" }
+        );
+        let block = components::component_block(true).title(title);
+        let inner_area = block.inner(area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(1)])
+            .split(inner_area);
+
+        // Only the visible matches are highlighted, since that means matching them again
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let offset = self.matches.state.offset();
+        let height = layout[1].height as usize;
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .enumerate()
+            .map(|(index, path_index)| {
+                if index < offset || index > offset + height {
+                    ListItem::new("") // Off screen
+                } else {
+                    let path = &self.paths[*path_index];
+                    let indices = matcher
+                        .fuzzy_indices(path, &self.input)
+                        .map(|(_, indices)| indices)
+                        .unwrap_or_default();
+                    ListItem::new(Self::highlighted_line(path, &indices))
+                }
+            })
+            .collect();
+        let list = List::new(items).highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(Paragraph::new(format!("> {}", self.input)), layout[0]);
+        frame.render_stateful_widget(list, layout[1], &mut self.matches.state);
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Select"),
+            KeyHint::new("Enter", "Go to"),
+            KeyHint::new("Esc", "Close"),
+        ]
+    }
+
+    fn cancel_walk(&mut self) {
+        if let Some(cancellation_token) = self.cancellation_token.take() {
+            cancellation_token.cancel();
+        }
+        self.is_walking = false;
+    }
+
+    fn selected_path(&self) -> Option<PathBuf> {
+        self.matches
+            .selected()
+            .filter(|selected| *selected < self.matches.len())
+            .map(|selected| {
+                self.root
+                    .join(Path::new(&self.paths[self.matches[selected]]))
+            })
+    }
+
+    // Scores every path against the input and keeps the best matches
+    fn update_matches(&mut self) {
+        let selected_path = self
+            .matches
+            .selected()
+            .filter(|selected| *selected < self.matches.len())
+            .map(|selected| self.matches[selected]);
+
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let mut scored: Vec<(i64, usize)> = self
+            .paths
+            .iter()
+            .enumerate()
+            .filter_map(|(index, path)| {
+                matcher
+                    .fuzzy_match(path, &self.input)
+                    .map(|score| (score, index))
+            })
+            .collect();
+        // Better scores first, then shorter paths
+        scored.sort_by(|(lhs_score, lhs_index), (rhs_score, rhs_index)| {
+            rhs_score.cmp(lhs_score).then_with(|| {
+                self.paths[*lhs_index]
+                    .len()
+                    .cmp(&self.paths[*rhs_index].len())
+            })
+        });
+        scored.truncate(MAX_MATCHES);
+
+        self.matches =
+            StatefulList::with_items(scored.into_iter().map(|(_, index)| index).collect());
+        // Keep the selection on the same path as more paths arrive
+        let selected = selected_path
+            .and_then(|path_index| self.matches.index_of(&path_index))
+            .unwrap_or(0);
+        self.matches.set_selected(Some(selected));
+    }
+
+    fn highlighted_line<'a>(path: &str, indices: &[usize]) -> Line<'a> {
+        let spans: Vec<Span> = path
+            .chars()
+            .enumerate()
+            .map(|(index, c)| {
+                if indices.contains(&index) {
+                    Span::styled(c.to_string(), styles::MATCH_STYLE)
+                } else {
+                    Span::raw(c.to_string())
+                }
+            })
+            .collect();
+        Line::from(spans)
+    }
+}
@@ -3,13 +3,34 @@
  * Created 2024-03-17
  */
 
+use crate::app::components::command_line::{self, Completer};
 use crate::util;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::prelude::{Line, Span};
 use ratatui::{layout::Rect, widgets::Paragraph, Frame};
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Component, PathBuf, MAIN_SEPARATOR};
+
+use super::key_hints::KeyHint;
+
+pub enum HeadEvent {
+    Cancelled,
+    Submitted(String),
+}
 
 #[derive(Default)]
 pub struct Head {
     path: Option<PathBuf>,
+
+    // The columns of the path's components where they were drawn, and the paths they stand for,
+    // so clicking one goes there
+    area: Rect,
+    breadcrumbs: Vec<(Range<u16>, PathBuf)>,
+
+    // The path being typed, while the path bar is being edited
+    is_editing: bool,
+    input: String,
+    completer: Completer,
 }
 
 impl Head {
@@ -17,16 +38,158 @@ impl Head {
         self.path = path;
     }
 
+    pub fn is_editing(&self) -> bool {
+        self.is_editing
+    }
+
+    // Starts editing the path bar, beginning with the current path
+    pub fn start_editing(&mut self) {
+        self.is_editing = true;
+        self.input = match &self.path {
+            Some(path) => util::entry_path(path.as_path()),
+            None => String::new(),
+        };
+        // Ready to type the next component
+        if !self.input.ends_with(std::path::MAIN_SEPARATOR) {
+            self.input.push(std::path::MAIN_SEPARATOR);
+        }
+        self.completer.reset();
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<HeadEvent> {
+        if key_event.code != KeyCode::Tab {
+            self.completer.reset();
+        }
+        match key_event.code {
+            KeyCode::Esc => {
+                self.is_editing = false;
+                return Some(HeadEvent::Cancelled);
+            }
+            KeyCode::Enter => {
+                self.is_editing = false;
+                return Some(HeadEvent::Submitted(self.input.clone()));
+            }
+            KeyCode::Tab => self
+                .completer
+                .complete(&mut self.input, command_line::path_completions),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            // Ctrl+U clears the path, to type a different one
+            KeyCode::Char('u') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.input.clear();
+            }
+            KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input.push(c);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    // Adds pasted text to the path being typed
+    pub fn paste(&mut self, text: &str) {
+        self.completer.reset();
+        self.input.push_str(text);
+    }
+
     pub fn render(&mut self, area: Rect, frame: &mut Frame) {
-        let text = if let Some(path) = &self.path {
-            util::entry_path(path.as_path())
-        } else {
-            String::new()
+        if self.is_editing {
+            let text = util::clip_string(&self.input, area.width.saturating_sub(1) as usize);
+            let cursor_x = area.x + util::display_width(&text) as u16;
+            frame.render_widget(Paragraph::new(text), area);
+            frame.set_cursor(cursor_x, area.y);
+            return;
+        }
+        self.area = area;
+        self.breadcrumbs.clear();
+        let mut segments = vec![("[".to_string(), None)];
+        if let Some(path) = &self.path {
+            segments.extend(Self::segments(path));
+        }
+        segments.push(("]".to_string(), None));
+
+        // The first components are left off if the path is too long, leaving the end of it
+        let width = |segments: &[(String, Option<PathBuf>)]| -> usize {
+            segments
+                .iter()
+                .map(|(text, _)| util::display_width(text))
+                .sum()
         };
-        let text = format!("[{text}]");
-        frame.render_widget(
-            Paragraph::new(util::clip_string(&text, area.width as usize)),
-            area,
-        );
+        while width(&segments) > area.width as usize && segments.len() > 2 {
+            segments.remove(1);
+            segments[0].0 = "[…".to_string();
+        }
+
+        let mut x = area.x;
+        let mut spans = vec![];
+        for (text, target) in segments {
+            let end = x.saturating_add(util::display_width(&text) as u16);
+            if let Some(target) = target {
+                self.breadcrumbs.push((x..end, target));
+            }
+            spans.push(Span::raw(text));
+            x = end;
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    // The path to go to when a breadcrumb is clicked there
+    pub fn breadcrumb_at(&self, x: u16, y: u16) -> Option<PathBuf> {
+        if self.is_editing || y != self.area.y {
+            return None;
+        }
+        self.breadcrumbs
+            .iter()
+            .find(|(columns, _)| columns.contains(&x))
+            .map(|(_, target)| target.clone())
+    }
+
+    // The path's components as text with the path each one stands for, and the separators between
+    // them as text that doesn't stand for anything
+    fn segments(path: &std::path::Path) -> Vec<(String, Option<PathBuf>)> {
+        let mut segments = vec![];
+        let mut target = PathBuf::new();
+        let mut components = path.components();
+        // A URL's scheme and host are one segment, which stands for the root
+        if path.to_string_lossy().contains("://") {
+            if let (Some(scheme), Some(host)) = (components.next(), components.next()) {
+                target.push(scheme);
+                target.push(host);
+                let text = format!(
+                    "{}//{}",
+                    scheme.as_os_str().to_string_lossy(),
+                    host.as_os_str().to_string_lossy()
+                );
+                segments.push((text, Some(target.clone())));
+            }
+        }
+        for component in components {
+            target.push(component);
+            let text = match component {
+                Component::RootDir => MAIN_SEPARATOR.to_string(),
+                component => {
+                    // The root already ends with a separator
+                    if segments
+                        .last()
+                        .is_some_and(|(text, _): &(String, _)| !text.ends_with(MAIN_SEPARATOR))
+                    {
+                        segments.push((MAIN_SEPARATOR.to_string(), None));
+                    }
+                    component.as_os_str().to_string_lossy().to_string()
+                }
+            };
+            segments.push((text, Some(target.clone())));
+        }
+        segments
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("Tab", "Complete"),
+            KeyHint::new("^U", "Clear"),
+            KeyHint::new("Enter", "Go"),
+            KeyHint::new("Esc", "Cancel"),
+        ]
     }
 }
@@ -0,0 +1,384 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::fs::Metadata;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::widgets::{Clear, LineGauge, Paragraph};
+use ratatui::{layout::Rect, Frame};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+#[cfg(unix)]
+use crate::app::attributes;
+use crate::app::checksum::{self, Checksums};
+use crate::app::disk_usage;
+use crate::app::{components, styles, tasks};
+use crate::tui::Event;
+use crate::util;
+
+use super::key_hints::KeyHint;
+
+// The details of a directory entry, with its checksums, or a directory's total size, computed in
+// the background
+#[derive(Default)]
+pub struct Info {
+    is_open: bool,
+    event_tx: Option<UnboundedSender<Event>>,
+
+    // The entry's properties, except for its checksums
+    entry: PathBuf,
+    properties: Vec<(&'static str, String)>,
+
+    // The checksum computation, how far it's got, and what it came up with
+    checksum_id: u32,
+    size: u64,
+    progress: u64,
+    checksums: Option<Result<Checksums, String>>,
+    cancellation_token: Option<CancellationToken>,
+
+    // The number of files under a directory and their total size, as far as they've been added
+    // up, whether they've all been, and the computation doing it
+    size_id: u32,
+    directory_size: (u64, u64),
+    is_directory_size_final: bool,
+    size_cancellation_token: Option<CancellationToken>,
+}
+
+impl Info {
+    pub fn set_event_tx(&mut self, event_tx: Option<UnboundedSender<Event>>) {
+        self.event_tx = event_tx;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    // Opens the popup for the entry, shown by the name it's given, and starts computing its
+    // checksums if it's a file
+    pub fn open(&mut self, entry: &Path, display_path: &Path) -> io::Result<()> {
+        let metadata = entry.symlink_metadata()?;
+        self.cancel_checksums();
+        self.cancel_directory_size();
+        self.is_open = true;
+        self.entry = entry.to_path_buf();
+        self.properties = Self::properties(entry, display_path, &metadata);
+        self.size = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        self.progress = 0;
+        self.checksums = None;
+        self.directory_size = (0, 0);
+        self.is_directory_size_final = false;
+        // Links are followed for the checksums, but not for the directory size
+        if entry.is_file() {
+            self.start_checksums();
+        } else if metadata.is_dir() {
+            self.start_directory_size();
+        }
+        Ok(())
+    }
+
+    pub fn close(&mut self) {
+        self.cancel_checksums();
+        self.cancel_directory_size();
+        self.is_open = false;
+    }
+
+    pub fn set_directory_size(&mut self, size_id: u32, file_count: u64, byte_count: u64) {
+        if self.is_open && size_id == self.size_id {
+            self.directory_size = (file_count, byte_count);
+        }
+    }
+
+    pub fn set_directory_size_final(&mut self, size_id: u32, file_count: u64, byte_count: u64) {
+        if self.is_open && size_id == self.size_id {
+            self.directory_size = (file_count, byte_count);
+            self.is_directory_size_final = true;
+            self.size_cancellation_token = None;
+        }
+    }
+
+    pub fn set_checksum_progress(&mut self, checksum_id: u32, progress: u64) {
+        if self.is_open && checksum_id == self.checksum_id {
+            self.progress = progress;
+        }
+    }
+
+    pub fn set_checksums(&mut self, checksum_id: u32, checksums: Result<Checksums, String>) {
+        if self.is_open && checksum_id == self.checksum_id {
+            self.checksums = Some(checksums);
+            self.cancellation_token = None;
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if let KeyCode::Esc | KeyCode::Enter | KeyCode::Char('i') | KeyCode::Char('q') =
+            key_event.code
+        {
+            self.close();
+        }
+    }
+
+    // How tall the popup has to be to show everything
+    pub fn height(&self) -> u16 {
+        self.properties.len() as u16 + 2 + self.gauge_height() + 2
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let block = components::component_block(true).title("[Details]");
+        let inner_area = block.inner(area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(self.gauge_height())])
+            .split(inner_area);
+
+        let mut properties = self.properties.clone();
+        // A directory's size is what's in it, which is shown as it's added up
+        if self.size_cancellation_token.is_some() || self.is_directory_size_final {
+            let (file_count, byte_count) = self.directory_size;
+            let more = if self.is_directory_size_final {
+                ""
+            } else {
+                " so far…"
+            };
+            let size = format!(
+                "{} bytes ({}) in {} files{more}",
+                Self::grouped(byte_count),
+                util::size_string(byte_count),
+                Self::grouped(file_count)
+            );
+            if let Some(property) = properties.iter_mut().find(|(name, _)| *name == "Size") {
+                property.1 = size;
+            }
+        }
+        if !self.entry.is_file() {
+            properties.push(("MD5", "—".to_string()));
+            properties.push(("SHA-256", "—".to_string()));
+        } else {
+            let (md5, sha256) = match &self.checksums {
+                None => ("Computing…".to_string(), "Computing…".to_string()),
+                Some(Ok(checksums)) => (checksums.md5.clone(), checksums.sha256.clone()),
+                Some(Err(error)) => (error.clone(), error.clone()),
+            };
+            properties.push(("MD5", md5));
+            properties.push(("SHA-256", sha256));
+        }
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(
+            Paragraph::new(components::property_lines(properties)),
+            layout[0],
+        );
+        if self.is_computing() {
+            let ratio = if self.size == 0 {
+                0.0
+            } else {
+                (self.progress as f64 / self.size as f64).min(1.0)
+            };
+            let gauge = LineGauge::default()
+                .label(format!(
+                    "{} of {}",
+                    util::size_string(self.progress),
+                    util::size_string(self.size)
+                ))
+                .gauge_style(styles::PROPERTY_NAME_STYLE)
+                .ratio(ratio);
+            frame.render_widget(gauge, layout[1]);
+        }
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![KeyHint::new("Esc", "Close")]
+    }
+
+    fn is_computing(&self) -> bool {
+        self.cancellation_token.is_some()
+    }
+
+    fn gauge_height(&self) -> u16 {
+        if self.is_computing() {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn start_checksums(&mut self) {
+        let cancellation_token = CancellationToken::new();
+        self.cancellation_token = Some(cancellation_token.clone());
+        self.checksum_id = self.checksum_id.wrapping_add(1);
+
+        let checksum_id = self.checksum_id;
+        let entry = self.entry.clone();
+        let event_tx = self.event_tx.clone().unwrap();
+        debug!(checksum_id, entry = %entry.display(), "computing checksums");
+        let task = tasks::start();
+        tokio::task::spawn_blocking(move || {
+            let _task = task;
+            let result = checksum::compute(&entry, &cancellation_token, |progress| {
+                // The receiver is only gone if the app is shutting down
+                let _ = event_tx.send(Event::ChecksumProgress(checksum_id, progress));
+            });
+            let checksums = match result {
+                Ok(Some(checksums)) => Ok(checksums),
+                Ok(None) => return, // Cancelled
+                Err(error) => {
+                    warn!(checksum_id, %error, "checksums failed");
+                    Err(error.to_string())
+                }
+            };
+            let _ = event_tx.send(Event::ChecksumsFinished(checksum_id, checksums));
+        });
+    }
+
+    fn start_directory_size(&mut self) {
+        let cancellation_token = CancellationToken::new();
+        self.size_cancellation_token = Some(cancellation_token.clone());
+        self.size_id = self.size_id.wrapping_add(1);
+
+        let size_id = self.size_id;
+        let entry = self.entry.clone();
+        let event_tx = self.event_tx.clone().unwrap();
+        debug!(size_id, entry = %entry.display(), "adding up directory size");
+        let task = tasks::start();
+        tokio::task::spawn_blocking(move || {
+            let _task = task;
+            let result =
+                disk_usage::total_size(&entry, &cancellation_token, |file_count, byte_count| {
+                    // The receiver is only gone if the app is shutting down
+                    let _ = event_tx.send(Event::DirectorySizeProgress(
+                        size_id, file_count, byte_count,
+                    ));
+                });
+            if let Some((file_count, byte_count)) = result {
+                let _ = event_tx.send(Event::DirectorySizeFinished(
+                    size_id, file_count, byte_count,
+                ));
+            }
+        });
+    }
+
+    fn cancel_directory_size(&mut self) {
+        if let Some(cancellation_token) = self.size_cancellation_token.take() {
+            cancellation_token.cancel();
+        }
+    }
+
+    fn cancel_checksums(&mut self) {
+        if let Some(cancellation_token) = self.cancellation_token.take() {
+            cancellation_token.cancel();
+        }
+    }
+
+    fn properties(
+        entry: &Path,
+        display_path: &Path,
+        metadata: &Metadata,
+    ) -> Vec<(&'static str, String)> {
+        let time = |time: io::Result<SystemTime>| {
+            // Some file systems don't record when a file was created, and say it was at the epoch
+            time.ok()
+                .filter(|time| *time != SystemTime::UNIX_EPOCH)
+                .map(|time| {
+                    DateTime::<Local>::from(time)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                })
+                .unwrap_or_else(|| "—".to_string())
+        };
+        let mut properties = vec![
+            ("Path", display_path.to_string_lossy().to_string()),
+            (
+                "Size",
+                format!(
+                    "{} bytes ({})",
+                    Self::grouped(metadata.len()),
+                    util::size_string(metadata.len())
+                ),
+            ),
+            ("Created", time(metadata.created())),
+            ("Modified", time(metadata.modified())),
+            ("Accessed", time(metadata.accessed())),
+        ];
+        properties.extend(Self::system_properties(entry, metadata));
+        properties
+    }
+
+    #[cfg(unix)]
+    fn system_properties(entry: &Path, metadata: &Metadata) -> Vec<(&'static str, String)> {
+        use std::os::unix::fs::MetadataExt;
+
+        let user = attributes::user_name(metadata.uid());
+        let group = attributes::group_name(metadata.gid());
+        let mut properties = vec![
+            (
+                "Permissions",
+                format!(
+                    "{} ({:04o})",
+                    util::permissions_string(metadata),
+                    metadata.mode() & 0o7777
+                ),
+            ),
+            ("Owner", format!("{user}:{group}")),
+            (
+                "Inode",
+                format!("{} on device {}", metadata.ino(), metadata.dev()),
+            ),
+            ("Links", metadata.nlink().to_string()),
+        ];
+        match attributes::read(entry) {
+            Ok(attributes) => {
+                properties.extend(Self::list_properties(
+                    "Attributes",
+                    attributes
+                        .xattrs
+                        .into_iter()
+                        .map(|(name, value)| format!("{name} = {value}"))
+                        .collect(),
+                ));
+                properties.extend(Self::list_properties("ACL", attributes.acl));
+            }
+            Err(error) => properties.push(("Attributes", error.to_string())),
+        }
+        properties
+    }
+
+    // A list of values, with the name on the first one only
+    #[cfg(unix)]
+    fn list_properties(name: &'static str, values: Vec<String>) -> Vec<(&'static str, String)> {
+        if values.is_empty() {
+            return vec![(name, "—".to_string())];
+        }
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| (if index == 0 { name } else { "" }, value))
+            .collect()
+    }
+
+    #[cfg(not(unix))]
+    fn system_properties(_entry: &Path, metadata: &Metadata) -> Vec<(&'static str, String)> {
+        vec![("Permissions", util::permissions_string(metadata))]
+    }
+
+    // The number with its digits grouped in threes
+    fn grouped(number: u64) -> String {
+        let digits = number.to_string();
+        let mut grouped = String::new();
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+}
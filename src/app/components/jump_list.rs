@@ -0,0 +1,149 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::widgets::{Clear, List, ListItem, Paragraph};
+use ratatui::{layout::Rect, Frame};
+
+use crate::app::{components, styles};
+use crate::stateful_list::StatefulList;
+use crate::util;
+
+use super::key_hints::KeyHint;
+
+pub enum JumpListEvent {
+    Cancelled,
+    Selected(PathBuf),
+}
+
+// A popup list of directories to jump to, best first, narrowed down by what's typed
+#[derive(Default)]
+pub struct JumpList {
+    is_open: bool,
+    title: String,
+    input: String,
+    directories: Vec<PathBuf>,
+
+    // Indexes into directories of the ones that match the input, in the same order
+    matches: StatefulList<usize>,
+}
+
+impl JumpList {
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn open(&mut self, title: &str, directories: Vec<PathBuf>) {
+        self.is_open = true;
+        self.title = format!("[{title}]");
+        self.input.clear();
+        self.directories = directories;
+        self.update_matches();
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<JumpListEvent> {
+        if util::is_up_key(key_event) {
+            self.matches.previous();
+        } else if util::is_down_key(key_event) {
+            self.matches.next();
+        } else {
+            match key_event.code {
+                KeyCode::Esc => {
+                    self.close();
+                    return Some(JumpListEvent::Cancelled);
+                }
+                KeyCode::Enter => {
+                    if let Some(selected) = self.selected_index() {
+                        let directory = self.directories[self.matches[selected]].clone();
+                        self.close();
+                        return Some(JumpListEvent::Selected(directory));
+                    }
+                }
+                KeyCode::Backspace => {
+                    if self.input.pop().is_some() {
+                        self.update_matches();
+                    }
+                }
+                KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.input.push(c);
+                    self.update_matches();
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        self.input.push_str(text);
+        self.update_matches();
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let block = components::component_block(true).title(self.title.as_str());
+        let inner_area = block.inner(area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(1)])
+            .split(inner_area);
+
+        let items: Vec<ListItem> = if self.directories.is_empty() {
+            vec![ListItem::new("No directories")]
+        } else {
+            self.matches
+                .iter()
+                .map(|index| ListItem::new(self.directories[*index].to_string_lossy().to_string()))
+                .collect()
+        };
+        let list = List::new(items).highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(Paragraph::new(format!("> {}", self.input)), layout[0]);
+        frame.render_stateful_widget(list, layout[1], &mut self.matches.state);
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("Type", "Filter"),
+            KeyHint::new("↑/↓", "Select"),
+            KeyHint::new("Enter", "Go"),
+            KeyHint::new("Esc", "Close"),
+        ]
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        self.matches
+            .selected()
+            .filter(|selected| *selected < self.matches.len())
+    }
+
+    // Keeps the directories whose paths fuzzy match the input, in their original order
+    fn update_matches(&mut self) {
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let matches = self
+            .directories
+            .iter()
+            .enumerate()
+            .filter(|(_, directory)| {
+                matcher
+                    .fuzzy_match(&directory.to_string_lossy(), &self.input)
+                    .is_some()
+            })
+            .map(|(index, _)| index)
+            .collect();
+        self.matches = StatefulList::with_items(matches);
+        self.matches.first();
+    }
+}
@@ -0,0 +1,49 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use ratatui::prelude::{Line, Span};
+use ratatui::{layout::Rect, widgets::Paragraph, Frame};
+
+use crate::app::styles;
+
+/// A key (or key combination) and a short description of what it does
+#[derive(Clone, Debug)]
+pub struct KeyHint {
+    key: String,
+    action: &'static str,
+}
+
+impl KeyHint {
+    /// A hint for the key, which is written like "^X" for Ctrl+X and "M-x" for Alt+X
+    pub fn new(key: impl Into<String>, action: &'static str) -> KeyHint {
+        KeyHint {
+            key: key.into(),
+            action,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct KeyHints {
+    hints: Vec<KeyHint>,
+}
+
+impl KeyHints {
+    pub fn set_hints(&mut self, hints: Vec<KeyHint>) {
+        self.hints = hints;
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let mut spans = vec![];
+        for hint in &self.hints {
+            if !spans.is_empty() {
+                spans.push(Span::raw("  "));
+            }
+            spans.push(Span::styled(hint.key.clone(), styles::KEY_HINT_KEY_STYLE));
+            spans.push(Span::raw(format!(" {}", hint.action)));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+}
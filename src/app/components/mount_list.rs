@@ -0,0 +1,127 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::widgets::{Clear, List, ListItem};
+use ratatui::{layout::Rect, Frame};
+
+use crate::app::mounts::Mount;
+use crate::app::{components, styles};
+use crate::stateful_list::StatefulList;
+use crate::util;
+
+use super::key_hints::KeyHint;
+
+pub enum MountListEvent {
+    Cancelled,
+    Selected(PathBuf),
+}
+
+// A popup list of the drives or mounted file systems, with their labels, file system types and
+// free space
+#[derive(Default)]
+pub struct MountList {
+    is_open: bool,
+    mounts: StatefulList<Mount>,
+}
+
+impl MountList {
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn open(&mut self, mounts: Vec<Mount>) {
+        self.is_open = true;
+        self.mounts = StatefulList::with_items(mounts);
+        self.mounts.first();
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<MountListEvent> {
+        if util::is_up_key(key_event) {
+            self.mounts.previous();
+        } else if util::is_down_key(key_event) {
+            self.mounts.next();
+        } else {
+            match key_event.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.close();
+                    return Some(MountListEvent::Cancelled);
+                }
+                KeyCode::Enter => {
+                    if let Some(selected) = self
+                        .mounts
+                        .selected()
+                        .filter(|selected| *selected < self.mounts.len())
+                    {
+                        self.close();
+                        return Some(MountListEvent::Selected(self.mounts[selected].path.clone()));
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // How tall the popup has to be to show every mount
+    pub fn height(&self) -> u16 {
+        self.mounts.len().max(1) as u16 + 2
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let block = components::component_block(true).title("[Drives]");
+        let path_width = self
+            .mounts
+            .iter()
+            .map(|mount| util::display_width(&mount.path.to_string_lossy()))
+            .max()
+            .unwrap_or(0);
+        let label_width = self
+            .mounts
+            .iter()
+            .map(|mount| util::display_width(&mount.label))
+            .max()
+            .unwrap_or(0);
+        let items: Vec<ListItem> = if self.mounts.is_empty() {
+            vec![ListItem::new("No drives found.")]
+        } else {
+            self.mounts
+                .iter()
+                .map(|mount| {
+                    let free_space = mount
+                        .free_space
+                        .map(|free_space| format!("{} free", util::size_string(free_space)))
+                        .unwrap_or_default();
+                    ListItem::new(format!(
+                        "{:path_width$}  {:label_width$}  {:8}  {free_space}",
+                        mount.path.to_string_lossy(),
+                        mount.label,
+                        mount.file_system
+                    ))
+                })
+                .collect()
+        };
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.mounts.state);
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Select"),
+            KeyHint::new("Enter", "Go"),
+            KeyHint::new("Esc", "Close"),
+        ]
+    }
+}
@@ -0,0 +1,108 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::widgets::{Clear, List, ListItem};
+use ratatui::{layout::Rect, Frame};
+
+use crate::app::opener::Opener;
+use crate::app::{components, styles};
+use crate::stateful_list::StatefulList;
+use crate::util;
+
+use super::key_hints::KeyHint;
+
+pub enum OpenWithMenuEvent {
+    Cancelled,
+    Selected(Opener, PathBuf),
+}
+
+// A popup list of what a file can be opened with: the editor, the OS's default application, and
+// the programs in the settings
+#[derive(Default)]
+pub struct OpenWithMenu {
+    is_open: bool,
+    file: PathBuf,
+    openers: StatefulList<Opener>,
+}
+
+impl OpenWithMenu {
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn open(&mut self, file: &Path, programs: &[String]) {
+        let mut openers = vec![Opener::Editor, Opener::Default];
+        openers.extend(programs.iter().cloned().map(Opener::Program));
+        self.is_open = true;
+        self.file = file.to_path_buf();
+        self.openers = StatefulList::with_items(openers);
+        self.openers.first();
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<OpenWithMenuEvent> {
+        if util::is_up_key(key_event) {
+            self.openers.previous();
+        } else if util::is_down_key(key_event) {
+            self.openers.next();
+        } else {
+            match key_event.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.close();
+                    return Some(OpenWithMenuEvent::Cancelled);
+                }
+                KeyCode::Enter => {
+                    if let Some(selected) = self
+                        .openers
+                        .selected()
+                        .filter(|selected| *selected < self.openers.len())
+                    {
+                        self.close();
+                        return Some(OpenWithMenuEvent::Selected(
+                            self.openers[selected].clone(),
+                            self.file.clone(),
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    pub fn height(&self) -> u16 {
+        self.openers.len() as u16 + 2
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let title = format!("[Open {} with]", util::entry_name(&self.file));
+        let block = components::component_block(true).title(title);
+        let items: Vec<ListItem> = self
+            .openers
+            .iter()
+            .map(|opener| ListItem::new(opener.label()))
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.openers.state);
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Select"),
+            KeyHint::new("Enter", "Open"),
+            KeyHint::new("Esc", "Close"),
+        ]
+    }
+}
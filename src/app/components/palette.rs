@@ -0,0 +1,144 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::prelude::{Line, Span};
+use ratatui::widgets::{Clear, List, ListItem, Paragraph};
+use ratatui::{layout::Rect, Frame};
+
+use crate::app::action::Action;
+use crate::app::{components, styles};
+use crate::stateful_list::StatefulList;
+use crate::util;
+
+use super::key_hints::KeyHint;
+
+pub enum PaletteEvent {
+    Cancelled,
+    Selected(Action),
+}
+
+#[derive(Default)]
+pub struct Palette {
+    is_open: bool,
+    input: String,
+    matches: StatefulList<Action>,
+}
+
+impl Palette {
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn open(&mut self) {
+        self.is_open = true;
+        self.input.clear();
+        self.update_matches();
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<PaletteEvent> {
+        if util::is_up_key(key_event) {
+            self.matches.previous();
+        } else if util::is_down_key(key_event) {
+            self.matches.next();
+        } else {
+            match key_event.code {
+                KeyCode::Esc => {
+                    self.close();
+                    return Some(PaletteEvent::Cancelled);
+                }
+                KeyCode::Enter => {
+                    if let Some(selected) = self.selected_index() {
+                        let action = self.matches[selected];
+                        self.close();
+                        return Some(PaletteEvent::Selected(action));
+                    }
+                }
+                KeyCode::Backspace => {
+                    if self.input.pop().is_some() {
+                        self.update_matches();
+                    }
+                }
+                KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.input.push(c);
+                    self.update_matches();
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        self.input.push_str(text);
+        self.update_matches();
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let block = components::component_block(true).title("[Commands]");
+        let inner_area = block.inner(area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(1)])
+            .split(inner_area);
+
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|action| {
+                let mut spans = vec![Span::raw(action.name())];
+                if let Some(key) = action.key() {
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(format!("({key})"), styles::KEY_HINT_STYLE));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items).highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(Paragraph::new(format!("> {}", self.input)), layout[0]);
+        frame.render_stateful_widget(list, layout[1], &mut self.matches.state);
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Select"),
+            KeyHint::new("Enter", "Run"),
+            KeyHint::new("Esc", "Close"),
+        ]
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        self.matches
+            .selected()
+            .filter(|selected| *selected < self.matches.len())
+    }
+
+    // Filters the actions by fuzzy matching their names against the input, best matches first
+    fn update_matches(&mut self) {
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let mut scored: Vec<(i64, Action)> = Action::ALL
+            .iter()
+            .filter_map(|action| {
+                matcher
+                    .fuzzy_match(action.name(), &self.input)
+                    .map(|score| (score, *action))
+            })
+            .collect();
+        // A stable sort keeps equally scored actions in their natural order
+        scored.sort_by(|(lhs, _), (rhs, _)| rhs.cmp(lhs));
+        self.matches = StatefulList::with_items(scored.into_iter().map(|(_, a)| a).collect());
+        self.matches.first();
+    }
+}
@@ -0,0 +1,57 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::{Path, PathBuf};
+
+use ratatui::widgets::List;
+use ratatui::{layout::Rect, Frame};
+
+use crate::app::components::ListingOptions;
+use crate::app::fs_error::FsError;
+use crate::app::{components, styles};
+use crate::stateful_list::StatefulList;
+use crate::util;
+
+// The entries of the current directory's parent, with the current directory selected, shown to
+// the left of the directory pane in the Miller columns layout
+#[derive(Default)]
+pub struct ParentList {
+    entries: StatefulList<PathBuf>,
+    metadata: util::KnownMetadata,
+    title: String,
+}
+
+impl ParentList {
+    // Reads the parent of the directory. The root directory has no parent, so the list is empty.
+    pub async fn load(
+        &mut self,
+        directory: &Path,
+        options: &ListingOptions,
+    ) -> Result<(), FsError> {
+        self.entries = StatefulList::default();
+        self.metadata.clear();
+        self.title.clear();
+        if let Some(parent) = directory.parent() {
+            let listing = components::read_directory(parent, options).await?;
+            let entries = listing.iter().map(|(entry, _)| entry.clone()).collect();
+            self.metadata.extend(listing);
+            self.entries = StatefulList::with_items(entries);
+            self.entries
+                .set_selected(self.entries.index_of(&directory.to_path_buf()));
+            self.title = format!("[{}]", util::entry_name(parent));
+        }
+        Ok(())
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let height = area.height.saturating_sub(2) as usize; // Inside the borders
+        let (items, mut state) = util::list_items(&mut self.entries, height, &mut self.metadata);
+        let block = components::component_block(false).title(self.title.as_str());
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+}
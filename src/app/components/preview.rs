@@ -4,49 +4,174 @@
  */
 
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
 
-use crossterm::event::{KeyEvent, MouseEvent};
+use ::image::DynamicImage;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
 use probably_binary::{EntryType, FileType};
 use ratatui::layout::{Alignment, Position};
 use ratatui::widgets::{Paragraph, Wrap};
 use ratatui::{layout::Rect, Frame};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+use tracing::debug;
 
+use archive::Archive;
+use audio::Audio;
 use binary::Binary;
+use cache::{CacheKey, PreviewCache, ScrollMemory};
+use diff::Diff;
 use folder::Folder;
+use image::Image;
+use json::Json;
 use list_pane::ListPane;
 use message_pane::MessagePane;
 use other::Other;
 use oversize::Oversize;
+use pdf::Pdf;
 use preview_pane::PreviewPane;
+use streamed::Streamed;
 use text::Text;
 
-use crate::app::{components, styles};
-use crate::util;
+use crate::app::archive::{ArchiveEntry, ArchiveKind};
+use crate::app::audio::AudioInfo;
+use crate::app::components::wheel::WheelScroll;
+use crate::app::components::ListingOptions;
+use crate::app::exif::ExifInfo;
+use crate::app::fs_error::{FsError, Operation};
+use crate::app::fs_provider::{self, EntryMetadata, FsProvider, ListingEntry};
+use crate::app::previewer::Previewer;
+use crate::app::{components, styles, tasks};
+use crate::graphics::Protocol;
+use crate::tui::Event;
+use crate::{constants, util};
 
+use super::key_hints::KeyHint;
 use super::Component;
 
+mod ansi;
+mod archive;
+mod audio;
 mod binary;
+mod cache;
+mod diff;
 mod folder;
+mod image;
+mod json;
 mod list_pane;
+mod markdown;
 mod message_pane;
 mod other;
 mod oversize;
+mod pdf;
 mod preview_pane;
+mod streamed;
+mod strings;
 mod text;
+mod thumb_drag;
 
 enum PreviewType {
     Folder,
     TextFile,
     OversizeTextFile,
+    StreamedTextFile,
     BinaryFile,
+    ArchiveFile,
+    AudioFile,
+    ImageFile,
+    JsonFile,
+    PdfFile,
     OtherFile,
+    Diff,
     Error(String),
 }
 
+/// What's read to preview an entry
+#[derive(Clone, Debug)]
+pub enum PreviewContent {
+    /// A folder's entries
+    Folder(Vec<ListingEntry>),
+    /// A text file's lines
+    Text(Vec<String>),
+    /// A text file that's too big to read all at once, which is read as it's scrolled through
+    OversizeText,
+    /// A file that isn't text, which is shown a page of bytes at a time
+    Binary,
+    /// An archive's entries
+    Archive(Vec<ArchiveEntry>),
+    /// An audio file's tags and properties
+    Audio(AudioInfo),
+    /// An image, if it could be decoded, and its EXIF data, if it has any
+    Image(Option<DynamicImage>, Option<ExifInfo>),
+    /// A JSON file's contents
+    Json(serde_json::Value),
+    /// A PDF's pages' text
+    Pdf(Vec<Vec<String>>),
+    /// The lines a previewer wrote, and its command
+    Output(Vec<String>, String),
+    /// Anything else, which is described by its metadata
+    Other,
+    /// Why the entry couldn't be read
+    Error(String),
+}
+
+impl PreviewContent {
+    // Roughly how many bytes the content takes up, counting what it holds but not the overhead
+    // of holding it
+    pub(crate) fn approximate_size(&self) -> usize {
+        match self {
+            PreviewContent::Folder(entries) => entries
+                .iter()
+                .map(|(path, _)| path.as_os_str().len() + std::mem::size_of::<EntryMetadata>())
+                .sum(),
+            PreviewContent::Text(lines) | PreviewContent::Output(lines, _) => {
+                lines.iter().map(String::len).sum()
+            }
+            PreviewContent::Archive(entries) => entries
+                .iter()
+                .map(|entry| entry.name.len() + std::mem::size_of::<ArchiveEntry>())
+                .sum(),
+            PreviewContent::Audio(_) => std::mem::size_of::<AudioInfo>(),
+            PreviewContent::Image(image, exif) => {
+                image.as_ref().map_or(0, |image| image.as_bytes().len())
+                    + exif.as_ref().map_or(0, |_| std::mem::size_of::<ExifInfo>())
+            }
+            PreviewContent::Json(value) => json_size(value),
+            PreviewContent::Pdf(pages) => pages
+                .iter()
+                .flat_map(|page| page.iter().map(String::len))
+                .sum(),
+            PreviewContent::Error(message) => message.len(),
+            PreviewContent::OversizeText | PreviewContent::Binary | PreviewContent::Other => 0,
+        }
+    }
+}
+
+fn json_size(value: &serde_json::Value) -> usize {
+    std::mem::size_of::<serde_json::Value>()
+        + match value {
+            serde_json::Value::String(string) => string.len(),
+            serde_json::Value::Array(values) => values.iter().map(json_size).sum(),
+            serde_json::Value::Object(map) => map
+                .iter()
+                .map(|(key, value)| key.len() + json_size(value))
+                .sum(),
+            _ => 0,
+        }
+}
+
+// Loads are numbered across every tab's preview pane, so one pane can't take another's content
+static NEXT_LOAD_ID: AtomicU32 = AtomicU32::new(0);
+
+/// The preview pane, which shows the selected entry: a text file's lines, an image, a folder's
+/// entries, an archive's contents, and so on, each in a pane of its own. What's shown is read in
+/// the background, and arrives as an [`Event::PreviewLoaded`].
 #[derive(Default)]
 pub struct Preview<'a> {
     has_focus: bool,
     area: Rect,
+    event_tx: Option<UnboundedSender<Event>>,
 
     // The entry being previewed
     entry: Option<PathBuf>,
@@ -54,17 +179,57 @@ pub struct Preview<'a> {
     // What kind of item the entry is
     preview_type: Option<PreviewType>,
 
-    binary_pane: Binary,
+    // The entry being loaded in the background, if there is one, when the load started, and the
+    // ticks since then, which animate the loading indicator
+    load_id: u32,
+    loading: Option<JoinHandle<()>>,
+    loading_entry: Option<(PathBuf, Instant)>,
+    loading_ticks: usize,
+
+    // Recently loaded previews, and what the one being loaded will be kept as
+    cache: PreviewCache,
+    loading_key: Option<CacheKey>,
+
+    // Where text files were scrolled to, and the text file being shown as it was when it was shown
+    scroll_memory: ScrollMemory,
+    text_key: Option<CacheKey>,
+
+    // How folder previews are listed
+    listing_options: ListingOptions,
+
+    // Text files at least this big are oversize; the default size if it hasn't been set
+    max_text_size: Option<u64>,
+
+    // The programs that preview files instead of reading them, and how long they're given, or
+    // the default time if it hasn't been set
+    previewers: Vec<Previewer>,
+    previewer_timeout: Option<Duration>,
+
+    archive_pane: Archive<'a>,
+    audio_pane: Audio,
+    binary_pane: Binary<'a>,
+    diff_pane: Diff<'a>,
+    image_pane: Image,
+    json_pane: Json<'a>,
     other_pane: Other,
     oversize_pane: Oversize,
+    pdf_pane: Pdf<'a>,
     folder_pane: Folder<'a>,
+    streamed_pane: Streamed<'a>,
     text_pane: Text<'a>,
 }
 
 impl<'a> Component for Preview<'a> {
     fn set_area(&mut self, area: Rect) {
         self.area = area;
+        self.archive_pane.set_area(self.area);
+        self.binary_pane.set_area(self.area);
+        self.diff_pane.set_area(self.area);
+        self.image_pane.set_area(self.area);
+        self.json_pane.set_area(self.area);
+        self.pdf_pane.set_area(self.area);
         self.folder_pane.set_area(self.area);
+        self.streamed_pane.set_area(self.area);
         self.text_pane.set_area(self.area);
     }
 
@@ -80,31 +245,59 @@ impl<'a> Component for Preview<'a> {
         self.area.contains(Position { x, y })
     }
 
-    async fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> Result<(), std::io::Error> {
+    async fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> Result<(), FsError> {
         if let Some(preview_type) = &self.preview_type {
             match preview_type {
                 PreviewType::Folder => self.folder_pane.handle_mouse_event(mouse_event),
                 PreviewType::TextFile => self.text_pane.handle_mouse_event(mouse_event),
+                PreviewType::StreamedTextFile => self.streamed_pane.handle_mouse_event(mouse_event),
+                PreviewType::BinaryFile => self.binary_pane.handle_mouse_event(mouse_event),
+                PreviewType::ArchiveFile => self.archive_pane.handle_mouse_event(mouse_event),
+                PreviewType::JsonFile => self.json_pane.handle_mouse_event(mouse_event),
+                PreviewType::PdfFile => self.pdf_pane.handle_mouse_event(mouse_event),
+                PreviewType::Diff => self.diff_pane.handle_mouse_event(mouse_event),
                 _ => {}
             }
         }
         Ok(())
     }
 
-    async fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<(), std::io::Error> {
+    async fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<(), FsError> {
         if let Some(file_contents) = &self.preview_type {
             match file_contents {
                 PreviewType::Folder => self.folder_pane.handle_key_event(key_event),
                 PreviewType::TextFile => self.text_pane.handle_key_event(key_event),
+                PreviewType::OversizeTextFile => {
+                    if let Some(entry) = self.entry.clone() {
+                        match key_event.code {
+                            KeyCode::Char('s') => self.set_streamed_text_file(&entry),
+                            KeyCode::Char('h') => self.load_oversize_text_file(&entry, true).await,
+                            KeyCode::Char('f') => self.load_oversize_text_file(&entry, false).await,
+                            _ => {}
+                        }
+                    }
+                }
+                PreviewType::StreamedTextFile => self.streamed_pane.handle_key_event(key_event),
+                PreviewType::BinaryFile => self.binary_pane.handle_key_event(key_event),
+                PreviewType::ArchiveFile => self.archive_pane.handle_key_event(key_event),
+                PreviewType::JsonFile => self.json_pane.handle_key_event(key_event),
+                PreviewType::PdfFile => self.pdf_pane.handle_key_event(key_event),
+                PreviewType::Diff => self.diff_pane.handle_key_event(key_event),
                 _ => {}
             }
         }
         Ok(())
     }
 
-    fn render(&mut self, area: Rect, frame: &mut Frame<'_>) -> Result<(), std::io::Error> {
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>) -> Result<(), FsError> {
         self.set_area(area);
 
+        // What was previewed before isn't shown while the next entry is slow to load
+        if let Some(entry) = self.slow_loading_entry() {
+            let title = format!("[{}]", util::entry_name(entry));
+            components::render_loading(title, self.loading_ticks, self.has_focus, self.area, frame);
+            return Ok(());
+        }
         if let Some(file_contents) = &self.preview_type {
             match file_contents {
                 PreviewType::Folder => {
@@ -117,12 +310,34 @@ impl<'a> Component for Preview<'a> {
                     self.oversize_pane
                         .render(self.area, frame, self.has_focus())?;
                 }
+                PreviewType::StreamedTextFile => {
+                    self.streamed_pane
+                        .render(self.area, frame, self.has_focus)?;
+                }
                 PreviewType::BinaryFile => {
                     self.binary_pane.render(self.area, frame, self.has_focus)?;
                 }
+                PreviewType::ArchiveFile => {
+                    self.archive_pane.render(self.area, frame, self.has_focus)?;
+                }
+                PreviewType::AudioFile => {
+                    self.audio_pane.render(self.area, frame, self.has_focus)?;
+                }
+                PreviewType::ImageFile => {
+                    self.image_pane.render(self.area, frame, self.has_focus)?;
+                }
+                PreviewType::JsonFile => {
+                    self.json_pane.render(self.area, frame, self.has_focus)?;
+                }
+                PreviewType::PdfFile => {
+                    self.pdf_pane.render(self.area, frame, self.has_focus)?;
+                }
                 PreviewType::OtherFile => {
                     self.other_pane.render(self.area, frame, self.has_focus())?;
                 }
+                PreviewType::Diff => {
+                    self.diff_pane.render(self.area, frame, self.has_focus)?;
+                }
                 PreviewType::Error(message) => {
                     self.render_error(message, frame);
                 }
@@ -130,27 +345,116 @@ impl<'a> Component for Preview<'a> {
         }
         Ok(())
     }
+
+    fn key_hints(&self) -> Vec<KeyHint> {
+        match &self.preview_type {
+            Some(PreviewType::Folder) => self.folder_pane.key_hints(),
+            Some(PreviewType::TextFile) => self.text_pane.key_hints(),
+            Some(PreviewType::OversizeTextFile) => vec![
+                KeyHint::new("s", "Stream"),
+                KeyHint::new("h", "View the start"),
+                KeyHint::new("f", "View it all"),
+            ],
+            Some(PreviewType::StreamedTextFile) => self.streamed_pane.key_hints(),
+            Some(PreviewType::BinaryFile) => self.binary_pane.key_hints(),
+            Some(PreviewType::ArchiveFile) => self.archive_pane.key_hints(),
+            Some(PreviewType::JsonFile) => self.json_pane.key_hints(),
+            Some(PreviewType::PdfFile) => self.pdf_pane.key_hints(),
+            Some(PreviewType::Diff) => self.diff_pane.key_hints(),
+            _ => vec![],
+        }
+    }
 }
 
 impl<'a> Preview<'a> {
+    /// Shows nothing
     pub fn clear(&mut self) {
+        if let Some(text_key) = self.text_key.take() {
+            self.scroll_memory
+                .insert(text_key, self.text_pane.offsets());
+        }
         self.entry = None;
         self.preview_type = None;
 
+        self.archive_pane.clear();
+        self.audio_pane.clear();
         self.binary_pane.clear();
+        self.diff_pane.clear();
+        self.image_pane.clear();
+        self.json_pane.clear();
         self.other_pane.clear();
         self.oversize_pane.clear();
+        self.streamed_pane.clear();
+        self.pdf_pane.clear();
         self.folder_pane.clear();
         self.text_pane.clear();
     }
 
-    pub fn set_error(&mut self, entry: &Path, message: String) {
+    /// Where what's loaded in the background is sent, which [`request_entry`](Preview::request_entry)
+    /// needs
+    pub fn set_event_tx(&mut self, event_tx: Option<UnboundedSender<Event>>) {
+        self.event_tx = event_tx;
+    }
+
+    /// How previewed images are drawn
+    pub fn set_graphics_protocol(&mut self, protocol: Protocol) {
+        self.image_pane.set_protocol(protocol);
+    }
+
+    /// How previewed folders are listed
+    pub fn set_listing_options(&mut self, listing_options: ListingOptions) {
+        self.listing_options = listing_options;
+        self.cache.clear();
+    }
+
+    // How far the mouse wheel scrolls folder, text and diff previews
+    pub(crate) fn set_wheel_scroll(&mut self, wheel_scroll: WheelScroll) {
+        self.folder_pane.set_wheel_scroll(wheel_scroll);
+        self.text_pane.set_wheel_scroll(wheel_scroll);
+        self.diff_pane.set_wheel_scroll(wheel_scroll);
+    }
+
+    /// Text files at least this many bytes long aren't read all at once
+    pub fn set_max_text_size(&mut self, max_text_size: u64) {
+        self.max_text_size = Some(max_text_size);
+        self.cache.clear();
+    }
+
+    pub(crate) fn set_previewers(&mut self, previewers: Vec<Previewer>, timeout: Duration) {
+        self.previewers = previewers;
+        self.previewer_timeout = Some(timeout);
+        self.cache.clear();
+    }
+
+    // How many previews are cached, and roughly how many bytes they take up
+    pub(crate) fn cache_usage(&self) -> (usize, usize) {
+        (self.cache.len(), self.cache.size())
+    }
+
+    fn max_text_size(&self) -> u64 {
+        self.max_text_size
+            .unwrap_or(constants::DEFAULT_MAX_TEXT_SIZE)
+    }
+
+    // The first previewer for the entry, if any are, and how long it has
+    fn previewer_for(&self, entry: &Path) -> Option<(Previewer, Duration)> {
+        let previewer = self
+            .previewers
+            .iter()
+            .find(|previewer| previewer.is_match(entry))?;
+        let timeout = self
+            .previewer_timeout
+            .unwrap_or(constants::DEFAULT_PREVIEWER_TIMEOUT);
+        Some((previewer.clone(), timeout))
+    }
+
+    pub(crate) fn set_error(&mut self, entry: &Path, message: String) {
         self.clear();
         self.entry = Some(PathBuf::from(entry));
         self.preview_type = Some(PreviewType::Error(message));
     }
 
-    pub fn set_folder_items(&mut self, entry: &Path, items: Vec<PathBuf>) {
+    pub(crate) fn set_folder_items(&mut self, entry: &Path, items: Vec<ListingEntry>) {
         self.clear();
         self.entry = Some(PathBuf::from(entry));
         self.folder_pane
@@ -158,72 +462,304 @@ impl<'a> Preview<'a> {
         self.preview_type = Some(PreviewType::Folder);
     }
 
-    pub fn set_text_file(&mut self, entry: &Path, lines: Vec<String>) {
+    pub(crate) fn set_text_file(&mut self, entry: &Path, lines: Vec<String>) {
         self.clear();
         self.entry = Some(PathBuf::from(entry));
         self.text_pane
             .init(Some(&entry.to_path_buf()), lines, self.area);
         self.preview_type = Some(PreviewType::TextFile);
+        self.text_key = CacheKey::new(entry);
+        if let Some(offsets) = self
+            .text_key
+            .as_ref()
+            .and_then(|text_key| self.scroll_memory.get(text_key))
+        {
+            self.text_pane.set_offsets(offsets);
+        }
+    }
+
+    // Shows what a previewer wrote about a file like a text file's lines
+    pub(crate) fn set_command_output(&mut self, entry: &Path, lines: Vec<String>, command: &str) {
+        self.set_text_file(entry, lines);
+        self.text_pane.set_previewer(command);
     }
 
-    pub fn set_oversize_text_file(&mut self, entry: &Path) {
+    pub(crate) fn set_oversize_text_file(&mut self, entry: &Path) {
         self.clear();
         self.entry = Some(PathBuf::from(entry));
         self.oversize_pane.init(Some(&entry.to_path_buf()));
+        self.oversize_pane.set_max_size(self.max_text_size());
         self.preview_type = Some(PreviewType::OversizeTextFile);
     }
 
-    pub fn set_binary_file(&mut self, entry: &Path) {
+    // Shows an oversize text file anyway, either all of it or as much of the start as isn't
+    // oversize
+    async fn load_oversize_text_file(&mut self, entry: &Path, head_only: bool) {
+        let lines = if head_only {
+            components::read_file_head(entry, self.max_text_size()).await
+        } else {
+            components::read_file(entry).await
+        };
+        match lines {
+            Ok(lines) => {
+                self.set_text_file(entry, lines);
+                self.text_pane.set_truncated(head_only);
+            }
+            Err(error) => self.set_error(entry, error.to_string()),
+        }
+    }
+
+    // Shows a text file that's too big to read all at once a window at a time
+    pub(crate) fn set_streamed_text_file(&mut self, entry: &Path) {
+        self.clear();
+        self.entry = Some(PathBuf::from(entry));
+        match self
+            .streamed_pane
+            .init(Some(&entry.to_path_buf()), self.area)
+        {
+            Ok(()) => self.preview_type = Some(PreviewType::StreamedTextFile),
+            Err(error) => self.set_error(entry, error.to_string()),
+        }
+    }
+
+    pub(crate) fn set_binary_file(&mut self, entry: &Path) {
         self.clear();
         self.entry = Some(PathBuf::from(entry));
-        self.binary_pane.init(Some(&entry.to_path_buf()));
+        self.binary_pane.init(Some(&entry.to_path_buf()), self.area);
         self.preview_type = Some(PreviewType::BinaryFile);
     }
 
-    pub fn set_other_file(&mut self, entry: &Path) {
+    pub(crate) fn set_archive_file(&mut self, entry: &Path, items: Vec<ArchiveEntry>) {
+        self.clear();
+        self.entry = Some(PathBuf::from(entry));
+        self.archive_pane
+            .init(Some(&entry.to_path_buf()), items, self.area);
+        self.preview_type = Some(PreviewType::ArchiveFile);
+    }
+
+    pub(crate) fn set_audio_file(&mut self, entry: &Path, info: AudioInfo) {
+        self.clear();
+        self.entry = Some(PathBuf::from(entry));
+        self.audio_pane.init(Some(&entry.to_path_buf()), info);
+        self.preview_type = Some(PreviewType::AudioFile);
+    }
+
+    pub(crate) fn set_image_file(
+        &mut self,
+        entry: &Path,
+        image: Option<DynamicImage>,
+        exif: Option<ExifInfo>,
+    ) {
+        self.clear();
+        self.entry = Some(PathBuf::from(entry));
+        self.image_pane
+            .init(Some(&entry.to_path_buf()), image, exif, self.area);
+        self.preview_type = Some(PreviewType::ImageFile);
+    }
+
+    pub(crate) fn set_json_file(&mut self, entry: &Path, root: serde_json::Value) {
+        self.clear();
+        self.entry = Some(PathBuf::from(entry));
+        self.json_pane
+            .init(Some(&entry.to_path_buf()), root, self.area);
+        self.preview_type = Some(PreviewType::JsonFile);
+    }
+
+    pub(crate) fn set_pdf_file(&mut self, entry: &Path, pages: Vec<Vec<String>>) {
+        self.clear();
+        self.entry = Some(PathBuf::from(entry));
+        self.pdf_pane
+            .init(Some(&entry.to_path_buf()), pages, self.area);
+        self.preview_type = Some(PreviewType::PdfFile);
+    }
+
+    pub(crate) fn set_other_file(&mut self, entry: &Path) {
         self.clear();
         self.entry = Some(PathBuf::from(entry));
         self.other_pane.init(Some(&entry.to_path_buf()));
         self.preview_type = Some(PreviewType::OtherFile);
     }
 
-    pub async fn load_entry(&mut self, entry: Option<PathBuf>) {
+    // Shows how two text files differ, instead of previewing an entry, until another entry is
+    // previewed
+    pub(crate) async fn load_diff(&mut self, left: &Path, right: &Path) -> Result<(), FsError> {
+        let left_lines = read_text_file(left).await?;
+        let right_lines = read_text_file(right).await?;
+        self.cancel_load();
         self.clear();
+        self.diff_pane
+            .init(left, right, left_lines, right_lines, self.area);
+        self.preview_type = Some(PreviewType::Diff);
+        Ok(())
+    }
 
-        if let Some(entry) = entry.as_ref() {
-            match probably_binary::entry_type(entry) {
-                Ok(entry_type) => match entry_type {
-                    EntryType::Directory => {
-                        match components::read_directory(entry).await {
-                            Ok(entries) => self.set_folder_items(entry, entries),
-                            Err(error) => self.set_error(entry, error.to_string()),
-                        };
-                    }
-                    EntryType::File(file_type) => self.load_file(file_type, entry).await,
-                    EntryType::Other => self.set_other_file(entry),
-                },
-                Err(error) => {
-                    self.set_error(entry, error.to_string());
+    /// The image being previewed, and where it goes, if the terminal can draw it
+    pub fn image_placement(&self) -> Option<(&Path, &DynamicImage, Rect)> {
+        if self.slow_loading_entry().is_some() {
+            return None;
+        }
+        match self.preview_type {
+            Some(PreviewType::ImageFile) => self.image_pane.placement(),
+            _ => None,
+        }
+    }
+
+    /// Animates the loading indicator, and reads what's been appended to the previewed text file,
+    /// if it's being followed
+    /// A followed file is only read if it's polled, which it isn't while the terminal doesn't have
+    /// focus
+    pub fn handle_tick(&mut self, is_polled: bool) {
+        if self.loading_entry.is_some() {
+            self.loading_ticks = self.loading_ticks.wrapping_add(1);
+        }
+        if !is_polled {
+            return;
+        }
+        if let Some(PreviewType::TextFile) = self.preview_type {
+            self.text_pane.read_followed();
+        }
+    }
+
+    // Whether search text or a line number is being typed into a text file preview
+    pub(crate) fn is_input(&self) -> bool {
+        matches!(self.preview_type, Some(PreviewType::TextFile)) && self.text_pane.is_input()
+    }
+
+    // Adds pasted text to the search or line number being typed into a text file preview
+    pub(crate) fn paste(&mut self, text: &str) {
+        if let Some(PreviewType::TextFile) = self.preview_type {
+            self.text_pane.paste(text);
+        }
+    }
+
+    pub(crate) fn is_searched(&self) -> bool {
+        matches!(self.preview_type, Some(PreviewType::TextFile)) && self.text_pane.is_searched()
+    }
+
+    pub(crate) fn is_selecting(&self) -> bool {
+        matches!(self.preview_type, Some(PreviewType::TextFile)) && self.text_pane.is_selecting()
+    }
+
+    pub(crate) fn take_copied_text(&mut self) -> Option<String> {
+        self.text_pane.take_copied_text()
+    }
+
+    /// Scrolls a text file preview so the (1-based) line is at the top.
+    /// Returns false if the preview isn't a text file.
+    pub fn scroll_to_line(&mut self, line: usize) -> bool {
+        if let Some(PreviewType::TextFile) = self.preview_type {
+            self.text_pane.scroll_to_line(line);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Loads the entry, or shows nothing, right away
+    pub async fn load_entry(&mut self, entry: Option<PathBuf>) {
+        self.cancel_load();
+        match entry {
+            Some(entry) => {
+                let key = CacheKey::new(&entry);
+                let previewer = self.previewer_for(&entry);
+                let content = read_content(
+                    &entry,
+                    self.listing_options,
+                    self.max_text_size(),
+                    previewer,
+                )
+                .await;
+                if let Some(key) = key {
+                    self.cache.insert(key, content.clone());
                 }
+                self.show_content(&entry, content);
             }
+            None => self.clear(),
         }
     }
 
-    async fn load_file(&mut self, file_type: FileType, entry: &Path) {
-        match file_type {
-            FileType::Text => {
-                if util::file_size(entry) >= 50_000 {
-                    self.set_oversize_text_file(entry);
-                } else {
-                    match components::read_file(entry).await {
-                        Ok(lines) => {
-                            self.set_text_file(entry, lines);
-                        }
-                        Err(error) => self.set_error(entry, error.to_string()),
-                    }
-                }
+    /// Loads the entry in the background after a moment, so that moving quickly through a
+    /// directory only loads the entry it stops on. The last entry is shown until then.
+    pub fn request_entry(&mut self, entry: Option<PathBuf>) {
+        self.cancel_load();
+        let Some(entry) = entry else {
+            self.clear();
+            return;
+        };
+        // An entry that hasn't changed since it was loaded is shown right away
+        let key = CacheKey::new(&entry);
+        if let Some(content) = key.as_ref().and_then(|key| self.cache.get(key)) {
+            self.show_content(&entry, content);
+            return;
+        }
+        self.loading_key = key;
+        self.loading_entry = Some((entry.clone(), Instant::now() + constants::PREVIEW_DELAY));
+        self.loading_ticks = 0;
+        let load_id = self.load_id;
+        let listing_options = self.listing_options;
+        let max_text_size = self.max_text_size();
+        let previewer = self.previewer_for(&entry);
+        let event_tx = self.event_tx.clone().unwrap();
+        let task = tasks::start();
+        self.loading = Some(tokio::spawn(async move {
+            let _task = task;
+            tokio::time::sleep(constants::PREVIEW_DELAY).await;
+            let start = Instant::now();
+            let content = read_content(&entry, listing_options, max_text_size, previewer).await;
+            debug!(load_id, entry = %entry.display(), elapsed = ?start.elapsed(), "preview loaded");
+            // The receiver is only gone if the app is shutting down
+            let _ = event_tx.send(Event::PreviewLoaded(load_id, entry, content));
+        }));
+    }
+
+    /// Shows what was loaded in the background, which arrives as an [`Event::PreviewLoaded`], unless
+    /// another entry's been requested since
+    pub fn set_loaded(&mut self, load_id: u32, entry: PathBuf, content: PreviewContent) {
+        // Ignore a load that's been replaced
+        if load_id == self.load_id && self.loading.take().is_some() {
+            self.loading_entry = None;
+            if let Some(key) = self.loading_key.take() {
+                self.cache.insert(key, content.clone());
             }
-            FileType::Binary => self.set_binary_file(entry),
+            self.show_content(&entry, content);
+        }
+    }
+
+    /// Stops whatever is being loaded in the background, and makes sure it's ignored if it's
+    /// already finished
+    pub fn cancel_load(&mut self) {
+        if let Some(loading) = self.loading.take() {
+            loading.abort();
+        }
+        self.loading_entry = None;
+        self.loading_key = None;
+        self.load_id = NEXT_LOAD_ID.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // The entry being loaded, if it's been loading long enough to say so
+    fn slow_loading_entry(&self) -> Option<&Path> {
+        self.loading_entry
+            .as_ref()
+            .filter(|(_, started)| started.elapsed() >= constants::LOADING_INDICATOR_DELAY)
+            .map(|(entry, _)| entry.as_path())
+    }
+
+    fn show_content(&mut self, entry: &Path, content: PreviewContent) {
+        match content {
+            PreviewContent::Folder(items) => self.set_folder_items(entry, items),
+            PreviewContent::Text(lines) => self.set_text_file(entry, lines),
+            PreviewContent::OversizeText => self.set_oversize_text_file(entry),
+            PreviewContent::Binary => self.set_binary_file(entry),
+            PreviewContent::Archive(items) => self.set_archive_file(entry, items),
+            PreviewContent::Audio(info) => self.set_audio_file(entry, info),
+            PreviewContent::Image(image, exif) => self.set_image_file(entry, image, exif),
+            PreviewContent::Json(root) => self.set_json_file(entry, root),
+            PreviewContent::Pdf(pages) => self.set_pdf_file(entry, pages),
+            PreviewContent::Output(lines, command) => {
+                self.set_command_output(entry, lines, &command)
+            }
+            PreviewContent::Other => self.set_other_file(entry),
+            PreviewContent::Error(message) => self.set_error(entry, message),
         }
     }
 
@@ -238,9 +774,161 @@ impl<'a> Preview<'a> {
             Rect::new(
                 self.area.x + 2,
                 self.area.y + 2,
-                self.area.width - 4,
-                self.area.height - 4,
+                self.area.width.saturating_sub(4),
+                self.area.height.saturating_sub(4),
             ),
         )
     }
 }
+
+// Reads what's needed to preview an entry, from whichever file system it's on, or runs the
+// previewer for it if it's a file that has one
+async fn read_content(
+    entry: &Path,
+    listing_options: ListingOptions,
+    max_text_size: u64,
+    previewer: Option<(Previewer, Duration)>,
+) -> PreviewContent {
+    let provider = fs_provider::provider(entry);
+    match provider.entry_type(entry).await {
+        Ok(EntryType::Directory) => match components::read_directory(entry, &listing_options).await
+        {
+            Ok(entries) => PreviewContent::Folder(entries),
+            Err(error) => PreviewContent::Error(error.to_string()),
+        },
+        Ok(EntryType::File(file_type)) => match previewer {
+            Some((previewer, timeout)) => match provider.local_path(entry).await {
+                Ok(local_path) => match previewer.run(&local_path, timeout).await {
+                    Ok(lines) => PreviewContent::Output(lines, previewer.command().to_string()),
+                    Err(message) => PreviewContent::Error(message),
+                },
+                Err(error) => {
+                    PreviewContent::Error(FsError::io(Operation::Reading, entry, error).to_string())
+                }
+            },
+            None => read_file_content(&*provider, file_type, entry, max_text_size).await,
+        },
+        Ok(EntryType::Other) => PreviewContent::Other,
+        Err(error) => {
+            PreviewContent::Error(FsError::io(Operation::Reading, entry, error).to_string())
+        }
+    }
+}
+
+// Text is read from the file system the file's on. What's previewed by reading the file itself,
+// like an archive, a PDF or an image, is read from a copy on this computer if it isn't on it.
+async fn read_file_content(
+    provider: &dyn FsProvider,
+    file_type: FileType,
+    entry: &Path,
+    max_text_size: u64,
+) -> PreviewContent {
+    let is_read_from_file = ArchiveKind::from_path(entry).is_some()
+        || crate::app::audio::is_audio_file(entry)
+        || crate::app::exif::is_tiff_file(entry)
+        || Pdf::is_pdf_file(entry)
+        || (file_type == FileType::Binary && Image::is_image_file(entry));
+    if !is_read_from_file {
+        return match file_type {
+            FileType::Text => read_text_content(provider, entry, max_text_size).await,
+            FileType::Binary => PreviewContent::Binary,
+        };
+    }
+    match provider.local_path(entry).await {
+        Ok(local_path) => read_local_file_content(entry, &local_path),
+        Err(error) => {
+            PreviewContent::Error(FsError::io(Operation::Reading, entry, error).to_string())
+        }
+    }
+}
+
+async fn read_text_content(
+    provider: &dyn FsProvider,
+    entry: &Path,
+    max_text_size: u64,
+) -> PreviewContent {
+    let file_size = match provider.metadata(entry).await {
+        Ok(metadata) => metadata.len,
+        Err(error) => {
+            return PreviewContent::Error(FsError::io(Operation::Reading, entry, error).to_string())
+        }
+    };
+    if Json::is_json_file(entry) && file_size <= json::MAX_JSON_SIZE {
+        // JSON that doesn't parse is shown as text
+        if let Ok(root) = read_json(entry).await {
+            return PreviewContent::Json(root);
+        }
+    }
+    if file_size >= max_text_size {
+        PreviewContent::OversizeText
+    } else {
+        match components::read_file(entry).await {
+            Ok(lines) => PreviewContent::Text(lines),
+            Err(error) => PreviewContent::Error(error.to_string()),
+        }
+    }
+}
+
+// Previews an archive, an audio file, a PDF or an image from the file on this computer with its
+// contents. What goes wrong is reported about the entry.
+fn read_local_file_content(entry: &Path, local_path: &Path) -> PreviewContent {
+    if let Some(archive_kind) = ArchiveKind::from_path(entry) {
+        return match crate::app::archive::list_entries(local_path, archive_kind) {
+            Ok(items) => PreviewContent::Archive(items),
+            Err(error) => PreviewContent::Error(FsError::archive(entry, error).to_string()),
+        };
+    }
+    if crate::app::audio::is_audio_file(entry) {
+        return match crate::app::audio::read_info(local_path) {
+            Ok(info) => PreviewContent::Audio(info),
+            Err(error) => {
+                PreviewContent::Error(FsError::io(Operation::Reading, entry, error).to_string())
+            }
+        };
+    }
+    if crate::app::exif::is_tiff_file(entry) {
+        // Only the EXIF data of files that can't be decoded is shown
+        return PreviewContent::Image(None, crate::app::exif::read_info(local_path).ok());
+    }
+    if Pdf::is_pdf_file(entry) {
+        return match Pdf::read_pages(local_path) {
+            Ok(pages) => PreviewContent::Pdf(pages),
+            Err(error) => {
+                PreviewContent::Error(FsError::io(Operation::Reading, entry, error).to_string())
+            }
+        };
+    }
+    match ::image::open(local_path) {
+        Ok(image) => {
+            let exif = crate::app::exif::read_info(local_path).ok();
+            PreviewContent::Image(Some(image), exif)
+        }
+        Err(::image::ImageError::IoError(error)) => {
+            PreviewContent::Error(FsError::io(Operation::Reading, entry, error).to_string())
+        }
+        Err(error) => PreviewContent::Error(
+            FsError::Decode {
+                path: entry.to_path_buf(),
+                message: error.to_string(),
+            }
+            .to_string(),
+        ),
+    }
+}
+
+// Reads the lines of a file that's going to be compared with another one
+async fn read_text_file(entry: &Path) -> Result<Vec<String>, FsError> {
+    match fs_provider::provider(entry).entry_type(entry).await {
+        Ok(EntryType::File(FileType::Text)) => components::read_file(entry).await,
+        Ok(_) => Err(FsError::Other(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Can't compare {}: it isn't a text file", entry.display()),
+        ))),
+        Err(error) => Err(FsError::io(Operation::Reading, entry, error)),
+    }
+}
+
+async fn read_json(entry: &Path) -> std::io::Result<serde_json::Value> {
+    let bytes = fs_provider::provider(entry).read(entry, None).await?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
@@ -0,0 +1,176 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use ratatui::prelude::{Color, Modifier, Style};
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+// A line with its ANSI escape sequences taken out, and the styles they set, as
+// (start, end, style) byte ranges of the text that's left
+#[derive(Default)]
+pub(super) struct StyledLine {
+    pub text: String,
+    pub styles: Vec<(usize, usize, Style)>,
+}
+
+pub(super) fn has_escape_sequences(lines: &[String]) -> bool {
+    lines.iter().any(|line| line.contains(ESC))
+}
+
+// Parses the escape sequences out of a file's lines. SGR (color and text attribute) sequences
+// set the style of the text that follows, even on later lines; anything else is dropped.
+pub(super) fn parse_lines(lines: &[String]) -> Vec<StyledLine> {
+    let mut style = Style::default();
+    lines
+        .iter()
+        .map(|line| parse_line(line, &mut style))
+        .collect()
+}
+
+fn parse_line(line: &str, style: &mut Style) -> StyledLine {
+    let mut styled_line = StyledLine::default();
+    let mut run_start = 0;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != ESC {
+            styled_line.text.push(c);
+            continue;
+        }
+        match chars.next() {
+            // Control sequence: parameters, then a final byte
+            Some('[') => {
+                let mut parameters = String::new();
+                let mut final_byte = None;
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        final_byte = Some(c);
+                        break;
+                    }
+                    parameters.push(c);
+                }
+                if final_byte == Some('m') {
+                    let new_style = apply_sgr(*style, &parameters);
+                    if new_style != *style {
+                        push_run(&mut styled_line, run_start, *style);
+                        run_start = styled_line.text.len();
+                        *style = new_style;
+                    }
+                }
+            }
+            // Operating system command, ended by BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == BEL {
+                        break;
+                    }
+                    if c == ESC && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Some other two character sequence
+            _ => {}
+        }
+    }
+    push_run(&mut styled_line, run_start, *style);
+    styled_line
+}
+
+// Records the style of the text since the last style change, unless it's unstyled
+fn push_run(styled_line: &mut StyledLine, start: usize, style: Style) {
+    let end = styled_line.text.len();
+    if end > start && style != Style::default() {
+        styled_line.styles.push((start, end, style));
+    }
+}
+
+// Returns the style after the SGR parameters are applied to it
+fn apply_sgr(style: Style, parameters: &str) -> Style {
+    // No parameters means reset
+    let codes: Vec<u16> = parameters
+        .split([';', ':'])
+        .map(|code| code.parse().unwrap_or(0))
+        .collect();
+    let mut style = style;
+    let mut codes = codes.into_iter();
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 => style.add_modifier(Modifier::SLOW_BLINK),
+            6 => style.add_modifier(Modifier::RAPID_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            8 => style.add_modifier(Modifier::HIDDEN),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            25 => style.remove_modifier(Modifier::SLOW_BLINK | Modifier::RAPID_BLINK),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            28 => style.remove_modifier(Modifier::HIDDEN),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(basic_color(code - 30)),
+            38 => match extended_color(&mut codes) {
+                Some(color) => style.fg(color),
+                None => style,
+            },
+            39 => style.fg(Color::Reset),
+            40..=47 => style.bg(basic_color(code - 40)),
+            48 => match extended_color(&mut codes) {
+                Some(color) => style.bg(color),
+                None => style,
+            },
+            49 => style.bg(Color::Reset),
+            90..=97 => style.fg(bright_color(code - 90)),
+            100..=107 => style.bg(bright_color(code - 100)),
+            _ => style,
+        };
+    }
+    style
+}
+
+// Reads the rest of a 256 color (5;n) or RGB (2;r;g;b) color
+fn extended_color(codes: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    match codes.next()? {
+        5 => Some(Color::Indexed(codes.next()? as u8)),
+        2 => Some(Color::Rgb(
+            codes.next()? as u8,
+            codes.next()? as u8,
+            codes.next()? as u8,
+        )),
+        _ => None,
+    }
+}
+
+fn basic_color(index: u16) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        _ => Color::Gray,
+    }
+}
+
+fn bright_color(index: u16) -> Color {
+    match index {
+        0 => Color::DarkGray,
+        1 => Color::LightRed,
+        2 => Color::LightGreen,
+        3 => Color::LightYellow,
+        4 => Color::LightBlue,
+        5 => Color::LightMagenta,
+        6 => Color::LightCyan,
+        _ => Color::White,
+    }
+}
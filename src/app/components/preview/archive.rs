@@ -0,0 +1,238 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io::Error;
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Margin, Position, Rect};
+use ratatui::prelude::{Line, Span};
+use ratatui::widgets::{
+    List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarPosition, ScrollbarState,
+};
+use ratatui::Frame;
+
+use crate::app::archive::ArchiveEntry;
+use crate::app::styles;
+use crate::stateful_list::StatefulList;
+use crate::util;
+
+use super::components;
+use super::components::key_hints::KeyHint;
+use super::preview_pane;
+use super::preview_pane::PreviewPane;
+
+// The width of the size columns
+const SIZE_WIDTH: usize = 10;
+
+// Lists the contents of an archive file, under a row of column headings
+#[derive(Default)]
+pub(super) struct Archive<'a> {
+    area: Rect,
+    heading_area: Rect,
+    inner_area: Rect,
+
+    // The archive's directory entry and title
+    entry: Option<PathBuf>,
+    title: String,
+
+    // What's in the archive
+    entry_list: StatefulList<ArchiveEntry>,
+
+    // Scrollbar stuff
+    scrollbar: Scrollbar<'a>,
+    scrollbar_state: ScrollbarState,
+    scrollbar_area: Rect,
+}
+
+impl<'a> Archive<'a> {
+    pub fn init(&mut self, entry: Option<&PathBuf>, items: Vec<ArchiveEntry>, area: Rect) {
+        self.entry = entry.cloned();
+        self.title = entry.map_or_else(String::new, |entry| preview_pane::file_title(entry));
+        self.entry_list = StatefulList::with_items(items);
+
+        self.scrollbar = Scrollbar::default().orientation(ScrollbarOrientation::VerticalRight);
+        self.set_area(area);
+    }
+
+    pub fn clear(&mut self) {
+        self.init(None, vec![], self.area);
+    }
+
+    pub fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let position = Position {
+                    x: mouse_event.column,
+                    y: mouse_event.row,
+                };
+                let key_code = match self.scrollbar.hit_test(
+                    position,
+                    self.scrollbar_area,
+                    &self.scrollbar_state,
+                ) {
+                    Some(ScrollbarPosition::Begin) => Some(KeyCode::Up),
+                    Some(ScrollbarPosition::TrackLow) => Some(KeyCode::PageUp),
+                    Some(ScrollbarPosition::TrackHigh) => Some(KeyCode::PageDown),
+                    Some(ScrollbarPosition::End) => Some(KeyCode::Down),
+                    _ => None,
+                };
+                if let Some(key_code) = key_code {
+                    self.handle_key_event(KeyEvent::new(key_code, KeyModifiers::NONE));
+                }
+            }
+            MouseEventKind::ScrollUp => {
+                self.handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE));
+            }
+            MouseEventKind::ScrollDown => {
+                self.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+            }
+            _ => { /* ignore */ }
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) {
+        let frame_height = self.inner_area.height as usize;
+        let offset = self.entry_list.offset();
+        if util::is_up_key(key_event) || key_event.code == KeyCode::Char('k') {
+            self.set_offset(offset.saturating_sub(1));
+        } else if util::is_down_key(key_event) || key_event.code == KeyCode::Char('j') {
+            self.set_offset(offset.saturating_add(1));
+        } else {
+            match key_event.code {
+                KeyCode::Home => self.set_offset(0),
+                KeyCode::End => self.set_offset(self.vertical_page_limit()),
+                KeyCode::PageUp => self.set_offset(offset.saturating_sub(frame_height)),
+                KeyCode::PageDown => self.set_offset(offset.saturating_add(frame_height)),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+        let padded_area = area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        // The column headings take the top row
+        self.heading_area = Rect {
+            height: padded_area.height.min(1),
+            ..padded_area
+        };
+        self.inner_area = Rect {
+            y: padded_area.y + self.heading_area.height,
+            height: padded_area.height - self.heading_area.height,
+            ..padded_area
+        };
+        self.scrollbar_area = Rect {
+            x: area.x,
+            width: area.width,
+            ..self.inner_area
+        };
+        self.set_scrollbar_state();
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Scroll"),
+            KeyHint::new("PgUp/PgDn", "Page"),
+            KeyHint::new("Home/End", "Top/Bottom"),
+        ]
+    }
+
+    fn vertical_page_limit(&self) -> usize {
+        <Self as PreviewPane>::page_limit(self.entry_list.len(), self.inner_area.height as usize)
+    }
+
+    // Scrolls the list, clamped to the page limit, and syncs the scrollbar to it
+    fn set_offset(&mut self, offset: usize) {
+        let offset = offset.min(self.vertical_page_limit());
+        *self.entry_list.state.offset_mut() = offset;
+        self.scrollbar_state = self.scrollbar_state.position(offset);
+    }
+
+    fn set_scrollbar_state(&mut self) {
+        let frame_length = self.inner_area.height as usize;
+        if self.entry_list.len() <= frame_length {
+            // Hide scrollbar
+            self.scrollbar_state = self.scrollbar_state.position(0).content_length(0);
+            *self.entry_list.state.offset_mut() = 0;
+        } else {
+            // Show scrollbar
+            self.scrollbar_state = self
+                .scrollbar_state
+                .content_length(self.entry_list.len() - frame_length)
+                .viewport_content_length(frame_length);
+            self.set_offset(self.entry_list.offset());
+        }
+    }
+
+    fn row_line<'b>(entry: &ArchiveEntry) -> Line<'b> {
+        let size = if entry.is_dir {
+            String::new()
+        } else {
+            util::size_string(entry.size)
+        };
+        let compressed_size = match entry.compressed_size {
+            Some(compressed_size) if !entry.is_dir => util::size_string(compressed_size),
+            _ => String::new(),
+        };
+        Line::from(vec![
+            Span::raw(format!(
+                "{size:>SIZE_WIDTH$} {compressed_size:>SIZE_WIDTH$}  "
+            )),
+            Span::raw(entry.name.clone()),
+        ])
+    }
+}
+
+impl<'a> PreviewPane for Archive<'a> {
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>, has_focus: bool) -> Result<(), Error> {
+        self.set_area(area);
+
+        if self.entry.is_some() {
+            let count = self.entry_list.len();
+            let mut title = self.title.clone();
+            title.push_str(&format!(
+                " {count} entr{}",
+                if count != 1 { "ies" } else { "y" }
+            ));
+            let block = components::component_block(has_focus).title(title);
+
+            let heading = format!("{:>SIZE_WIDTH$} {:>SIZE_WIDTH$}  Name", "Size", "Packed");
+            let offset = self.entry_list.offset();
+            let height = self.inner_area.height as usize;
+            let items: Vec<ListItem> = self
+                .entry_list
+                .iter()
+                .enumerate()
+                .map(|(index, entry)| {
+                    if index < offset || index >= offset + height {
+                        ListItem::new("") // Off screen
+                    } else {
+                        ListItem::new(Self::row_line(entry))
+                    }
+                })
+                .collect();
+            frame.render_widget(block, self.area);
+            frame.render_widget(
+                Paragraph::new(heading).style(styles::LINE_NUMBER_STYLE),
+                self.heading_area,
+            );
+            frame.render_stateful_widget(
+                List::new(items),
+                self.inner_area,
+                &mut self.entry_list.state,
+            );
+            frame.render_stateful_widget(
+                self.scrollbar.clone(),
+                self.scrollbar_area,
+                &mut self.scrollbar_state,
+            );
+        }
+        Ok(())
+    }
+}
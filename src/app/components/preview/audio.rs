@@ -0,0 +1,97 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io::Error;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use ratatui::layout::{Margin, Rect};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+
+use crate::app::audio::AudioInfo;
+
+use super::components;
+use super::preview_pane;
+use super::preview_pane::PreviewPane;
+
+// Shows an audio file's tags, length and bitrate
+#[derive(Default)]
+pub(super) struct Audio {
+    // The audio file's directory entry and title
+    entry: Option<PathBuf>,
+    title: String,
+
+    info: AudioInfo,
+}
+
+impl Audio {
+    pub fn init(&mut self, entry: Option<&PathBuf>, info: AudioInfo) {
+        self.entry = entry.cloned();
+        self.title = entry.map_or_else(String::new, |entry| preview_pane::file_title(entry));
+        self.info = info;
+    }
+
+    pub fn clear(&mut self) {
+        self.init(None, AudioInfo::default());
+    }
+
+    fn duration_string(duration: Duration) -> String {
+        let seconds = duration.as_secs();
+        if seconds >= 3600 {
+            format!(
+                "{}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            )
+        } else {
+            format!("{}:{:02}", seconds / 60, seconds % 60)
+        }
+    }
+}
+
+impl PreviewPane for Audio {
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>, has_focus: bool) -> Result<(), Error> {
+        if self.entry.is_some() {
+            let title = self.title.clone();
+            let block = components::component_block(has_focus).title(title);
+
+            let unknown = || "—".to_string();
+            let info = &self.info;
+            let properties = vec![
+                ("Title", info.title.clone().unwrap_or_else(unknown)),
+                ("Artist", info.artist.clone().unwrap_or_else(unknown)),
+                ("Album", info.album.clone().unwrap_or_else(unknown)),
+                (
+                    "Duration",
+                    info.duration
+                        .map(Self::duration_string)
+                        .unwrap_or_else(unknown),
+                ),
+                (
+                    "Bitrate",
+                    info.bitrate
+                        .map(|bitrate| format!("{} kbps", (bitrate + 500) / 1000))
+                        .unwrap_or_else(unknown),
+                ),
+                (
+                    "Artwork",
+                    if info.has_artwork { "Yes" } else { "No" }.to_string(),
+                ),
+            ];
+            let lines = components::property_lines(properties);
+            frame.render_widget(block, area);
+            frame.render_widget(
+                Paragraph::new(lines),
+                area.inner(Margin {
+                    vertical: 1,
+                    horizontal: 2,
+                }),
+            );
+        }
+        Ok(())
+    }
+}
@@ -3,40 +3,341 @@
  * Created 2024-04-02
  */
 
-use std::io::Error;
+use std::fs::File;
+use std::io::{Error, Read, Seek, SeekFrom};
 use std::path::PathBuf;
 
-use ratatui::layout::Rect;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Margin, Position, Rect};
+use ratatui::prelude::{Line, Span};
+use ratatui::widgets::{
+    Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarPosition, ScrollbarState,
+};
 use ratatui::Frame;
 
 use crate::app::styles;
+use crate::util;
 
-use super::message_pane::MessagePane;
+use super::components;
+use super::components::key_hints::KeyHint;
+use super::preview_pane;
 use super::preview_pane::PreviewPane;
+use super::strings;
 
+// The numbers of bytes that can be shown on each row of the hex dump, most preferred first
+const ROW_LENGTHS: [usize; 3] = [16, 8, 4];
+
+// Shows a binary file as a hex dump, or as the strings in it. Only the rows of the dump that are
+// showing are read from the file.
 #[derive(Default)]
-pub(super) struct Binary {
-    // The file's directory entry
+pub(super) struct Binary<'a> {
+    area: Rect,
+    inner_area: Rect,
+
+    // The file's directory entry and title, and its size
     entry: Option<PathBuf>,
+    title: String,
+    file_size: u64,
+
+    // The number of bytes on each row, which depends on how wide the pane is
+    bytes_per_row: usize,
+
+    // Whether the strings are shown instead of the dump. They're found when they're first shown,
+    // and kept with their offsets.
+    show_strings: bool,
+    strings: Option<Vec<(u64, String)>>,
+
+    // The bytes that were read last, and where in the file they start
+    chunk: Vec<u8>,
+    chunk_offset: u64,
+
+    // Scrollbar stuff
+    scrollbar: Scrollbar<'a>,
+    scrollbar_state: ScrollbarState,
+    scrollbar_area: Rect,
+    vertical_offset: usize,
+
+    // Count typed before a movement key, e.g. the 20 in 20j
+    pending_count: Option<usize>,
 }
 
-impl MessagePane for Binary {
-    fn init(&mut self, entry: Option<&PathBuf>) {
+impl<'a> Binary<'a> {
+    pub fn init(&mut self, entry: Option<&PathBuf>, area: Rect) {
         self.entry = entry.cloned();
+        self.title = entry.map_or_else(String::new, |entry| preview_pane::file_title(entry));
+        self.file_size = entry.map_or(0, |entry| util::file_size(entry));
+        self.chunk.clear();
+        self.chunk_offset = 0;
+        self.strings = None;
+        self.vertical_offset = 0;
+
+        self.scrollbar = Scrollbar::default().orientation(ScrollbarOrientation::VerticalRight);
+        self.set_area(area);
+    }
+
+    pub fn clear(&mut self) {
+        self.init(None, self.area);
+    }
+
+    pub fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        match mouse_event.kind {
+            MouseEventKind::Down(mouse_button) => {
+                if mouse_button == MouseButton::Left {
+                    let position = Position {
+                        x: mouse_event.column,
+                        y: mouse_event.row,
+                    };
+                    let key_code = match self.scrollbar.hit_test(
+                        position,
+                        self.scrollbar_area,
+                        &self.scrollbar_state,
+                    ) {
+                        Some(ScrollbarPosition::Begin) => Some(KeyCode::Up),
+                        Some(ScrollbarPosition::TrackLow) => Some(KeyCode::PageUp),
+                        Some(ScrollbarPosition::TrackHigh) => Some(KeyCode::PageDown),
+                        Some(ScrollbarPosition::End) => Some(KeyCode::Down),
+                        _ => None,
+                    };
+                    if let Some(key_code) = key_code {
+                        self.handle_key_event(KeyEvent::new(key_code, KeyModifiers::NONE));
+                    }
+                }
+            }
+            MouseEventKind::ScrollUp => {
+                let key_event = KeyEvent::new(KeyCode::Up, KeyModifiers::NONE);
+                self.handle_key_event(key_event);
+            }
+            MouseEventKind::ScrollDown => {
+                let key_event = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
+                self.handle_key_event(key_event);
+            }
+            _ => { /* ignore */ }
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) {
+        // Digits accumulate a count for the next movement key
+        if let Some(digit) = util::count_digit(key_event, self.pending_count.is_some()) {
+            self.pending_count = util::push_count_digit(self.pending_count, digit);
+            return;
+        }
+        let count = self.pending_count.take().unwrap_or(1);
+        let page_height = self.inner_area.height as usize;
+
+        if util::is_up_key(key_event) || key_event.code == KeyCode::Char('k') {
+            self.set_vertical_offset(self.vertical_offset.saturating_sub(count));
+        } else if util::is_down_key(key_event) || key_event.code == KeyCode::Char('j') {
+            self.set_vertical_offset(self.vertical_offset.saturating_add(count));
+        } else {
+            match key_event.code {
+                KeyCode::Char('s') => {
+                    self.show_strings = !self.show_strings;
+                    self.vertical_offset = 0;
+                    self.set_scrollbar_state();
+                }
+                KeyCode::Home => self.set_vertical_offset(0),
+                KeyCode::End => self.set_vertical_offset(self.page_limit()),
+                KeyCode::PageUp => {
+                    self.set_vertical_offset(
+                        self.vertical_offset
+                            .saturating_sub(page_height.saturating_mul(count)),
+                    );
+                }
+                KeyCode::PageDown => {
+                    self.set_vertical_offset(
+                        self.vertical_offset
+                            .saturating_add(page_height.saturating_mul(count)),
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+        self.inner_area = area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        self.scrollbar_area = area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        });
+
+        // Use the longest rows that fit, keeping the same bytes at the top
+        let bytes_per_row = ROW_LENGTHS
+            .into_iter()
+            .find(|length| Self::row_width(*length) <= self.inner_area.width as usize)
+            .unwrap_or(ROW_LENGTHS[ROW_LENGTHS.len() - 1]);
+        if bytes_per_row != self.bytes_per_row {
+            if self.bytes_per_row != 0 {
+                self.vertical_offset = self.vertical_offset * self.bytes_per_row / bytes_per_row;
+            }
+            self.bytes_per_row = bytes_per_row;
+        }
+        self.set_scrollbar_state();
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Scroll"),
+            KeyHint::new("PgUp/PgDn", "Page"),
+            KeyHint::new("Home/End", "Top/Bottom"),
+            KeyHint::new("s", if self.show_strings { "Hex" } else { "Strings" }),
+        ]
+    }
+
+    fn row_count(&self) -> usize {
+        if self.show_strings {
+            self.strings.as_ref().map_or(0, |strings| strings.len())
+        } else {
+            (self.file_size as usize).div_ceil(self.bytes_per_row.max(1))
+        }
+    }
+
+    // The width of a row: the offset, three columns for each byte in hex and one for it in ASCII,
+    // and the gaps between them
+    fn row_width(bytes_per_row: usize) -> usize {
+        10 + bytes_per_row * 4 + 2
+    }
+
+    fn page_limit(&self) -> usize {
+        <Self as PreviewPane>::page_limit(self.row_count(), self.inner_area.height as usize)
+    }
+
+    // Sets the vertical offset, clamped to the page limit, and syncs the scrollbar to it
+    fn set_vertical_offset(&mut self, offset: usize) {
+        self.vertical_offset = offset.min(self.page_limit());
+        self.scrollbar_state = self.scrollbar_state.position(self.vertical_offset);
+    }
+
+    fn set_scrollbar_state(&mut self) {
+        let frame_length = self.inner_area.height as usize;
+        let row_count = self.row_count();
+        if row_count <= frame_length {
+            // Hide scrollbar
+            self.scrollbar_state = self.scrollbar_state.position(0).content_length(0);
+            self.vertical_offset = 0;
+        } else {
+            // Show scrollbar
+            self.scrollbar_state = self
+                .scrollbar_state
+                .content_length(row_count - frame_length)
+                .viewport_content_length(frame_length);
+            self.set_vertical_offset(self.vertical_offset);
+        }
+    }
+
+    // Returns the bytes for the rows that are showing, reading them from the file if the last
+    // read didn't get them
+    fn visible_bytes(&mut self) -> Result<&[u8], Error> {
+        let start = (self.vertical_offset * self.bytes_per_row) as u64;
+        let length = self.inner_area.height as usize * self.bytes_per_row;
+        let end = (start + length as u64).min(self.file_size);
+        let chunk_end = self.chunk_offset + self.chunk.len() as u64;
+        if start < self.chunk_offset || end > chunk_end {
+            if let Some(entry) = &self.entry {
+                let mut file = File::open(entry)?;
+                file.seek(SeekFrom::Start(start))?;
+                self.chunk.clear();
+                file.take(length as u64).read_to_end(&mut self.chunk)?;
+                self.chunk_offset = start;
+            }
+        }
+        let from = ((start - self.chunk_offset) as usize).min(self.chunk.len());
+        let to = ((end.saturating_sub(self.chunk_offset)) as usize).clamp(from, self.chunk.len());
+        Ok(&self.chunk[from..to])
+    }
+
+    // Returns the rows of strings that are showing
+    fn visible_strings<'b>(&self) -> Vec<Line<'b>> {
+        let height = self.inner_area.height as usize;
+        self.strings
+            .iter()
+            .flatten()
+            .skip(self.vertical_offset)
+            .take(height)
+            .map(|(offset, string)| {
+                Line::from(vec![
+                    Span::styled(format!("{offset:08x}  "), styles::LINE_NUMBER_STYLE),
+                    Span::raw(string.clone()),
+                ])
+            })
+            .collect()
+    }
+
+    // Formats a row of the dump: the offset, the bytes in hex, and the bytes as ASCII
+    fn hex_row<'b>(offset: u64, bytes: &[u8], bytes_per_row: usize) -> Line<'b> {
+        let mut hex = String::new();
+        for index in 0..bytes_per_row {
+            match bytes.get(index) {
+                Some(byte) => hex.push_str(&format!("{byte:02x} ")),
+                None => hex.push_str("   "),
+            }
+            if index == bytes_per_row / 2 - 1 {
+                hex.push(' ');
+            }
+        }
+        let ascii: String = bytes
+            .iter()
+            .map(|byte| {
+                if byte.is_ascii_graphic() || *byte == b' ' {
+                    *byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        Line::from(vec![
+            Span::styled(format!("{offset:08x}  "), styles::LINE_NUMBER_STYLE),
+            Span::raw(hex),
+            Span::raw(format!(" {ascii}")),
+        ])
     }
 }
 
-impl PreviewPane for Binary {
+impl<'a> PreviewPane for Binary<'a> {
     fn render(&mut self, area: Rect, frame: &mut Frame<'_>, has_focus: bool) -> Result<(), Error> {
-        if let Some(entry) = &self.entry {
-            <Self as MessagePane>::render_message(
-                entry,
-                "Binary File",
-                has_focus,
-                styles::BINARY_FILE_STYLE,
-                frame,
-                area,
-            )?;
+        if let (true, None, Some(entry)) = (self.show_strings, &self.strings, &self.entry) {
+            self.strings = Some(strings::find_strings(entry)?);
+        }
+        self.set_area(area);
+
+        if self.entry.is_some() {
+            let mut title = self.title.clone();
+            if self.show_strings {
+                title.push_str(" Strings");
+                if self.file_size > strings::MAX_SEARCH_SIZE {
+                    title.push_str(&format!(
+                        " (first {})",
+                        util::size_string(strings::MAX_SEARCH_SIZE)
+                    ));
+                }
+            }
+            let block = components::component_block(has_focus).title(title);
+
+            let lines: Vec<Line> = if self.show_strings {
+                self.visible_strings()
+            } else {
+                let bytes_per_row = self.bytes_per_row;
+                let first_offset = (self.vertical_offset * bytes_per_row) as u64;
+                self.visible_bytes()?
+                    .chunks(bytes_per_row)
+                    .enumerate()
+                    .map(|(index, bytes)| {
+                        let offset = first_offset + (index * bytes_per_row) as u64;
+                        Self::hex_row(offset, bytes, bytes_per_row)
+                    })
+                    .collect()
+            };
+            frame.render_widget(block, self.area);
+            frame.render_widget(Paragraph::new(lines), self.inner_area);
+            frame.render_stateful_widget(
+                self.scrollbar.clone(),
+                self.scrollbar_area,
+                &mut self.scrollbar_state,
+            );
         }
         Ok(())
     }
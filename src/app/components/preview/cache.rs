@@ -0,0 +1,118 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::constants;
+
+use super::PreviewContent;
+
+// Identifies an entry as it was when it was read, so a changed entry isn't found in the cache.
+// The size is included because some file systems only record modified times to the second.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct CacheKey {
+    path: PathBuf,
+    modified: SystemTime,
+    size: u64,
+}
+
+impl CacheKey {
+    // The key for the entry as it is now, unless it can't be looked up
+    pub fn new(entry: &Path) -> Option<CacheKey> {
+        let metadata = entry.metadata().ok()?;
+        Some(CacheKey {
+            path: entry.to_path_buf(),
+            modified: metadata.modified().ok()?,
+            size: metadata.len(),
+        })
+    }
+}
+
+// The previews that were loaded most recently, so going back and forth between a few entries
+// doesn't read them again
+#[derive(Default)]
+pub(super) struct PreviewCache {
+    // The least recently used first
+    previews: VecDeque<(CacheKey, PreviewContent)>,
+    // Roughly how many bytes the previews take up
+    size: usize,
+}
+
+impl PreviewCache {
+    pub fn get(&mut self, key: &CacheKey) -> Option<PreviewContent> {
+        let index = self.previews.iter().position(|(k, _)| k == key)?;
+        let preview = self.previews.remove(index)?;
+        let content = preview.1.clone();
+        self.previews.push_back(preview);
+        Some(content)
+    }
+
+    // Adds the preview, replacing any older one of the same entry. Errors aren't kept, so the
+    // entry is read again next time.
+    pub fn insert(&mut self, key: CacheKey, content: PreviewContent) {
+        if let PreviewContent::Error(_) = content {
+            return;
+        }
+        if let Some(index) = self.previews.iter().position(|(k, _)| k.path == key.path) {
+            self.remove(index);
+        }
+        if self.previews.len() >= constants::PREVIEW_CACHE_SIZE {
+            self.remove(0);
+        }
+        self.size += content.approximate_size();
+        self.previews.push_back((key, content));
+    }
+
+    pub fn clear(&mut self) {
+        self.previews.clear();
+        self.size = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.previews.len()
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    fn remove(&mut self, index: usize) {
+        if let Some((_, content)) = self.previews.remove(index) {
+            self.size -= content.approximate_size();
+        }
+    }
+}
+
+// Where text previews were scrolled to, vertically and horizontally, so coming back to a file
+// shows where it was being read. A file that's changed since starts at the top again.
+#[derive(Default)]
+pub(super) struct ScrollMemory {
+    // The least recently viewed first
+    offsets: VecDeque<(CacheKey, (usize, usize))>,
+}
+
+impl ScrollMemory {
+    pub fn get(&self, key: &CacheKey) -> Option<(usize, usize)> {
+        self.offsets
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, offsets)| *offsets)
+    }
+
+    // Remembers the offsets, forgetting any for an older version of the file
+    pub fn insert(&mut self, key: CacheKey, offsets: (usize, usize)) {
+        self.offsets.retain(|(k, _)| k.path != key.path);
+        // A file at the top doesn't need remembering
+        if offsets == (0, 0) {
+            return;
+        }
+        if self.offsets.len() >= constants::SCROLL_MEMORY_SIZE {
+            self.offsets.pop_front();
+        }
+        self.offsets.push_back((key, offsets));
+    }
+}
@@ -0,0 +1,496 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io::Error;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Margin, Position, Rect};
+use ratatui::prelude::{Line, Span, Style};
+use ratatui::widgets::{
+    Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarPosition, ScrollbarState,
+};
+use ratatui::Frame;
+use similar::{Algorithm, DiffTag};
+
+use crate::app::styles;
+use crate::util;
+
+use super::components;
+use super::components::key_hints::KeyHint;
+use super::components::wheel::{WheelDirection, WheelScroll};
+use super::preview_pane::PreviewPane;
+
+// How long finding the smallest diff can take before a bigger one that's quicker to find is used
+const DIFF_DEADLINE: Duration = Duration::from_millis(500);
+
+// How a row of the diff differs between the files
+#[derive(Clone, Copy, PartialEq)]
+enum Change {
+    Same,
+    Removed,
+    Added,
+    Changed,
+}
+
+// A row of the diff, with the (0-based) lines of the left and right files that are on it
+#[derive(Clone, Copy)]
+struct DiffRow {
+    change: Change,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+// Shows how two text files differ, side by side, or unified, with the lines removed from the
+// left file above the lines that replace them
+#[derive(Default)]
+pub(super) struct Diff<'a> {
+    area: Rect,
+    inner_area: Rect,
+
+    title: String,
+
+    // The files' lines, the rows of the diff shown side by side and unified, and which is shown
+    left_lines: Vec<String>,
+    right_lines: Vec<String>,
+    side_by_side_rows: Vec<DiffRow>,
+    unified_rows: Vec<DiffRow>,
+    is_unified: bool,
+    widest_line_len: usize,
+
+    vertical_offset: usize,
+    horizontal_offset: usize,
+
+    // Scrollbar stuff
+    scrollbar: Scrollbar<'a>,
+    scrollbar_state: ScrollbarState,
+    scrollbar_area: Rect,
+
+    // How far the mouse wheel scrolls the diff
+    wheel_scroll: WheelScroll,
+}
+
+impl<'a> Diff<'a> {
+    pub fn init(
+        &mut self,
+        left: &Path,
+        right: &Path,
+        left_lines: Vec<String>,
+        right_lines: Vec<String>,
+        area: Rect,
+    ) {
+        self.left_lines = left_lines
+            .iter()
+            .map(|line| Self::expand_tabs(line))
+            .collect();
+        self.right_lines = right_lines
+            .iter()
+            .map(|line| Self::expand_tabs(line))
+            .collect();
+        self.widest_line_len = self
+            .left_lines
+            .iter()
+            .chain(&self.right_lines)
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        self.side_by_side_rows.clear();
+        self.unified_rows.clear();
+
+        let deadline = Instant::now() + DIFF_DEADLINE;
+        let ops = similar::capture_diff_slices_deadline(
+            Algorithm::Myers,
+            &self.left_lines,
+            &self.right_lines,
+            Some(deadline),
+        );
+        let (mut removed_count, mut added_count) = (0, 0);
+        for op in ops {
+            let (tag, left_range, right_range) = op.as_tag_tuple();
+            if tag == DiffTag::Equal {
+                for (left, right) in left_range.zip(right_range) {
+                    let row = DiffRow {
+                        change: Change::Same,
+                        left: Some(left),
+                        right: Some(right),
+                    };
+                    self.side_by_side_rows.push(row);
+                    self.unified_rows.push(row);
+                }
+                continue;
+            }
+            // Side by side, the removed lines are beside the lines that replace them
+            let (left_count, right_count) = (left_range.len(), right_range.len());
+            for index in 0..left_count.max(right_count) {
+                let left = (index < left_count).then_some(left_range.start + index);
+                let right = (index < right_count).then_some(right_range.start + index);
+                let change = match (left, right) {
+                    (Some(_), Some(_)) => Change::Changed,
+                    (Some(_), None) => Change::Removed,
+                    _ => Change::Added,
+                };
+                self.side_by_side_rows.push(DiffRow {
+                    change,
+                    left,
+                    right,
+                });
+            }
+            self.unified_rows.extend(left_range.map(|left| DiffRow {
+                change: Change::Removed,
+                left: Some(left),
+                right: None,
+            }));
+            self.unified_rows.extend(right_range.map(|right| DiffRow {
+                change: Change::Added,
+                left: None,
+                right: Some(right),
+            }));
+            removed_count += left_count;
+            added_count += right_count;
+        }
+
+        self.title = format!("[{} ↔ {}]", util::entry_name(left), util::entry_name(right));
+        if removed_count == 0 && added_count == 0 {
+            self.title.push_str(" Identical");
+        } else {
+            self.title
+                .push_str(&format!(" -{removed_count} +{added_count}"));
+        }
+        self.vertical_offset = 0;
+        self.horizontal_offset = 0;
+        self.scrollbar = Scrollbar::default().orientation(ScrollbarOrientation::VerticalRight);
+        self.set_area(area);
+    }
+
+    pub fn clear(&mut self) {
+        self.title = String::new();
+        self.left_lines = vec![];
+        self.right_lines = vec![];
+        self.side_by_side_rows = vec![];
+        self.unified_rows = vec![];
+        self.widest_line_len = 0;
+        self.vertical_offset = 0;
+        self.horizontal_offset = 0;
+        self.set_area(self.area);
+    }
+
+    pub fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let position = Position {
+                    x: mouse_event.column,
+                    y: mouse_event.row,
+                };
+                let key_code = match self.scrollbar.hit_test(
+                    position,
+                    self.scrollbar_area,
+                    &self.scrollbar_state,
+                ) {
+                    Some(ScrollbarPosition::Begin) => Some(KeyCode::Up),
+                    Some(ScrollbarPosition::TrackLow) => Some(KeyCode::PageUp),
+                    Some(ScrollbarPosition::TrackHigh) => Some(KeyCode::PageDown),
+                    Some(ScrollbarPosition::End) => Some(KeyCode::Down),
+                    _ => None,
+                };
+                if let Some(key_code) = key_code {
+                    self.handle_key_event(KeyEvent::new(key_code, KeyModifiers::NONE));
+                }
+            }
+            MouseEventKind::ScrollUp => {
+                let lines = self.wheel_scroll.lines(WheelDirection::Up);
+                self.set_vertical_offset(self.vertical_offset.saturating_sub(lines));
+            }
+            MouseEventKind::ScrollDown => {
+                let lines = self.wheel_scroll.lines(WheelDirection::Down);
+                self.set_vertical_offset(self.vertical_offset.saturating_add(lines));
+            }
+            _ => { /* ignore */ }
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) {
+        let frame_height = self.inner_area.height as usize;
+        let is_control = key_event.modifiers == KeyModifiers::CONTROL;
+        if util::is_up_key(key_event) || key_event.code == KeyCode::Char('k') {
+            self.set_vertical_offset(self.vertical_offset.saturating_sub(1));
+        } else if util::is_down_key(key_event) || key_event.code == KeyCode::Char('j') {
+            self.set_vertical_offset(self.vertical_offset.saturating_add(1));
+        } else {
+            match key_event.code {
+                KeyCode::Home if is_control => self.set_vertical_offset(0),
+                KeyCode::End if is_control => self.set_vertical_offset(self.vertical_page_limit()),
+                KeyCode::Home => self.set_horizontal_offset(0),
+                KeyCode::End => self.set_horizontal_offset(self.horizontal_page_limit()),
+                KeyCode::PageUp => {
+                    self.set_vertical_offset(self.vertical_offset.saturating_sub(frame_height))
+                }
+                KeyCode::PageDown => {
+                    self.set_vertical_offset(self.vertical_offset.saturating_add(frame_height))
+                }
+                KeyCode::Left => {
+                    self.set_horizontal_offset(self.horizontal_offset.saturating_sub(1))
+                }
+                KeyCode::Right => {
+                    self.set_horizontal_offset(self.horizontal_offset.saturating_add(1))
+                }
+                KeyCode::Char('n') => self.step_change(true),
+                KeyCode::Char('N') => self.step_change(false),
+                KeyCode::Char('u') => {
+                    // Keep the top line at the top
+                    let top_row = self.rows().get(self.vertical_offset).copied();
+                    self.is_unified = !self.is_unified;
+                    self.set_area(self.area);
+                    if let Some(top_row) = top_row {
+                        let index = self.rows().iter().position(|row| {
+                            row.left.is_some() && row.left == top_row.left
+                                || row.right.is_some() && row.right == top_row.right
+                        });
+                        self.set_vertical_offset(index.unwrap_or(0));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+        self.inner_area = area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        self.scrollbar_area = Rect {
+            x: area.x,
+            width: area.width,
+            ..self.inner_area
+        };
+        self.set_scrollbar_state();
+    }
+
+    pub fn set_wheel_scroll(&mut self, wheel_scroll: WheelScroll) {
+        self.wheel_scroll = wheel_scroll;
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Scroll"),
+            KeyHint::new("←/→", "Pan"),
+            KeyHint::new("PgUp/PgDn", "Page"),
+            KeyHint::new("^Home/^End", "Top/Bottom"),
+            KeyHint::new("n/N", "Next/Previous change"),
+            KeyHint::new(
+                "u",
+                if self.is_unified {
+                    "Side by side"
+                } else {
+                    "Unified"
+                },
+            ),
+        ]
+    }
+
+    fn rows(&self) -> &[DiffRow] {
+        if self.is_unified {
+            &self.unified_rows
+        } else {
+            &self.side_by_side_rows
+        }
+    }
+
+    // Scrolls to the first row of the next or previous group of changed rows
+    fn step_change(&mut self, forward: bool) {
+        let rows = self.rows();
+        let is_change_start = |index: usize| {
+            rows[index].change != Change::Same
+                && (index == 0 || rows[index - 1].change == Change::Same)
+        };
+        let index = if forward {
+            (self.vertical_offset + 1..rows.len()).find(|index| is_change_start(*index))
+        } else {
+            (0..self.vertical_offset.min(rows.len()))
+                .rev()
+                .find(|index| is_change_start(*index))
+        };
+        if let Some(index) = index {
+            self.set_vertical_offset(index);
+        }
+    }
+
+    // How wide the line numbers are, and how much room each side has for its lines
+    fn column_widths(&self) -> (usize, usize) {
+        let number_width = self
+            .left_lines
+            .len()
+            .max(self.right_lines.len())
+            .to_string()
+            .len();
+        let width = self.inner_area.width as usize;
+        let column_width = if self.is_unified {
+            width
+        } else {
+            // A line goes between the sides
+            width.saturating_sub(1) / 2
+        };
+        // A space after the number, and the +/- sign when it's unified
+        let gutter_width = number_width + if self.is_unified { 3 } else { 1 };
+        (number_width, column_width.saturating_sub(gutter_width))
+    }
+
+    fn vertical_page_limit(&self) -> usize {
+        <Self as PreviewPane>::page_limit(self.rows().len(), self.inner_area.height as usize)
+    }
+
+    fn horizontal_page_limit(&self) -> usize {
+        let (_, text_width) = self.column_widths();
+        <Self as PreviewPane>::page_limit(self.widest_line_len, text_width)
+    }
+
+    fn expand_tabs(line: &str) -> String {
+        line.replace('\t', "        ")
+    }
+
+    // Sets the vertical offset, clamped to the page limit, and syncs the scrollbar to it
+    fn set_vertical_offset(&mut self, offset: usize) {
+        self.vertical_offset = offset.min(self.vertical_page_limit());
+        self.scrollbar_state = self.scrollbar_state.position(self.vertical_offset);
+    }
+
+    fn set_horizontal_offset(&mut self, offset: usize) {
+        self.horizontal_offset = offset.min(self.horizontal_page_limit());
+    }
+
+    fn set_scrollbar_state(&mut self) {
+        let frame_length = self.inner_area.height as usize;
+        let row_count = self.rows().len();
+        if row_count <= frame_length {
+            // Hide scrollbar
+            self.scrollbar_state = self.scrollbar_state.position(0).content_length(0);
+            self.vertical_offset = 0;
+        } else {
+            // Show scrollbar
+            self.scrollbar_state = self
+                .scrollbar_state
+                .content_length(row_count - frame_length)
+                .viewport_content_length(frame_length);
+            self.set_vertical_offset(self.vertical_offset);
+        }
+        self.set_horizontal_offset(self.horizontal_offset);
+    }
+
+    // A line of one of the files, numbered, scrolled sideways and colored by how it changed. A
+    // side of the row without a line is blank.
+    fn numbered_line(
+        &self,
+        lines: &[String],
+        line: Option<usize>,
+        sign: &str,
+        style: Style,
+    ) -> Line<'a> {
+        let Some(line) = line else {
+            return Line::default();
+        };
+        let (number_width, _) = self.column_widths();
+        let text: String = lines[line].chars().skip(self.horizontal_offset).collect();
+        Line::from(vec![
+            Span::styled(
+                format!("{:>number_width$} ", line + 1),
+                styles::LINE_NUMBER_STYLE,
+            ),
+            Span::styled(format!("{sign}{text}"), style),
+        ])
+    }
+
+    fn change_style(change: Change, is_left: bool) -> Style {
+        match change {
+            Change::Same => Style::default(),
+            Change::Removed => styles::DIFF_REMOVED_STYLE,
+            Change::Added => styles::DIFF_ADDED_STYLE,
+            Change::Changed if is_left => styles::DIFF_REMOVED_STYLE,
+            Change::Changed => styles::DIFF_ADDED_STYLE,
+        }
+    }
+}
+
+impl<'a> PreviewPane for Diff<'a> {
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>, has_focus: bool) -> Result<(), Error> {
+        self.set_area(area);
+
+        let block = components::component_block(has_focus).title(self.title.clone());
+        frame.render_widget(block, self.area);
+
+        let height = self.inner_area.height as usize;
+        let rows = self.rows().iter().skip(self.vertical_offset).take(height);
+        if self.is_unified {
+            let lines: Vec<Line> = rows
+                .map(|row| match row.change {
+                    Change::Added => self.numbered_line(
+                        &self.right_lines,
+                        row.right,
+                        "+ ",
+                        styles::DIFF_ADDED_STYLE,
+                    ),
+                    Change::Removed => self.numbered_line(
+                        &self.left_lines,
+                        row.left,
+                        "- ",
+                        styles::DIFF_REMOVED_STYLE,
+                    ),
+                    _ => self.numbered_line(&self.left_lines, row.left, "  ", Style::default()),
+                })
+                .collect();
+            frame.render_widget(Paragraph::new(lines), self.inner_area);
+        } else {
+            let (left_lines, right_lines): (Vec<Line>, Vec<Line>) = rows
+                .map(|row| {
+                    (
+                        self.numbered_line(
+                            &self.left_lines,
+                            row.left,
+                            "",
+                            Self::change_style(row.change, true),
+                        ),
+                        self.numbered_line(
+                            &self.right_lines,
+                            row.right,
+                            "",
+                            Self::change_style(row.change, false),
+                        ),
+                    )
+                })
+                .unzip();
+            let column_width = self.inner_area.width.saturating_sub(1) / 2;
+            let left_area = Rect {
+                width: column_width,
+                ..self.inner_area
+            };
+            let divider_area = Rect {
+                x: left_area.right(),
+                width: 1.min(self.inner_area.width),
+                ..self.inner_area
+            };
+            let right_area = Rect {
+                x: divider_area.right(),
+                width: self.inner_area.right().saturating_sub(divider_area.right()),
+                ..self.inner_area
+            };
+            let divider: Vec<Line> = (0..self.inner_area.height)
+                .map(|_| Line::from("│"))
+                .collect();
+            frame.render_widget(Paragraph::new(left_lines), left_area);
+            frame.render_widget(
+                Paragraph::new(divider).style(styles::LINE_NUMBER_STYLE),
+                divider_area,
+            );
+            frame.render_widget(Paragraph::new(right_lines), right_area);
+        }
+        frame.render_stateful_widget(
+            self.scrollbar.clone(),
+            self.scrollbar_area,
+            &mut self.scrollbar_state,
+        );
+        Ok(())
+    }
+}
@@ -10,37 +10,52 @@ use ratatui::layout::{Margin, Position, Rect};
 use ratatui::widgets::{List, Scrollbar, ScrollbarOrientation, ScrollbarPosition, ScrollbarState};
 use ratatui::Frame;
 
+use crate::app::fs_provider::ListingEntry;
 use crate::stateful_list::StatefulList;
 use crate::util;
 
 use super::components;
+use super::components::key_hints::KeyHint;
+use super::components::wheel::{WheelDirection, WheelScroll};
 use super::list_pane::ListPane;
 use super::preview_pane;
 use super::preview_pane::PreviewPane;
+use super::thumb_drag::ThumbDrag;
 
 #[derive(Default)]
 pub(super) struct Folder<'a> {
     area: Rect,
     inner_area: Rect,
 
-    // The folder's directory entry
+    // The folder's directory entry, and its title
     entry: Option<PathBuf>,
+    title: String,
 
-    // The folder's contents
+    // The folder's contents, and what was listed about them
     entry_list: StatefulList<PathBuf>,
+    metadata: util::KnownMetadata,
 
     // Scrollbar stuff
     scrollbar: Scrollbar<'a>,
     scrollbar_state: ScrollbarState,
     scrollbar_area: Rect,
+    thumb_drag: Option<ThumbDrag>,
+
+    // How far the mouse wheel scrolls the list
+    wheel_scroll: WheelScroll,
 }
 
-impl<'a> ListPane<PathBuf> for Folder<'a> {
-    fn init(&mut self, entry: Option<&PathBuf>, items: Vec<PathBuf>, area: Rect) {
+impl<'a> ListPane<ListingEntry> for Folder<'a> {
+    fn init(&mut self, entry: Option<&PathBuf>, items: Vec<ListingEntry>, area: Rect) {
         self.set_area(area);
 
         self.entry = entry.cloned();
-        self.entry_list = StatefulList::with_items(items);
+        self.title = entry.map_or_else(String::new, |entry| {
+            preview_pane::folder_title(entry, items.len())
+        });
+        self.entry_list =
+            StatefulList::with_items(items.iter().map(|(item, _)| item.clone()).collect());
+        self.metadata = items.into_iter().collect();
 
         self.scrollbar = Scrollbar::default().orientation(ScrollbarOrientation::VerticalRight);
         self.set_scrollbar_state();
@@ -49,6 +64,7 @@ impl<'a> ListPane<PathBuf> for Folder<'a> {
     fn clear(&mut self) {
         self.entry = None;
         self.entry_list = StatefulList::with_items(vec![]);
+        self.thumb_drag = None;
 
         self.set_scrollbar_state();
     }
@@ -61,42 +77,67 @@ impl<'a> ListPane<PathBuf> for Folder<'a> {
                         x: mouse_event.column,
                         y: mouse_event.row,
                     };
+                    // Dragging the thumb scrolls the list along with it
+                    self.thumb_drag = ThumbDrag::start(
+                        &self.scrollbar,
+                        self.scrollbar_area,
+                        &self.scrollbar_state,
+                        true,
+                        position,
+                        self.entry_list.offset(),
+                        self.entry_list.len(),
+                    );
+                    if self.thumb_drag.is_some() {
+                        return;
+                    }
                     match self.scrollbar.hit_test(
                         position,
                         self.scrollbar_area,
                         &self.scrollbar_state,
                     ) {
                         None => {}
-                        Some(scrollbar_position) => {
-                            match scrollbar_position {
-                                ScrollbarPosition::Begin => self.handle_key_event(KeyEvent::new(
-                                    KeyCode::Up,
-                                    KeyModifiers::NONE,
-                                )),
-                                ScrollbarPosition::TrackLow => self.handle_key_event(
-                                    KeyEvent::new(KeyCode::PageUp, KeyModifiers::NONE),
-                                ),
-                                // ScrollbarPosition::Thumb => {}
-                                ScrollbarPosition::TrackHigh => self.handle_key_event(
-                                    KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE),
-                                ),
-                                ScrollbarPosition::End => self.handle_key_event(KeyEvent::new(
-                                    KeyCode::Down,
-                                    KeyModifiers::NONE,
-                                )),
-                                _ => {}
-                            }
-                        }
+                        Some(scrollbar_position) => match scrollbar_position {
+                            ScrollbarPosition::Begin => self
+                                .handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE)),
+                            ScrollbarPosition::TrackLow => self.handle_key_event(KeyEvent::new(
+                                KeyCode::PageUp,
+                                KeyModifiers::NONE,
+                            )),
+                            ScrollbarPosition::TrackHigh => self.handle_key_event(KeyEvent::new(
+                                KeyCode::PageDown,
+                                KeyModifiers::NONE,
+                            )),
+                            ScrollbarPosition::End => self
+                                .handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)),
+                            _ => {}
+                        },
                     }
                 }
             }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some(thumb_drag) = &self.thumb_drag {
+                    let offset = thumb_drag.offset(Position {
+                        x: mouse_event.column,
+                        y: mouse_event.row,
+                    });
+                    self.entry_list
+                        .set_offset(offset.min(self.vertical_page_limit()));
+                    self.sync_scrollbar_position();
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => self.thumb_drag = None,
             MouseEventKind::ScrollUp => {
-                let key_event = KeyEvent::new(KeyCode::Up, KeyModifiers::NONE);
-                self.handle_key_event(key_event);
+                let lines = self.wheel_scroll.lines(WheelDirection::Up);
+                self.entry_list
+                    .set_offset(self.entry_list.offset().saturating_sub(lines));
+                self.sync_scrollbar_position();
             }
             MouseEventKind::ScrollDown => {
-                let key_event = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
-                self.handle_key_event(key_event);
+                let lines = self.wheel_scroll.lines(WheelDirection::Down);
+                let offset = self.entry_list.offset().saturating_add(lines);
+                self.entry_list
+                    .set_offset(offset.min(self.vertical_page_limit()));
+                self.sync_scrollbar_position();
             }
             _ => { /* ignore */ }
         }
@@ -176,6 +217,14 @@ impl<'a> ListPane<PathBuf> for Folder<'a> {
         });
         self.set_scrollbar_state();
     }
+
+    fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Scroll"),
+            KeyHint::new("PgUp/PgDn", "Page"),
+            KeyHint::new("Home/End", "Top/Bottom"),
+        ]
+    }
 }
 
 impl<'a> PreviewPane for Folder<'a> {
@@ -187,14 +236,17 @@ impl<'a> PreviewPane for Folder<'a> {
     ) -> Result<(), std::io::Error> {
         self.set_area(area);
 
-        if let Some(entry) = &self.entry {
-            let title = preview_pane::folder_title(entry, self.entry_list.len())?;
-            let block = components::component_block(has_focus).title(title);
+        if self.entry.is_some() {
+            let block = components::component_block(has_focus).title(self.title.as_str());
 
-            let items = util::list_items(&self.entry_list, self.inner_area.height as usize);
+            let (items, mut state) = util::list_items(
+                &mut self.entry_list,
+                self.inner_area.height as usize,
+                &mut self.metadata,
+            );
             let list = List::new(items);
             frame.render_widget(block, self.area);
-            frame.render_stateful_widget(list, self.inner_area, &mut self.entry_list.state);
+            frame.render_stateful_widget(list, self.inner_area, &mut state);
 
             frame.render_stateful_widget(
                 self.scrollbar.clone(),
@@ -207,6 +259,10 @@ impl<'a> PreviewPane for Folder<'a> {
 }
 
 impl<'a> Folder<'a> {
+    pub fn set_wheel_scroll(&mut self, wheel_scroll: WheelScroll) {
+        self.wheel_scroll = wheel_scroll;
+    }
+
     fn vertical_page_limit(&self) -> usize {
         <Self as PreviewPane>::page_limit(self.entry_list.len(), self.inner_area.height as usize)
     }
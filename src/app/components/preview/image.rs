@@ -0,0 +1,227 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io::Error;
+use std::path::{Path, PathBuf};
+
+use image::{DynamicImage, ImageFormat};
+use ratatui::layout::{Margin, Rect};
+use ratatui::prelude::{Color, Line, Span, Style};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+
+use crate::app::exif::ExifInfo;
+use crate::graphics;
+use crate::graphics::Protocol;
+
+use super::components;
+use super::preview_pane;
+use super::preview_pane::PreviewPane;
+
+// The image formats that are previewed as images
+const IMAGE_FORMATS: [ImageFormat; 4] = [
+    ImageFormat::Png,
+    ImageFormat::Jpeg,
+    ImageFormat::Gif,
+    ImageFormat::WebP,
+];
+
+// Shows an image file. If the terminal has a graphics protocol, the pane only draws its frame,
+// and the image itself is drawn over the inside of it after the frame is drawn. Otherwise the
+// image is approximated with colored half blocks, two pixels to a cell. What the photo's EXIF
+// data says goes underneath it. Files that can't be decoded only show that.
+#[derive(Default)]
+pub(super) struct Image {
+    area: Rect,
+    image_area: Rect,
+    exif_area: Rect,
+
+    // The file's directory entry and title, the decoded image, and its EXIF data
+    entry: Option<PathBuf>,
+    title: String,
+    image: Option<DynamicImage>,
+    exif: Option<ExifInfo>,
+
+    // How the terminal draws images
+    protocol: Protocol,
+
+    // The half block rows, and the size they were made for
+    half_blocks: Option<(Rect, Vec<Line<'static>>)>,
+}
+
+impl Image {
+    pub fn init(
+        &mut self,
+        entry: Option<&PathBuf>,
+        image: Option<DynamicImage>,
+        exif: Option<ExifInfo>,
+        area: Rect,
+    ) {
+        self.entry = entry.cloned();
+        self.title = entry.map_or_else(String::new, |entry| preview_pane::file_title(entry));
+        self.image = image;
+        self.exif = exif;
+        self.half_blocks = None;
+        self.set_area(area);
+    }
+
+    pub fn clear(&mut self) {
+        self.init(None, None, None, self.area);
+    }
+
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+        let inner_area = area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        // The EXIF data takes the bottom rows, with a blank row between it and the image
+        let exif_height = match (&self.image, self.exif_properties().len() as u16) {
+            (None, _) => inner_area.height,
+            (Some(_), 0) => 0,
+            (Some(_), rows) => rows.min(inner_area.height / 2),
+        };
+        let image_height = inner_area
+            .height
+            .saturating_sub(exif_height)
+            .saturating_sub(if exif_height > 0 { 1 } else { 0 });
+        self.image_area = Rect {
+            height: image_height,
+            ..inner_area
+        };
+        self.exif_area = Rect {
+            y: inner_area.bottom() - exif_height,
+            height: exif_height,
+            ..inner_area
+        };
+    }
+
+    pub fn set_protocol(&mut self, protocol: Protocol) {
+        self.protocol = protocol;
+    }
+
+    // The image, and where it goes, if the terminal can draw it
+    pub fn placement(&self) -> Option<(&Path, &DynamicImage, Rect)> {
+        match (&self.entry, &self.image, self.protocol) {
+            (_, _, Protocol::None) => None,
+            (Some(entry), Some(image), _) => Some((entry, image, self.image_area)),
+            _ => None,
+        }
+    }
+
+    // Whether the file is in one of the image formats that are previewed
+    pub fn is_image_file(entry: &Path) -> bool {
+        ImageFormat::from_path(entry).is_ok_and(|format| IMAGE_FORMATS.contains(&format))
+    }
+
+    // Returns the image as rows of half blocks that fit in the inside of the pane, making them
+    // again if the pane's size has changed
+    fn half_block_lines(&mut self) -> Vec<Line<'static>> {
+        let size = Rect::new(0, 0, self.image_area.width, self.image_area.height);
+        let stale = self.half_blocks.as_ref().map(|(rect, _)| *rect) != Some(size);
+        if let (true, Some(image)) = (stale, &self.image) {
+            self.half_blocks = Some((size, Self::half_blocks(image, size)));
+        }
+        self.half_blocks
+            .as_ref()
+            .map(|(_, lines)| lines.clone())
+            .unwrap_or_default()
+    }
+
+    // The EXIF data that's there, as names and values
+    fn exif_properties(&self) -> Vec<(&'static str, String)> {
+        let Some(exif) = &self.exif else {
+            return vec![];
+        };
+        let mut properties = vec![];
+        if let Some(camera) = &exif.camera {
+            properties.push(("Camera", camera.clone()));
+        }
+        if let Some(taken) = &exif.taken {
+            properties.push(("Taken", taken.clone()));
+        }
+        if let Some((width, height)) = exif.dimensions {
+            properties.push(("Dimensions", format!("{width}x{height}")));
+        }
+        if let Some((latitude, longitude)) = exif.location {
+            let hemisphere = |value: f64, positive, negative| {
+                format!(
+                    "{:.5}° {}",
+                    value.abs(),
+                    if value < 0.0 { negative } else { positive }
+                )
+            };
+            properties.push((
+                "Location",
+                format!(
+                    "{}, {}",
+                    hemisphere(latitude, "N", "S"),
+                    hemisphere(longitude, "E", "W")
+                ),
+            ));
+        }
+        if let Some(orientation) = &exif.orientation {
+            properties.push(("Orientation", orientation.clone()));
+        }
+        properties
+    }
+
+    // Each cell is an upper half block, colored with the pixel above, on the pixel below
+    fn half_blocks(image: &DynamicImage, size: Rect) -> Vec<Line<'static>> {
+        let pixels = graphics::fit_image(image, size.width as u32, size.height as u32 * 2);
+        let color = |x, y| {
+            if y < pixels.height() {
+                let [r, g, b, a] = pixels.get_pixel(x, y).0;
+                if a >= 128 {
+                    return Color::Rgb(r, g, b);
+                }
+            }
+            Color::Reset
+        };
+        (0..pixels.height())
+            .step_by(2)
+            .map(|y| {
+                let cells: Vec<Span> = (0..pixels.width())
+                    .map(|x| Span::styled("▀", Style::new().fg(color(x, y)).bg(color(x, y + 1))))
+                    .collect();
+                Line::from(cells)
+            })
+            .collect()
+    }
+}
+
+impl PreviewPane for Image {
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>, has_focus: bool) -> Result<(), Error> {
+        self.set_area(area);
+
+        if self.entry.is_some() {
+            let mut title = self.title.clone();
+            let dimensions = match &self.image {
+                Some(image) => Some((image.width(), image.height())),
+                None => self.exif.as_ref().and_then(|exif| exif.dimensions),
+            };
+            if let Some((width, height)) = dimensions {
+                title.push_str(&format!(" {width}x{height}"));
+            }
+            let block = components::component_block(has_focus).title(title);
+            frame.render_widget(block, self.area);
+
+            if self.image.is_some() && self.protocol == Protocol::None {
+                let lines = self.half_block_lines();
+                frame.render_widget(Paragraph::new(lines), self.image_area);
+            }
+            let properties = self.exif_properties();
+            if self.image.is_none() && properties.is_empty() {
+                frame.render_widget(Paragraph::new("No EXIF data"), self.exif_area);
+            } else {
+                frame.render_widget(
+                    Paragraph::new(components::property_lines(properties)),
+                    self.exif_area,
+                );
+            }
+        }
+        Ok(())
+    }
+}
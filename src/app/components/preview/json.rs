@@ -0,0 +1,397 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::collections::HashSet;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Margin, Position, Rect};
+use ratatui::prelude::{Line, Span};
+use ratatui::widgets::{
+    List, ListItem, Scrollbar, ScrollbarOrientation, ScrollbarPosition, ScrollbarState,
+};
+use ratatui::Frame;
+use serde_json::Value;
+
+use crate::app::styles;
+use crate::stateful_list::StatefulList;
+use crate::util;
+
+use super::components;
+use super::components::key_hints::KeyHint;
+use super::preview_pane;
+use super::preview_pane::PreviewPane;
+
+// The largest JSON file that's shown as a tree. It's bigger than the largest text file that's
+// previewed, since the tree only shows what's expanded.
+pub(super) const MAX_JSON_SIZE: u64 = 16 * 1024 * 1024;
+
+// A row of the tree: the path of child indexes from the root to a value, and the value's key or
+// index in its parent
+#[derive(Debug, PartialEq)]
+struct JsonRow {
+    path: Vec<usize>,
+    key: Option<String>,
+}
+
+// Shows a JSON file as a tree, whose objects and arrays can be expanded and collapsed
+#[derive(Default)]
+pub(super) struct Json<'a> {
+    area: Rect,
+    inner_area: Rect,
+
+    // The file's directory entry and title, and its contents
+    entry: Option<PathBuf>,
+    title: String,
+    root: Value,
+
+    // The paths of the objects and arrays that are expanded, and the rows that are showing
+    expanded: HashSet<Vec<usize>>,
+    rows: StatefulList<JsonRow>,
+
+    // Scrollbar stuff
+    scrollbar: Scrollbar<'a>,
+    scrollbar_state: ScrollbarState,
+    scrollbar_area: Rect,
+}
+
+impl<'a> Json<'a> {
+    pub fn init(&mut self, entry: Option<&PathBuf>, root: Value, area: Rect) {
+        self.entry = entry.cloned();
+        self.title = entry.map_or_else(String::new, |entry| preview_pane::file_title(entry));
+        self.root = root;
+        // The top level starts out expanded
+        self.expanded = HashSet::from([vec![]]);
+        self.set_rows(0);
+
+        self.scrollbar = Scrollbar::default().orientation(ScrollbarOrientation::VerticalRight);
+        self.set_area(area);
+    }
+
+    pub fn clear(&mut self) {
+        self.init(None, Value::Null, self.area);
+    }
+
+    // Whether the file's extension says it's JSON
+    pub fn is_json_file(entry: &Path) -> bool {
+        entry
+            .extension()
+            .is_some_and(|extension| extension.to_string_lossy().eq_ignore_ascii_case("json"))
+    }
+
+    pub fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let position = Position {
+                    x: mouse_event.column,
+                    y: mouse_event.row,
+                };
+                let key_code = match self.scrollbar.hit_test(
+                    position,
+                    self.scrollbar_area,
+                    &self.scrollbar_state,
+                ) {
+                    Some(ScrollbarPosition::Begin) => Some(KeyCode::Up),
+                    Some(ScrollbarPosition::TrackLow) => Some(KeyCode::PageUp),
+                    Some(ScrollbarPosition::TrackHigh) => Some(KeyCode::PageDown),
+                    Some(ScrollbarPosition::End) => Some(KeyCode::Down),
+                    _ => None,
+                };
+                if let Some(key_code) = key_code {
+                    self.handle_key_event(KeyEvent::new(key_code, KeyModifiers::NONE));
+                } else if self.inner_area.contains(position) {
+                    // Clicking a row selects it, and clicking the selected row toggles it
+                    let index = self.rows.offset() + (mouse_event.row - self.inner_area.y) as usize;
+                    if index < self.rows.len() {
+                        if self.rows.selected() == Some(index) {
+                            self.toggle_selected();
+                        } else {
+                            self.rows.set_selected(Some(index));
+                            self.sync_scrollbar_position();
+                        }
+                    }
+                }
+            }
+            MouseEventKind::ScrollUp => {
+                self.handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE));
+            }
+            MouseEventKind::ScrollDown => {
+                self.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+            }
+            _ => { /* ignore */ }
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) {
+        let page_height = self.inner_area.height as usize;
+        if util::is_up_key(key_event) || key_event.code == KeyCode::Char('k') {
+            self.rows.previous();
+        } else if util::is_down_key(key_event) || key_event.code == KeyCode::Char('j') {
+            self.rows.next();
+        } else {
+            match key_event.code {
+                KeyCode::Enter | KeyCode::Char(' ') => self.toggle_selected(),
+                KeyCode::Right | KeyCode::Char('l') => self.expand_selected(),
+                KeyCode::Left | KeyCode::Char('h') => self.collapse_selected(),
+                KeyCode::Home => {
+                    self.rows.first();
+                }
+                KeyCode::End => {
+                    self.rows.last();
+                }
+                KeyCode::PageUp => {
+                    self.rows.retreat(page_height);
+                }
+                KeyCode::PageDown => {
+                    self.rows.advance(page_height);
+                }
+                _ => {}
+            }
+        }
+        self.sync_scrollbar_position();
+    }
+
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+        self.inner_area = area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        self.scrollbar_area = area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        });
+        self.set_scrollbar_state();
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Select"),
+            KeyHint::new("Enter", "Expand/Collapse"),
+            KeyHint::new("→/←", "Expand/Collapse"),
+            KeyHint::new("PgUp/PgDn", "Page"),
+            KeyHint::new("Home/End", "Top/Bottom"),
+        ]
+    }
+
+    fn selected_row(&self) -> Option<&JsonRow> {
+        self.rows.selected().map(|index| &self.rows[index])
+    }
+
+    fn toggle_selected(&mut self) {
+        if let Some(row) = self.selected_row() {
+            if self.expanded.contains(&row.path) {
+                self.collapse_selected();
+            } else {
+                self.expand_selected();
+            }
+        }
+    }
+
+    // Expands the selected object or array, or if it's already expanded, selects its first child
+    fn expand_selected(&mut self) {
+        let Some(selected) = self.rows.selected() else {
+            return;
+        };
+        let path = self.rows[selected].path.clone();
+        if !Self::is_container(self.value_at(&path)) {
+            return;
+        }
+        if self.expanded.contains(&path) {
+            if selected + 1 < self.rows.len() && self.rows[selected + 1].path.len() > path.len() {
+                self.rows.set_selected(Some(selected + 1));
+            }
+        } else {
+            self.expanded.insert(path);
+            self.set_rows(selected);
+        }
+    }
+
+    // Collapses the selected object or array, or if it's already collapsed, selects its parent
+    fn collapse_selected(&mut self) {
+        let Some(selected) = self.rows.selected() else {
+            return;
+        };
+        let path = self.rows[selected].path.clone();
+        if self.expanded.remove(&path) {
+            // Forget what was expanded inside it, too
+            self.expanded
+                .retain(|expanded| !expanded.starts_with(&path));
+            self.set_rows(selected);
+        } else if let Some(parent) = path.split_last().map(|(_, parent)| parent) {
+            if let Some(index) = self.rows.iter().position(|row| row.path == parent) {
+                self.rows.set_selected(Some(index));
+            }
+        }
+    }
+
+    // Lists the rows that are showing, and selects the one at the index
+    fn set_rows(&mut self, selected: usize) {
+        let mut rows = vec![];
+        self.push_rows(&self.root, vec![], None, &mut rows);
+        let offset = self.rows.offset();
+        self.rows = StatefulList::with_items(rows);
+        *self.rows.state.offset_mut() = offset;
+        self.rows
+            .set_selected(Some(selected.min(self.rows.upper_bound())));
+        self.set_scrollbar_state();
+    }
+
+    // Adds a row for the value, and if it's expanded, rows for its children
+    fn push_rows(
+        &self,
+        value: &Value,
+        path: Vec<usize>,
+        key: Option<String>,
+        rows: &mut Vec<JsonRow>,
+    ) {
+        let expanded = self.expanded.contains(&path);
+        rows.push(JsonRow {
+            path: path.clone(),
+            key,
+        });
+        if expanded {
+            let children: Vec<(Option<String>, &Value)> = match value {
+                Value::Object(map) => map
+                    .iter()
+                    .map(|(key, value)| (Some(key.clone()), value))
+                    .collect(),
+                Value::Array(values) => values
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| (Some(index.to_string()), value))
+                    .collect(),
+                _ => vec![],
+            };
+            for (index, (key, value)) in children.into_iter().enumerate() {
+                let mut child_path = path.clone();
+                child_path.push(index);
+                self.push_rows(value, child_path, key, rows);
+            }
+        }
+    }
+
+    // Follows a path of child indexes down from the root
+    fn value_at(&self, path: &[usize]) -> &Value {
+        let mut value = &self.root;
+        for index in path {
+            value = match value {
+                Value::Object(map) => map.values().nth(*index).unwrap_or(&Value::Null),
+                Value::Array(values) => values.get(*index).unwrap_or(&Value::Null),
+                _ => &Value::Null,
+            };
+        }
+        value
+    }
+
+    fn is_container(value: &Value) -> bool {
+        matches!(value, Value::Object(_) | Value::Array(_))
+    }
+
+    // Formats a row: indented by depth, with a marker showing whether it's expanded, its key,
+    // and its value, or a summary of its children
+    fn row_line<'b>(&self, row: &JsonRow) -> Line<'b> {
+        let value = self.value_at(&row.path);
+        let indent = "  ".repeat(row.path.len());
+        let marker = match (Self::is_container(value), self.expanded.contains(&row.path)) {
+            (true, true) => "▾ ",
+            (true, false) => "▸ ",
+            _ => "  ",
+        };
+        let mut spans = vec![Span::raw(format!("{indent}{marker}"))];
+        if let Some(key) = &row.key {
+            spans.push(Span::styled(key.clone(), styles::JSON_KEY_STYLE));
+            spans.push(Span::raw(": "));
+        }
+        spans.push(match value {
+            Value::Object(map) => Span::styled(
+                format!(
+                    "{{{} key{}}}",
+                    map.len(),
+                    if map.len() != 1 { "s" } else { "" }
+                ),
+                styles::JSON_SUMMARY_STYLE,
+            ),
+            Value::Array(values) => Span::styled(
+                format!(
+                    "[{} item{}]",
+                    values.len(),
+                    if values.len() != 1 { "s" } else { "" }
+                ),
+                styles::JSON_SUMMARY_STYLE,
+            ),
+            Value::String(_) => Span::styled(value.to_string(), styles::JSON_STRING_STYLE),
+            Value::Number(_) => Span::styled(value.to_string(), styles::JSON_NUMBER_STYLE),
+            Value::Bool(_) | Value::Null => {
+                Span::styled(value.to_string(), styles::JSON_LITERAL_STYLE)
+            }
+        });
+        Line::from(spans)
+    }
+
+    fn sync_scrollbar_position(&mut self) {
+        self.scrollbar_state = self
+            .scrollbar_state
+            .position(self.rows.selected().unwrap_or(0));
+    }
+
+    fn set_scrollbar_state(&mut self) {
+        let frame_length = self.inner_area.height as usize;
+        if self.rows.len() <= frame_length {
+            // Hide scrollbar
+            self.scrollbar_state = self.scrollbar_state.position(0).content_length(0);
+        } else {
+            // Show scrollbar
+            self.scrollbar_state = self
+                .scrollbar_state
+                .content_length(self.rows.len())
+                .viewport_content_length(frame_length);
+            self.sync_scrollbar_position();
+        }
+    }
+}
+
+impl<'a> PreviewPane for Json<'a> {
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>, has_focus: bool) -> Result<(), Error> {
+        self.set_area(area);
+
+        if self.entry.is_some() {
+            let mut title = self.title.clone();
+            title.push_str(" JSON");
+            let block = components::component_block(has_focus).title(title);
+
+            // Scroll the selected row into view here, so only the rows that show are formatted
+            let height = self.inner_area.height as usize;
+            let selected = self.rows.selected().unwrap_or(0);
+            let mut offset = self.rows.offset().min(selected);
+            if selected >= offset + height {
+                offset = selected + 1 - height;
+            }
+            *self.rows.state.offset_mut() = offset;
+            let items: Vec<ListItem> = self
+                .rows
+                .iter()
+                .enumerate()
+                .map(|(index, row)| {
+                    if index < offset || index >= offset + height {
+                        ListItem::new("") // Off screen
+                    } else {
+                        ListItem::new(self.row_line(row))
+                    }
+                })
+                .collect();
+            let list = List::new(items).highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+            frame.render_widget(block, self.area);
+            frame.render_stateful_widget(list, self.inner_area, &mut self.rows.state);
+            frame.render_stateful_widget(
+                self.scrollbar.clone(),
+                self.scrollbar_area,
+                &mut self.scrollbar_state,
+            );
+        }
+        Ok(())
+    }
+}
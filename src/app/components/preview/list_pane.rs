@@ -8,6 +8,8 @@ use std::path::PathBuf;
 use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::layout::Rect;
 
+use crate::app::components::key_hints::KeyHint;
+
 pub trait ListPane<T> {
     fn init(&mut self, entry: Option<&PathBuf>, items: Vec<T>, area: Rect);
 
@@ -18,4 +20,6 @@ pub trait ListPane<T> {
     fn handle_key_event(&mut self, key_event: KeyEvent);
 
     fn set_area(&mut self, area: Rect);
+
+    fn key_hints(&self) -> Vec<KeyHint>;
 }
@@ -0,0 +1,247 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::Path;
+
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::prelude::{Modifier, Style};
+
+use crate::app::styles;
+
+use super::ansi::StyledLine;
+
+// Whether the file's extension says it's Markdown
+pub(super) fn is_markdown_file(entry: &Path) -> bool {
+    entry.extension().is_some_and(|extension| {
+        let extension = extension.to_string_lossy().to_lowercase();
+        extension == "md" || extension == "markdown"
+    })
+}
+
+// Renders Markdown as lines of styled text: headings, emphasis, links and code are styled, list
+// items get bullets or numbers, and block quotes get a bar down their left side
+pub(super) fn render_lines(lines: &[String]) -> Vec<StyledLine> {
+    let source = lines.join("\n");
+    let options =
+        Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS;
+    let mut renderer = Renderer::default();
+    for event in Parser::new_ext(&source, options) {
+        renderer.handle_event(event);
+    }
+    renderer.finish()
+}
+
+#[derive(Default)]
+struct Renderer {
+    lines: Vec<StyledLine>,
+    line: StyledLine,
+
+    // The styles of the elements the text is in, innermost last
+    styles: Vec<Style>,
+
+    // The lists the text is in, innermost last, with the next item's number if they're ordered
+    lists: Vec<Option<u64>>,
+
+    block_quote_depth: usize,
+    in_code_block: bool,
+}
+
+impl Renderer {
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start_tag(tag),
+            Event::End(tag_end) => self.end_tag(tag_end),
+            Event::Text(text) => {
+                if self.in_code_block {
+                    // Code blocks keep their lines, even blank ones
+                    for line in text.trim_end_matches('\n').split('\n') {
+                        self.push(line, styles::MARKDOWN_CODE_STYLE);
+                        self.lines.push(std::mem::take(&mut self.line));
+                    }
+                } else {
+                    self.push(&text, self.style());
+                }
+            }
+            Event::Code(code) => self.push(&code, styles::MARKDOWN_CODE_STYLE),
+            Event::Html(html) => {
+                self.push(html.trim_end_matches('\n'), styles::MARKDOWN_MARKUP_STYLE);
+                self.end_line();
+            }
+            Event::InlineHtml(html) => self.push(&html, styles::MARKDOWN_MARKUP_STYLE),
+            Event::FootnoteReference(label) => {
+                self.push(&format!("[^{label}]"), styles::MARKDOWN_LINK_STYLE)
+            }
+            Event::SoftBreak => self.push(" ", self.style()),
+            Event::HardBreak => self.end_line(),
+            Event::Rule => {
+                self.end_block();
+                self.push(&"─".repeat(40), styles::MARKDOWN_MARKUP_STYLE);
+                self.end_block();
+            }
+            Event::TaskListMarker(checked) => {
+                self.push(if checked { "[x] " } else { "[ ] " }, self.style())
+            }
+            _ => {}
+        }
+    }
+
+    fn start_tag(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.end_block();
+                let style = match level {
+                    HeadingLevel::H1 => {
+                        styles::MARKDOWN_HEADING_STYLE.add_modifier(Modifier::UNDERLINED)
+                    }
+                    _ => styles::MARKDOWN_HEADING_STYLE,
+                };
+                self.styles.push(style);
+                self.push(&format!("{} ", "#".repeat(level as usize)), style);
+            }
+            Tag::BlockQuote(_) => {
+                self.end_block();
+                self.block_quote_depth += 1;
+            }
+            Tag::CodeBlock(_) => {
+                self.end_block();
+                self.in_code_block = true;
+            }
+            Tag::List(first_number) => {
+                // Lists in list items start on their own line
+                if self.lists.is_empty() {
+                    self.end_block();
+                } else {
+                    self.end_line();
+                }
+                self.lists.push(first_number);
+            }
+            Tag::Item => {
+                self.end_line();
+                let depth = self.lists.len();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "• ".to_string(),
+                };
+                let indent = "  ".repeat(depth.saturating_sub(1));
+                self.push(&format!("{indent}{marker}"), styles::MARKDOWN_MARKUP_STYLE);
+            }
+            Tag::TableHead => {
+                self.end_line();
+                self.styles.push(Style::new().add_modifier(Modifier::BOLD));
+            }
+            Tag::TableRow => self.end_line(),
+            Tag::TableCell => {
+                if !self.line.text.is_empty() {
+                    self.push(" │ ", styles::MARKDOWN_MARKUP_STYLE);
+                }
+            }
+            Tag::Emphasis => self
+                .styles
+                .push(Style::new().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self.styles.push(Style::new().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => self
+                .styles
+                .push(Style::new().add_modifier(Modifier::CROSSED_OUT)),
+            Tag::Link { .. } | Tag::Image { .. } => self.styles.push(styles::MARKDOWN_LINK_STYLE),
+            Tag::Table(_) => self.end_block(),
+            _ => {}
+        }
+    }
+
+    fn end_tag(&mut self, tag_end: TagEnd) {
+        match tag_end {
+            TagEnd::Paragraph => {
+                // The paragraphs of list items aren't spaced out
+                if self.lists.is_empty() {
+                    self.end_block();
+                } else {
+                    self.end_line();
+                }
+            }
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.end_block();
+            }
+            TagEnd::BlockQuote => {
+                self.end_block();
+                self.block_quote_depth = self.block_quote_depth.saturating_sub(1);
+            }
+            TagEnd::CodeBlock => {
+                self.in_code_block = false;
+                self.end_block();
+            }
+            TagEnd::List(_) => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.end_block();
+                }
+            }
+            TagEnd::Item => self.end_line(),
+            TagEnd::TableHead => {
+                self.styles.pop();
+                self.end_line();
+            }
+            TagEnd::Table => self.end_block(),
+            TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Link
+            | TagEnd::Image => {
+                self.styles.pop();
+            }
+            _ => {}
+        }
+    }
+
+    // The style for text: the styles of the elements it's in, combined
+    fn style(&self) -> Style {
+        self.styles
+            .iter()
+            .fold(Style::default(), |style, patch| style.patch(*patch))
+    }
+
+    // Adds text to the current line, starting it with the block quote bars if it's new
+    fn push(&mut self, text: &str, style: Style) {
+        if self.line.text.is_empty() && self.block_quote_depth > 0 {
+            self.line
+                .text
+                .push_str(&"│ ".repeat(self.block_quote_depth));
+            self.line
+                .styles
+                .push((0, self.line.text.len(), styles::MARKDOWN_MARKUP_STYLE));
+        }
+        let start = self.line.text.len();
+        self.line.text.push_str(text);
+        if style != Style::default() {
+            self.line.styles.push((start, self.line.text.len(), style));
+        }
+    }
+
+    // Ends the current line, if anything's been put on it
+    fn end_line(&mut self) {
+        if !self.line.text.is_empty() {
+            self.lines.push(std::mem::take(&mut self.line));
+        }
+    }
+
+    // Ends the current line, and leaves a blank line after it, unless there's already one
+    fn end_block(&mut self) {
+        self.end_line();
+        if self.lines.last().is_some_and(|line| !line.text.is_empty()) {
+            self.lines.push(StyledLine::default());
+        }
+    }
+
+    fn finish(mut self) -> Vec<StyledLine> {
+        self.end_line();
+        while self.lines.last().is_some_and(|line| line.text.is_empty()) {
+            self.lines.pop();
+        }
+        self.lines
+    }
+}
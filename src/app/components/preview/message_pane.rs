@@ -4,7 +4,7 @@
  */
 
 use std::io::Error;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 use ratatui::layout::{Alignment, Rect};
 use ratatui::prelude::Style;
@@ -12,7 +12,6 @@ use ratatui::widgets::{Paragraph, Wrap};
 use ratatui::Frame;
 
 use super::components;
-use super::preview_pane;
 
 pub trait MessagePane {
     fn init(&mut self, entry: Option<&PathBuf>);
@@ -21,14 +20,13 @@ pub trait MessagePane {
     }
 
     fn render_message(
-        entry: &Path,
+        title: &str,
         message: &str,
         has_focus: bool,
         style: Style,
         frame: &mut Frame<'_>,
         area: Rect,
     ) -> Result<(), Error> {
-        let title = preview_pane::file_title(entry)?;
         let block = components::component_block(has_focus).title(title);
         frame.render_widget(block, area);
         frame.render_widget(
@@ -36,7 +34,7 @@ pub trait MessagePane {
                 .alignment(Alignment::Center)
                 .wrap(Wrap { trim: false })
                 .style(style),
-            Rect::new(area.x + 2, area.y + 2, area.width - 4, 1),
+            Rect::new(area.x + 2, area.y + 2, area.width.saturating_sub(4), 1).intersection(area),
         );
         Ok(())
     }
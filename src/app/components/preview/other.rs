@@ -11,25 +11,28 @@ use ratatui::Frame;
 use crate::app::styles;
 
 use super::message_pane::MessagePane;
+use super::preview_pane;
 use super::preview_pane::PreviewPane;
 
 #[derive(Default)]
 pub(super) struct Other {
-    // The file's directory entry
+    // The file's directory entry and title
     entry: Option<PathBuf>,
+    title: String,
 }
 
 impl MessagePane for Other {
     fn init(&mut self, entry: Option<&PathBuf>) {
         self.entry = entry.cloned();
+        self.title = entry.map_or_else(String::new, |entry| preview_pane::file_title(entry));
     }
 }
 
 impl PreviewPane for Other {
     fn render(&mut self, area: Rect, frame: &mut Frame<'_>, has_focus: bool) -> Result<(), Error> {
-        if let Some(entry) = &self.entry {
+        if self.entry.is_some() {
             <Self as MessagePane>::render_message(
-                entry,
+                &self.title,
                 "Unsupported File Type",
                 has_focus,
                 styles::OTHER_FILE_STYLE,
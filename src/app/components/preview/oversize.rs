@@ -10,28 +10,44 @@ use ratatui::layout::Rect;
 use ratatui::Frame;
 
 use crate::app::styles;
+use crate::util;
 
 use super::message_pane::MessagePane;
+use super::preview_pane;
 use super::preview_pane::PreviewPane;
 
 #[derive(Default)]
 pub(super) struct Oversize {
-    // The file's directory entry
+    // The file's directory entry and title
     entry: Option<PathBuf>,
+    title: String,
+
+    // The size text files have to be under to be shown
+    max_size: u64,
+}
+
+impl Oversize {
+    pub fn set_max_size(&mut self, max_size: u64) {
+        self.max_size = max_size;
+    }
 }
 
 impl MessagePane for Oversize {
     fn init(&mut self, entry: Option<&PathBuf>) {
         self.entry = entry.cloned();
+        self.title = entry.map_or_else(String::new, |entry| preview_pane::file_title(entry));
     }
 }
 
 impl PreviewPane for Oversize {
     fn render(&mut self, area: Rect, frame: &mut Frame<'_>, has_focus: bool) -> Result<(), Error> {
-        if let Some(entry) = &self.entry {
+        if self.entry.is_some() {
             <Self as MessagePane>::render_message(
-                entry,
-                "Oversize Text File (Max 50 kb)",
+                &self.title,
+                &format!(
+                    "Oversize Text File (Max {})",
+                    util::size_string(self.max_size)
+                ),
                 has_focus,
                 styles::OVERSIZE_FILE_STYLE,
                 frame,
@@ -0,0 +1,242 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use lopdf::Document;
+use ratatui::layout::{Margin, Position, Rect};
+use ratatui::widgets::{
+    List, ListItem, Scrollbar, ScrollbarOrientation, ScrollbarPosition, ScrollbarState,
+};
+use ratatui::Frame;
+
+use crate::stateful_list::StatefulList;
+use crate::util;
+
+use super::components;
+use super::components::key_hints::KeyHint;
+use super::preview_pane;
+use super::preview_pane::PreviewPane;
+
+// Shows the text of a PDF file, a page at a time
+#[derive(Default)]
+pub(super) struct Pdf<'a> {
+    area: Rect,
+    inner_area: Rect,
+
+    // The PDF's directory entry and title
+    entry: Option<PathBuf>,
+    title: String,
+
+    // The lines of text on each page, and the page that's showing
+    pages: Vec<Vec<String>>,
+    page: usize,
+    line_list: StatefulList<String>,
+
+    // Scrollbar stuff
+    scrollbar: Scrollbar<'a>,
+    scrollbar_state: ScrollbarState,
+    scrollbar_area: Rect,
+}
+
+impl<'a> Pdf<'a> {
+    // Whether the file's extension says it's a PDF
+    pub fn is_pdf_file(entry: &Path) -> bool {
+        entry
+            .extension()
+            .is_some_and(|extension| extension.to_string_lossy().to_lowercase() == "pdf")
+    }
+
+    // Extracts the text of each page. Pages without any text are empty.
+    pub fn read_pages(entry: &Path) -> Result<Vec<Vec<String>>, Error> {
+        let document =
+            Document::load(entry).map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+        if document.is_encrypted() {
+            return Err(Error::new(ErrorKind::InvalidData, "The PDF is encrypted"));
+        }
+        Ok(document
+            .get_pages()
+            .keys()
+            .map(|page_number| {
+                document
+                    .extract_text(&[*page_number])
+                    .unwrap_or_default()
+                    .lines()
+                    .map(|line| line.trim_end().to_string())
+                    .collect()
+            })
+            .collect())
+    }
+
+    pub fn init(&mut self, entry: Option<&PathBuf>, pages: Vec<Vec<String>>, area: Rect) {
+        self.entry = entry.cloned();
+        self.title = entry.map_or_else(String::new, |entry| preview_pane::file_title(entry));
+        self.pages = pages;
+
+        self.scrollbar = Scrollbar::default().orientation(ScrollbarOrientation::VerticalRight);
+        self.show_page(0);
+        self.set_area(area);
+    }
+
+    pub fn clear(&mut self) {
+        self.init(None, vec![], self.area);
+    }
+
+    pub fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let position = Position {
+                    x: mouse_event.column,
+                    y: mouse_event.row,
+                };
+                let key_code = match self.scrollbar.hit_test(
+                    position,
+                    self.scrollbar_area,
+                    &self.scrollbar_state,
+                ) {
+                    Some(ScrollbarPosition::Begin) => Some(KeyCode::Up),
+                    Some(ScrollbarPosition::TrackLow) => Some(KeyCode::PageUp),
+                    Some(ScrollbarPosition::TrackHigh) => Some(KeyCode::PageDown),
+                    Some(ScrollbarPosition::End) => Some(KeyCode::Down),
+                    _ => None,
+                };
+                if let Some(key_code) = key_code {
+                    self.handle_key_event(KeyEvent::new(key_code, KeyModifiers::NONE));
+                }
+            }
+            MouseEventKind::ScrollUp => {
+                self.handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE));
+            }
+            MouseEventKind::ScrollDown => {
+                self.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+            }
+            _ => { /* ignore */ }
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) {
+        let frame_height = self.inner_area.height as usize;
+        let offset = self.line_list.offset();
+        if util::is_up_key(key_event) || key_event.code == KeyCode::Char('k') {
+            self.set_offset(offset.saturating_sub(1));
+        } else if util::is_down_key(key_event) || key_event.code == KeyCode::Char('j') {
+            self.set_offset(offset.saturating_add(1));
+        } else {
+            match key_event.code {
+                KeyCode::Home => self.set_offset(0),
+                KeyCode::End => self.set_offset(self.vertical_page_limit()),
+                KeyCode::PageUp => self.set_offset(offset.saturating_sub(frame_height)),
+                KeyCode::PageDown => self.set_offset(offset.saturating_add(frame_height)),
+                KeyCode::Char('[') => self.show_page(self.page.saturating_sub(1)),
+                KeyCode::Char(']') => self.show_page(self.page.saturating_add(1)),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+        self.inner_area = area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        self.scrollbar_area = Rect {
+            x: area.x,
+            width: area.width,
+            ..self.inner_area
+        };
+        self.set_scrollbar_state();
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("[/]", "Previous/Next page"),
+            KeyHint::new("↑/↓", "Scroll"),
+            KeyHint::new("PgUp/PgDn", "Page"),
+            KeyHint::new("Home/End", "Top/Bottom"),
+        ]
+    }
+
+    // Shows the (0-based) page from the top, if there is one
+    fn show_page(&mut self, page: usize) {
+        let page = page.min(self.pages.len().saturating_sub(1));
+        self.page = page;
+        self.line_list =
+            StatefulList::with_items(self.pages.get(page).cloned().unwrap_or_default());
+        self.set_scrollbar_state();
+    }
+
+    fn vertical_page_limit(&self) -> usize {
+        <Self as PreviewPane>::page_limit(self.line_list.len(), self.inner_area.height as usize)
+    }
+
+    // Scrolls the page, clamped to the page limit, and syncs the scrollbar to it
+    fn set_offset(&mut self, offset: usize) {
+        let offset = offset.min(self.vertical_page_limit());
+        *self.line_list.state.offset_mut() = offset;
+        self.scrollbar_state = self.scrollbar_state.position(offset);
+    }
+
+    fn set_scrollbar_state(&mut self) {
+        let frame_length = self.inner_area.height as usize;
+        if self.line_list.len() <= frame_length {
+            // Hide scrollbar
+            self.scrollbar_state = self.scrollbar_state.position(0).content_length(0);
+            *self.line_list.state.offset_mut() = 0;
+        } else {
+            // Show scrollbar
+            self.scrollbar_state = self
+                .scrollbar_state
+                .content_length(self.line_list.len() - frame_length)
+                .viewport_content_length(frame_length);
+            self.set_offset(self.line_list.offset());
+        }
+    }
+}
+
+impl<'a> PreviewPane for Pdf<'a> {
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>, has_focus: bool) -> Result<(), Error> {
+        self.set_area(area);
+
+        if self.entry.is_some() {
+            let mut title = self.title.clone();
+            if self.pages.is_empty() {
+                title.push_str(" No pages");
+            } else {
+                title.push_str(&format!(" Page {}/{}", self.page + 1, self.pages.len()));
+            }
+            let block = components::component_block(has_focus).title(title);
+
+            let offset = self.line_list.offset();
+            let height = self.inner_area.height as usize;
+            let items: Vec<ListItem> = self
+                .line_list
+                .iter()
+                .enumerate()
+                .map(|(index, line)| {
+                    if index < offset || index >= offset + height {
+                        ListItem::new("") // Off screen
+                    } else {
+                        ListItem::new(line.clone())
+                    }
+                })
+                .collect();
+            frame.render_widget(block, self.area);
+            frame.render_stateful_widget(
+                List::new(items),
+                self.inner_area,
+                &mut self.line_list.state,
+            );
+            frame.render_stateful_widget(
+                self.scrollbar.clone(),
+                self.scrollbar_area,
+                &mut self.scrollbar_state,
+            );
+        }
+        Ok(())
+    }
+}
@@ -3,15 +3,13 @@
  * Created 2024-04-03
  */
 
-use std::fs::Metadata;
 use std::path::Path;
-use std::time::SystemTime;
 
-use chrono::{DateTime, Local};
-use number_prefix::NumberPrefix;
 use ratatui::layout::Rect;
 use ratatui::Frame;
 
+use crate::util::{metadata_modified_string, metadata_size_string};
+
 pub trait PreviewPane {
     fn render(
         &mut self,
@@ -29,54 +27,27 @@ pub trait PreviewPane {
     }
 }
 
-pub fn file_title(entry: &Path) -> Result<String, std::io::Error> {
-    let metadata = &entry.metadata()?;
-    Ok(format!(
-        "[{} - {}]",
-        metadata_modified_string(metadata),
-        metadata_size_string(metadata)
-    ))
-}
-
-pub fn folder_title(entry: &Path, item_count: usize) -> Result<String, std::io::Error> {
-    let metadata = &entry.metadata()?;
-    Ok(format!(
-        "[{} - {} item{}]",
-        metadata_modified_string(metadata),
-        item_count,
-        if item_count != 1 { "s" } else { "" },
-    ))
-}
-
-fn metadata_modified_string(metadata: &Metadata) -> String {
-    match modified_datetime(metadata) {
-        Some(modified) => {
-            format!("{}", modified.format("%Y-%m-%d %H:%M"))
-        }
-        _ => "".to_string(),
+// Titles are looked up when an entry is loaded, rather than each time it's shown. An entry that
+// has gone away has no title.
+pub fn file_title(entry: &Path) -> String {
+    match entry.metadata() {
+        Ok(metadata) => format!(
+            "[{} - {}]",
+            metadata_modified_string(&metadata),
+            metadata_size_string(&metadata)
+        ),
+        Err(_) => String::new(),
     }
 }
 
-fn modified_datetime(metadata: &Metadata) -> Option<DateTime<Local>> {
-    match metadata.modified() {
-        Ok(modified) => {
-            let dur = modified.duration_since(SystemTime::UNIX_EPOCH).unwrap();
-            Some::<DateTime<Local>>(
-                chrono::DateTime::from_timestamp(dur.as_secs() as i64, 0)
-                    .unwrap()
-                    .into(),
-            )
-        }
-        _ => None, // No modified value
-    }
-}
-
-fn metadata_size_string(metadata: &Metadata) -> String {
-    // Not meant to be precise...
-    match NumberPrefix::decimal(metadata.len() as f64) {
-        NumberPrefix::Standalone(_) => "1 kB".into(),
-        NumberPrefix::Prefixed(prefix, n) => {
-            format!("{:.0} {}B", n, prefix.symbol())
-        }
+pub fn folder_title(entry: &Path, item_count: usize) -> String {
+    match entry.metadata() {
+        Ok(metadata) => format!(
+            "[{} - {} item{}]",
+            metadata_modified_string(&metadata),
+            item_count,
+            if item_count != 1 { "s" } else { "" },
+        ),
+        Err(_) => String::new(),
     }
 }
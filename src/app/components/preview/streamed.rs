@@ -0,0 +1,432 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::fs::File;
+use std::io::{Error, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Margin, Position, Rect};
+use ratatui::prelude::Line;
+use ratatui::widgets::{
+    Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarPosition, ScrollbarState,
+};
+use ratatui::Frame;
+
+use crate::app::styles;
+use crate::util;
+
+use super::components;
+use super::components::key_hints::KeyHint;
+use super::preview_pane;
+use super::preview_pane::PreviewPane;
+
+// How much of the file is searched for line breaks at a time
+const INDEX_CHUNK_SIZE: usize = 1 << 20;
+
+// How much of a line is read. Anything past that isn't shown.
+const MAX_LINE_LENGTH: u64 = 4096;
+
+// Shows a text file that's too big to read all at once. Only the lines that are showing are read,
+// and the file is only searched for where its lines start as far as it's been scrolled.
+#[derive(Default)]
+pub(super) struct Streamed<'a> {
+    area: Rect,
+    inner_area: Rect,
+
+    // Line numbers are shown to the left of the text, and don't scroll horizontally
+    show_line_numbers: bool,
+    line_number_area: Rect,
+
+    // The file's directory entry and title, the open file, and its size
+    entry: Option<PathBuf>,
+    title: String,
+    file: Option<File>,
+    file_size: u64,
+
+    // Where each line starts, as far as the file has been searched, and how far that is
+    line_starts: Vec<u64>,
+    indexed_size: u64,
+
+    // The lines that are showing, and the line they start at
+    window: Vec<String>,
+    window_start: usize,
+
+    // Horizontal scrollbar stuff
+    horizontal_scrollbar: Scrollbar<'a>,
+    horizontal_scrollbar_state: ScrollbarState,
+    horizontal_scrollbar_area: Rect,
+    horizontal_offset: usize,
+
+    // Vertical scrollbar stuff
+    vertical_scrollbar: Scrollbar<'a>,
+    vertical_scrollbar_state: ScrollbarState,
+    vertical_scrollbar_area: Rect,
+    vertical_offset: usize,
+}
+
+impl<'a> Streamed<'a> {
+    pub fn init(&mut self, entry: Option<&PathBuf>, area: Rect) -> Result<(), Error> {
+        self.entry = entry.cloned();
+        self.title = entry.map_or_else(String::new, |entry| preview_pane::file_title(entry));
+        self.file = None;
+        self.file_size = 0;
+        if let Some(entry) = entry {
+            let file = File::open(entry)?;
+            self.file_size = file.metadata()?.len();
+            self.file = Some(file);
+        }
+        self.line_starts = if self.file_size > 0 { vec![0] } else { vec![] };
+        self.indexed_size = 0;
+        self.window.clear();
+        self.window_start = 0;
+        self.vertical_offset = 0;
+        self.horizontal_offset = 0;
+
+        self.vertical_scrollbar =
+            Scrollbar::default().orientation(ScrollbarOrientation::VerticalRight);
+        self.horizontal_scrollbar =
+            Scrollbar::default().orientation(ScrollbarOrientation::HorizontalBottom);
+        self.set_area(area);
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        let _ = self.init(None, self.area);
+    }
+
+    pub fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let position = Position {
+                    x: mouse_event.column,
+                    y: mouse_event.row,
+                };
+                let vertical_key = match self.vertical_scrollbar.hit_test(
+                    position,
+                    self.vertical_scrollbar_area,
+                    &self.vertical_scrollbar_state,
+                ) {
+                    Some(ScrollbarPosition::Begin) => Some(KeyEvent::from(KeyCode::Up)),
+                    Some(ScrollbarPosition::TrackLow) => Some(KeyEvent::from(KeyCode::PageUp)),
+                    Some(ScrollbarPosition::TrackHigh) => Some(KeyEvent::from(KeyCode::PageDown)),
+                    Some(ScrollbarPosition::End) => Some(KeyEvent::from(KeyCode::Down)),
+                    _ => None,
+                };
+                let horizontal_key = match self.horizontal_scrollbar.hit_test(
+                    position,
+                    self.horizontal_scrollbar_area,
+                    &self.horizontal_scrollbar_state,
+                ) {
+                    Some(ScrollbarPosition::Begin) => Some(KeyEvent::from(KeyCode::Left)),
+                    Some(ScrollbarPosition::TrackLow) => {
+                        Some(KeyEvent::new(KeyCode::Left, KeyModifiers::CONTROL))
+                    }
+                    Some(ScrollbarPosition::TrackHigh) => {
+                        Some(KeyEvent::new(KeyCode::Right, KeyModifiers::CONTROL))
+                    }
+                    Some(ScrollbarPosition::End) => Some(KeyEvent::from(KeyCode::Right)),
+                    _ => None,
+                };
+                if let Some(key_event) = vertical_key.or(horizontal_key) {
+                    self.handle_key_event(key_event);
+                }
+            }
+            MouseEventKind::ScrollUp => self.handle_key_event(KeyEvent::from(KeyCode::Up)),
+            MouseEventKind::ScrollDown => self.handle_key_event(KeyEvent::from(KeyCode::Down)),
+            _ => { /* ignore */ }
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) {
+        let frame_height = self.inner_area.height as usize;
+        let frame_width = self.inner_area.width as usize;
+        let is_control = key_event.modifiers == KeyModifiers::CONTROL;
+        if util::is_up_key(key_event) || key_event.code == KeyCode::Char('k') {
+            self.set_vertical_offset(self.vertical_offset.saturating_sub(1));
+        } else if util::is_down_key(key_event) || key_event.code == KeyCode::Char('j') {
+            self.set_vertical_offset(self.vertical_offset.saturating_add(1));
+        } else {
+            match key_event.code {
+                KeyCode::Char('#') => {
+                    self.show_line_numbers = !self.show_line_numbers;
+                    self.set_area(self.area);
+                }
+                KeyCode::Home if is_control => self.set_vertical_offset(0),
+                KeyCode::End if is_control => self.scroll_to_bottom(),
+                KeyCode::Char('G') => self.scroll_to_bottom(),
+                KeyCode::Home => self.set_horizontal_offset(0),
+                KeyCode::End => self.set_horizontal_offset(usize::MAX),
+                KeyCode::PageUp => {
+                    self.set_vertical_offset(self.vertical_offset.saturating_sub(frame_height))
+                }
+                KeyCode::PageDown => {
+                    self.set_vertical_offset(self.vertical_offset.saturating_add(frame_height))
+                }
+                KeyCode::Left if is_control => {
+                    self.set_horizontal_offset(self.horizontal_offset.saturating_sub(frame_width))
+                }
+                KeyCode::Right if is_control => {
+                    self.set_horizontal_offset(self.horizontal_offset.saturating_add(frame_width))
+                }
+                KeyCode::Left => {
+                    self.set_horizontal_offset(self.horizontal_offset.saturating_sub(1))
+                }
+                KeyCode::Right => {
+                    self.set_horizontal_offset(self.horizontal_offset.saturating_add(1))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+        // Give the content some horizontal padding
+        self.inner_area = area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        if self.show_line_numbers {
+            // Room for the widest line number that's showing, and a space
+            let last_line = self.vertical_offset + self.inner_area.height as usize;
+            let gutter_width = (last_line.to_string().len() as u16 + 1).min(self.inner_area.width);
+            self.line_number_area = Rect {
+                width: gutter_width,
+                ..self.inner_area
+            };
+            self.inner_area.x += gutter_width;
+            self.inner_area.width -= gutter_width;
+        } else {
+            self.line_number_area = Rect::default();
+        }
+        self.vertical_scrollbar_area = area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        });
+        self.horizontal_scrollbar_area = area.inner(Margin {
+            vertical: 0,
+            horizontal: 1,
+        });
+        // Make sure the lines that fill the pane have been found
+        self.index_to_line(self.vertical_offset + self.inner_area.height as usize);
+        self.set_scrollbar_state();
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Scroll"),
+            KeyHint::new("←/→", "Pan"),
+            KeyHint::new("PgUp/PgDn", "Page"),
+            KeyHint::new("^Home/^End", "Top/Bottom"),
+            KeyHint::new("#", "Line numbers"),
+        ]
+    }
+
+    fn is_fully_indexed(&self) -> bool {
+        self.indexed_size >= self.file_size
+    }
+
+    // Searches the file for line breaks until the line's start is known, or the end of the file
+    fn index_to_line(&mut self, line: usize) {
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        let mut buffer = vec![0u8; INDEX_CHUNK_SIZE];
+        while self.line_starts.len() <= line && self.indexed_size < self.file_size {
+            let read = file
+                .seek(SeekFrom::Start(self.indexed_size))
+                .and_then(|_| file.read(&mut buffer));
+            let read = match read {
+                Ok(read) if read > 0 => read,
+                // Don't keep trying if the file can't be read, or has got shorter
+                _ => {
+                    self.file_size = self.indexed_size;
+                    break;
+                }
+            };
+            for (index, byte) in buffer[..read].iter().enumerate() {
+                let next = self.indexed_size + index as u64 + 1;
+                if *byte == b'\n' && next < self.file_size {
+                    self.line_starts.push(next);
+                }
+            }
+            self.indexed_size += read as u64;
+        }
+    }
+
+    // The number of lines in the file. Until the whole file has been searched, that's an estimate
+    // from how many lines there are in the part that has.
+    fn line_count(&self) -> usize {
+        let found = self.line_starts.len();
+        if self.is_fully_indexed() || self.indexed_size == 0 {
+            found
+        } else {
+            let estimate = found as f64 * self.file_size as f64 / self.indexed_size as f64;
+            (estimate as usize).max(found)
+        }
+    }
+
+    fn vertical_page_limit(&self) -> usize {
+        let limit =
+            <Self as PreviewPane>::page_limit(self.line_count(), self.inner_area.height as usize);
+        // Never scroll past the lines that have actually been found
+        limit.min(self.line_starts.len().saturating_sub(1))
+    }
+
+    fn horizontal_page_limit(&self) -> usize {
+        <Self as PreviewPane>::page_limit(
+            Self::widest_line_length(&self.window),
+            self.inner_area.width as usize,
+        )
+    }
+
+    // Sets the vertical offset, clamped to the page limit, and syncs the scrollbar to it
+    fn set_vertical_offset(&mut self, offset: usize) {
+        let height = self.inner_area.height as usize;
+        self.index_to_line(offset.saturating_add(height));
+        self.vertical_offset = offset.min(self.vertical_page_limit());
+        self.set_scrollbar_state();
+    }
+
+    fn scroll_to_bottom(&mut self) {
+        // The whole file has to be searched to find its last line
+        self.index_to_line(usize::MAX);
+        self.set_vertical_offset(usize::MAX);
+    }
+
+    // Sets the horizontal offset, clamped to the page limit, and syncs the scrollbar to it
+    fn set_horizontal_offset(&mut self, offset: usize) {
+        self.horizontal_offset = offset.min(self.horizontal_page_limit());
+        self.horizontal_scrollbar_state = self
+            .horizontal_scrollbar_state
+            .position(self.horizontal_offset);
+    }
+
+    fn set_scrollbar_state(&mut self) {
+        let frame_length = self.inner_area.height as usize;
+        let line_count = self.line_count();
+        if line_count <= frame_length {
+            // Hide scrollbar
+            self.vertical_scrollbar_state =
+                self.vertical_scrollbar_state.position(0).content_length(0);
+        } else {
+            // Show scrollbar
+            self.vertical_scrollbar_state = self
+                .vertical_scrollbar_state
+                .content_length(line_count - frame_length)
+                .viewport_content_length(frame_length)
+                .position(self.vertical_offset);
+        }
+
+        let frame_length = self.inner_area.width as usize;
+        let widest_line_len = Self::widest_line_length(&self.window);
+        if widest_line_len <= frame_length {
+            // Hide scrollbar
+            self.horizontal_scrollbar_state = self
+                .horizontal_scrollbar_state
+                .position(0)
+                .content_length(0);
+        } else {
+            // Show scrollbar
+            self.horizontal_scrollbar_state = self
+                .horizontal_scrollbar_state
+                .content_length(widest_line_len - frame_length)
+                .viewport_content_length(frame_length)
+                .position(self.horizontal_offset);
+        }
+    }
+
+    // Reads the lines that are showing, unless they already have been
+    fn read_window(&mut self) -> Result<(), Error> {
+        let start = self.vertical_offset;
+        let end = (start + self.inner_area.height as usize).min(self.line_starts.len());
+        if self.window_start == start && self.window.len() == end.saturating_sub(start) {
+            return Ok(());
+        }
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+        self.window.clear();
+        for line in start..end {
+            let line_start = self.line_starts[line];
+            let line_end = self
+                .line_starts
+                .get(line + 1)
+                .copied()
+                .unwrap_or(self.file_size);
+            let mut bytes = vec![];
+            file.seek(SeekFrom::Start(line_start))?;
+            file.by_ref()
+                .take((line_end - line_start).min(MAX_LINE_LENGTH))
+                .read_to_end(&mut bytes)?;
+            let text = String::from_utf8_lossy(&bytes);
+            self.window.push(
+                text.trim_end_matches(['\n', '\r'])
+                    .replace('\t', "        "),
+            );
+        }
+        self.window_start = start;
+        self.set_scrollbar_state();
+        Ok(())
+    }
+
+    fn widest_line_length(lines: &[String]) -> usize {
+        lines
+            .iter()
+            .map(|line| util::display_width(line))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<'a> PreviewPane for Streamed<'a> {
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>, has_focus: bool) -> Result<(), Error> {
+        self.set_area(area);
+        self.read_window()?;
+
+        if self.entry.is_some() {
+            let mut title = self.title.clone();
+            let line_count = self.line_count();
+            title.push_str(&format!(
+                " {}{line_count} line{}",
+                if self.is_fully_indexed() { "" } else { "~" },
+                if line_count != 1 { "s" } else { "" }
+            ));
+            let block = components::component_block(has_focus).title(title);
+
+            let lines: Vec<Line> = self
+                .window
+                .iter()
+                .map(|line| Line::from(line.clone()))
+                .collect();
+            let paragraph = Paragraph::new(lines).scroll((0, self.horizontal_offset as u16));
+            frame.render_widget(block, self.area);
+            frame.render_widget(paragraph, self.inner_area);
+            if self.show_line_numbers {
+                let width = self.line_number_area.width.saturating_sub(1) as usize;
+                let line_numbers: Vec<Line> = (0..self.window.len())
+                    .map(|index| Line::from(format!("{:>width$}", self.window_start + index + 1)))
+                    .collect();
+                frame.render_widget(
+                    Paragraph::new(line_numbers).style(styles::LINE_NUMBER_STYLE),
+                    self.line_number_area,
+                );
+            }
+
+            frame.render_stateful_widget(
+                self.vertical_scrollbar.clone(),
+                self.vertical_scrollbar_area,
+                &mut self.vertical_scrollbar_state,
+            );
+            frame.render_stateful_widget(
+                self.horizontal_scrollbar.clone(),
+                self.horizontal_scrollbar_area,
+                &mut self.horizontal_scrollbar_state,
+            );
+        }
+        Ok(())
+    }
+}
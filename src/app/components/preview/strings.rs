@@ -0,0 +1,75 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::fs::File;
+use std::io::{Error, Read};
+use std::path::Path;
+
+// The shortest run of printable characters that counts as a string, like the strings tool
+const MIN_STRING_LENGTH: usize = 4;
+
+// The most of a file that's searched for strings
+pub(super) const MAX_SEARCH_SIZE: u64 = 16 * 1024 * 1024;
+
+// Finds the runs of printable ASCII or UTF-8 characters in the first part of a file, and returns
+// them with their offsets in the file
+pub(super) fn find_strings(path: &Path) -> Result<Vec<(u64, String)>, Error> {
+    let mut bytes = vec![];
+    File::open(path)?
+        .take(MAX_SEARCH_SIZE)
+        .read_to_end(&mut bytes)?;
+
+    let mut strings = vec![];
+    let mut run = String::new();
+    let mut run_start = 0;
+    let mut position = 0;
+    while position < bytes.len() {
+        match printable_char(&bytes[position..]) {
+            Some((c, length)) => {
+                if run.is_empty() {
+                    run_start = position;
+                }
+                run.push(c);
+                position += length;
+            }
+            None => {
+                push_run(&mut strings, &mut run, run_start);
+                position += 1;
+            }
+        }
+    }
+    push_run(&mut strings, &mut run, run_start);
+    Ok(strings)
+}
+
+// Keeps the run if it's long enough, and starts a new one
+fn push_run(strings: &mut Vec<(u64, String)>, run: &mut String, run_start: usize) {
+    if run.chars().count() >= MIN_STRING_LENGTH {
+        strings.push((run_start as u64, std::mem::take(run)));
+    } else {
+        run.clear();
+    }
+}
+
+// Decodes the character at the start of the bytes, and returns it and its length in bytes,
+// if it's a printable character
+fn printable_char(bytes: &[u8]) -> Option<(char, usize)> {
+    let length = match bytes[0] {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return None,
+    };
+    let c = std::str::from_utf8(bytes.get(..length)?)
+        .ok()?
+        .chars()
+        .next()?;
+    if c == '\t' || !c.is_control() {
+        Some((c, length))
+    } else {
+        None
+    }
+}
@@ -3,33 +3,77 @@
  * Created 2024-04-03
  */
 
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::PathBuf;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::layout::{Margin, Position, Rect};
-use ratatui::prelude::Line;
+use ratatui::prelude::{Line, Span, Style};
 use ratatui::widgets::{
-    Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarPosition, ScrollbarState,
+    Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarPosition, ScrollbarState, Wrap,
 };
 use ratatui::Frame;
+use regex::RegexBuilder;
 
+use crate::app::styles;
 use crate::util;
 
+use super::ansi;
 use super::components;
+use super::components::key_hints::KeyHint;
+use super::components::wheel::{WheelDirection, WheelScroll};
 use super::list_pane::ListPane;
+use super::markdown;
 use super::preview_pane;
 use super::preview_pane::PreviewPane;
+use super::thumb_drag::ThumbDrag;
 
 #[derive(Default)]
 pub(super) struct Text<'a> {
     area: Rect,
     inner_area: Rect,
 
-    // The file's directory entry
+    // Line numbers are shown to the left of the text, and don't scroll horizontally
+    show_line_numbers: bool,
+    line_number_area: Rect,
+
+    // The file's directory entry and title, and whether only the start of the file was read
     entry: Option<PathBuf>,
+    title: String,
+    is_truncated: bool,
+
+    // The command of the previewer the text came from, when it isn't the file's contents, in which
+    // case the file can't be followed
+    previewer: Option<String>,
 
-    // The file's contents
+    // Whether the file is followed as it grows, how much of it has been read, and whether the last
+    // line read was cut off by the end of the file
+    is_following: bool,
+    followed_length: u64,
+    is_last_line_partial: bool,
+
+    // The file's contents as they're shown, and as they were read
     file_text: Vec<String>,
+    raw_text: Vec<String>,
+
+    // Whether the file has ANSI escape sequences in it, and whether they're shown as they are
+    // instead of being rendered as styles. When they're rendered, line_styles has the
+    // (start, end, style) byte ranges of each line's styled text.
+    has_ansi: bool,
+    show_escape_sequences: bool,
+    line_styles: Vec<Vec<(usize, usize, Style)>>,
+
+    // Whether the file is Markdown, and whether its source is shown instead of rendering it
+    is_markdown: bool,
+    show_markdown_source: bool,
+
+    // Long lines wrap instead of scrolling horizontally. When they do, the vertical offset counts
+    // rows, not lines, and first_rows has the row each line starts on, plus the total number of
+    // rows, for the width they were wrapped to.
+    wrap: bool,
+    first_rows: Vec<usize>,
+    wrapped_width: u16,
 
     // Horizontal scrollbar stuff
     widest_line_len: usize,
@@ -43,18 +87,46 @@ pub(super) struct Text<'a> {
     vertical_scrollbar_state: ScrollbarState,
     vertical_scrollbar_area: Rect,
     vertical_offset: usize,
+
+    // Count typed before a movement key, e.g. the 20 in 20G
+    pending_count: Option<usize>,
+
+    // Text being searched for, and whether it's still being typed
+    search: Option<String>,
+    is_search_input: bool,
+
+    // Line number being typed after g
+    line_input: Option<String>,
+
+    // Where the search text was found, as (line, start, end) byte positions, in order,
+    // and which one is current
+    search_matches: Vec<(usize, usize, usize)>,
+    search_match_index: Option<usize>,
+
+    // Lines marked to be copied, as the line the selection started on and the line it's been
+    // moved to, the line a mouse drag started on, and the text that was copied, until it's put on
+    // the clipboard
+    selection: Option<(usize, usize)>,
+    drag_start: Option<usize>,
+    copied_text: Option<String>,
+
+    // A scrollbar thumb being dragged
+    thumb_drag: Option<ThumbDrag>,
+
+    // How far the mouse wheel scrolls the text
+    wheel_scroll: WheelScroll,
 }
 
 impl<'a> ListPane<String> for Text<'a> {
     fn init(&mut self, entry: Option<&PathBuf>, lines: Vec<String>, area: Rect) {
-        self.set_area(area);
-
         self.entry = entry.cloned();
-        self.file_text = lines
-            .iter()
-            .map(|item| item.replace('\t', "        "))
-            .collect();
-        self.widest_line_len = Self::widest_line_length(&self.file_text);
+        self.title = entry.map_or_else(String::new, |entry| preview_pane::file_title(entry));
+        self.raw_text = lines.iter().map(|item| Self::expand_tabs(item)).collect();
+        self.has_ansi = ansi::has_escape_sequences(&self.raw_text);
+        self.is_markdown = entry.is_some_and(|entry| markdown::is_markdown_file(entry));
+        self.previewer = None;
+        self.area = area;
+        self.set_file_text();
 
         self.vertical_scrollbar =
             Scrollbar::default().orientation(ScrollbarOrientation::VerticalRight);
@@ -65,7 +137,24 @@ impl<'a> ListPane<String> for Text<'a> {
 
     fn clear(&mut self) {
         self.entry = None;
+        self.title = String::new();
+        self.is_truncated = false;
+        self.previewer = None;
+        self.is_following = false;
         self.file_text = vec![];
+        self.raw_text = vec![];
+        self.has_ansi = false;
+        self.is_markdown = false;
+        self.line_styles.clear();
+        self.pending_count = None;
+        self.search = None;
+        self.is_search_input = false;
+        self.search_matches.clear();
+        self.search_match_index = None;
+        self.line_input = None;
+        self.selection = None;
+        self.drag_start = None;
+        self.thumb_drag = None;
 
         self.set_scrollbar_state();
     }
@@ -78,6 +167,30 @@ impl<'a> ListPane<String> for Text<'a> {
                         x: mouse_event.column,
                         y: mouse_event.row,
                     };
+                    // Dragging a thumb scrolls the text along with it
+                    self.thumb_drag = ThumbDrag::start(
+                        &self.vertical_scrollbar,
+                        self.vertical_scrollbar_area,
+                        &self.vertical_scrollbar_state,
+                        true,
+                        position,
+                        self.vertical_offset,
+                        self.row_count(),
+                    )
+                    .or_else(|| {
+                        ThumbDrag::start(
+                            &self.horizontal_scrollbar,
+                            self.horizontal_scrollbar_area,
+                            &self.horizontal_scrollbar_state,
+                            false,
+                            position,
+                            self.horizontal_offset,
+                            self.widest_line_len,
+                        )
+                    });
+                    if self.thumb_drag.is_some() {
+                        return;
+                    }
 
                     match self.vertical_scrollbar.hit_test(
                         position,
@@ -85,26 +198,21 @@ impl<'a> ListPane<String> for Text<'a> {
                         &self.vertical_scrollbar_state,
                     ) {
                         None => {}
-                        Some(scrollbar_position) => {
-                            match scrollbar_position {
-                                ScrollbarPosition::Begin => self.handle_key_event(KeyEvent::new(
-                                    KeyCode::Up,
-                                    KeyModifiers::NONE,
-                                )),
-                                ScrollbarPosition::TrackLow => self.handle_key_event(
-                                    KeyEvent::new(KeyCode::PageUp, KeyModifiers::NONE),
-                                ),
-                                // ScrollbarPosition::Thumb => {}
-                                ScrollbarPosition::TrackHigh => self.handle_key_event(
-                                    KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE),
-                                ),
-                                ScrollbarPosition::End => self.handle_key_event(KeyEvent::new(
-                                    KeyCode::Down,
-                                    KeyModifiers::NONE,
-                                )),
-                                _ => {}
-                            }
-                        }
+                        Some(scrollbar_position) => match scrollbar_position {
+                            ScrollbarPosition::Begin => self
+                                .handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE)),
+                            ScrollbarPosition::TrackLow => self.handle_key_event(KeyEvent::new(
+                                KeyCode::PageUp,
+                                KeyModifiers::NONE,
+                            )),
+                            ScrollbarPosition::TrackHigh => self.handle_key_event(KeyEvent::new(
+                                KeyCode::PageDown,
+                                KeyModifiers::NONE,
+                            )),
+                            ScrollbarPosition::End => self
+                                .handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)),
+                            _ => {}
+                        },
                     }
                     match self.horizontal_scrollbar.hit_test(
                         position,
@@ -112,54 +220,120 @@ impl<'a> ListPane<String> for Text<'a> {
                         &self.horizontal_scrollbar_state,
                     ) {
                         None => {}
-                        Some(scrollbar_position) => {
-                            match scrollbar_position {
-                                ScrollbarPosition::Begin => self.handle_key_event(KeyEvent::new(
-                                    KeyCode::Left,
-                                    KeyModifiers::NONE,
-                                )),
-                                ScrollbarPosition::TrackLow => self.handle_key_event(
-                                    KeyEvent::new(KeyCode::Left, KeyModifiers::CONTROL),
-                                ),
-                                // ScrollbarPosition::Thumb => {}
-                                ScrollbarPosition::TrackHigh => self.handle_key_event(
-                                    KeyEvent::new(KeyCode::Right, KeyModifiers::CONTROL),
-                                ),
-                                ScrollbarPosition::End => self.handle_key_event(KeyEvent::new(
-                                    KeyCode::Right,
-                                    KeyModifiers::NONE,
-                                )),
-                                _ => {}
-                            }
-                        }
+                        Some(scrollbar_position) => match scrollbar_position {
+                            ScrollbarPosition::Begin => self
+                                .handle_key_event(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE)),
+                            ScrollbarPosition::TrackLow => self.handle_key_event(KeyEvent::new(
+                                KeyCode::Left,
+                                KeyModifiers::CONTROL,
+                            )),
+                            ScrollbarPosition::TrackHigh => self.handle_key_event(KeyEvent::new(
+                                KeyCode::Right,
+                                KeyModifiers::CONTROL,
+                            )),
+                            ScrollbarPosition::End => self.handle_key_event(KeyEvent::new(
+                                KeyCode::Right,
+                                KeyModifiers::NONE,
+                            )),
+                            _ => {}
+                        },
+                    }
+                    // Dragging over the text selects the lines it goes over
+                    self.drag_start = self.line_at_position(position);
+                }
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                let position = Position {
+                    x: mouse_event.column,
+                    y: mouse_event.row,
+                };
+                if let Some(thumb_drag) = &self.thumb_drag {
+                    let offset = thumb_drag.offset(position);
+                    if thumb_drag.is_vertical() {
+                        self.set_vertical_offset(offset);
+                    } else {
+                        self.set_horizontal_offset(offset);
                     }
+                } else if let (Some(drag_start), Some(line)) =
+                    (self.drag_start, self.line_at_position(position))
+                {
+                    self.selection = Some((drag_start, line));
                 }
             }
+            MouseEventKind::Up(MouseButton::Left) => {
+                self.drag_start = None;
+                self.thumb_drag = None;
+            }
             MouseEventKind::ScrollUp => {
-                let key_event = KeyEvent::new(KeyCode::Up, KeyModifiers::NONE);
-                self.handle_key_event(key_event);
+                let lines = self.wheel_scroll.lines(WheelDirection::Up);
+                if self.can_scroll_vertically() {
+                    self.set_vertical_offset(self.vertical_offset.saturating_sub(lines));
+                }
             }
             MouseEventKind::ScrollDown => {
-                let key_event = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
-                self.handle_key_event(key_event);
+                let lines = self.wheel_scroll.lines(WheelDirection::Down);
+                if self.can_scroll_vertically() {
+                    self.set_vertical_offset(self.vertical_offset.saturating_add(lines));
+                }
             }
             _ => { /* ignore */ }
         }
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent) {
-        if util::is_up_key(key_event) {
+        if self.handle_selection_key_event(key_event)
+            || self.handle_line_key_event(key_event)
+            || self.handle_search_key_event(key_event)
+        {
+            return;
+        }
+        // Digits accumulate a count for the next movement key
+        if let Some(digit) = util::count_digit(key_event, self.pending_count.is_some()) {
+            self.pending_count = util::push_count_digit(self.pending_count, digit);
+            return;
+        }
+        let pending_count = self.pending_count.take();
+        let count = pending_count.unwrap_or(1);
+
+        if util::is_up_key(key_event) || key_event.code == KeyCode::Char('k') {
             if self.can_scroll_vertically() && self.vertical_offset > 0 {
-                // Scroll up one line
-                self.vertical_offset -= 1;
-                self.vertical_scrollbar_state.prev();
+                // Scroll up count lines
+                self.set_vertical_offset(self.vertical_offset.saturating_sub(count));
+            }
+        } else if util::is_down_key(key_event) || key_event.code == KeyCode::Char('j') {
+            if self.can_scroll_vertically() {
+                // Scroll down count lines
+                self.set_vertical_offset(self.vertical_offset.saturating_add(count));
+            }
+        } else if key_event.code == KeyCode::Char('w') {
+            // Keep the top line at the top
+            let top_line = self.line_at_row(self.vertical_offset);
+            self.wrap = !self.wrap;
+            self.wrapped_width = 0;
+            self.set_area(self.area);
+            self.set_vertical_offset(self.first_row(top_line));
+        } else if key_event.code == KeyCode::Char('a') && self.has_ansi {
+            self.show_escape_sequences = !self.show_escape_sequences;
+            self.set_file_text();
+        } else if key_event.code == KeyCode::Char('m') && self.is_markdown {
+            self.show_markdown_source = !self.show_markdown_source;
+            self.vertical_offset = 0;
+            self.set_file_text();
+        } else if key_event.code == KeyCode::Char('F') && self.can_follow() {
+            if self.is_following {
+                self.is_following = false;
+            } else {
+                self.start_following();
             }
-        } else if util::is_down_key(key_event) {
+        } else if key_event.code == KeyCode::Char('#') {
+            self.show_line_numbers = !self.show_line_numbers;
+            self.set_area(self.area);
+        } else if key_event.code == KeyCode::Char('G') {
             if self.can_scroll_vertically() {
-                // Scroll down one line
-                if self.vertical_offset < self.vertical_page_limit() {
-                    self.vertical_offset += 1;
-                    self.vertical_scrollbar_state.next();
+                // Scroll to the count-th line, or to the bottom of the file without a count
+                match pending_count {
+                    Some(line) => self.set_vertical_offset(self.first_row(line - 1)),
+                    None => self.set_vertical_offset(self.vertical_page_limit()),
                 }
             }
         } else {
@@ -240,8 +414,8 @@ impl<'a> ListPane<String> for Text<'a> {
                         && key_event.modifiers != KeyModifiers::CONTROL
                         && self.horizontal_offset > 0
                     {
-                        self.horizontal_offset -= 1;
-                        self.horizontal_scrollbar_state.prev();
+                        // Scroll left count characters
+                        self.set_horizontal_offset(self.horizontal_offset.saturating_sub(count));
                     }
                 }
                 KeyCode::Right => {
@@ -264,9 +438,8 @@ impl<'a> ListPane<String> for Text<'a> {
                         && key_event.modifiers != KeyModifiers::CONTROL
                         && self.horizontal_offset < self.horizontal_page_limit()
                     {
-                        // Scroll right one character
-                        self.horizontal_offset += 1;
-                        self.horizontal_scrollbar_state.next();
+                        // Scroll right count characters
+                        self.set_horizontal_offset(self.horizontal_offset.saturating_add(count));
                     }
                 }
                 _ => {}
@@ -281,6 +454,22 @@ impl<'a> ListPane<String> for Text<'a> {
             vertical: 1,
             horizontal: 2,
         });
+        if self.show_line_numbers {
+            // Room for the widest line number and a space
+            let gutter_width =
+                (self.file_text.len().to_string().len() as u16 + 1).min(self.inner_area.width);
+            self.line_number_area = Rect {
+                width: gutter_width,
+                ..self.inner_area
+            };
+            self.inner_area.x += gutter_width;
+            self.inner_area.width -= gutter_width;
+        } else {
+            self.line_number_area = Rect::default();
+        }
+        if self.wrap && self.wrapped_width != self.inner_area.width {
+            self.wrap_lines();
+        }
         self.vertical_scrollbar_area = area.inner(Margin {
             vertical: 1,
             horizontal: 0,
@@ -291,6 +480,72 @@ impl<'a> ListPane<String> for Text<'a> {
         });
         self.set_scrollbar_state();
     }
+
+    fn key_hints(&self) -> Vec<KeyHint> {
+        if self.selection.is_some() {
+            return vec![
+                KeyHint::new("↑/↓", "Select lines"),
+                KeyHint::new("y", "Copy"),
+                KeyHint::new("Esc", "Cancel"),
+            ];
+        }
+        if self.line_input.is_some() {
+            return vec![
+                KeyHint::new("0-9", "Line number"),
+                KeyHint::new("Enter", "Go to line"),
+                KeyHint::new("Esc", "Cancel"),
+            ];
+        }
+        if self.is_search_input {
+            return vec![
+                KeyHint::new("Type", "Search"),
+                KeyHint::new("Enter", "Accept"),
+                KeyHint::new("Esc", "Clear"),
+            ];
+        }
+        if self.search.is_some() {
+            return vec![
+                KeyHint::new("n/N", "Next/Previous match"),
+                KeyHint::new("/", "Search"),
+                KeyHint::new("Esc", "Clear search"),
+            ];
+        }
+        match self.pending_count {
+            Some(count) => vec![
+                KeyHint::new(count.to_string(), "Count"),
+                KeyHint::new("j/k", "Scroll"),
+                KeyHint::new("G", "Go to line"),
+            ],
+            None => {
+                let mut hints = vec![
+                    KeyHint::new("↑/↓", "Scroll"),
+                    KeyHint::new("←/→", "Pan"),
+                    KeyHint::new("PgUp/PgDn", "Page"),
+                    KeyHint::new("^Home/^End", "Top/Bottom"),
+                    KeyHint::new("w", "Wrap"),
+                    KeyHint::new("#", "Line numbers"),
+                    KeyHint::new("v", "Select"),
+                ];
+                if self.can_follow() {
+                    hints.push(KeyHint::new(
+                        "F",
+                        if self.is_following {
+                            "Stop following"
+                        } else {
+                            "Follow"
+                        },
+                    ));
+                }
+                if self.has_ansi {
+                    hints.push(KeyHint::new("a", "ANSI colors/codes"));
+                }
+                if self.is_markdown {
+                    hints.push(KeyHint::new("m", "Rendered/source"));
+                }
+                hints
+            }
+        }
+    }
 }
 
 impl<'a> PreviewPane for Text<'a> {
@@ -302,19 +557,66 @@ impl<'a> PreviewPane for Text<'a> {
     ) -> Result<(), std::io::Error> {
         self.set_area(area);
 
-        if let Some(entry) = &self.entry {
-            let title = preview_pane::file_title(entry)?;
+        if self.entry.is_some() {
+            let mut title = self.title.clone();
+            if self.is_truncated {
+                title.push_str(" Start only");
+            }
+            if let Some(previewer) = &self.previewer {
+                title.push_str(&format!(" via {previewer}"));
+            }
+            if self.is_following {
+                title.push_str(" Following");
+            }
+            if let Some(search) = &self.search {
+                let position = match self.search_match_index {
+                    Some(index) => format!("{} of {}", index + 1, self.search_matches.len()),
+                    None => "no matches".to_string(),
+                };
+                title.push_str(&format!(" /{search} [{position}]"));
+            }
+            if let Some((first, last)) = self.selected_lines() {
+                title.push_str(&format!(" Selected {} lines", last - first + 1));
+            }
+            if let Some(line_input) = &self.line_input {
+                title.push_str(&format!(" Go to line: {line_input}"));
+            }
             let block = components::component_block(has_focus).title(title);
 
             let items: Vec<Line> = self
                 .file_text
                 .iter()
-                .map(|item| Line::from(item.clone()))
+                .enumerate()
+                .map(|(index, item)| {
+                    let line = self.styled_line(index, item);
+                    if self.is_selected(index) {
+                        line.patch_style(styles::SELECTION_STYLE)
+                    } else {
+                        line
+                    }
+                })
                 .collect();
-            let paragraph = Paragraph::new(items.clone())
+            let mut paragraph = Paragraph::new(items.clone())
                 .scroll((self.vertical_offset as u16, self.horizontal_offset as u16));
+            if self.wrap {
+                paragraph = paragraph.wrap(Wrap { trim: false });
+            }
             frame.render_widget(block, self.area);
             frame.render_widget(paragraph, self.inner_area);
+            if self.show_line_numbers {
+                let width = self.line_number_area.width.saturating_sub(1) as usize;
+                let mut line_numbers: Vec<Line> = vec![];
+                for line in 0..self.file_text.len() {
+                    line_numbers.push(Line::from(format!("{:>width$}", line + 1)));
+                    // Wrapped lines are only numbered on their first row
+                    let row_count = self.first_row(line + 1) - self.first_row(line);
+                    line_numbers.extend((1..row_count).map(|_| Line::default()));
+                }
+                let line_numbers = Paragraph::new(line_numbers)
+                    .style(styles::LINE_NUMBER_STYLE)
+                    .scroll((self.vertical_offset as u16, 0));
+                frame.render_widget(line_numbers, self.line_number_area);
+            }
 
             frame.render_stateful_widget(
                 self.vertical_scrollbar.clone(),
@@ -333,21 +635,204 @@ impl<'a> PreviewPane for Text<'a> {
 }
 impl<'a> Text<'a> {
     fn can_scroll_horizontally(&self) -> bool {
-        self.widest_line_len > self.inner_area.width as usize
+        !self.wrap && self.widest_line_len > self.inner_area.width as usize
     }
 
     fn can_scroll_vertically(&self) -> bool {
-        self.file_text.len() > self.inner_area.height as usize
+        self.row_count() > self.inner_area.height as usize
     }
 
     fn vertical_page_limit(&self) -> usize {
-        <Self as PreviewPane>::page_limit(self.file_text.len(), self.inner_area.height as usize)
+        <Self as PreviewPane>::page_limit(self.row_count(), self.inner_area.height as usize)
+    }
+
+    // The number of rows the text takes up, which is the number of lines unless they're wrapped
+    fn row_count(&self) -> usize {
+        self.first_row(self.file_text.len())
+    }
+
+    // The row a line starts on
+    fn first_row(&self, line: usize) -> usize {
+        if self.wrap {
+            self.first_rows
+                .get(line)
+                .or(self.first_rows.last())
+                .copied()
+                .unwrap_or(line)
+        } else {
+            line
+        }
+    }
+
+    // The line a row is part of
+    fn line_at_row(&self, row: usize) -> usize {
+        if self.wrap {
+            self.first_rows
+                .partition_point(|first_row| *first_row <= row)
+                .saturating_sub(1)
+        } else {
+            row
+        }
+    }
+
+    // Works out which row each line starts on when they're wrapped to the inner width
+    fn wrap_lines(&mut self) {
+        let width = self.inner_area.width;
+        self.first_rows.clear();
+        let mut row = 0;
+        for line in &self.file_text {
+            self.first_rows.push(row);
+            row += Paragraph::new(line.as_str())
+                .wrap(Wrap { trim: false })
+                .line_count(width)
+                .max(1);
+        }
+        self.first_rows.push(row);
+        self.wrapped_width = width;
     }
 
     fn horizontal_page_limit(&self) -> usize {
         <Self as PreviewPane>::page_limit(self.widest_line_len, self.inner_area.width as usize)
     }
 
+    fn expand_tabs(line: &str) -> String {
+        line.replace('\t', "        ")
+    }
+
+    // Reads the whole file again, and from then on reads what's appended to it
+    fn start_following(&mut self) {
+        let Some(entry) = self.entry.as_ref() else {
+            return;
+        };
+        if let Ok(bytes) = std::fs::read(entry) {
+            self.raw_text.clear();
+            self.followed_length = 0;
+            self.is_last_line_partial = false;
+            self.is_following = true;
+            self.append_text(&bytes);
+        }
+    }
+
+    // Reads what's been appended to a followed file since it was last read, and scrolls to the
+    // bottom if there was anything. A file that's got shorter is read again from the start.
+    pub fn read_followed(&mut self) {
+        let Some(entry) = self.entry.as_ref().filter(|_| self.is_following) else {
+            return;
+        };
+        let Ok(length) = entry.metadata().map(|metadata| metadata.len()) else {
+            return;
+        };
+        if length != self.followed_length {
+            // The title has the file's size and modified time
+            self.title = preview_pane::file_title(entry);
+        }
+        if length < self.followed_length {
+            self.start_following();
+        } else if length > self.followed_length {
+            let mut bytes = vec![];
+            let read = File::open(entry).and_then(|mut file| {
+                file.seek(SeekFrom::Start(self.followed_length))?;
+                file.read_to_end(&mut bytes)
+            });
+            if read.is_ok() {
+                self.append_text(&bytes);
+            }
+        }
+    }
+
+    // Adds text to the end of the file's lines. A character that's cut off at the end is left to
+    // be read with the rest of it.
+    fn append_text(&mut self, bytes: &[u8]) {
+        let valid_length = match std::str::from_utf8(bytes) {
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            _ => bytes.len(),
+        };
+        if valid_length == 0 {
+            return;
+        }
+        let text = String::from_utf8_lossy(&bytes[..valid_length]);
+        self.followed_length += valid_length as u64;
+
+        let mut lines = text.lines().map(Self::expand_tabs);
+        if self.is_last_line_partial {
+            if let (Some(last), Some(rest)) = (self.raw_text.last_mut(), lines.next()) {
+                last.push_str(&rest);
+            }
+        }
+        self.raw_text.extend(lines);
+        self.is_last_line_partial = !text.ends_with('\n');
+
+        self.has_ansi = ansi::has_escape_sequences(&self.raw_text);
+        self.set_file_text();
+        self.set_vertical_offset(self.vertical_page_limit());
+    }
+
+    pub fn set_truncated(&mut self, is_truncated: bool) {
+        self.is_truncated = is_truncated;
+    }
+
+    // The text is a previewer's output, which isn't Markdown even if the file is
+    pub fn set_previewer(&mut self, command: &str) {
+        self.previewer = Some(command.to_string());
+        self.is_markdown = false;
+        self.set_file_text();
+    }
+
+    fn can_follow(&self) -> bool {
+        !self.is_truncated && self.previewer.is_none()
+    }
+
+    // How far the text is scrolled, vertically and horizontally
+    pub fn offsets(&self) -> (usize, usize) {
+        (self.vertical_offset, self.horizontal_offset)
+    }
+
+    pub fn set_offsets(&mut self, (vertical_offset, horizontal_offset): (usize, usize)) {
+        self.set_vertical_offset(vertical_offset);
+        self.set_horizontal_offset(horizontal_offset);
+    }
+
+    pub fn set_wheel_scroll(&mut self, wheel_scroll: WheelScroll) {
+        self.wheel_scroll = wheel_scroll;
+    }
+
+    // Whether search text or a line number is being typed
+    pub fn is_input(&self) -> bool {
+        self.is_search_input || self.line_input.is_some()
+    }
+
+    pub fn is_searched(&self) -> bool {
+        self.search.is_some()
+    }
+
+    pub fn is_selecting(&self) -> bool {
+        self.selection.is_some()
+    }
+
+    // The text that was copied from the selection, if it hasn't been taken yet
+    pub fn take_copied_text(&mut self) -> Option<String> {
+        self.copied_text.take()
+    }
+
+    pub fn scroll_to_line(&mut self, line: usize) {
+        self.set_vertical_offset(self.first_row(line.saturating_sub(1)));
+    }
+
+    // Sets the vertical offset, clamped to the page limit, and syncs the scrollbar to it
+    fn set_vertical_offset(&mut self, offset: usize) {
+        self.vertical_offset = offset.min(self.vertical_page_limit());
+        self.vertical_scrollbar_state =
+            self.vertical_scrollbar_state.position(self.vertical_offset);
+    }
+
+    // Sets the horizontal offset, clamped to the page limit, and syncs the scrollbar to it
+    fn set_horizontal_offset(&mut self, offset: usize) {
+        self.horizontal_offset = offset.min(self.horizontal_page_limit());
+        self.horizontal_scrollbar_state = self
+            .horizontal_scrollbar_state
+            .position(self.horizontal_offset);
+    }
+
     fn set_scrollbar_state(&mut self) {
         self.set_horizontal_scrollbar_state();
         self.set_vertical_scrollbar_state();
@@ -355,7 +840,7 @@ impl<'a> Text<'a> {
 
     fn set_horizontal_scrollbar_state(&mut self) {
         let frame_length = self.inner_area.width as usize;
-        if self.widest_line_len <= frame_length {
+        if self.wrap || self.widest_line_len <= frame_length {
             // Hide scrollbar
             self.horizontal_scrollbar_state = self
                 .horizontal_scrollbar_state
@@ -373,7 +858,8 @@ impl<'a> Text<'a> {
 
     fn set_vertical_scrollbar_state(&mut self) {
         let frame_length = self.inner_area.height as usize;
-        if self.file_text.len() <= frame_length {
+        let row_count = self.row_count();
+        if row_count <= frame_length {
             // Hide scrollbar
             self.vertical_scrollbar_state =
                 self.vertical_scrollbar_state.position(0).content_length(0);
@@ -382,21 +868,370 @@ impl<'a> Text<'a> {
             // Show scrollbar
             self.vertical_scrollbar_state = self
                 .vertical_scrollbar_state
-                .content_length(self.file_text.len() - frame_length)
+                .content_length(row_count - frame_length)
                 .viewport_content_length(frame_length);
         };
     }
 
-    fn widest_line_length(lines: &[String]) -> usize {
-        lines.iter().fold(
-            0,
-            |acc, line| {
-                if acc < line.len() {
-                    line.len()
-                } else {
-                    acc
+    // Handles v, which starts selecting lines at the top of the view, and the keys that move the
+    // end of the selection, copy it and cancel it. Returns true if the key was handled.
+    fn handle_selection_key_event(&mut self, key_event: KeyEvent) -> bool {
+        let Some((start, end)) = self.selection else {
+            if key_event.code == KeyCode::Char('v')
+                && self.pending_count.is_none()
+                && !self.is_input()
+                && !self.file_text.is_empty()
+            {
+                let top_line = self.line_at_row(self.vertical_offset);
+                self.selection = Some((top_line, top_line));
+                return true;
+            }
+            return false;
+        };
+        let last_line = self.file_text.len().saturating_sub(1);
+        let height = self.inner_area.height as usize;
+        let end = if util::is_up_key(key_event) || key_event.code == KeyCode::Char('k') {
+            end.saturating_sub(1)
+        } else if util::is_down_key(key_event) || key_event.code == KeyCode::Char('j') {
+            end + 1
+        } else {
+            match key_event.code {
+                KeyCode::PageUp => end.saturating_sub(height),
+                KeyCode::PageDown => end + height,
+                KeyCode::Home => 0,
+                KeyCode::End => last_line,
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    self.copy_selection();
+                    return true;
+                }
+                KeyCode::Esc => {
+                    self.selection = None;
+                    return true;
                 }
+                _ => return true,
+            }
+        };
+        let end = end.min(last_line);
+        self.selection = Some((start, end));
+        self.scroll_to_selection_end(end);
+        true
+    }
+
+    // Scrolls the end of the selection into view if it's moved out of it
+    fn scroll_to_selection_end(&mut self, line: usize) {
+        let height = self.inner_area.height as usize;
+        let row = self.first_row(line);
+        if row < self.vertical_offset {
+            self.set_vertical_offset(row);
+        } else if row >= self.vertical_offset + height {
+            self.set_vertical_offset(row + 1 - height);
+        }
+    }
+
+    // The first and last selected lines
+    fn selected_lines(&self) -> Option<(usize, usize)> {
+        self.selection
+            .map(|(start, end)| (start.min(end), start.max(end)))
+    }
+
+    fn is_selected(&self, line: usize) -> bool {
+        self.selected_lines()
+            .is_some_and(|(first, last)| first <= line && line <= last)
+    }
+
+    // Copies the selected lines as they're shown, and ends the selection
+    fn copy_selection(&mut self) {
+        if let Some((first, last)) = self.selected_lines() {
+            let last = last.min(self.file_text.len().saturating_sub(1));
+            self.copied_text = self
+                .file_text
+                .get(first..=last)
+                .map(|lines| lines.join("\n"));
+        }
+        self.selection = None;
+    }
+
+    // The line of text at a position on the screen, if the position is over the text
+    fn line_at_position(&self, position: Position) -> Option<usize> {
+        if !self.inner_area.contains(position) {
+            return None;
+        }
+        let row = self.vertical_offset + (position.y - self.inner_area.y) as usize;
+        let line = self.line_at_row(row);
+        (line < self.file_text.len()).then_some(line)
+    }
+
+    // Adds pasted text to the search or the line number being typed. Only the digits go into a
+    // line number.
+    pub fn paste(&mut self, text: &str) {
+        if let Some(line_input) = self.line_input.as_mut() {
+            line_input.extend(text.chars().filter(char::is_ascii_digit));
+        } else if self.is_search_input {
+            if let Some(search) = self.search.as_mut() {
+                search.push_str(text);
+            }
+            self.apply_search();
+        }
+    }
+
+    // Handles g and the line number typed after it. Returns true if the key was handled.
+    fn handle_line_key_event(&mut self, key_event: KeyEvent) -> bool {
+        match self.line_input.as_mut() {
+            Some(line_input) => {
+                match key_event.code {
+                    KeyCode::Char(c) if c.is_ascii_digit() => line_input.push(c),
+                    KeyCode::Backspace => {
+                        if line_input.pop().is_none() {
+                            self.line_input = None;
+                        }
+                    }
+                    KeyCode::Enter => {
+                        if let Some(line) =
+                            self.line_input.take().and_then(|line| line.parse().ok())
+                        {
+                            self.scroll_to_line(line);
+                        }
+                    }
+                    KeyCode::Esc => self.line_input = None,
+                    _ => {}
+                }
+                true
+            }
+            None => match key_event.code {
+                KeyCode::Char('g') if self.pending_count.is_none() && !self.is_search_input => {
+                    self.line_input = Some(String::new());
+                    true
+                }
+                _ => false,
             },
-        )
+        }
+    }
+
+    // Handles the keys that start, edit, navigate and clear the search. Returns true if the key
+    // was handled.
+    fn handle_search_key_event(&mut self, key_event: KeyEvent) -> bool {
+        if self.is_search_input {
+            match key_event.code {
+                KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    if let Some(search) = self.search.as_mut() {
+                        search.push(c);
+                    }
+                    self.apply_search();
+                    true
+                }
+                KeyCode::Backspace => {
+                    match self.search.as_mut() {
+                        Some(search) if !search.is_empty() => {
+                            search.pop();
+                            self.apply_search();
+                        }
+                        _ => self.clear_search(),
+                    }
+                    true
+                }
+                KeyCode::Enter => {
+                    // Stop typing, but keep the search unless it's empty
+                    self.is_search_input = false;
+                    if self.search.as_ref().is_some_and(|search| search.is_empty()) {
+                        self.clear_search();
+                    }
+                    true
+                }
+                KeyCode::Esc => {
+                    self.clear_search();
+                    true
+                }
+                _ => false,
+            }
+        } else {
+            match key_event.code {
+                KeyCode::Char('/') if self.pending_count.is_none() => {
+                    self.search = Some(String::new());
+                    self.is_search_input = true;
+                    self.apply_search();
+                    true
+                }
+                KeyCode::Char('n') if self.search.is_some() => {
+                    self.step_search_match(true);
+                    true
+                }
+                KeyCode::Char('N') if self.search.is_some() => {
+                    self.step_search_match(false);
+                    true
+                }
+                KeyCode::Esc if self.search.is_some() => {
+                    self.clear_search();
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    fn clear_search(&mut self) {
+        self.search = None;
+        self.is_search_input = false;
+        self.search_matches.clear();
+        self.search_match_index = None;
+    }
+
+    // Finds the search text in every line, ignoring case, and shows the first match at or below
+    // the top of the view
+    fn apply_search(&mut self) {
+        self.search_matches.clear();
+        self.search_match_index = None;
+        let Some(search) = self.search.as_ref().filter(|search| !search.is_empty()) else {
+            return;
+        };
+        let Ok(regex) = RegexBuilder::new(&regex::escape(search))
+            .case_insensitive(true)
+            .build()
+        else {
+            return;
+        };
+        for (index, line) in self.file_text.iter().enumerate() {
+            self.search_matches.extend(
+                regex
+                    .find_iter(line)
+                    .map(|found| (index, found.start(), found.end())),
+            );
+        }
+        if !self.search_matches.is_empty() {
+            let top_line = self.line_at_row(self.vertical_offset);
+            let index = self
+                .search_matches
+                .iter()
+                .position(|(line, _, _)| *line >= top_line)
+                .unwrap_or(0);
+            self.show_search_match(index);
+        }
+    }
+
+    // Makes the next (or previous) match current, wrapping around at the ends
+    fn step_search_match(&mut self, forward: bool) {
+        let count = self.search_matches.len();
+        if count == 0 {
+            return;
+        }
+        let index = match self.search_match_index {
+            Some(index) if forward => (index + 1) % count,
+            Some(index) => (index + count - 1) % count,
+            None => 0,
+        };
+        self.show_search_match(index);
+    }
+
+    // Makes a match current, and scrolls it into view if it isn't already
+    fn show_search_match(&mut self, index: usize) {
+        self.search_match_index = Some(index);
+        let (line, start, end) = self.search_matches[index];
+
+        let height = self.inner_area.height as usize;
+        let row = self.first_row(line);
+        if row < self.vertical_offset || row >= self.vertical_offset + height {
+            self.set_vertical_offset(row.saturating_sub(height / 2));
+        }
+        let text = &self.file_text[line];
+        let start_column = util::display_width(&text[..start]);
+        let end_column = util::display_width(&text[..end]);
+        let width = self.inner_area.width as usize;
+        if self.can_scroll_horizontally()
+            && (start_column < self.horizontal_offset
+                || end_column > self.horizontal_offset + width)
+        {
+            self.set_horizontal_offset(start_column.saturating_sub(width / 2));
+        }
+    }
+
+    // Sets the text that's shown from the text that was read, with Markdown or ANSI escape
+    // sequences either rendered or left in, and updates everything that depends on it
+    fn set_file_text(&mut self) {
+        let styled_lines = if self.is_markdown && !self.show_markdown_source {
+            Some(markdown::render_lines(&self.raw_text))
+        } else if self.has_ansi && !self.show_escape_sequences {
+            Some(ansi::parse_lines(&self.raw_text))
+        } else {
+            None
+        };
+        if let Some(styled_lines) = styled_lines {
+            let (file_text, line_styles) = styled_lines
+                .into_iter()
+                .map(|styled_line| (styled_line.text, styled_line.styles))
+                .unzip();
+            self.file_text = file_text;
+            self.line_styles = line_styles;
+        } else {
+            self.file_text = self.raw_text.clone();
+            self.line_styles.clear();
+        }
+        self.widest_line_len = Self::widest_line_length(&self.file_text);
+        self.first_rows.clear();
+        self.wrapped_width = 0;
+        if self.search.is_some() {
+            self.apply_search();
+        }
+
+        // The line number gutter's width and the wrapped rows depend on the lines
+        self.set_area(self.area);
+    }
+
+    // Returns a line with its ANSI styles applied and the search matches in it highlighted
+    fn styled_line(&self, index: usize, text: &str) -> Line<'a> {
+        let first = self
+            .search_matches
+            .partition_point(|(line, _, _)| *line < index);
+        let last = self
+            .search_matches
+            .partition_point(|(line, _, _)| *line <= index);
+        let line_styles: &[(usize, usize, Style)] = match self.line_styles.get(index) {
+            Some(line_styles) => line_styles,
+            None => &[],
+        };
+        if first == last && line_styles.is_empty() {
+            return Line::from(text.to_string());
+        }
+
+        // Split the line wherever a style starts or ends
+        let mut boundaries = vec![0, text.len()];
+        for (start, end, _) in line_styles {
+            boundaries.extend([*start, *end]);
+        }
+        for (_, start, end) in &self.search_matches[first..last] {
+            boundaries.extend([*start, *end]);
+        }
+        boundaries.sort();
+        boundaries.dedup();
+
+        let spans: Vec<Span> = boundaries
+            .windows(2)
+            .map(|window| {
+                let (start, end) = (window[0], window[1]);
+                let mut style = line_styles
+                    .iter()
+                    .find(|(style_start, style_end, _)| *style_start <= start && start < *style_end)
+                    .map(|(_, _, style)| *style)
+                    .unwrap_or_default();
+                if let Some(match_index) = (first..last).find(|match_index| {
+                    let (_, match_start, match_end) = self.search_matches[*match_index];
+                    match_start <= start && start < match_end
+                }) {
+                    style = style.patch(if Some(match_index) == self.search_match_index {
+                        styles::CURRENT_MATCH_STYLE
+                    } else {
+                        styles::MATCH_STYLE
+                    });
+                }
+                Span::styled(text[start..end].to_string(), style)
+            })
+            .collect();
+        Line::from(spans)
+    }
+
+    fn widest_line_length(lines: &[String]) -> usize {
+        lines
+            .iter()
+            .map(|line| util::display_width(line))
+            .max()
+            .unwrap_or(0)
     }
 }
@@ -0,0 +1,86 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use ratatui::layout::{Position, Rect};
+use ratatui::widgets::{Scrollbar, ScrollbarPosition, ScrollbarState};
+
+// A scrollbar thumb being dragged: where the mouse was when the drag started, and the offset the
+// content was scrolled to then. The content scrolls as far for each cell the mouse moves as the
+// thumb moves for each cell the content scrolls.
+pub(super) struct ThumbDrag {
+    is_vertical: bool,
+    start: u16,
+    start_offset: usize,
+    offsets_per_cell: f64,
+}
+
+impl ThumbDrag {
+    // Starts dragging the scrollbar's thumb, if the mouse went down on it. The content size is
+    // how many rows or columns the content takes up.
+    pub fn start(
+        scrollbar: &Scrollbar,
+        area: Rect,
+        state: &ScrollbarState,
+        is_vertical: bool,
+        position: Position,
+        offset: usize,
+        content_size: usize,
+    ) -> Option<ThumbDrag> {
+        if scrollbar.hit_test(position, area, state)? != ScrollbarPosition::Thumb {
+            return None;
+        }
+        // The track is the part of the bar between the arrows
+        let track_length = if is_vertical {
+            (area.top()..area.bottom())
+                .filter(|&y| Self::is_track(scrollbar, area, state, Position { x: position.x, y }))
+                .count()
+        } else {
+            (area.left()..area.right())
+                .filter(|&x| Self::is_track(scrollbar, area, state, Position { x, y: position.y }))
+                .count()
+        };
+        if track_length == 0 {
+            return None;
+        }
+        Some(ThumbDrag {
+            is_vertical,
+            start: Self::coordinate(is_vertical, position),
+            start_offset: offset,
+            offsets_per_cell: content_size as f64 / track_length as f64,
+        })
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.is_vertical
+    }
+
+    // The offset to scroll the content to with the mouse where it is now. It's up to the
+    // scrolling pane to keep it in range.
+    pub fn offset(&self, position: Position) -> usize {
+        let cells = Self::coordinate(self.is_vertical, position) as f64 - self.start as f64;
+        (self.start_offset as f64 + cells * self.offsets_per_cell)
+            .round()
+            .max(0.0) as usize
+    }
+
+    fn is_track(scrollbar: &Scrollbar, area: Rect, state: &ScrollbarState, cell: Position) -> bool {
+        matches!(
+            scrollbar.hit_test(cell, area, state),
+            Some(
+                ScrollbarPosition::TrackLow
+                    | ScrollbarPosition::Thumb
+                    | ScrollbarPosition::TrackHigh
+            )
+        )
+    }
+
+    fn coordinate(is_vertical: bool, position: Position) -> u16 {
+        if is_vertical {
+            position.y
+        } else {
+            position.x
+        }
+    }
+}
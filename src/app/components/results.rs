@@ -0,0 +1,292 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
+use ratatui::prelude::{Line, Span};
+use ratatui::widgets::{List, ListItem};
+use ratatui::{layout::Rect, Frame};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+
+use regex::Regex;
+
+use crate::app::fs_error::FsError;
+use crate::app::search::{self, NameMatcher, SearchResult};
+use crate::app::{components, styles};
+use crate::stateful_list::StatefulList;
+use crate::tui::Event;
+use crate::util;
+
+use super::key_hints::KeyHint;
+use super::Component;
+
+// Lists the results of a search, as they're found
+#[derive(Default)]
+pub struct Results {
+    is_open: bool,
+    has_focus: bool,
+    area: Rect,
+    event_tx: Option<UnboundedSender<Event>>,
+
+    // What was searched for, and where
+    description: String,
+    root: PathBuf,
+
+    results: StatefulList<SearchResult>,
+
+    // The search that's finding results
+    search_id: u32,
+    is_searching: bool,
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl Component for Results {
+    fn set_area(&mut self, area: Rect) {
+        self.area = area;
+    }
+
+    fn has_focus(&self) -> bool {
+        self.has_focus
+    }
+
+    fn set_focus(&mut self, focus: bool) {
+        self.has_focus = focus;
+    }
+
+    fn hit_test(&self, x: u16, y: u16) -> bool {
+        self.is_open && self.area.contains(Position { x, y })
+    }
+
+    async fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> Result<(), FsError> {
+        match mouse_event.kind {
+            MouseEventKind::Down(mouse_button) => {
+                // A left click on the selected result opens it.
+                // A left click on an unselected result selects it.
+                if mouse_button == MouseButton::Left {
+                    if let Some(index) = self.index_from_row(mouse_event.row) {
+                        if self.results.selected() == Some(index) {
+                            let key_event = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+                            self.handle_key_event(key_event).await?;
+                        } else {
+                            self.results.set_selected(Some(index));
+                        }
+                    }
+                }
+            }
+            MouseEventKind::ScrollUp => {
+                let key_event = KeyEvent::new(KeyCode::Up, KeyModifiers::NONE);
+                self.handle_key_event(key_event).await?;
+            }
+            MouseEventKind::ScrollDown => {
+                let key_event = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
+                self.handle_key_event(key_event).await?;
+            }
+            _ => { /* ignore */ }
+        }
+        Ok(())
+    }
+
+    async fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<(), FsError> {
+        let page_height = self.area.height.saturating_sub(2) as usize;
+        if util::is_up_key(key_event) {
+            self.results.previous();
+        } else if util::is_down_key(key_event) {
+            self.results.next();
+        } else {
+            match key_event.code {
+                KeyCode::Home => {
+                    self.results.first();
+                }
+                KeyCode::End => {
+                    self.results.last();
+                }
+                KeyCode::PageUp => {
+                    self.results.retreat(page_height);
+                }
+                KeyCode::PageDown => {
+                    self.results.advance(page_height);
+                }
+                KeyCode::Enter => {
+                    if let Some(result) = self.selected_result() {
+                        self.event_tx
+                            .as_ref()
+                            .unwrap()
+                            .send(Event::OpenSearchResult(result))
+                            .expect("Panic sending open search result event");
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>) -> Result<(), FsError> {
+        self.set_area(area);
+
+        let title = format!(
+            "[{} - {} result{}{}]",
+            self.description,
+            self.results.len(),
+            if self.results.len() != 1 { "s" } else { "" },
+            if self.is_searching { " …" } else { "" }
+        );
+        let block = components::component_block(self.has_focus).title(title);
+        let offset = self.results.state.offset();
+        let height = self.area.height as usize;
+        let items: Vec<ListItem> = self
+            .results
+            .iter()
+            .enumerate()
+            .map(|(index, result)| {
+                if index < offset || index > offset + height {
+                    ListItem::new("") // Off screen
+                } else {
+                    let path = result.path.strip_prefix(&self.root).unwrap_or(&result.path);
+                    let path = path.to_string_lossy();
+                    match (result.line, &result.snippet) {
+                        (Some(line), Some(snippet)) => ListItem::new(Line::from(vec![
+                            Span::styled(format!("{path}:{line}: "), styles::MATCH_STYLE),
+                            Span::raw(snippet.clone()),
+                        ])),
+                        _ => ListItem::new(path.to_string()),
+                    }
+                }
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+        frame.render_stateful_widget(list, self.area, &mut self.results.state);
+        Ok(())
+    }
+
+    fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Select"),
+            KeyHint::new("Enter", "Go to"),
+            KeyHint::new("Esc", "Close results"),
+        ]
+    }
+}
+
+impl Results {
+    pub fn set_event_tx(&mut self, event_tx: Option<UnboundedSender<Event>>) {
+        self.event_tx = event_tx;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    // Opens the pane and starts finding the entries under root whose names match the pattern
+    pub fn start_find(
+        &mut self,
+        root: PathBuf,
+        pattern: &str,
+        show_hidden: bool,
+    ) -> Result<(), String> {
+        let matcher = NameMatcher::new(pattern)?;
+        let cancellation_token = self.start(root.clone(), format!("Find {pattern}"));
+        search::spawn_find(
+            root,
+            matcher,
+            show_hidden,
+            self.search_id,
+            self.event_tx.clone().unwrap(),
+            cancellation_token,
+        );
+        Ok(())
+    }
+
+    // Opens the pane and starts searching the text files under root for lines that match the regex
+    pub fn start_grep(
+        &mut self,
+        root: PathBuf,
+        pattern: &str,
+        show_hidden: bool,
+    ) -> Result<(), String> {
+        let regex = Regex::new(pattern).map_err(|error| error.to_string())?;
+        let cancellation_token = self.start(root.clone(), format!("Grep {pattern}"));
+        search::spawn_grep(
+            root,
+            regex,
+            show_hidden,
+            self.search_id,
+            self.event_tx.clone().unwrap(),
+            cancellation_token,
+        );
+        Ok(())
+    }
+
+    pub fn close(&mut self) {
+        self.cancel_search();
+        self.is_open = false;
+        self.has_focus = false;
+        self.results = StatefulList::default();
+    }
+
+    pub fn add_results(&mut self, search_id: u32, results: Vec<SearchResult>) {
+        // Ignore results from a search that's been replaced or cancelled
+        if !self.is_open || search_id != self.search_id {
+            return;
+        }
+        let selected = self.results.selected();
+        let mut all_results: Vec<SearchResult> = self.results.iter().cloned().collect();
+        all_results.extend(results);
+        self.results = StatefulList::with_items(all_results);
+        self.results.set_selected(selected.or(Some(0)));
+    }
+
+    pub fn set_finished(&mut self, search_id: u32) {
+        if search_id == self.search_id {
+            self.is_searching = false;
+            self.cancellation_token = None;
+        }
+    }
+
+    // Resets the pane for a new search, and returns the token that cancels it
+    fn start(&mut self, root: PathBuf, description: String) -> CancellationToken {
+        self.cancel_search();
+        self.is_open = true;
+        self.root = root;
+        self.description = description;
+        self.results = StatefulList::default();
+
+        let cancellation_token = CancellationToken::new();
+        self.cancellation_token = Some(cancellation_token.clone());
+        self.search_id = self.search_id.wrapping_add(1);
+        self.is_searching = true;
+        cancellation_token
+    }
+
+    fn cancel_search(&mut self) {
+        if let Some(cancellation_token) = self.cancellation_token.take() {
+            cancellation_token.cancel();
+        }
+        self.is_searching = false;
+    }
+
+    fn selected_result(&self) -> Option<SearchResult> {
+        self.results
+            .selected()
+            .filter(|selected| *selected < self.results.len())
+            .map(|selected| self.results[selected].clone())
+    }
+
+    fn index_from_row(&self, row: u16) -> Option<usize> {
+        // Allow for the border
+        let row = row.checked_sub(self.area.y + 1)? as usize;
+        let index = row + self.results.state.offset();
+        if index < self.results.len() {
+            Some(index)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,87 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::Path;
+
+use ratatui::layout::{Alignment, Constraint, Direction, Layout};
+use ratatui::{layout::Rect, widgets::Paragraph, Frame};
+
+#[cfg(unix)]
+use crate::app::attributes;
+use crate::util;
+
+#[derive(Default)]
+pub struct Status {
+    // Details of the selected entry
+    details: String,
+    // Free space on the filesystem containing the selected entry
+    free_space: String,
+    // What an operation did, which is shown instead of the details until another entry is
+    // selected
+    message: Option<String>,
+}
+
+impl Status {
+    pub fn set_entry(&mut self, entry: Option<&Path>, position: Option<(usize, usize)>) {
+        self.message = None;
+        self.details = match entry {
+            Some(entry) => Self::entry_details(entry, position),
+            None => String::new(),
+        };
+        self.free_space = match entry.and_then(Path::parent) {
+            Some(directory) => match fs2::available_space(directory) {
+                Ok(available) => format!("{} free", util::size_string(available)),
+                Err(_) => String::new(),
+            },
+            None => String::new(),
+        };
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let free_space_width = util::display_width(&self.free_space) as u16;
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(1), Constraint::Length(free_space_width)])
+            .split(area);
+        let details = self.message.as_ref().unwrap_or(&self.details);
+        frame.render_widget(
+            Paragraph::new(util::clip_string(details, layout[0].width as usize)),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(self.free_space.as_str()).alignment(Alignment::Right),
+            layout[1],
+        );
+    }
+
+    fn entry_details(entry: &Path, position: Option<(usize, usize)>) -> String {
+        let mut details = vec![];
+        if let Some((index, total)) = position {
+            details.push(format!("{index}/{total}"));
+        }
+        if let Ok(metadata) = entry.symlink_metadata() {
+            details.push(util::permissions_string(&metadata));
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+
+                details.push(format!(
+                    "{}:{}",
+                    attributes::user_name(metadata.uid()),
+                    attributes::group_name(metadata.gid())
+                ));
+            }
+            if !metadata.is_dir() {
+                details.push(util::size_string(metadata.len()));
+            }
+            details.push(util::metadata_modified_string(&metadata));
+        }
+        details.join("  ")
+    }
+}
@@ -0,0 +1,114 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::widgets::{Clear, List, ListItem};
+use ratatui::{layout::Rect, Frame};
+
+use crate::app::trash::TrashedItem;
+use crate::app::{components, styles};
+use crate::stateful_list::StatefulList;
+use crate::util;
+
+use super::key_hints::KeyHint;
+
+pub enum TrashListEvent {
+    Cancelled,
+    Restore(TrashedItem),
+    Empty,
+}
+
+// A popup list of what's in the trash, where it was deleted from and when
+#[derive(Default)]
+pub struct TrashList {
+    is_open: bool,
+    items: StatefulList<TrashedItem>,
+}
+
+impl TrashList {
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn open(&mut self, items: Vec<TrashedItem>) {
+        self.is_open = true;
+        self.items = StatefulList::with_items(items);
+        self.items.first();
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<TrashListEvent> {
+        if util::is_up_key(key_event) {
+            self.items.previous();
+        } else if util::is_down_key(key_event) {
+            self.items.next();
+        } else {
+            match key_event.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.close();
+                    return Some(TrashListEvent::Cancelled);
+                }
+                KeyCode::Enter | KeyCode::Char('r') => {
+                    if let Some(selected) = self
+                        .items
+                        .selected()
+                        .filter(|selected| *selected < self.items.len())
+                    {
+                        self.close();
+                        return Some(TrashListEvent::Restore(self.items[selected].clone()));
+                    }
+                }
+                // Emptying the trash can't be undone, so the app asks first
+                KeyCode::Char('E') if !self.items.is_empty() => {
+                    self.close();
+                    return Some(TrashListEvent::Empty);
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // How tall the popup has to be to show everything in the trash
+    pub fn height(&self) -> u16 {
+        self.items.len().max(1) as u16 + 2
+    }
+
+    pub fn render(&mut self, area: Rect, frame: &mut Frame) {
+        let block = components::component_block(true).title("[Trash]");
+        let items: Vec<ListItem> = if self.items.is_empty() {
+            vec![ListItem::new("The trash is empty.")]
+        } else {
+            self.items
+                .iter()
+                .map(|item| {
+                    ListItem::new(format!(
+                        "{}  {}",
+                        item.deletion_date,
+                        item.original_path.to_string_lossy()
+                    ))
+                })
+                .collect()
+        };
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(styles::LIST_HIGHLIGHT_STYLE);
+
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.items.state);
+    }
+
+    pub fn key_hints(&self) -> Vec<KeyHint> {
+        vec![
+            KeyHint::new("↑/↓", "Select"),
+            KeyHint::new("Enter", "Restore"),
+            KeyHint::new("E", "Empty trash"),
+            KeyHint::new("Esc", "Close"),
+        ]
+    }
+}
@@ -0,0 +1,72 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::time::{Duration, Instant};
+
+use crate::constants;
+
+// Wheel events closer together than this, in the same direction, are a fast spin
+const SPIN_INTERVAL: Duration = Duration::from_millis(40);
+
+// How many events of a fast spin it takes to scroll one more step per event, and the most steps
+// an event can scroll
+const EVENTS_PER_STEP: u32 = 4;
+const MAX_STEPS: u32 = 5;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WheelDirection {
+    Up,
+    Down,
+}
+
+// How far the mouse wheel scrolls a pane. Each event scrolls some lines, and with acceleration
+// spinning the wheel fast scrolls further with each event, until the spin slows down.
+#[derive(Clone, Copy)]
+pub struct WheelScroll {
+    lines: usize,
+    is_accelerated: bool,
+    // The last event's direction and time, and how many events the spin has had
+    last_event: Option<(WheelDirection, Instant)>,
+    spin_events: u32,
+}
+
+impl Default for WheelScroll {
+    fn default() -> Self {
+        WheelScroll::new(constants::DEFAULT_WHEEL_LINES, true)
+    }
+}
+
+impl WheelScroll {
+    pub fn new(lines: usize, is_accelerated: bool) -> WheelScroll {
+        WheelScroll {
+            lines: lines.max(1),
+            is_accelerated,
+            last_event: None,
+            spin_events: 0,
+        }
+    }
+
+    // How many lines a wheel event in the direction scrolls
+    pub fn lines(&mut self, direction: WheelDirection) -> usize {
+        let now = Instant::now();
+        let is_spinning = matches!(
+            self.last_event,
+            Some((last_direction, time))
+                if last_direction == direction && now.duration_since(time) < SPIN_INTERVAL
+        );
+        self.spin_events = if is_spinning {
+            self.spin_events.saturating_add(1)
+        } else {
+            0
+        };
+        self.last_event = Some((direction, now));
+        if self.is_accelerated {
+            let steps = (1 + self.spin_events / EVENTS_PER_STEP).min(MAX_STEPS);
+            self.lines * steps as usize
+        } else {
+            self.lines
+        }
+    }
+}
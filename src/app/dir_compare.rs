@@ -0,0 +1,76 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::Metadata;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+// Compares the entries of two directories by name, like Midnight Commander's compare
+// directories, and returns the entries on each side that would need copying to make the other
+// side the same: the ones that aren't on the other side, and of the files that are on both, the
+// newer one, or both if they're as new but differ in size. Directories that are on both sides
+// aren't compared.
+pub fn differing_entries(left: &[PathBuf], right: &[PathBuf]) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let by_name = |entries: &[PathBuf]| -> HashMap<OsString, PathBuf> {
+        entries
+            .iter()
+            .filter_map(|entry| Some((entry.file_name()?.to_os_string(), entry.clone())))
+            .collect()
+    };
+    let right_by_name = by_name(right);
+    let left_by_name = by_name(left);
+
+    let mut left_differing = vec![];
+    let mut right_differing = vec![];
+    for entry in left {
+        let Some(other) = entry.file_name().and_then(|name| right_by_name.get(name)) else {
+            left_differing.push(entry.clone());
+            continue;
+        };
+        let (Ok(metadata), Ok(other_metadata)) = (entry.metadata(), other.metadata()) else {
+            continue;
+        };
+        if metadata.is_dir() && other_metadata.is_dir() {
+            continue;
+        }
+        // A file and a directory with the same name are as different as can be
+        if metadata.is_dir() != other_metadata.is_dir() {
+            left_differing.push(entry.clone());
+            right_differing.push(other.clone());
+            continue;
+        }
+        // Copies don't always keep fractions of a second, so they aren't compared
+        match modified_seconds(&metadata).cmp(&modified_seconds(&other_metadata)) {
+            std::cmp::Ordering::Greater => left_differing.push(entry.clone()),
+            std::cmp::Ordering::Less => right_differing.push(other.clone()),
+            std::cmp::Ordering::Equal if metadata.len() != other_metadata.len() => {
+                left_differing.push(entry.clone());
+                right_differing.push(other.clone());
+            }
+            std::cmp::Ordering::Equal => {}
+        }
+    }
+    right_differing.extend(
+        right
+            .iter()
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .is_some_and(|name| !left_by_name.contains_key(name))
+            })
+            .cloned(),
+    );
+    (left_differing, right_differing)
+}
+
+fn modified_seconds(metadata: &Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_secs())
+}
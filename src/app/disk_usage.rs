@@ -0,0 +1,128 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use tokio_util::sync::CancellationToken;
+use walkdir::WalkDir;
+
+// How often the scan's progress is reported
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+// The sizes of everything under a directory, with directories' sizes being the total of
+// everything in them
+#[derive(Clone, Debug, Default)]
+pub struct DiskUsage {
+    sizes: HashMap<PathBuf, u64>,
+    children: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl DiskUsage {
+    pub fn size(&self, path: &Path) -> u64 {
+        self.sizes.get(path).copied().unwrap_or(0)
+    }
+
+    pub fn is_directory(&self, path: &Path) -> bool {
+        self.children.contains_key(path)
+    }
+
+    // What's in the directory, with the sizes, biggest first
+    pub fn children(&self, directory: &Path) -> Vec<(PathBuf, u64)> {
+        let mut children: Vec<(PathBuf, u64)> = self
+            .children
+            .get(directory)
+            .into_iter()
+            .flatten()
+            .map(|child| (child.clone(), self.size(child)))
+            .collect();
+        children.sort_by(|(lhs_path, lhs_size), (rhs_path, rhs_size)| {
+            rhs_size.cmp(lhs_size).then_with(|| lhs_path.cmp(rhs_path))
+        });
+        children
+    }
+}
+
+// Walks the directory tree under the root, without leaving its file system or following links,
+// adding up the sizes of the files in each directory. Calls on_progress with the number of files
+// and bytes found so far every so often. Entries that can't be read are left out. Returns None if
+// it was cancelled. This blocks, so it should be run with spawn_blocking.
+pub fn scan(
+    root: &Path,
+    cancellation_token: &CancellationToken,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Option<DiskUsage> {
+    let mut disk_usage = DiskUsage::default();
+    disk_usage.children.insert(root.to_path_buf(), vec![]);
+    let mut file_count = 0u64;
+    let mut byte_count = 0u64;
+    let mut progress_time = Instant::now();
+    // A directory's contents come before the directory itself, so its size is known by then
+    for entry in WalkDir::new(root)
+        .contents_first(true)
+        .same_file_system(true)
+        .into_iter()
+        .flatten()
+    {
+        if cancellation_token.is_cancelled() {
+            return None;
+        }
+        let path = entry.path().to_path_buf();
+        let size = if entry.file_type().is_dir() {
+            disk_usage.children.entry(path.clone()).or_default();
+            disk_usage.size(&path)
+        } else {
+            let size = entry.metadata().map_or(0, |metadata| metadata.len());
+            file_count += 1;
+            byte_count += size;
+            size
+        };
+        disk_usage.sizes.insert(path.clone(), size);
+        if let Some(parent) = path.parent().filter(|_| path != root) {
+            *disk_usage.sizes.entry(parent.to_path_buf()).or_default() += size;
+            disk_usage
+                .children
+                .entry(parent.to_path_buf())
+                .or_default()
+                .push(path);
+        }
+        if progress_time.elapsed() >= PROGRESS_INTERVAL {
+            on_progress(file_count, byte_count);
+            progress_time = Instant::now();
+        }
+    }
+    Some(disk_usage)
+}
+
+// Adds up the number and sizes of the files under the root, the same way scan does, without
+// keeping track of where they are. Returns None if it was cancelled.
+pub fn total_size(
+    root: &Path,
+    cancellation_token: &CancellationToken,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Option<(u64, u64)> {
+    let mut file_count = 0u64;
+    let mut byte_count = 0u64;
+    let mut progress_time = Instant::now();
+    for entry in WalkDir::new(root)
+        .same_file_system(true)
+        .into_iter()
+        .flatten()
+    {
+        if cancellation_token.is_cancelled() {
+            return None;
+        }
+        if !entry.file_type().is_dir() {
+            file_count += 1;
+            byte_count += entry.metadata().map_or(0, |metadata| metadata.len());
+        }
+        if progress_time.elapsed() >= PROGRESS_INTERVAL {
+            on_progress(file_count, byte_count);
+            progress_time = Instant::now();
+        }
+    }
+    Some((file_count, byte_count))
+}
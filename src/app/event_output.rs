@@ -0,0 +1,105 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+// The app's significant changes, written as JSON lines for a wrapper or a test to follow, like
+//
+//     {"event":"directory_changed","path":"/home/me"}
+//     {"event":"selection_changed","path":"/home/me/notes.md"}
+//     {"event":"file_opened","path":"/home/me/notes.md","with":"Editor (vi)"}
+//
+// The directory and selection are compared with what was last written after each event the app
+// handles, so only the ones that stick are written.
+pub struct EventOutput {
+    file: Option<File>,
+    directory: Option<PathBuf>,
+    selection: Option<PathBuf>,
+}
+
+impl EventOutput {
+    // Writes to a file descriptor the app was started with, like 3 in "rfb --events-json 3
+    // 3>events.jsonl". It's closed when the app quits.
+    #[cfg(unix)]
+    pub fn from_fd(fd: i32) -> io::Result<EventOutput> {
+        use std::os::fd::FromRawFd;
+
+        // The app reads keys from standard input and draws on standard error, but standard
+        // output is free
+        if fd == 0 || fd == 2 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("File descriptor {fd} is used for the terminal"),
+            ));
+        }
+        if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("File descriptor {fd} isn't open"),
+            ));
+        }
+        // Safety: it's open, and nothing else in the app uses it
+        let file = unsafe { File::from_raw_fd(fd) };
+        Ok(EventOutput {
+            file: Some(file),
+            directory: None,
+            selection: None,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn from_fd(_fd: i32) -> io::Result<EventOutput> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Events can only be written to a file descriptor on Unix",
+        ))
+    }
+
+    // Writes what's changed since the last time
+    pub fn update(&mut self, directory: Option<PathBuf>, selection: Option<PathBuf>) {
+        if directory.is_some() && directory != self.directory {
+            self.directory = directory;
+            if let Some(directory) = &self.directory {
+                self.write(json!({
+                    "event": "directory_changed",
+                    "path": directory.to_string_lossy(),
+                }));
+            }
+        }
+        // Nothing's selected for a moment while a directory's read, which isn't worth writing
+        if selection.is_some() && selection != self.selection {
+            self.selection = selection;
+            if let Some(selection) = &self.selection {
+                self.write(json!({
+                    "event": "selection_changed",
+                    "path": selection.to_string_lossy(),
+                }));
+            }
+        }
+    }
+
+    pub fn file_opened(&mut self, file: &Path, with: &str) {
+        self.write(json!({
+            "event": "file_opened",
+            "path": file.to_string_lossy(),
+            "with": with,
+        }));
+    }
+
+    // Stops writing if it can't, since whatever was reading has gone away
+    fn write(&mut self, value: serde_json::Value) {
+        let Some(file) = &mut self.file else {
+            return;
+        };
+        if let Err(error) = writeln!(file, "{value}").and_then(|_| file.flush()) {
+            tracing::warn!(%error, "can't write events");
+            self.file = None;
+        }
+    }
+}
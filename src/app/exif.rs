@@ -0,0 +1,119 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::path::Path;
+
+use ::exif::{Exif, Field, In, Reader, Tag, Value};
+
+// Camera raw formats that are TIFF files underneath, so their EXIF data can be read
+const RAW_EXTENSIONS: [&str; 9] = [
+    "dng", "cr2", "nef", "nrw", "arw", "orf", "rw2", "pef", "srw",
+];
+
+// What a photo's EXIF data says about it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExifInfo {
+    pub camera: Option<String>,
+    pub taken: Option<String>,
+    pub dimensions: Option<(u32, u32)>,
+    // Latitude and longitude in degrees; south and west are negative
+    pub location: Option<(f64, f64)>,
+    pub orientation: Option<String>,
+}
+
+// Whether the file is a TIFF or a camera raw file. These aren't decoded, but their EXIF data is shown.
+pub fn is_tiff_file(path: &Path) -> bool {
+    extension(path).is_some_and(|extension| {
+        extension == "tif" || extension == "tiff" || RAW_EXTENSIONS.contains(&extension.as_str())
+    })
+}
+
+fn extension(path: &Path) -> Option<String> {
+    Some(path.extension()?.to_string_lossy().to_lowercase())
+}
+
+pub fn read_info(path: &Path) -> io::Result<ExifInfo> {
+    let exif = Reader::new()
+        .read_from_container(&mut BufReader::new(File::open(path)?))
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    let field = |tag| exif.get_field(tag, In::PRIMARY);
+    let text = |tag| field(tag).and_then(ascii);
+    let number = |tag| field(tag).and_then(|field| field.value.get_uint(0));
+
+    let camera = match (text(Tag::Make), text(Tag::Model)) {
+        // The model often starts with the make already
+        (Some(make), Some(model)) if model.starts_with(&make) => Some(model),
+        (Some(make), Some(model)) => Some(format!("{make} {model}")),
+        (make, model) => make.or(model),
+    };
+    let dimensions = match (number(Tag::PixelXDimension), number(Tag::PixelYDimension)) {
+        (Some(width), Some(height)) => Some((width, height)),
+        _ => match (number(Tag::ImageWidth), number(Tag::ImageLength)) {
+            (Some(width), Some(height)) => Some((width, height)),
+            _ => None,
+        },
+    };
+    let orientation = number(Tag::Orientation).map(|orientation| {
+        match orientation {
+            1 => "Normal",
+            2 => "Mirrored",
+            3 => "Rotated 180°",
+            4 => "Mirrored vertically",
+            5 => "Mirrored, rotated 90° counterclockwise",
+            6 => "Rotated 90° clockwise",
+            7 => "Mirrored, rotated 90° clockwise",
+            8 => "Rotated 90° counterclockwise",
+            _ => "Unknown",
+        }
+        .to_string()
+    });
+    Ok(ExifInfo {
+        camera,
+        taken: text(Tag::DateTimeOriginal).or_else(|| text(Tag::DateTime)),
+        dimensions,
+        location: location(&exif),
+        orientation,
+    })
+}
+
+fn ascii(field: &Field) -> Option<String> {
+    match &field.value {
+        Value::Ascii(values) => {
+            let text = String::from_utf8_lossy(values.first()?).trim().to_string();
+            (!text.is_empty()).then_some(text)
+        }
+        _ => None,
+    }
+}
+
+// The GPS coordinates are degrees, minutes and seconds, with N/S and E/W given separately
+fn location(exif: &Exif) -> Option<(f64, f64)> {
+    let coordinate = |tag, reference_tag, negative: &str| {
+        let degrees = match &exif.get_field(tag, In::PRIMARY)?.value {
+            Value::Rational(parts) if !parts.is_empty() => parts
+                .iter()
+                .zip([1.0, 60.0, 3600.0])
+                .map(|(part, divisor)| part.to_f64() / divisor)
+                .sum::<f64>(),
+            _ => return None,
+        };
+        let reference = exif
+            .get_field(reference_tag, In::PRIMARY)
+            .and_then(ascii)
+            .unwrap_or_default();
+        Some(if reference == negative {
+            -degrees
+        } else {
+            degrees
+        })
+    };
+    Some((
+        coordinate(Tag::GPSLatitude, Tag::GPSLatitudeRef, "S")?,
+        coordinate(Tag::GPSLongitude, Tag::GPSLongitudeRef, "W")?,
+    ))
+}
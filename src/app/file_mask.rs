@@ -0,0 +1,61 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use globset::{GlobBuilder, GlobMatcher};
+use regex::{Regex, RegexBuilder};
+
+// Restricts a listing to the files whose names match, like Midnight Commander's filter. A mask is
+// a glob, like "*.rs" or "*.{c,h}", or a regular expression between slashes, like "/^test_.*/".
+// Case is ignored unless the mask has capitals in it.
+#[derive(Clone)]
+pub struct FileMask {
+    pattern: String,
+    matcher: Matcher,
+}
+
+#[derive(Clone)]
+enum Matcher {
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+impl FileMask {
+    pub fn new(pattern: &str) -> Result<FileMask, String> {
+        let is_case_insensitive = !pattern.chars().any(char::is_uppercase);
+        let regex = pattern
+            .strip_prefix('/')
+            .and_then(|pattern| pattern.strip_suffix('/'))
+            .filter(|regex| !regex.is_empty());
+        let matcher = match regex {
+            Some(regex) => RegexBuilder::new(regex)
+                .case_insensitive(is_case_insensitive)
+                .build()
+                .map(Matcher::Regex)
+                .map_err(|error| error.to_string())?,
+            None => GlobBuilder::new(pattern)
+                .case_insensitive(is_case_insensitive)
+                .literal_separator(true)
+                .build()
+                .map(|glob| Matcher::Glob(glob.compile_matcher()))
+                .map_err(|error| error.to_string())?,
+        };
+        Ok(FileMask {
+            pattern: pattern.to_string(),
+            matcher,
+        })
+    }
+
+    // The mask as it was typed
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn is_match(&self, name: &str) -> bool {
+        match &self.matcher {
+            Matcher::Glob(glob) => glob.is_match(name),
+            Matcher::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
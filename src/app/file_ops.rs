@@ -0,0 +1,84 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+// Copies a file or a directory and everything in it into a directory, and returns the copy's
+// path. Something that's already there isn't overwritten.
+pub fn copy_entry(source: &Path, directory: &Path) -> io::Result<PathBuf> {
+    let destination = destination(source, directory)?;
+    copy_to(source, &destination)?;
+    Ok(destination)
+}
+
+// Copies a file or a directory and everything in it to the destination path
+fn copy_to(source: &Path, destination: &Path) -> io::Result<()> {
+    if source.is_dir() {
+        if destination.starts_with(source) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Can't copy a directory into itself",
+            ));
+        }
+        for entry in WalkDir::new(source) {
+            let entry = entry?;
+            let target =
+                destination.join(entry.path().strip_prefix(source).unwrap_or(Path::new("")));
+            if entry.file_type().is_dir() {
+                std::fs::create_dir_all(&target)?;
+            } else {
+                std::fs::copy(entry.path(), &target)?;
+            }
+        }
+    } else {
+        std::fs::copy(source, destination)?;
+    }
+    Ok(())
+}
+
+// Moves a file or a directory into a directory, and returns its new path
+pub fn move_entry(source: &Path, directory: &Path) -> io::Result<PathBuf> {
+    let destination = destination(source, directory)?;
+    move_to(source, &destination)?;
+    Ok(destination)
+}
+
+// Moves a file or a directory to the destination path. Moving it to another file system means
+// copying it and deleting the original.
+pub fn move_to(source: &Path, destination: &Path) -> io::Result<()> {
+    match std::fs::rename(source, destination) {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == io::ErrorKind::CrossesDevices => {
+            copy_to(source, destination)?;
+            if source.is_dir() {
+                std::fs::remove_dir_all(source)
+            } else {
+                std::fs::remove_file(source)
+            }
+        }
+        Err(error) => Err(error),
+    }
+}
+
+// Where an entry goes in a directory, as long as there isn't something there already
+fn destination(source: &Path, directory: &Path) -> io::Result<PathBuf> {
+    let Some(name) = source.file_name() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Can't copy {}", source.display()),
+        ));
+    };
+    let destination = directory.join(name);
+    if destination.symlink_metadata().is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", destination.display()),
+        ));
+    }
+    Ok(destination)
+}
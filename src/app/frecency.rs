@@ -0,0 +1,139 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::constants;
+
+const FRECENCY_FILE: &str = "frecency";
+
+// The most directories that are remembered. The lowest ranked are forgotten first.
+const MAX_DIRECTORIES: usize = 500;
+
+// A visited directory, how many times it's been visited, and when it was last visited, in
+// seconds since the epoch
+struct Visit {
+    path: PathBuf,
+    count: u32,
+    last: u64,
+}
+
+impl Visit {
+    // How often the directory's been visited, weighted by how recently, the way zoxide does it
+    fn score(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.last);
+        let weight = if age < 60 * 60 {
+            4.0
+        } else if age < 24 * 60 * 60 {
+            2.0
+        } else if age < 7 * 24 * 60 * 60 {
+            0.5
+        } else {
+            0.25
+        };
+        self.count as f64 * weight
+    }
+}
+
+// The directories that have been visited, kept in a file in the config directory with a line
+// for each: the visit count, the time of the last visit, and the path, separated by tabs
+#[derive(Default)]
+pub struct Frecency {
+    visits: Vec<Visit>,
+}
+
+impl Frecency {
+    // Reads the frecency file. Nothing's been visited if it doesn't exist yet, and lines that
+    // can't be read are skipped.
+    pub fn load() -> io::Result<Frecency> {
+        let contents = match std::fs::read_to_string(Self::file()?) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+        let visits = contents
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, '\t');
+                Some(Visit {
+                    count: fields.next()?.parse().ok()?,
+                    last: fields.next()?.parse().ok()?,
+                    path: PathBuf::from(fields.next()?),
+                })
+            })
+            .collect();
+        Ok(Frecency { visits })
+    }
+
+    // Records a visit to the directory and saves the visits
+    pub fn visit(&mut self, path: &Path) -> io::Result<()> {
+        let now = Self::now();
+        match self.visits.iter_mut().find(|visit| visit.path == path) {
+            Some(visit) => {
+                visit.count = visit.count.saturating_add(1);
+                visit.last = now;
+            }
+            None => self.visits.push(Visit {
+                path: path.to_path_buf(),
+                count: 1,
+                last: now,
+            }),
+        }
+        if self.visits.len() > MAX_DIRECTORIES {
+            self.sort(now);
+            self.visits.truncate(MAX_DIRECTORIES);
+        }
+        self.save()
+    }
+
+    // The visited directories that are still there, highest ranked first
+    pub fn ranked(&mut self) -> Vec<PathBuf> {
+        self.sort(Self::now());
+        self.visits
+            .iter()
+            .filter(|visit| visit.path.is_dir())
+            .map(|visit| visit.path.clone())
+            .collect()
+    }
+
+    fn sort(&mut self, now: u64) {
+        self.visits
+            .sort_by(|lhs, rhs| rhs.score(now).total_cmp(&lhs.score(now)));
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let file = Self::file()?;
+        if let Some(directory) = file.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        let mut contents = String::new();
+        for visit in &self.visits {
+            contents.push_str(&format!(
+                "{}\t{}\t{}\n",
+                visit.count,
+                visit.last,
+                visit.path.to_string_lossy()
+            ));
+        }
+        std::fs::write(file, contents)
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs())
+    }
+
+    fn file() -> io::Result<PathBuf> {
+        dirs::config_dir()
+            .map(|config| config.join(constants::CONFIG_DIRECTORY).join(FRECENCY_FILE))
+            .ok_or(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Can't find config directory",
+            ))
+    }
+}
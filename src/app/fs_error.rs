@@ -0,0 +1,240 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use tracing::warn;
+
+// The most errors that are kept. The oldest are forgotten first.
+const MAX_ERRORS: usize = 100;
+
+// What was being done when an error happened
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation {
+    Reading,
+    Listing,
+    Entering,
+    Creating,
+    Copying,
+    Moving,
+    Trashing,
+    Restoring,
+    Deleting,
+    ChangingPermissions,
+    ChangingOwner,
+}
+
+impl Operation {
+    fn verb(self) -> &'static str {
+        match self {
+            Operation::Reading => "reading",
+            Operation::Listing => "listing",
+            Operation::Entering => "entering",
+            Operation::Creating => "creating",
+            Operation::Copying => "copying",
+            Operation::Moving => "moving",
+            Operation::Trashing => "moving to the trash",
+            Operation::Restoring => "restoring",
+            Operation::Deleting => "deleting",
+            Operation::ChangingPermissions => "changing the permissions of",
+            Operation::ChangingOwner => "changing the owner of",
+        }
+    }
+}
+
+// An error, with what was being done, and to what, when that's known, so it can be described
+// better than the OS describes it
+#[derive(Debug)]
+pub enum FsError {
+    // The user isn't allowed to do it
+    PermissionDenied {
+        operation: Operation,
+        path: PathBuf,
+    },
+    // Anything else the file system reported
+    Io {
+        operation: Operation,
+        path: PathBuf,
+        source: io::Error,
+    },
+    // A file's contents aren't what they were expected to be
+    Decode {
+        path: PathBuf,
+        message: String,
+    },
+    // An archive couldn't be read or extracted
+    Archive {
+        path: PathBuf,
+        message: String,
+    },
+    // Another computer or a bucket couldn't be reached or mounted
+    Network {
+        location: String,
+        message: String,
+    },
+    // An error that isn't about anything in particular
+    Other(io::Error),
+}
+
+impl FsError {
+    // Puts an I/O error in context, picking out the kinds that are described differently
+    pub fn io(operation: Operation, path: &Path, error: io::Error) -> FsError {
+        let path = path.to_path_buf();
+        match error.kind() {
+            io::ErrorKind::PermissionDenied => FsError::PermissionDenied { operation, path },
+            io::ErrorKind::InvalidData => FsError::Decode {
+                path,
+                message: error.to_string(),
+            },
+            _ => FsError::Io {
+                operation,
+                path,
+                source: error,
+            },
+        }
+    }
+
+    // An archive that couldn't be read, which is only a permission problem if that's what it was
+    pub fn archive(path: &Path, error: io::Error) -> FsError {
+        match error.kind() {
+            io::ErrorKind::PermissionDenied => FsError::io(Operation::Reading, path, error),
+            _ => FsError::Archive {
+                path: path.to_path_buf(),
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for FsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Which owners an entry can be given isn't obvious, so it's explained
+            FsError::PermissionDenied {
+                operation: Operation::ChangingOwner,
+                path,
+            } => write!(
+                f,
+                "Permission denied changing the owner of {}: only root can give an entry to another \
+                user, and its owner can only give it to a group they're in",
+                path.display()
+            ),
+            FsError::PermissionDenied { operation, path } => write!(
+                f,
+                "Permission denied {} {}",
+                operation.verb(),
+                path.display()
+            ),
+            FsError::Io { path, source, .. } if source.kind() == io::ErrorKind::NotFound => {
+                write!(f, "{} doesn't exist", path.display())
+            }
+            FsError::Io {
+                operation,
+                path,
+                source,
+            } => write!(
+                f,
+                "Error {} {}: {}",
+                operation.verb(),
+                path.display(),
+                source
+            ),
+            FsError::Decode { path, message } => {
+                write!(f, "Can't decode {}: {}", path.display(), message)
+            }
+            FsError::Archive { path, message } => {
+                write!(f, "Can't open archive {}: {}", path.display(), message)
+            }
+            FsError::Network { location, message } => {
+                write!(f, "Can't reach {}: {}", location, message)
+            }
+            FsError::Other(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for FsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FsError::Io { source, .. } | FsError::Other(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FsError {
+    fn from(error: io::Error) -> Self {
+        FsError::Other(error)
+    }
+}
+
+// Says what was being done, and to what, when an I/O operation fails
+pub trait Context<T> {
+    fn context(self, operation: Operation, path: &Path) -> Result<T, FsError>;
+}
+
+impl<T> Context<T> for io::Result<T> {
+    fn context(self, operation: Operation, path: &Path) -> Result<T, FsError> {
+        self.map_err(|error| FsError::io(operation, path, error))
+    }
+}
+
+// An error, when it happened, and the directory the app was in at the time
+pub struct QueuedError {
+    pub error: FsError,
+    pub time: String,
+    pub directory: Option<PathBuf>,
+}
+
+// The errors that have happened, oldest first, and how many of the latest haven't been dismissed
+#[derive(Default)]
+pub struct ErrorQueue {
+    errors: Vec<QueuedError>,
+    undismissed: usize,
+}
+
+impl ErrorQueue {
+    pub fn push(&mut self, error: impl Into<FsError>) {
+        let error = error.into();
+        warn!(%error);
+        self.errors.push(QueuedError {
+            error,
+            time: Local::now().format("%H:%M:%S").to_string(),
+            directory: std::env::current_dir().ok(),
+        });
+        let excess = self.errors.len().saturating_sub(MAX_ERRORS);
+        self.errors.drain(..excess);
+        self.undismissed = (self.undismissed + 1).min(self.errors.len());
+    }
+
+    // Whether there are errors that haven't been dismissed, which the error popup shows
+    pub fn is_showing(&self) -> bool {
+        self.undismissed > 0
+    }
+
+    pub fn undismissed(&self) -> usize {
+        self.undismissed
+    }
+
+    pub fn latest(&self) -> Option<&QueuedError> {
+        self.errors.last()
+    }
+
+    pub fn dismiss(&mut self) {
+        self.undismissed = 0;
+    }
+
+    pub fn errors(&self) -> &[QueuedError] {
+        &self.errors
+    }
+
+    pub fn clear(&mut self) {
+        self.errors.clear();
+        self.undismissed = 0;
+    }
+}
@@ -0,0 +1,501 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream};
+use futures::{FutureExt, StreamExt};
+use probably_binary::{EntryType, FileType};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
+
+use crate::app::disk_usage;
+use crate::util;
+
+// How much of the start of a file is looked at to tell whether it's text
+const FILE_TYPE_SAMPLE_SIZE: u64 = 8000;
+
+// What's known about an entry, in a form any file system can provide
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EntryMetadata {
+    pub is_dir: bool,
+    pub is_file: bool,
+    pub is_executable: bool,
+    pub is_hidden: bool,
+    pub len: u64,
+    pub modified: Option<SystemTime>,
+}
+
+// An entry in a directory, and its metadata if it could be read
+pub type ListingEntry = (PathBuf, Option<EntryMetadata>);
+
+// Where directory listings, files' contents and entries' metadata are read from. Each kind of
+// file system implements this, and provider() picks the one a path is on, so the directory and
+// preview panes and the searches don't have to know which it is. What's read is awaited on other
+// threads.
+pub(crate) trait FsProvider: Send + Sync {
+    // The entries in a directory, in no particular order
+    fn read_dir<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<Vec<ListingEntry>>>;
+
+    // The entries in a directory as they're found, so a big one can be shown before it's all been
+    // read. Opening it fails if it can't be read at all. Unless a file system can do better,
+    // they're all found at once.
+    fn open_dir<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxFuture<'a, io::Result<BoxStream<'static, io::Result<ListingEntry>>>> {
+        async move {
+            let entries = self.read_dir(path).await?;
+            Ok(stream::iter(entries.into_iter().map(Ok)).boxed())
+        }
+        .boxed()
+    }
+
+    fn metadata<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<EntryMetadata>>;
+
+    // A file's contents, or as much of the start of them as the maximum size
+    fn read<'a>(
+        &'a self,
+        path: &'a Path,
+        max_size: Option<u64>,
+    ) -> BoxFuture<'a, io::Result<Vec<u8>>>;
+
+    // Whether an entry is a directory, a text file, a binary file or something else. A file with
+    // a NUL byte near the start is a binary one.
+    fn entry_type<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<EntryType>> {
+        async move {
+            let metadata = self.metadata(path).await?;
+            if metadata.is_dir {
+                return Ok(EntryType::Directory);
+            }
+            if !metadata.is_file {
+                return Ok(EntryType::Other);
+            }
+            let sample = self.read(path, Some(FILE_TYPE_SAMPLE_SIZE)).await?;
+            let file_type = if sample.contains(&0) {
+                FileType::Binary
+            } else {
+                FileType::Text
+            };
+            Ok(EntryType::File(file_type))
+        }
+        .boxed()
+    }
+
+    // Writes a file's contents to a local file. Unless a file system can do better, they're all
+    // read first.
+    fn download<'a>(
+        &'a self,
+        path: &'a Path,
+        file: &'a mut tokio::fs::File,
+    ) -> BoxFuture<'a, io::Result<()>> {
+        async move {
+            let contents = self.read(path, None).await?;
+            file.write_all(&contents).await
+        }
+        .boxed()
+    }
+
+    // A file on this computer with the entry's contents, for what can only read files, like the
+    // image and PDF previews and the programs files are opened with. For a directory, it's one
+    // with everything in it. Unless a file system can do better, the files that haven't been
+    // downloaded yet are downloaded to where they're shown, in the directory it's mounted on.
+    fn local_path<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<PathBuf>> {
+        async move {
+            let mut entries = vec![(path.to_path_buf(), self.metadata(path).await?)];
+            while let Some((entry, metadata)) = entries.pop() {
+                if metadata.is_dir {
+                    for (entry, metadata) in self.read_dir(&entry).await? {
+                        entries.extend(metadata.map(|metadata| (entry, metadata)));
+                    }
+                } else if metadata.is_file && tokio::fs::symlink_metadata(&entry).await.is_err() {
+                    download_file(self, &entry).await?;
+                }
+            }
+            Ok(path.to_path_buf())
+        }
+        .boxed()
+    }
+}
+
+// A file is downloaded next to where it goes, and moved there when it's all there, so one that's
+// half-downloaded is never taken for one that's been downloaded
+async fn download_file<P: FsProvider + ?Sized>(provider: &P, path: &Path) -> io::Result<()> {
+    static PARTIAL_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let partial = path.with_file_name(format!(
+        ".{}.rfb-partial-{}",
+        util::entry_name(path),
+        PARTIAL_COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut file = tokio::fs::File::create(&partial).await?;
+    let result = match provider.download(path, &mut file).await {
+        Ok(()) => tokio::fs::rename(&partial, path).await,
+        Err(error) => Err(error),
+    };
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&partial).await;
+    }
+    result
+}
+
+// The file systems that aren't the computer's own, like open archives', by the local directory
+// each is shown in. Only their directories are there, so the app can go into them; their files'
+// contents are read through the file system.
+static MOUNTS: Mutex<Vec<(PathBuf, Arc<dyn FsProvider>)>> = Mutex::new(vec![]);
+
+// Shows a file system in a local directory, in place of anything that was shown there
+pub(crate) fn mount(root: PathBuf, provider: Arc<dyn FsProvider>) {
+    let mut mounts = MOUNTS.lock().unwrap();
+    mounts.retain(|(mounted_root, _)| *mounted_root != root);
+    mounts.push((root, provider));
+}
+
+// The file system mounted on the directory a path is in. Archives can be opened from inside
+// others, so it's the one whose directory is the innermost.
+fn mounted(path: &Path) -> Option<Arc<dyn FsProvider>> {
+    MOUNTS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(root, _)| path.starts_with(root))
+        .max_by_key(|(root, _)| root.components().count())
+        .map(|(_, provider)| provider.clone())
+}
+
+// The file system a path is on
+pub(crate) fn provider(path: &Path) -> Box<dyn FsProvider> {
+    match mounted(path) {
+        Some(provider) => Box::new(provider),
+        None => Box::new(LocalFs),
+    }
+}
+
+// Whether a path is on the computer's own file system, whose trees can be walked without going
+// through a provider
+pub(crate) fn is_local(path: &Path) -> bool {
+    mounted(path).is_none()
+}
+
+// Fails unless a path is on the computer's own file system. What's shown of an archive, another
+// computer or a bucket is only a placeholder or a copy in a local directory, so changing it
+// wouldn't change what's really there.
+pub(crate) fn check_local(path: &Path) -> io::Result<()> {
+    if is_local(path) {
+        return Ok(());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "it's in an archive or on another computer, which can't be changed",
+    ))
+}
+
+// A mounted file system is shared by everything that reads through it
+impl<T: FsProvider + ?Sized> FsProvider for Arc<T> {
+    fn read_dir<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<Vec<ListingEntry>>> {
+        (**self).read_dir(path)
+    }
+
+    fn open_dir<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxFuture<'a, io::Result<BoxStream<'static, io::Result<ListingEntry>>>> {
+        (**self).open_dir(path)
+    }
+
+    fn metadata<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<EntryMetadata>> {
+        (**self).metadata(path)
+    }
+
+    fn read<'a>(
+        &'a self,
+        path: &'a Path,
+        max_size: Option<u64>,
+    ) -> BoxFuture<'a, io::Result<Vec<u8>>> {
+        (**self).read(path, max_size)
+    }
+
+    fn entry_type<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<EntryType>> {
+        (**self).entry_type(path)
+    }
+
+    fn download<'a>(
+        &'a self,
+        path: &'a Path,
+        file: &'a mut tokio::fs::File,
+    ) -> BoxFuture<'a, io::Result<()>> {
+        (**self).download(path, file)
+    }
+
+    fn local_path<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<PathBuf>> {
+        (**self).local_path(path)
+    }
+}
+
+// Adds up the sizes of the files under a directory. A local one is walked on a blocking thread,
+// and any other is read a directory at a time. Entries that can't be read are skipped. Returns None
+// if it's cancelled.
+pub(crate) async fn total_size(
+    directory: &Path,
+    cancellation_token: &CancellationToken,
+) -> Option<u64> {
+    if is_local(directory) {
+        let directory = directory.to_path_buf();
+        let cancellation_token = cancellation_token.clone();
+        return tokio::task::spawn_blocking(move || {
+            disk_usage::total_size(&directory, &cancellation_token, |_, _| {})
+        })
+        .await
+        .ok()?
+        .map(|(_, byte_count)| byte_count);
+    }
+    let provider = provider(directory);
+    let mut directories = vec![directory.to_path_buf()];
+    let mut size = 0;
+    while let Some(directory) = directories.pop() {
+        if cancellation_token.is_cancelled() {
+            return None;
+        }
+        for (entry, metadata) in provider.read_dir(&directory).await.unwrap_or_default() {
+            match metadata {
+                Some(metadata) if metadata.is_dir => directories.push(entry),
+                Some(metadata) => size += metadata.len,
+                None => {}
+            }
+        }
+    }
+    Some(size)
+}
+
+// The computer's own file system, which includes anything mounted on it
+pub(crate) struct LocalFs;
+
+impl LocalFs {
+    // Follows links, like the directory pane does. This blocks.
+    pub fn entry_metadata(path: &Path) -> Option<EntryMetadata> {
+        Some(Self::convert_metadata(path, &path.metadata().ok()?))
+    }
+
+    fn convert_metadata(path: &Path, metadata: &std::fs::Metadata) -> EntryMetadata {
+        EntryMetadata {
+            is_dir: metadata.is_dir(),
+            is_file: metadata.is_file(),
+            is_executable: util::is_executable(metadata),
+            is_hidden: util::is_hidden(path, Some(metadata)),
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        }
+    }
+}
+
+impl FsProvider for LocalFs {
+    // Looking up every entry's metadata can take a while in a big directory, so it's done on a
+    // blocking thread
+    fn read_dir<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<Vec<ListingEntry>>> {
+        let path = path.to_path_buf();
+        async move {
+            tokio::task::spawn_blocking(move || {
+                let mut entries = vec![];
+                for dir_entry in std::fs::read_dir(path)? {
+                    let entry = dir_entry?.path();
+                    let metadata = Self::entry_metadata(&entry);
+                    entries.push((entry, metadata));
+                }
+                Ok(entries)
+            })
+            .await?
+        }
+        .boxed()
+    }
+
+    // The entries are found on a blocking thread, which stops when the stream is dropped
+    fn open_dir<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxFuture<'a, io::Result<BoxStream<'static, io::Result<ListingEntry>>>> {
+        async move {
+            let read_dir = std::fs::read_dir(path)?;
+            let (entry_tx, entry_rx) = futures::channel::mpsc::unbounded();
+            tokio::task::spawn_blocking(move || {
+                for dir_entry in read_dir {
+                    let entry = dir_entry.map(|dir_entry| {
+                        let entry = dir_entry.path();
+                        let metadata = Self::entry_metadata(&entry);
+                        (entry, metadata)
+                    });
+                    let is_error = entry.is_err();
+                    if entry_tx.unbounded_send(entry).is_err() || is_error {
+                        break;
+                    }
+                }
+            });
+            Ok(entry_rx.boxed())
+        }
+        .boxed()
+    }
+
+    fn metadata<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<EntryMetadata>> {
+        async move {
+            let metadata = tokio::fs::metadata(path).await?;
+            Ok(Self::convert_metadata(path, &metadata))
+        }
+        .boxed()
+    }
+
+    fn read<'a>(
+        &'a self,
+        path: &'a Path,
+        max_size: Option<u64>,
+    ) -> BoxFuture<'a, io::Result<Vec<u8>>> {
+        async move {
+            match max_size {
+                Some(max_size) => {
+                    let mut bytes = vec![];
+                    tokio::fs::File::open(path)
+                        .await?
+                        .take(max_size)
+                        .read_to_end(&mut bytes)
+                        .await?;
+                    Ok(bytes)
+                }
+                None => tokio::fs::read(path).await,
+            }
+        }
+        .boxed()
+    }
+
+    // Files here are told apart the way they always have been
+    fn entry_type<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<EntryType>> {
+        let path = path.to_path_buf();
+        async move { tokio::task::spawn_blocking(move || probably_binary::entry_type(&path)).await? }
+            .boxed()
+    }
+
+    fn local_path<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<PathBuf>> {
+        async move { Ok(path.to_path_buf()) }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    // Files whose contents are kept in memory, to check what every file system gets by default
+    struct MemoryFs(HashMap<PathBuf, Vec<u8>>);
+
+    impl FsProvider for MemoryFs {
+        fn read_dir<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<Vec<ListingEntry>>> {
+            async move {
+                Ok(self
+                    .0
+                    .keys()
+                    .filter(|file| file.parent() == Some(path))
+                    .map(|file| (file.clone(), None))
+                    .collect())
+            }
+            .boxed()
+        }
+
+        fn metadata<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<EntryMetadata>> {
+            async move {
+                let contents = self.0.get(path).ok_or(io::ErrorKind::NotFound)?;
+                Ok(EntryMetadata {
+                    is_file: true,
+                    len: contents.len() as u64,
+                    ..EntryMetadata::default()
+                })
+            }
+            .boxed()
+        }
+
+        fn read<'a>(
+            &'a self,
+            path: &'a Path,
+            max_size: Option<u64>,
+        ) -> BoxFuture<'a, io::Result<Vec<u8>>> {
+            async move {
+                let contents = self.0.get(path).ok_or(io::ErrorKind::NotFound)?;
+                let len = max_size.map_or(contents.len(), |max_size| {
+                    contents.len().min(max_size as usize)
+                });
+                Ok(contents[..len].to_vec())
+            }
+            .boxed()
+        }
+
+        fn local_path<'a>(&'a self, _path: &'a Path) -> BoxFuture<'a, io::Result<PathBuf>> {
+            async move { Err(io::ErrorKind::Unsupported.into()) }.boxed()
+        }
+    }
+
+    fn memory_fs() -> MemoryFs {
+        let mut text = b"line\n".repeat(2000);
+        // Past the part that's looked at
+        text.push(0);
+        MemoryFs(HashMap::from([
+            (PathBuf::from("/notes.txt"), text),
+            (
+                PathBuf::from("/image.png"),
+                b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec(),
+            ),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn tells_text_from_binary_by_the_start_of_a_file() {
+        let fs = memory_fs();
+        assert_eq!(
+            fs.entry_type(Path::new("/notes.txt")).await.unwrap(),
+            EntryType::File(FileType::Text)
+        );
+        assert_eq!(
+            fs.entry_type(Path::new("/image.png")).await.unwrap(),
+            EntryType::File(FileType::Binary)
+        );
+        assert_eq!(
+            fs.entry_type(Path::new("/missing"))
+                .await
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn opens_a_directory_all_at_once() {
+        let fs = memory_fs();
+        let mut entries: Vec<PathBuf> = fs
+            .open_dir(Path::new("/"))
+            .await
+            .unwrap()
+            .map(|entry| entry.unwrap().0)
+            .collect()
+            .await;
+        entries.sort();
+        assert_eq!(
+            entries,
+            [PathBuf::from("/image.png"), PathBuf::from("/notes.txt")]
+        );
+    }
+
+    #[test]
+    fn only_local_entries_can_be_changed() {
+        let root = std::env::temp_dir().join(format!("rfb-test-memory-fs-{}", std::process::id()));
+        mount(root.clone(), Arc::new(memory_fs()));
+        assert_eq!(
+            check_local(&root.join("notes.txt")).unwrap_err().kind(),
+            io::ErrorKind::Unsupported
+        );
+        assert!(check_local(&root).is_err());
+        assert!(check_local(&std::env::temp_dir()).is_ok());
+    }
+}
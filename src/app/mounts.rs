@@ -0,0 +1,143 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io;
+use std::path::PathBuf;
+
+// A drive or mounted file system that can be gone to
+#[derive(Debug, PartialEq)]
+pub struct Mount {
+    pub path: PathBuf,
+    pub label: String,
+    pub file_system: String,
+    pub free_space: Option<u64>,
+}
+
+impl Mount {
+    fn new(path: PathBuf, label: String, file_system: String) -> Mount {
+        let free_space = fs2::available_space(&path).ok();
+        Mount {
+            path,
+            label,
+            file_system,
+            free_space,
+        }
+    }
+}
+
+// The file systems mounted on Linux, from /proc/self/mounts, leaving out the kernel's own
+// pseudo file systems. Labels are found by following the links in /dev/disk/by-label.
+// Looking up free space can block on network file systems, so this should be run with
+// spawn_blocking.
+#[cfg(target_os = "linux")]
+pub fn mounts() -> io::Result<Vec<Mount>> {
+    const PSEUDO_FILE_SYSTEMS: [&str; 19] = [
+        "autofs",
+        "binfmt_misc",
+        "bpf",
+        "cgroup",
+        "cgroup2",
+        "configfs",
+        "debugfs",
+        "devpts",
+        "devtmpfs",
+        "fusectl",
+        "hugetlbfs",
+        "mqueue",
+        "nsfs",
+        "proc",
+        "pstore",
+        "securityfs",
+        "sysfs",
+        "tracefs",
+        "efivarfs",
+    ];
+    let labels: Vec<(PathBuf, String)> = std::fs::read_dir("/dev/disk/by-label")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let device = std::fs::canonicalize(entry.path()).ok()?;
+            let label = unescape(&entry.file_name().to_string_lossy(), "\\x", 2, 16);
+            Some((device, label))
+        })
+        .collect();
+    let mut mounts: Vec<Mount> = vec![];
+    for line in std::fs::read_to_string("/proc/self/mounts")?.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(device), Some(path), Some(file_system)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let path = PathBuf::from(unescape(path, "\\", 3, 8));
+        // A file system mounted more than once on the same place is only listed once
+        if PSEUDO_FILE_SYSTEMS.contains(&file_system)
+            || mounts.iter().any(|mount| mount.path == path)
+        {
+            continue;
+        }
+        let device = std::fs::canonicalize(device).ok();
+        let label = labels
+            .iter()
+            .find(|(labelled, _)| Some(labelled) == device.as_ref())
+            .map(|(_, label)| label.clone())
+            .unwrap_or_default();
+        mounts.push(Mount::new(path, label, file_system.to_string()));
+    }
+    mounts.sort_by(|lhs, rhs| lhs.path.cmp(&rhs.path));
+    Ok(mounts)
+}
+
+// Other Unixes don't have /proc, so just the root and, on macOS, the volumes
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn mounts() -> io::Result<Vec<Mount>> {
+    let mut mounts = vec![Mount::new(PathBuf::from("/"), String::new(), String::new())];
+    if let Ok(read_dir) = std::fs::read_dir("/Volumes") {
+        for entry in read_dir.flatten() {
+            let label = entry.file_name().to_string_lossy().to_string();
+            mounts.push(Mount::new(entry.path(), label, String::new()));
+        }
+    }
+    Ok(mounts)
+}
+
+// The drive letters that have something in them
+#[cfg(windows)]
+pub fn mounts() -> io::Result<Vec<Mount>> {
+    Ok(('A'..='Z')
+        .map(|letter| PathBuf::from(format!("{letter}:\\")))
+        .filter(|path| path.is_dir())
+        .map(|path| Mount::new(path, String::new(), String::new()))
+        .collect())
+}
+
+// Mount points escape spaces and the like in octal, e.g. \040 for a space, and labels in hex,
+// e.g. \x20
+#[cfg(target_os = "linux")]
+fn unescape(string: &str, prefix: &str, digits: usize, radix: u32) -> String {
+    let mut bytes = vec![];
+    let mut rest = string.as_bytes();
+    while !rest.is_empty() {
+        let escaped = rest
+            .strip_prefix(prefix.as_bytes())
+            .and_then(|tail| Some((tail.get(..digits)?, &tail[digits..])))
+            .and_then(|(code, tail)| {
+                let code = u8::from_str_radix(std::str::from_utf8(code).ok()?, radix).ok()?;
+                Some((code, tail))
+            });
+        match escaped {
+            Some((code, tail)) => {
+                bytes.push(code);
+                rest = tail;
+            }
+            None => {
+                bytes.push(rest[0]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
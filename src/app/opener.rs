@@ -0,0 +1,143 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::control;
+
+// What Enter does to a file
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OpenFile {
+    Nothing,
+    // Opens it in $VISUAL or $EDITOR, which gets the terminal until it's done
+    Editor,
+    // Opens it in the application the OS opens it in
+    Default,
+    // Asks what to open it with
+    Menu,
+}
+
+impl OpenFile {
+    pub fn from_name(name: &str) -> Option<OpenFile> {
+        match name {
+            "nothing" => Some(OpenFile::Nothing),
+            "editor" => Some(OpenFile::Editor),
+            "default" => Some(OpenFile::Default),
+            "menu" => Some(OpenFile::Menu),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OpenFile::Nothing => "nothing",
+            OpenFile::Editor => "editor",
+            OpenFile::Default => "default",
+            OpenFile::Menu => "menu",
+        }
+    }
+}
+
+// Something a file can be opened with, from the open with menu
+#[derive(Clone, Debug, PartialEq)]
+pub enum Opener {
+    Editor,
+    Default,
+    // A program from the settings, with any arguments it takes before the file. One that ends with
+    // & runs in the background instead of getting the terminal, like a GUI program.
+    Program(String),
+}
+
+impl Opener {
+    pub fn label(&self) -> String {
+        match self {
+            Opener::Editor => format!("Editor ({})", editor()),
+            Opener::Default => "Default application".to_string(),
+            Opener::Program(program) => program.clone(),
+        }
+    }
+}
+
+// How a file is opened: with a program that gets the terminal until it's done, or one that's been
+// started in the background
+pub enum Opening {
+    InTerminal(Command),
+    Started,
+}
+
+// The program is told where the app's control socket is, if it's listening on one
+pub fn open(opener: &Opener, path: &Path, control_socket: Option<&Path>) -> io::Result<Opening> {
+    let (mut command, in_terminal) = match opener {
+        Opener::Editor => (program_command(&editor())?, true),
+        Opener::Default => (default_command(), false),
+        Opener::Program(program) => match program.trim().strip_suffix('&') {
+            Some(program) => (program_command(program)?, false),
+            None => (program_command(program)?, true),
+        },
+    };
+    command.arg(path);
+    if let Some(control_socket) = control_socket {
+        command.env(control::SOCKET_VARIABLE, control_socket);
+    }
+    if in_terminal {
+        Ok(Opening::InTerminal(command))
+    } else {
+        start_in_background(command)?;
+        Ok(Opening::Started)
+    }
+}
+
+// The editor the user wants, like other programs that run one, or vi if they haven't said
+fn editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+// A program with arguments, like "code --wait", split on whitespace
+fn program_command(program: &str) -> io::Result<Command> {
+    let mut words = program.split_whitespace();
+    let name = words
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No program to open it with"))?;
+    let mut command = Command::new(name);
+    command.args(words);
+    Ok(command)
+}
+
+#[cfg(target_os = "macos")]
+fn default_command() -> Command {
+    Command::new("open")
+}
+
+#[cfg(windows)]
+fn default_command() -> Command {
+    // The empty argument is start's window title, so a quoted path isn't taken for one
+    let mut command = Command::new("cmd");
+    command.args(["/C", "start", ""]);
+    command
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn default_command() -> Command {
+    Command::new("xdg-open")
+}
+
+// Starts a program that doesn't use the terminal, without letting anything it prints get on the
+// screen. Tokio reaps it when it exits. An error is about the program, not the file.
+fn start_in_background(command: Command) -> io::Result<()> {
+    let name = command.get_program().to_string_lossy().to_string();
+    tokio::process::Command::from(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|error| io::Error::new(error.kind(), format!("Can't run {name}: {error}")))
+}
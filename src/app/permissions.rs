@@ -0,0 +1,290 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::fs::Permissions;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+// The permission bits each class of user has, and each permission for every class
+const CLASSES: &[(char, u32)] = &[('u', 0o4700), ('g', 0o2070), ('o', 0o1007)];
+const ALL_CLASSES: u32 = 0o7777;
+const PERMISSIONS: &[(char, u32)] = &[
+    ('r', 0o444),
+    ('w', 0o222),
+    ('x', 0o111),
+    ('s', 0o6000),
+    ('t', 0o1000),
+];
+
+// A mode to give entries, like chmod takes: octal, like 755, or symbolic, like +x, go-w or
+// u=rw,g=r
+#[derive(Clone, Debug)]
+pub enum Mode {
+    Absolute(u32),
+    Symbolic(Vec<Clause>),
+}
+
+// A symbolic mode's change to some classes' permissions. X is execute for directories, and for
+// files that someone can already execute.
+#[derive(Clone, Debug)]
+pub struct Clause {
+    classes: u32,
+    operator: char,
+    permissions: u32,
+    is_conditional_execute: bool,
+}
+
+impl Mode {
+    pub fn parse(text: &str) -> Result<Mode, String> {
+        let bad_mode = || format!("Bad mode: {text}");
+        if !text.is_empty() && text.chars().all(|c| c.is_digit(8)) {
+            return u32::from_str_radix(text, 8)
+                .ok()
+                .filter(|mode| *mode <= ALL_CLASSES)
+                .map(Mode::Absolute)
+                .ok_or_else(bad_mode);
+        }
+        let mut clauses = vec![];
+        for part in text.split(',') {
+            let operator_index = part.find(['+', '-', '=']).ok_or_else(bad_mode)?;
+            let (who, mut actions) = part.split_at(operator_index);
+            let mut classes = 0;
+            for c in who.chars() {
+                classes |= match c {
+                    'a' => ALL_CLASSES,
+                    _ => CLASSES
+                        .iter()
+                        .find(|(class, _)| *class == c)
+                        .map(|(_, bits)| *bits)
+                        .ok_or_else(bad_mode)?,
+                };
+            }
+            if classes == 0 {
+                classes = ALL_CLASSES;
+            }
+            // Each operator applies the permissions up to the next one, like u+x-w
+            while let Some(operator) = actions.chars().next() {
+                let rest = &actions[1..];
+                let end = rest.find(['+', '-', '=']).unwrap_or(rest.len());
+                let mut clause = Clause {
+                    classes,
+                    operator,
+                    permissions: 0,
+                    is_conditional_execute: false,
+                };
+                for c in rest[..end].chars() {
+                    if c == 'X' {
+                        clause.is_conditional_execute = true;
+                        continue;
+                    }
+                    clause.permissions |= PERMISSIONS
+                        .iter()
+                        .find(|(permission, _)| *permission == c)
+                        .map(|(_, bits)| *bits)
+                        .ok_or_else(bad_mode)?;
+                }
+                clauses.push(clause);
+                actions = &rest[end..];
+            }
+        }
+        Ok(Mode::Symbolic(clauses))
+    }
+
+    // The mode an entry with the mode ends up with
+    pub fn apply(&self, mode: u32, is_dir: bool) -> u32 {
+        let clauses = match self {
+            Mode::Absolute(absolute) => return (mode & !ALL_CLASSES) | absolute,
+            Mode::Symbolic(clauses) => clauses,
+        };
+        clauses.iter().fold(mode, |mode, clause| {
+            let mut permissions = clause.permissions;
+            if clause.is_conditional_execute && (is_dir || mode & 0o111 != 0) {
+                permissions |= 0o111;
+            }
+            let bits = permissions & clause.classes;
+            match clause.operator {
+                '+' => mode | bits,
+                '-' => mode & !bits,
+                _ => (mode & !clause.classes) | bits,
+            }
+        })
+    }
+}
+
+// An owner and group to give entries, like chown takes: owner, owner:group or :group, by name or
+// id. Whichever isn't given is left as it is.
+#[derive(Clone, Debug)]
+pub struct Owner {
+    uid: Option<u32>,
+    gid: Option<u32>,
+}
+
+impl Owner {
+    pub fn parse(text: &str) -> Result<Owner, String> {
+        let (user, group) = match text.split_once(':') {
+            Some((user, group)) => (user, group),
+            None => (text, ""),
+        };
+        if user.is_empty() && group.is_empty() {
+            return Err(format!("Bad owner: {text}"));
+        }
+        let uid = match user {
+            "" => None,
+            _ => Some(
+                user.parse()
+                    .ok()
+                    .or_else(|| uzers::get_user_by_name(user).map(|user| user.uid()))
+                    .ok_or_else(|| format!("No such user: {user}"))?,
+            ),
+        };
+        let gid = match group {
+            "" => None,
+            _ => Some(
+                group
+                    .parse()
+                    .ok()
+                    .or_else(|| uzers::get_group_by_name(group).map(|group| group.gid()))
+                    .ok_or_else(|| format!("No such group: {group}"))?,
+            ),
+        };
+        Ok(Owner { uid, gid })
+    }
+}
+
+// Changes the entries, and everything in the directories among them if it's recursive. Symbolic
+// links in the directories are left alone, like chmod -R does. Returns how many entries were
+// changed, and the ones that couldn't be, with why.
+pub fn change_all(
+    entries: &[PathBuf],
+    is_recursive: bool,
+    change: impl Fn(&Path) -> io::Result<()>,
+) -> (usize, Vec<(PathBuf, io::Error)>) {
+    let mut changed_count = 0;
+    let mut failures = vec![];
+    for entry in entries {
+        let mut paths = vec![Ok(entry.clone())];
+        if is_recursive && entry.is_dir() {
+            paths.extend(
+                WalkDir::new(entry)
+                    .min_depth(1)
+                    .into_iter()
+                    .filter(|descendant| {
+                        descendant
+                            .as_ref()
+                            .map_or(true, |descendant| !descendant.path_is_symlink())
+                    })
+                    .map(|descendant| match descendant {
+                        Ok(descendant) => Ok(descendant.into_path()),
+                        Err(error) => Err((
+                            error.path().unwrap_or(entry).to_path_buf(),
+                            io::Error::from(error),
+                        )),
+                    }),
+            );
+        }
+        for path in paths {
+            match path.and_then(|path| change(&path).map_err(|error| (path, error))) {
+                Ok(()) => changed_count += 1,
+                Err(failure) => failures.push(failure),
+            }
+        }
+    }
+    (changed_count, failures)
+}
+
+pub fn change_mode(path: &Path, mode: &Mode) -> io::Result<()> {
+    let metadata = std::fs::metadata(path)?;
+    let new_mode = mode.apply(metadata.permissions().mode(), metadata.is_dir());
+    std::fs::set_permissions(path, Permissions::from_mode(new_mode))
+}
+
+// Makes the file executable by whoever can read it, or if anyone can already execute it, by no one.
+// Returns whether it's executable now.
+pub fn toggle_execute(path: &Path) -> io::Result<bool> {
+    let mode = std::fs::metadata(path)?.permissions().mode();
+    let (new_mode, is_executable) = if mode & 0o111 != 0 {
+        (mode & !0o111, false)
+    } else {
+        (mode | (mode & 0o444) >> 2, true)
+    };
+    std::fs::set_permissions(path, Permissions::from_mode(new_mode))?;
+    Ok(is_executable)
+}
+
+pub fn change_owner(path: &Path, owner: &Owner) -> io::Result<()> {
+    std::os::unix::fs::chown(path, owner.uid, owner.gid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(mode: &str, to: u32, is_dir: bool) -> u32 {
+        Mode::parse(mode).unwrap().apply(to, is_dir)
+    }
+
+    #[test]
+    fn parses_octal_modes() {
+        assert_eq!(apply("755", 0o100600, false), 0o100755);
+        assert_eq!(apply("0", 0o40777, true), 0o40000);
+        assert!(Mode::parse("17777").is_err());
+        assert!(Mode::parse("789").is_err());
+    }
+
+    #[test]
+    fn parses_symbolic_modes() {
+        assert_eq!(apply("+x", 0o644, false), 0o755);
+        assert_eq!(apply("go-w", 0o666, false), 0o644);
+        assert_eq!(apply("u=rw,g=r,o=", 0o777, false), 0o640);
+        // Each operator applies up to the next
+        assert_eq!(apply("u+x-w", 0o644, false), 0o544);
+        assert_eq!(apply("a+t", 0o755, true), 0o1755);
+    }
+
+    #[test]
+    fn conditional_execute_is_for_directories_and_executables() {
+        assert_eq!(apply("a+X", 0o644, true), 0o755);
+        assert_eq!(apply("a+X", 0o744, false), 0o755);
+        assert_eq!(apply("a+X", 0o644, false), 0o644);
+    }
+
+    #[test]
+    fn rejects_bad_modes() {
+        for mode in ["", "x", "z+x", "u+q", "u,g"] {
+            assert!(Mode::parse(mode).is_err(), "{mode}");
+        }
+    }
+
+    #[test]
+    fn parses_owners_by_id() {
+        let owner = Owner::parse("1000:100").unwrap();
+        assert_eq!((owner.uid, owner.gid), (Some(1000), Some(100)));
+        let owner = Owner::parse("1000").unwrap();
+        assert_eq!((owner.uid, owner.gid), (Some(1000), None));
+        let owner = Owner::parse(":100").unwrap();
+        assert_eq!((owner.uid, owner.gid), (None, Some(100)));
+    }
+
+    #[test]
+    fn parses_owners_by_name() {
+        let owner = Owner::parse("root:0").unwrap();
+        assert_eq!((owner.uid, owner.gid), (Some(0), Some(0)));
+        // Group 0 is root on some systems, and wheel on others
+        let group = uzers::get_group_by_gid(0).unwrap();
+        let owner = Owner::parse(&format!(":{}", group.name().to_string_lossy())).unwrap();
+        assert_eq!((owner.uid, owner.gid), (None, Some(0)));
+    }
+
+    #[test]
+    fn rejects_bad_owners() {
+        assert!(Owner::parse("").is_err());
+        assert!(Owner::parse(":").is_err());
+        assert!(Owner::parse("no-such-user-here").is_err());
+        assert!(Owner::parse(":no-such-group-here").is_err());
+    }
+}
@@ -0,0 +1,513 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, FnPtr, Map, AST};
+use tracing::{debug, info};
+
+use crate::constants;
+
+const PLUGIN_DIRECTORY: &str = "plugins";
+const PLUGIN_EXTENSION: &str = "rhai";
+
+// How much a script can do in one call, so one that loops forever doesn't hang the app
+const MAX_OPERATIONS: u64 = 50_000_000;
+
+// How long a script's setup, command or key can take, however little it does, since the app waits
+// for it. One that's waiting on something slow isn't stopped by MAX_OPERATIONS.
+const RUN_TIMEOUT: Duration = Duration::from_secs(2);
+
+// What a script asks the app to do while one of its commands or keys runs. It's done after the
+// script returns.
+#[derive(Clone, Debug)]
+pub enum PluginRequest {
+    Cd(PathBuf),
+    Select(PathBuf),
+    Mark(Vec<PathBuf>),
+    ClearMarks,
+    Message(String),
+    // A command line command, like "mkdir new"
+    Command(String),
+}
+
+// What a script's commands and keys are told about the app
+pub struct PluginContext {
+    pub cwd: PathBuf,
+    pub selected: Option<PathBuf>,
+    pub marked: Vec<PathBuf>,
+}
+
+// A function in a script, to be called when its command is run, its key is pressed or a file it
+// previews is selected
+#[derive(Clone)]
+struct Callback {
+    script: usize,
+    function: FnPtr,
+}
+
+// What the scripts register as they're loaded, and what a script asks for while it runs. They're
+// kept per thread, since previewers run in the background at the same time as commands.
+#[derive(Default)]
+struct Registrations {
+    commands: Vec<(String, FnPtr)>,
+    keys: Vec<((KeyCode, KeyModifiers), FnPtr)>,
+    previewers: Vec<(String, FnPtr)>,
+}
+
+thread_local! {
+    static REGISTRATIONS: RefCell<Registrations> = RefCell::default();
+    static REQUESTS: RefCell<Vec<PluginRequest>> = const { RefCell::new(vec![]) };
+    // When the running script has to stop, and whether what it's doing has been cancelled
+    static STOP: RefCell<Option<(Instant, Arc<AtomicBool>)>> = const { RefCell::new(None) };
+}
+
+// Rhai scripts in the config directory's plugins directory that add commands, keys and
+// previewers. A script registers them as it's run when the app starts:
+//
+//     fn hello(context) { message(`Hello from ${context.cwd}`); }
+//     register_command("hello", hello);
+//     bind_key("ctrl+h", hello);
+//     register_previewer("*.csv", |path| `A CSV file: ${path}`);
+//
+// Commands and keys are given a map of the current directory (cwd), the selected entry (selected)
+// and the marked entries (marked), and can call cd, select, mark, clear_marks, message and command
+// to have the app do things. Previewers are given the file, and return its preview's text, or an
+// array of its lines.
+#[derive(Default)]
+pub struct Plugins {
+    engine: Engine,
+    scripts: Vec<(PathBuf, AST)>,
+    commands: Vec<(String, Callback)>,
+    keys: Vec<((KeyCode, KeyModifiers), Callback)>,
+    previewers: Vec<(String, Callback)>,
+}
+
+impl Plugins {
+    // Loads the scripts, and says why for the ones that couldn't be. There aren't any if there's
+    // no plugins directory.
+    pub fn load() -> (Plugins, Vec<String>) {
+        match dirs::config_dir() {
+            Some(config) => Self::load_directory(
+                &config
+                    .join(constants::CONFIG_DIRECTORY)
+                    .join(PLUGIN_DIRECTORY),
+            ),
+            None => (Self::empty(), vec![]),
+        }
+    }
+
+    fn empty() -> Plugins {
+        Plugins {
+            engine: Self::engine(),
+            ..Plugins::default()
+        }
+    }
+
+    fn load_directory(directory: &Path) -> (Plugins, Vec<String>) {
+        let mut plugins = Self::empty();
+        let mut errors = vec![];
+        let mut files: Vec<PathBuf> = match std::fs::read_dir(directory) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == PLUGIN_EXTENSION))
+                .collect(),
+            Err(_) => return (plugins, errors),
+        };
+        files.sort();
+        for file in files {
+            if let Err(error) = plugins.load_script(&file) {
+                errors.push(format!("{}: {error}", file.display()));
+            }
+        }
+        (plugins, errors)
+    }
+
+    fn load_script(&mut self, file: &Path) -> Result<(), Box<EvalAltResult>> {
+        let ast = self.engine.compile_file(file.to_path_buf())?;
+        REGISTRATIONS.take();
+        Self::with_stop(Self::timeout(), || self.engine.run_ast(&ast))
+            .map_err(Self::describe_timeout)?;
+        let registrations = REGISTRATIONS.take();
+        let script = self.scripts.len();
+        let callback = |function| Callback { script, function };
+        info!(
+            script = %file.display(),
+            commands = registrations.commands.len(),
+            keys = registrations.keys.len(),
+            previewers = registrations.previewers.len(),
+            "loaded plugin"
+        );
+        self.commands.extend(
+            registrations
+                .commands
+                .into_iter()
+                .map(|(name, function)| (name, callback(function))),
+        );
+        self.keys.extend(
+            registrations
+                .keys
+                .into_iter()
+                .map(|(key, function)| (key, callback(function))),
+        );
+        self.previewers.extend(
+            registrations
+                .previewers
+                .into_iter()
+                .map(|(mask, function)| (mask, callback(function))),
+        );
+        self.scripts.push((file.to_path_buf(), ast));
+        Ok(())
+    }
+
+    pub fn has_command(&self, name: &str) -> bool {
+        self.commands.iter().any(|(command, _)| command == name)
+    }
+
+    pub fn has_key(&self, key_event: KeyEvent) -> bool {
+        self.key_callback(key_event).is_some()
+    }
+
+    // Runs a script's command, with what was typed after it, and returns what it asked the app to
+    // do, or why it failed
+    pub fn run_command(
+        &self,
+        name: &str,
+        argument: &str,
+        context: &PluginContext,
+    ) -> Result<Vec<PluginRequest>, String> {
+        let Some((_, callback)) = self.commands.iter().find(|(command, _)| command == name) else {
+            return Err(format!("Unknown command: {name}"));
+        };
+        let mut context = Self::context_map(context);
+        context.insert("argument".into(), argument.into());
+        self.run(callback, context)
+    }
+
+    // Runs the script function the key is bound to, if it's bound to one
+    pub fn run_key(
+        &self,
+        key_event: KeyEvent,
+        context: &PluginContext,
+    ) -> Result<Vec<PluginRequest>, String> {
+        match self.key_callback(key_event) {
+            Some(callback) => self.run(callback, Self::context_map(context)),
+            None => Ok(vec![]),
+        }
+    }
+
+    // The masks of the files the scripts preview, and the previewer for each one
+    pub fn previewers(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.previewers
+            .iter()
+            .enumerate()
+            .map(|(index, (mask, _))| (mask.as_str(), index))
+    }
+
+    // The name of the script a previewer is in
+    pub fn previewer_name(&self, index: usize) -> String {
+        self.previewers
+            .get(index)
+            .and_then(|(_, callback)| self.scripts[callback.script].0.file_name())
+            .map_or_else(String::new, |name| name.to_string_lossy().to_string())
+    }
+
+    // Runs a previewer on a file and returns the lines of its preview. It's stopped at the
+    // deadline, or when it's cancelled. What it asks the app to do is ignored.
+    pub fn preview(
+        &self,
+        index: usize,
+        file: &Path,
+        deadline: Instant,
+        cancelled: Arc<AtomicBool>,
+    ) -> Result<Vec<String>, String> {
+        let Some((_, callback)) = self.previewers.get(index) else {
+            return Err("No such previewer".to_string());
+        };
+        let (script, ast) = &self.scripts[callback.script];
+        let result: Dynamic = Self::with_stop(Some((deadline, cancelled)), || {
+            callback
+                .function
+                .call(&self.engine, ast, (file.to_string_lossy().to_string(),))
+        })
+        .map_err(|error| format!("{}: {error}", script.display()))?;
+        REQUESTS.take();
+        let lines = if result.is_array() {
+            result
+                .cast::<Array>()
+                .into_iter()
+                .map(|line| line.to_string())
+                .collect()
+        } else {
+            result.to_string().lines().map(str::to_string).collect()
+        };
+        Ok(lines)
+    }
+
+    fn run(&self, callback: &Callback, context: Map) -> Result<Vec<PluginRequest>, String> {
+        let (script, ast) = &self.scripts[callback.script];
+        debug!(script = %script.display(), function = callback.function.fn_name(), "running plugin");
+        REQUESTS.take();
+        Self::with_stop(Self::timeout(), || {
+            callback
+                .function
+                .call::<Dynamic>(&self.engine, ast, (context,))
+                .map(|_| ())
+        })
+        .map_err(Self::describe_timeout)
+        .map_err(|error| format!("{}: {error}", script.display()))?;
+        Ok(REQUESTS.take())
+    }
+
+    fn key_callback(&self, key_event: KeyEvent) -> Option<&Callback> {
+        // Shift is part of a character, so it only matters for other keys
+        let modifiers = match key_event.code {
+            KeyCode::Char(_) => key_event.modifiers - KeyModifiers::SHIFT,
+            _ => key_event.modifiers,
+        };
+        self.keys
+            .iter()
+            .find(|((code, key_modifiers), _)| {
+                *code == key_event.code && *key_modifiers == modifiers
+            })
+            .map(|(_, callback)| callback)
+    }
+
+    fn context_map(context: &PluginContext) -> Map {
+        let path = |path: &Path| Dynamic::from(path.to_string_lossy().to_string());
+        let mut map = Map::new();
+        map.insert("cwd".into(), path(&context.cwd));
+        map.insert(
+            "selected".into(),
+            context.selected.as_deref().map_or(Dynamic::UNIT, path),
+        );
+        map.insert(
+            "marked".into(),
+            Dynamic::from_array(context.marked.iter().map(|marked| path(marked)).collect()),
+        );
+        map
+    }
+
+    // A deadline RUN_TIMEOUT from now, for what nothing else cancels
+    fn timeout() -> Option<(Instant, Arc<AtomicBool>)> {
+        Some((Instant::now() + RUN_TIMEOUT, Arc::default()))
+    }
+
+    // Says a script was stopped because it took too long, rather than that it was terminated
+    fn describe_timeout(error: Box<EvalAltResult>) -> Box<EvalAltResult> {
+        match &*error {
+            EvalAltResult::ErrorTerminated(_, position) => format!(
+                "Took longer than {} seconds, at {position}",
+                RUN_TIMEOUT.as_secs()
+            )
+            .into(),
+            _ => error,
+        }
+    }
+
+    // Runs a script with a deadline and a cancellation flag, which previewers have
+    fn with_stop<T>(stop: Option<(Instant, Arc<AtomicBool>)>, run: impl FnOnce() -> T) -> T {
+        STOP.set(stop);
+        let result = run();
+        STOP.set(None);
+        result
+    }
+
+    fn engine() -> Engine {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.on_progress(|_| {
+            STOP.with_borrow(|stop| match stop {
+                Some((deadline, cancelled))
+                    if Instant::now() >= *deadline || cancelled.load(Ordering::Relaxed) =>
+                {
+                    Some(Dynamic::UNIT)
+                }
+                _ => None,
+            })
+        });
+        engine.on_print(|text| info!(text, "plugin printed"));
+        engine.on_debug(|text, source, position| debug!(text, ?source, %position, "plugin debug"));
+
+        engine.register_fn("register_command", |name: &str, function: FnPtr| {
+            REGISTRATIONS.with_borrow_mut(|registrations| {
+                registrations.commands.push((name.to_string(), function))
+            });
+        });
+        engine.register_fn(
+            "bind_key",
+            |key: &str, function: FnPtr| -> Result<(), Box<EvalAltResult>> {
+                let key = parse_key(key).ok_or_else(|| format!("Bad key: {key}"))?;
+                REGISTRATIONS
+                    .with_borrow_mut(|registrations| registrations.keys.push((key, function)));
+                Ok(())
+            },
+        );
+        engine.register_fn("register_previewer", |mask: &str, function: FnPtr| {
+            REGISTRATIONS.with_borrow_mut(|registrations| {
+                registrations.previewers.push((mask.to_string(), function))
+            });
+        });
+
+        let request = |request| REQUESTS.with_borrow_mut(|requests| requests.push(request));
+        engine.register_fn("cd", move |path: &str| {
+            request(PluginRequest::Cd(PathBuf::from(path)))
+        });
+        engine.register_fn("select", move |path: &str| {
+            request(PluginRequest::Select(PathBuf::from(path)))
+        });
+        engine.register_fn("mark", move |path: &str| {
+            request(PluginRequest::Mark(vec![PathBuf::from(path)]))
+        });
+        engine.register_fn("mark", move |paths: Array| {
+            let paths = paths
+                .into_iter()
+                .map(|path| PathBuf::from(path.to_string()))
+                .collect();
+            request(PluginRequest::Mark(paths))
+        });
+        engine.register_fn("clear_marks", move || request(PluginRequest::ClearMarks));
+        engine.register_fn("message", move |text: &str| {
+            request(PluginRequest::Message(text.to_string()))
+        });
+        engine.register_fn("command", move |text: &str| {
+            request(PluginRequest::Command(text.to_string()))
+        });
+        engine
+    }
+}
+
+// Reads a key like "F5", "ctrl+h", "alt+shift+left" or "H"
+fn parse_key(text: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let mut parts: Vec<&str> = text.split('+').collect();
+    // A + by itself, or at the end, is the key
+    let key = match parts.pop()? {
+        "" if text.ends_with('+') => {
+            parts.pop();
+            "+"
+        }
+        key => key,
+    };
+    let mut modifiers = KeyModifiers::NONE;
+    for part in parts {
+        modifiers |= match part.to_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+    }
+    let mut chars = key.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => {
+            modifiers -= KeyModifiers::SHIFT;
+            KeyCode::Char(c)
+        }
+        _ => match key.to_lowercase().as_str() {
+            "enter" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "esc" => KeyCode::Esc,
+            "space" => {
+                modifiers -= KeyModifiers::SHIFT;
+                KeyCode::Char(' ')
+            }
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+        },
+    };
+    Some((code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_keys_with_modifiers() {
+        assert_eq!(
+            parse_key("ctrl+h"),
+            Some((KeyCode::Char('h'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            parse_key("Alt+Shift+Left"),
+            Some((KeyCode::Left, KeyModifiers::ALT | KeyModifiers::SHIFT))
+        );
+        assert_eq!(parse_key("F5"), Some((KeyCode::F(5), KeyModifiers::NONE)));
+        assert_eq!(
+            parse_key("enter"),
+            Some((KeyCode::Enter, KeyModifiers::NONE))
+        );
+    }
+
+    #[test]
+    fn shift_is_part_of_a_character() {
+        assert_eq!(
+            parse_key("H"),
+            Some((KeyCode::Char('H'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("shift+space"),
+            Some((KeyCode::Char(' '), KeyModifiers::NONE))
+        );
+    }
+
+    #[test]
+    fn a_plus_can_be_the_key() {
+        assert_eq!(
+            parse_key("+"),
+            Some((KeyCode::Char('+'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("ctrl++"),
+            Some((KeyCode::Char('+'), KeyModifiers::CONTROL))
+        );
+    }
+
+    #[test]
+    fn rejects_keys_it_doesnt_know() {
+        assert_eq!(parse_key(""), None);
+        assert_eq!(parse_key("hyper+a"), None);
+        assert_eq!(parse_key("pgup"), None);
+        assert_eq!(parse_key("fx"), None);
+    }
+
+    #[test]
+    fn stops_a_command_that_runs_too_long() {
+        let directory = std::env::temp_dir().join(format!("rfb-plugins-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            directory.join("spin.rhai"),
+            "fn spin(context) { loop {} }\nregister_command(\"spin\", spin);\n",
+        )
+        .unwrap();
+        let (plugins, errors) = Plugins::load_directory(&directory);
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let context = PluginContext {
+            cwd: PathBuf::from("/"),
+            selected: None,
+            marked: vec![],
+        };
+        let start = Instant::now();
+        assert!(plugins.run_command("spin", "", &context).is_err());
+        assert!(start.elapsed() < RUN_TIMEOUT + Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,152 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::process::Command;
+
+use crate::app::file_mask::FileMask;
+use crate::app::plugins::Plugins;
+use crate::util;
+
+// A program whose output previews the files whose names match a mask, like ranger's scope.sh
+// does, for files like PDFs that bat, pdftotext or exiftool show better. {} in its arguments is
+// replaced by the file, which comes after them otherwise. A plugin's previewer is a function in
+// its script instead, and its command is the script's name.
+#[derive(Clone)]
+pub struct Previewer {
+    mask: FileMask,
+    command: String,
+    script: Option<(Arc<Plugins>, usize)>,
+}
+
+impl Previewer {
+    pub fn new(pattern: &str, command: &str) -> Result<Previewer, String> {
+        if command.trim().is_empty() {
+            return Err(format!("No command to preview {pattern} with"));
+        }
+        Ok(Previewer {
+            mask: FileMask::new(pattern)?,
+            command: command.trim().to_string(),
+            script: None,
+        })
+    }
+
+    pub fn from_script(
+        pattern: &str,
+        plugins: Arc<Plugins>,
+        index: usize,
+    ) -> Result<Previewer, String> {
+        Ok(Previewer {
+            mask: FileMask::new(pattern)?,
+            command: plugins.previewer_name(index),
+            script: Some((plugins, index)),
+        })
+    }
+
+    pub fn pattern(&self) -> &str {
+        self.mask.pattern()
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    pub fn is_match(&self, file: &Path) -> bool {
+        self.mask.is_match(&util::entry_name(file))
+    }
+
+    // Runs the program on the file and returns the lines it wrote, or why it couldn't. It's killed
+    // if it takes longer than the timeout, or if what's waiting for it is dropped, like a preview
+    // load that's been cancelled.
+    pub async fn run(&self, file: &Path, timeout: Duration) -> Result<Vec<String>, String> {
+        if let Some((plugins, index)) = &self.script {
+            return self.run_script(plugins, *index, file, timeout).await;
+        }
+        let mut words = self.command.split_whitespace();
+        let Some(program) = words.next() else {
+            return Err(format!("No command to preview {} with", self.pattern()));
+        };
+        let mut command = Command::new(program);
+        let mut has_file = false;
+        for word in words {
+            if word == "{}" {
+                command.arg(file);
+                has_file = true;
+            } else {
+                command.arg(word);
+            }
+        }
+        if !has_file {
+            command.arg(file);
+        }
+        let output = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .output();
+        let output = match tokio::time::timeout(timeout, output).await {
+            Ok(Ok(output)) => output,
+            Ok(Err(error)) => return Err(format!("Can't run {program}: {error}")),
+            Err(_) => return Err(took_too_long(program, timeout)),
+        };
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            let error = error.lines().next().unwrap_or_default();
+            return Err(format!("{program} exited with {}: {error}", output.status));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+
+    // Runs a plugin's previewer in the background, since it's a script that could take a while.
+    // It's stopped at the timeout, or when what's waiting for it is dropped.
+    async fn run_script(
+        &self,
+        plugins: &Arc<Plugins>,
+        index: usize,
+        file: &Path,
+        timeout: Duration,
+    ) -> Result<Vec<String>, String> {
+        let cancelled = Cancelled::default();
+        let plugins = plugins.clone();
+        let file = file.to_path_buf();
+        let deadline = Instant::now() + timeout;
+        let flag = cancelled.0.clone();
+        let result =
+            tokio::task::spawn_blocking(move || plugins.preview(index, &file, deadline, flag))
+                .await
+                .map_err(|error| error.to_string())?;
+        if result.is_err() && Instant::now() >= deadline {
+            return Err(took_too_long(&self.command, timeout));
+        }
+        result
+    }
+}
+
+// Tells a plugin's previewer to stop when it's dropped
+#[derive(Default)]
+struct Cancelled(Arc<AtomicBool>);
+
+impl Drop for Cancelled {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+fn took_too_long(name: &str, timeout: Duration) -> String {
+    let seconds = timeout.as_secs();
+    format!(
+        "{name} took longer than {seconds} second{}",
+        if seconds != 1 { "s" } else { "" }
+    )
+}
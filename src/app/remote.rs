@@ -0,0 +1,215 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::app::fs_provider::{self, FsProvider};
+#[cfg(feature = "s3")]
+use crate::app::s3::S3Fs;
+use crate::app::sftp::SftpFs;
+
+// The kinds of location that are somewhere else, and are mounted to be browsed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scheme {
+    // Another computer, reached over SFTP through ssh. Its files can be looked at, and copied
+    // from, but not changed.
+    Sftp,
+    // An S3 bucket, reached with whatever AWS credentials are set up. Its objects can be looked
+    // at, and copied from, but not changed.
+    S3,
+}
+
+impl Scheme {
+    fn prefix(self) -> &'static str {
+        match self {
+            Scheme::Sftp => "sftp://",
+            Scheme::S3 => "s3://",
+        }
+    }
+}
+
+// A location somewhere else, as sftp://[user@]host[:port]/path or s3://bucket/prefix
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    scheme: Scheme,
+    // The user and host, or the bucket, and the port if it isn't the usual one
+    destination: String,
+    port: Option<String>,
+    path: PathBuf,
+}
+
+impl Location {
+    pub fn parse(url: &str) -> Option<Location> {
+        let (scheme, rest) = [Scheme::Sftp, Scheme::S3]
+            .into_iter()
+            .find_map(|scheme| Some((scheme, url.strip_prefix(scheme.prefix())?)))?;
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+        let (destination, port) = match authority.rsplit_once(':') {
+            Some((destination, port))
+                if scheme == Scheme::Sftp && port.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                (destination, Some(port.to_string()))
+            }
+            _ => (authority, None),
+        };
+        if destination.is_empty() || destination.ends_with('@') {
+            return None;
+        }
+        // A user or host that starts with a dash would be taken for an option
+        if destination.split('@').any(|part| part.starts_with('-')) {
+            return None;
+        }
+        Some(Location {
+            scheme,
+            destination: destination.to_string(),
+            port,
+            path: PathBuf::from(path),
+        })
+    }
+
+    // The URL of the top of the host's file system or of the bucket, which is how its mount is
+    // shown
+    pub fn root(&self) -> PathBuf {
+        let prefix = self.scheme.prefix();
+        match &self.port {
+            Some(port) => PathBuf::from(format!("{prefix}{}:{port}", self.destination)),
+            None => PathBuf::from(format!("{prefix}{}", self.destination)),
+        }
+    }
+
+    // The location as it's shown, with the root's URL in front of the path
+    pub fn display_path(&self) -> PathBuf {
+        self.root()
+            .join(self.path.strip_prefix("/").unwrap_or(&self.path))
+    }
+
+    // Whether connecting to it may ask for a password or a passphrase, which is typed on the
+    // terminal
+    pub fn needs_terminal(&self) -> bool {
+        self.scheme == Scheme::Sftp
+    }
+}
+
+// Shows the top of the host's file system, or the bucket, in a new directory under the temporary
+// directory, and returns the directory
+pub async fn connect(location: &Location) -> io::Result<PathBuf> {
+    let name: String = location
+        .root()
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let mount = mount_root().join(name);
+    fs::create_dir_all(&mount)?;
+    let connected: io::Result<Arc<dyn FsProvider>> = match location.scheme {
+        Scheme::Sftp => SftpFs::connect(&location.destination, location.port.as_deref(), &mount)
+            .await
+            .map(|sftp_fs| Arc::new(sftp_fs) as _),
+        #[cfg(feature = "s3")]
+        Scheme::S3 => S3Fs::connect(&location.destination, &mount)
+            .await
+            .map(|s3_fs| Arc::new(s3_fs) as _),
+        #[cfg(not(feature = "s3"))]
+        Scheme::S3 => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "rfb was built without S3 support, which the s3 feature adds",
+        )),
+    };
+    match connected {
+        Ok(provider) => {
+            fs_provider::mount(mount.clone(), provider);
+            Ok(mount)
+        }
+        Err(error) => {
+            let _ = fs::remove_dir(&mount);
+            Err(error)
+        }
+    }
+}
+
+// Removes what's been downloaded from other computers and buckets
+pub fn remove_downloaded() {
+    // The current directory can't be removed on some systems
+    if std::env::current_dir().is_ok_and(|cwd| cwd.starts_with(mount_root())) {
+        let _ = std::env::set_current_dir(std::env::temp_dir());
+    }
+    let _ = fs::remove_dir_all(mount_root());
+}
+
+// Where locations are mounted, which is different for each running instance
+fn mount_root() -> PathBuf {
+    std::env::temp_dir().join(format!("rfb-remote-{}", std::process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(scheme: Scheme, destination: &str, port: Option<&str>, path: &str) -> Location {
+        Location {
+            scheme,
+            destination: destination.to_string(),
+            port: port.map(str::to_string),
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn parses_sftp_urls() {
+        assert_eq!(
+            Location::parse("sftp://me@example.com:2222/home/me"),
+            Some(location(
+                Scheme::Sftp,
+                "me@example.com",
+                Some("2222"),
+                "/home/me"
+            ))
+        );
+        assert_eq!(
+            Location::parse("sftp://example.com"),
+            Some(location(Scheme::Sftp, "example.com", None, "/"))
+        );
+    }
+
+    #[test]
+    fn parses_s3_urls() {
+        assert_eq!(
+            Location::parse("s3://bucket/photos/2024"),
+            Some(location(Scheme::S3, "bucket", None, "/photos/2024"))
+        );
+    }
+
+    #[test]
+    fn rejects_what_isnt_a_location() {
+        for url in [
+            "/home/me",
+            "http://example.com",
+            "sftp://",
+            "sftp://me@/",
+            "s3:///x",
+            "sftp://-oProxyCommand=evil",
+            "sftp://me@-oProxyCommand=evil/",
+            "sftp://-me@example.com",
+        ] {
+            assert_eq!(Location::parse(url), None, "{url}");
+        }
+    }
+
+    #[test]
+    fn shows_the_root_and_the_path() {
+        let location = Location::parse("sftp://me@example.com:2222/home/me").unwrap();
+        assert_eq!(location.root(), PathBuf::from("sftp://me@example.com:2222"));
+        assert_eq!(
+            location.display_path(),
+            PathBuf::from("sftp://me@example.com:2222/home/me")
+        );
+    }
+}
@@ -0,0 +1,333 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use aws_config::meta::region::RegionProviderChain;
+use aws_config::BehaviorVersion;
+use aws_sdk_s3::config::Region;
+use aws_sdk_s3::error::{DisplayErrorContext, ProvideErrorMetadata};
+use aws_sdk_s3::primitives::DateTime;
+use aws_sdk_s3::Client;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tokio::io::AsyncWriteExt;
+
+use crate::app::fs_provider::{EntryMetadata, FsProvider, ListingEntry};
+use crate::util;
+
+// Where buckets are looked for when nothing says where
+const DEFAULT_REGION: &str = "us-east-1";
+
+// An S3 bucket, shown under a local directory. Keys' prefixes, up to each slash, are its
+// directories, which are made there as they're found, so the app can go into them. Its objects
+// are read over the network, and downloaded when something needs one on disk.
+pub(crate) struct S3Fs {
+    client: Client,
+    bucket: String,
+    mount: PathBuf,
+}
+
+impl S3Fs {
+    // Uses whatever AWS credentials are set up, the way the AWS command-line tools do
+    pub async fn connect(bucket: &str, mount: &Path) -> io::Result<S3Fs> {
+        let config = aws_config::defaults(BehaviorVersion::latest())
+            .region(RegionProviderChain::default_provider().or_else(DEFAULT_REGION))
+            .load()
+            .await;
+        let client = Client::new(&config);
+        // A bucket is only answered for in its own region. Without permission to ask where that
+        // is, it's taken to be the one that's set up.
+        let location = client.get_bucket_location().bucket(bucket).send().await;
+        let client = match location {
+            Ok(location) => {
+                let region = match location
+                    .location_constraint()
+                    .map(|constraint| constraint.as_str())
+                {
+                    None | Some("") => DEFAULT_REGION,
+                    Some("EU") => "eu-west-1",
+                    Some(region) => region,
+                };
+                let config = aws_sdk_s3::config::Builder::from(&config)
+                    .region(Region::new(region.to_string()))
+                    .build();
+                Client::from_conf(config)
+            }
+            Err(_) => client,
+        };
+        client
+            .head_bucket()
+            .bucket(bucket)
+            .send()
+            .await
+            .map_err(s3_error)?;
+        Ok(S3Fs {
+            client,
+            bucket: bucket.to_string(),
+            mount: mount.to_path_buf(),
+        })
+    }
+
+    // The key of the object a path under the mount is
+    fn key(&self, path: &Path) -> io::Result<String> {
+        let relative = path.strip_prefix(&self.mount).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} isn't in the bucket", path.display()),
+            )
+        })?;
+        Ok(key(relative))
+    }
+
+    // What the keys of the objects in a directory start with
+    fn prefix(&self, path: &Path) -> io::Result<String> {
+        let key = self.key(path)?;
+        Ok(if key.is_empty() { key } else { key + "/" })
+    }
+
+    async fn get_object(
+        &self,
+        path: &Path,
+        range: Option<String>,
+    ) -> io::Result<aws_sdk_s3::primitives::ByteStream> {
+        let object = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.key(path)?)
+            .set_range(range)
+            .send()
+            .await
+            .map_err(s3_error)?;
+        Ok(object.body)
+    }
+}
+
+impl FsProvider for S3Fs {
+    fn read_dir<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<Vec<ListingEntry>>> {
+        async move {
+            let prefix = self.prefix(path)?;
+            let mut directories = HashSet::new();
+            let mut entries = vec![];
+            let mut continuation_token = None;
+            loop {
+                let listing = self
+                    .client
+                    .list_objects_v2()
+                    .bucket(&self.bucket)
+                    .prefix(&prefix)
+                    .delimiter("/")
+                    .set_continuation_token(continuation_token)
+                    .send()
+                    .await
+                    .map_err(s3_error)?;
+                for common_prefix in listing.common_prefixes() {
+                    let Some(name) = common_prefix
+                        .prefix()
+                        .and_then(|common_prefix| child_name(&prefix, common_prefix))
+                    else {
+                        continue;
+                    };
+                    let entry = path.join(name);
+                    tokio::fs::create_dir_all(&entry).await?;
+                    let metadata = directory_metadata(&entry);
+                    entries.push((entry, Some(metadata)));
+                    directories.insert(name.to_string());
+                }
+                for object in listing.contents() {
+                    let Some(name) = object.key().and_then(|key| child_name(&prefix, key)) else {
+                        continue;
+                    };
+                    let entry = path.join(name);
+                    let metadata = object_metadata(&entry, object.size(), object.last_modified());
+                    entries.push((entry, Some(metadata)));
+                }
+                continuation_token = listing.next_continuation_token().map(str::to_string);
+                if continuation_token.is_none() {
+                    break;
+                }
+            }
+            // A directory hides an object with the same name, which can't be gone to
+            entries.retain(|(entry, metadata)| {
+                metadata.is_some_and(|metadata| metadata.is_dir)
+                    || !directories.contains(&util::entry_name(entry))
+            });
+            Ok(entries)
+        }
+        .boxed()
+    }
+
+    // An object is looked for first, then a directory. Looking up an entry makes the directory it
+    // is, or is in, so it can be gone to.
+    fn metadata<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<EntryMetadata>> {
+        async move {
+            let key = self.key(path)?;
+            let metadata = if key.is_empty() {
+                directory_metadata(path)
+            } else {
+                let head = self
+                    .client
+                    .head_object()
+                    .bucket(&self.bucket)
+                    .key(&key)
+                    .send()
+                    .await;
+                match head {
+                    Ok(head) => object_metadata(path, head.content_length(), head.last_modified()),
+                    Err(error)
+                        if error
+                            .as_service_error()
+                            .is_some_and(|error| error.is_not_found()) =>
+                    {
+                        let listing = self
+                            .client
+                            .list_objects_v2()
+                            .bucket(&self.bucket)
+                            .prefix(key + "/")
+                            .max_keys(1)
+                            .send()
+                            .await
+                            .map_err(s3_error)?;
+                        if listing.key_count().unwrap_or(0) == 0 {
+                            return Err(io::Error::new(
+                                io::ErrorKind::NotFound,
+                                format!("{} isn't in the bucket", util::entry_name(path)),
+                            ));
+                        }
+                        directory_metadata(path)
+                    }
+                    Err(error) => return Err(s3_error(error)),
+                }
+            };
+            let directory = if metadata.is_dir {
+                Some(path)
+            } else {
+                path.parent()
+            };
+            if let Some(directory) = directory {
+                tokio::fs::create_dir_all(directory).await?;
+            }
+            Ok(metadata)
+        }
+        .boxed()
+    }
+
+    // Only the part that's wanted is asked for
+    fn read<'a>(
+        &'a self,
+        path: &'a Path,
+        max_size: Option<u64>,
+    ) -> BoxFuture<'a, io::Result<Vec<u8>>> {
+        async move {
+            if max_size == Some(0) {
+                return Ok(vec![]);
+            }
+            let range = max_size.map(|max_size| format!("bytes=0-{}", max_size - 1));
+            match self.get_object(path, range).await {
+                Ok(body) => Ok(body.collect().await?.to_vec()),
+                // There's no range of an empty object
+                Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => Ok(vec![]),
+                Err(error) => Err(error),
+            }
+        }
+        .boxed()
+    }
+
+    // The object's written as it's received
+    fn download<'a>(
+        &'a self,
+        path: &'a Path,
+        file: &'a mut tokio::fs::File,
+    ) -> BoxFuture<'a, io::Result<()>> {
+        async move {
+            let mut body = self.get_object(path, None).await?;
+            while let Some(bytes) = body.try_next().await? {
+                file.write_all(&bytes).await?;
+            }
+            file.flush().await
+        }
+        .boxed()
+    }
+}
+
+// The key of an object, from its path in the bucket
+fn key(relative: &Path) -> String {
+    relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// The name in a directory of a key, or of a prefix, in it. Names that can't be in a directory,
+// like an empty one from a doubled slash, are skipped.
+fn child_name<'a>(prefix: &str, key: &'a str) -> Option<&'a str> {
+    let name = key.strip_prefix(prefix)?;
+    let name = name.strip_suffix('/').unwrap_or(name);
+    (!name.is_empty() && name != "." && name != ".." && !name.contains('/')).then_some(name)
+}
+
+fn directory_metadata(directory: &Path) -> EntryMetadata {
+    EntryMetadata {
+        is_dir: true,
+        is_hidden: util::is_hidden(directory, None),
+        ..EntryMetadata::default()
+    }
+}
+
+fn object_metadata(path: &Path, size: Option<i64>, modified: Option<&DateTime>) -> EntryMetadata {
+    EntryMetadata {
+        is_file: true,
+        is_hidden: util::is_hidden(path, None),
+        len: size.unwrap_or(0).max(0) as u64,
+        modified: modified.and_then(|modified| SystemTime::try_from(*modified).ok()),
+        ..EntryMetadata::default()
+    }
+}
+
+// What went wrong, as S3 put it, or why it couldn't be reached
+fn s3_error<E: ProvideErrorMetadata + std::error::Error>(error: E) -> io::Error {
+    let kind = match error.code() {
+        Some("NoSuchKey" | "NoSuchBucket" | "NotFound") => io::ErrorKind::NotFound,
+        Some("AccessDenied" | "Forbidden") => io::ErrorKind::PermissionDenied,
+        Some("InvalidRange") => io::ErrorKind::UnexpectedEof,
+        _ => io::ErrorKind::Other,
+    };
+    let message = match error.message() {
+        Some(message) => message.to_string(),
+        None => DisplayErrorContext(&error).to_string(),
+    };
+    io::Error::new(kind, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn makes_keys_from_paths() {
+        assert_eq!(key(Path::new("photos/2024/a.jpg")), "photos/2024/a.jpg");
+        assert_eq!(key(Path::new("")), "");
+    }
+
+    #[test]
+    fn names_what_is_in_a_directory() {
+        assert_eq!(child_name("photos/", "photos/a.jpg"), Some("a.jpg"));
+        assert_eq!(child_name("photos/", "photos/2024/"), Some("2024"));
+        assert_eq!(child_name("", "notes.txt"), Some("notes.txt"));
+        // The object some tools make to stand for the directory itself
+        assert_eq!(child_name("photos/", "photos/"), None);
+        assert_eq!(child_name("photos/", "photos//"), None);
+        assert_eq!(child_name("photos/", "photos/../"), None);
+        assert_eq!(child_name("photos/", "music/a.mp3"), None);
+    }
+}
@@ -0,0 +1,153 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobMatcher};
+use probably_binary::{EntryType, FileType};
+use regex::Regex;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+use crate::app::fs_provider;
+use crate::app::{components, tasks};
+use crate::tui::Event;
+
+// The most matching lines that are kept from any one file
+const MAX_LINES_PER_FILE: usize = 100;
+
+// Something a search found. Content searches also find the line, and its text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub snippet: Option<String>,
+}
+
+// Matches file names against a glob if the pattern has glob characters in it,
+// otherwise against a substring. Either way, case is ignored.
+pub enum NameMatcher {
+    Glob(GlobMatcher),
+    Substring(String),
+}
+
+impl NameMatcher {
+    pub fn new(pattern: &str) -> Result<NameMatcher, String> {
+        if pattern.contains(['*', '?', '[', '{']) {
+            GlobBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map(|glob| NameMatcher::Glob(glob.compile_matcher()))
+                .map_err(|error| error.to_string())
+        } else {
+            Ok(NameMatcher::Substring(pattern.to_lowercase()))
+        }
+    }
+
+    pub fn is_match(&self, path: &Path) -> bool {
+        let Some(file_name) = path.file_name() else {
+            return false;
+        };
+        match self {
+            NameMatcher::Glob(glob) => glob.is_match(file_name),
+            NameMatcher::Substring(substring) => file_name
+                .to_string_lossy()
+                .to_lowercase()
+                .contains(substring),
+        }
+    }
+}
+
+// Finds the paths under root whose names match, in the background. The results are sent as
+// events, tagged with the search id, as they're found.
+pub fn spawn_find(
+    root: PathBuf,
+    matcher: NameMatcher,
+    show_hidden: bool,
+    search_id: u32,
+    event_tx: UnboundedSender<Event>,
+    cancellation_token: CancellationToken,
+) {
+    debug!(search_id, root = %root.display(), "find started");
+    let task = tasks::start();
+    tokio::task::spawn_blocking(move || {
+        let _task = task;
+        components::walk_directory(&root, show_hidden, &cancellation_token, |batch| {
+            let results: Vec<SearchResult> = batch
+                .into_iter()
+                .filter(|path| matcher.is_match(path))
+                .map(|path| SearchResult {
+                    path,
+                    line: None,
+                    snippet: None,
+                })
+                .collect();
+            if !results.is_empty() {
+                // The receiver is only gone if the app is shutting down
+                let _ = event_tx.send(Event::SearchResults(search_id, results));
+            }
+        });
+        let _ = event_tx.send(Event::SearchFinished(search_id));
+    });
+}
+
+// Searches the text files under root for lines that match the regex, in the background. The
+// results are sent as events, tagged with the search id, as they're found.
+pub fn spawn_grep(
+    root: PathBuf,
+    regex: Regex,
+    show_hidden: bool,
+    search_id: u32,
+    event_tx: UnboundedSender<Event>,
+    cancellation_token: CancellationToken,
+) {
+    debug!(search_id, root = %root.display(), regex = %regex, "grep started");
+    let task = tasks::start();
+    tokio::task::spawn_blocking(move || {
+        let _task = task;
+        components::walk_directory(&root, show_hidden, &cancellation_token, |batch| {
+            for path in batch {
+                if cancellation_token.is_cancelled() {
+                    return;
+                }
+                let results = grep_file(&path, &regex);
+                if !results.is_empty() {
+                    // The receiver is only gone if the app is shutting down
+                    let _ = event_tx.send(Event::SearchResults(search_id, results));
+                }
+            }
+        });
+        let _ = event_tx.send(Event::SearchFinished(search_id));
+    });
+}
+
+// Returns the lines in a file that match the regex. Anything that isn't a readable text file
+// has none. The file is read from whichever file system it's on, which blocks.
+fn grep_file(path: &Path, regex: &Regex) -> Vec<SearchResult> {
+    let provider = fs_provider::provider(path);
+    let bytes = tokio::runtime::Handle::current().block_on(async {
+        match provider.entry_type(path).await? {
+            EntryType::File(FileType::Text) => provider.read(path, None).await.map(Some),
+            _ => Ok(None),
+        }
+    });
+    let Ok(Some(bytes)) = bytes else {
+        return vec![];
+    };
+    let Ok(text) = String::from_utf8(bytes) else {
+        return vec![];
+    };
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| regex.is_match(line))
+        .take(MAX_LINES_PER_FILE)
+        .map(|(index, line)| SearchResult {
+            path: path.to_path_buf(),
+            line: Some(index + 1),
+            snippet: Some(line.trim().to_string()),
+        })
+        .collect()
+}
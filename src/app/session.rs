@@ -0,0 +1,98 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io;
+use std::path::PathBuf;
+
+use crate::app::components::{ListingOptions, SortKey};
+use crate::app::settings::Settings;
+use crate::constants;
+
+const SESSION_FILE: &str = "session";
+
+// Where the app was left when it last quit, kept in a file in the config directory as
+// "name = value" lines, so the next run can start there
+pub struct Session {
+    pub directory: PathBuf,
+    pub selection: Option<PathBuf>,
+    pub listing_options: ListingOptions,
+    pub split_percent: u16,
+}
+
+impl Session {
+    // Reads the session file. There's no session if it doesn't exist yet or doesn't say what the
+    // directory was, and anything else that's missing or can't be read keeps its default.
+    pub fn load() -> io::Result<Option<Session>> {
+        let contents = match std::fs::read_to_string(Self::file()?) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error),
+        };
+        let mut directory = None;
+        let mut session = Session {
+            directory: PathBuf::new(),
+            selection: None,
+            listing_options: ListingOptions::default(),
+            split_percent: constants::DEFAULT_SPLIT_PERCENT,
+        };
+        for line in contents.lines() {
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match name.trim() {
+                "directory" => directory = Some(PathBuf::from(value)),
+                "selection" => session.selection = Some(PathBuf::from(value)),
+                "sort" => {
+                    if let Some(sort_key) = SortKey::from_name(value) {
+                        session.listing_options.sort_key = sort_key;
+                    }
+                }
+                "show_hidden" => {
+                    if let Ok(show_hidden) = value.parse() {
+                        session.listing_options.show_hidden = show_hidden;
+                    }
+                }
+                "split_percent" => {
+                    if let Ok(split_percent) = value.parse::<u16>() {
+                        session.split_percent = Settings::clamp_split_percent(split_percent);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(directory.map(|directory| Session {
+            directory,
+            ..session
+        }))
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let file = Self::file()?;
+        if let Some(directory) = file.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        let mut contents = format!("directory = {}\n", self.directory.to_string_lossy());
+        if let Some(selection) = &self.selection {
+            contents.push_str(&format!("selection = {}\n", selection.to_string_lossy()));
+        }
+        contents.push_str(&format!(
+            "sort = {}\nshow_hidden = {}\nsplit_percent = {}\n",
+            self.listing_options.sort_key.name(),
+            self.listing_options.show_hidden,
+            self.split_percent
+        ));
+        std::fs::write(file, contents)
+    }
+
+    fn file() -> io::Result<PathBuf> {
+        dirs::config_dir()
+            .map(|config| config.join(constants::CONFIG_DIRECTORY).join(SESSION_FILE))
+            .ok_or(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Can't find config directory",
+            ))
+    }
+}
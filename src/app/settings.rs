@@ -0,0 +1,195 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::app::components::wheel::WheelScroll;
+use crate::app::opener::OpenFile;
+use crate::app::previewer::Previewer;
+use crate::constants;
+
+const SETTINGS_FILE: &str = "settings";
+
+// Settings that are changed in the app and remembered between runs, kept in a file in the config
+// directory as "name = value" lines. Some, like what Enter does to files, are only changed by
+// editing the file.
+pub struct Settings {
+    // How much of the main area's width, in percent, the directory pane takes
+    pub split_percent: u16,
+    // Whether the app starts where the last session left off, even without --restore
+    pub restore_session: bool,
+    // Whether the directories' views are saved, so they're remembered between runs
+    pub remember_views: bool,
+    // How many lines a mouse wheel event scrolls, and whether spinning the wheel fast scrolls
+    // further
+    pub wheel_lines: usize,
+    pub wheel_acceleration: bool,
+    // What Enter does to a file, unless there's an "open_file.<extension>" line for its extension,
+    // and the programs the open with menu offers, as a comma-separated list
+    pub open_file: OpenFile,
+    pub open_file_by_extension: Vec<(String, OpenFile)>,
+    pub open_with: Vec<String>,
+    // The programs that preview files whose names match their masks, from "previewer.<mask>"
+    // lines, and how long they have, in seconds
+    pub previewers: Vec<Previewer>,
+    pub previewer_timeout: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            split_percent: constants::DEFAULT_SPLIT_PERCENT,
+            restore_session: false,
+            remember_views: false,
+            wheel_lines: constants::DEFAULT_WHEEL_LINES,
+            wheel_acceleration: true,
+            open_file: OpenFile::Nothing,
+            open_file_by_extension: vec![],
+            open_with: vec![],
+            previewers: vec![],
+            previewer_timeout: constants::DEFAULT_PREVIEWER_TIMEOUT,
+        }
+    }
+}
+
+impl Settings {
+    // Reads the settings file. Settings that aren't in it, or can't be read, keep their defaults.
+    pub fn load() -> io::Result<Settings> {
+        let contents = match std::fs::read_to_string(Self::file()?) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+        let mut settings = Settings::default();
+        for line in contents.lines() {
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            match name.trim() {
+                "split_percent" => {
+                    if let Ok(split_percent) = value.trim().parse::<u16>() {
+                        settings.split_percent = Self::clamp_split_percent(split_percent);
+                    }
+                }
+                "restore_session" => {
+                    if let Ok(restore_session) = value.trim().parse() {
+                        settings.restore_session = restore_session;
+                    }
+                }
+                "remember_views" => {
+                    if let Ok(remember_views) = value.trim().parse() {
+                        settings.remember_views = remember_views;
+                    }
+                }
+                "wheel_lines" => {
+                    if let Ok(wheel_lines) = value.trim().parse::<usize>() {
+                        settings.wheel_lines = wheel_lines.clamp(1, constants::MAX_WHEEL_LINES);
+                    }
+                }
+                "wheel_acceleration" => {
+                    if let Ok(wheel_acceleration) = value.trim().parse() {
+                        settings.wheel_acceleration = wheel_acceleration;
+                    }
+                }
+                "open_file" => {
+                    if let Some(open_file) = OpenFile::from_name(value.trim()) {
+                        settings.open_file = open_file;
+                    }
+                }
+                "open_with" => {
+                    settings.open_with = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|program| !program.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                }
+                "previewer_timeout" => {
+                    if let Ok(seconds) = value.trim().parse::<u64>() {
+                        settings.previewer_timeout = Duration::from_secs(seconds.max(1));
+                    }
+                }
+                name => {
+                    if let Some(extension) = name.strip_prefix("open_file.") {
+                        if let Some(open_file) = OpenFile::from_name(value.trim()) {
+                            settings
+                                .open_file_by_extension
+                                .push((extension.to_lowercase(), open_file));
+                        }
+                    } else if let Some(mask) = name.strip_prefix("previewer.") {
+                        if let Ok(previewer) = Previewer::new(mask, value) {
+                            settings.previewers.push(previewer);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(settings)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let file = Self::file()?;
+        if let Some(directory) = file.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        let mut contents = format!(
+            "split_percent = {}\nrestore_session = {}\nremember_views = {}\nwheel_lines = {}\n\
+             wheel_acceleration = {}\nopen_file = {}\nopen_with = {}\npreviewer_timeout = {}\n",
+            self.split_percent,
+            self.restore_session,
+            self.remember_views,
+            self.wheel_lines,
+            self.wheel_acceleration,
+            self.open_file.name(),
+            self.open_with.join(", "),
+            self.previewer_timeout.as_secs()
+        );
+        for (extension, open_file) in &self.open_file_by_extension {
+            contents.push_str(&format!("open_file.{extension} = {}\n", open_file.name()));
+        }
+        for previewer in &self.previewers {
+            contents.push_str(&format!(
+                "previewer.{} = {}\n",
+                previewer.pattern(),
+                previewer.command()
+            ));
+        }
+        std::fs::write(file, contents)
+    }
+
+    // Keeps both panes wide enough to be useful
+    pub fn clamp_split_percent(split_percent: u16) -> u16 {
+        split_percent.clamp(
+            constants::MIN_SPLIT_PERCENT,
+            100 - constants::MIN_SPLIT_PERCENT,
+        )
+    }
+
+    // What Enter does to the file, going by its extension, which is matched without regard to case
+    pub fn open_file_for(&self, path: &Path) -> OpenFile {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        self.open_file_by_extension
+            .iter()
+            .find(|(name, _)| Some(name) == extension.as_ref())
+            .map_or(self.open_file, |(_, open_file)| *open_file)
+    }
+
+    pub fn wheel_scroll(&self) -> WheelScroll {
+        WheelScroll::new(self.wheel_lines, self.wheel_acceleration)
+    }
+
+    fn file() -> io::Result<PathBuf> {
+        dirs::config_dir()
+            .map(|config| config.join(constants::CONFIG_DIRECTORY).join(SETTINGS_FILE))
+            .ok_or(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Can't find config directory",
+            ))
+    }
+}
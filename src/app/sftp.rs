@@ -0,0 +1,794 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::process::Child;
+use tokio::sync::oneshot;
+
+use crate::app::fs_provider::{EntryMetadata, FsProvider, ListingEntry};
+use crate::util;
+
+// The version of the protocol that's spoken, which every server understands
+const VERSION: u32 = 3;
+
+// How much of a file is asked for at a time
+const READ_SIZE: u32 = 32 * 1024;
+
+// Packets bigger than this aren't from a server that's working properly
+const MAX_PACKET_SIZE: usize = 1024 * 1024;
+
+// The kinds of packet
+const FXP_INIT: u8 = 1;
+const FXP_VERSION: u8 = 2;
+const FXP_OPEN: u8 = 3;
+const FXP_CLOSE: u8 = 4;
+const FXP_READ: u8 = 5;
+const FXP_OPENDIR: u8 = 11;
+const FXP_READDIR: u8 = 12;
+const FXP_STAT: u8 = 17;
+const FXP_STATUS: u8 = 101;
+const FXP_HANDLE: u8 = 102;
+const FXP_DATA: u8 = 103;
+const FXP_NAME: u8 = 104;
+const FXP_ATTRS: u8 = 105;
+
+// The statuses that mean something here
+const FX_EOF: u32 = 1;
+const FX_NO_SUCH_FILE: u32 = 2;
+const FX_PERMISSION_DENIED: u32 = 3;
+
+// Which of an entry's attributes are sent
+const ATTR_SIZE: u32 = 0x1;
+const ATTR_UIDGID: u32 = 0x2;
+const ATTR_PERMISSIONS: u32 = 0x4;
+const ATTR_ACMODTIME: u32 = 0x8;
+const ATTR_EXTENDED: u32 = 0x8000_0000;
+
+const OPEN_READ: u32 = 0x1;
+
+// The kind of entry, in its permissions
+const S_IFMT: u32 = 0o170000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFREG: u32 = 0o100000;
+const S_IFLNK: u32 = 0o120000;
+
+// A request's fields, after its kind and id
+#[derive(Default)]
+struct Fields(Vec<u8>);
+
+impl Fields {
+    fn u32(mut self, value: u32) -> Fields {
+        self.0.extend(value.to_be_bytes());
+        self
+    }
+
+    fn u64(mut self, value: u64) -> Fields {
+        self.0.extend(value.to_be_bytes());
+        self
+    }
+
+    fn string(self, value: &[u8]) -> Fields {
+        let mut fields = self.u32(value.len() as u32);
+        fields.0.extend(value);
+        fields
+    }
+}
+
+// Reads a response's fields in order
+struct FieldReader<'a>(&'a [u8]);
+
+impl<'a> FieldReader<'a> {
+    fn bytes(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(invalid_data("The server's answer was cut short"));
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_be_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> io::Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.bytes(len)
+    }
+
+    // Only what the app shows is kept
+    fn attributes(&mut self) -> io::Result<Attributes> {
+        let flags = self.u32()?;
+        let mut attributes = Attributes::default();
+        if flags & ATTR_SIZE != 0 {
+            attributes.size = Some(self.u64()?);
+        }
+        if flags & ATTR_UIDGID != 0 {
+            self.bytes(8)?;
+        }
+        if flags & ATTR_PERMISSIONS != 0 {
+            attributes.permissions = Some(self.u32()?);
+        }
+        if flags & ATTR_ACMODTIME != 0 {
+            self.u32()?;
+            attributes.modified = Some(self.u32()?);
+        }
+        if flags & ATTR_EXTENDED != 0 {
+            for _ in 0..self.u32()? {
+                self.string()?;
+                self.string()?;
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Attributes {
+    size: Option<u64>,
+    permissions: Option<u32>,
+    modified: Option<u32>,
+}
+
+impl Attributes {
+    fn is_symlink(&self) -> bool {
+        self.permissions
+            .is_some_and(|permissions| permissions & S_IFMT == S_IFLNK)
+    }
+
+    // An entry that doesn't say what kind it is is taken to be a file
+    fn entry_metadata(&self, path: &Path) -> EntryMetadata {
+        let kind = self.permissions.map(|permissions| permissions & S_IFMT);
+        let is_file = kind.is_none_or(|kind| kind == S_IFREG);
+        EntryMetadata {
+            is_dir: kind == Some(S_IFDIR),
+            is_file,
+            is_executable: is_file
+                && self
+                    .permissions
+                    .is_some_and(|permissions| permissions & 0o111 != 0),
+            is_hidden: util::is_hidden(path, None),
+            len: self.size.unwrap_or(0),
+            modified: self
+                .modified
+                .map(|modified| SystemTime::UNIX_EPOCH + Duration::from_secs(modified.into())),
+        }
+    }
+}
+
+struct Response {
+    kind: u8,
+    body: Vec<u8>,
+}
+
+impl Response {
+    // The response's fields, if it's the kind that was expected. Otherwise it's a status saying
+    // why the request failed.
+    fn fields(&self, kind: u8) -> io::Result<FieldReader<'_>> {
+        let mut fields = FieldReader(&self.body);
+        if self.kind == kind {
+            return Ok(fields);
+        }
+        if self.kind != FXP_STATUS {
+            return Err(invalid_data("The server gave an answer that doesn't fit"));
+        }
+        let code = fields.u32()?;
+        let message = String::from_utf8_lossy(fields.string().unwrap_or_default()).to_string();
+        let kind = match code {
+            FX_EOF => io::ErrorKind::UnexpectedEof,
+            FX_NO_SUCH_FILE => io::ErrorKind::NotFound,
+            FX_PERMISSION_DENIED => io::ErrorKind::PermissionDenied,
+            _ => io::ErrorKind::Other,
+        };
+        Err(io::Error::new(kind, message))
+    }
+
+    fn is_end(&self) -> bool {
+        self.kind == FXP_STATUS && self.body.get(..4) == Some(&FX_EOF.to_be_bytes())
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn disconnected() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "The connection was closed")
+}
+
+async fn read_packet(reader: &mut (impl AsyncRead + Unpin)) -> io::Result<Vec<u8>> {
+    let len = reader.read_u32().await? as usize;
+    if len == 0 || len > MAX_PACKET_SIZE {
+        return Err(invalid_data("The server sent something that isn't SFTP"));
+    }
+    let mut packet = vec![0; len];
+    reader.read_exact(&mut packet).await?;
+    Ok(packet)
+}
+
+fn packet(kind: u8, id: Option<u32>, fields: Fields) -> Vec<u8> {
+    let mut body = vec![kind];
+    // Only the first packets, which start the session, have no id
+    if let Some(id) = id {
+        body.extend(id.to_be_bytes());
+    }
+    body.extend(fields.0);
+    let mut packet = (body.len() as u32).to_be_bytes().to_vec();
+    packet.extend(body);
+    packet
+}
+
+type Waiting = Arc<Mutex<Option<HashMap<u32, oneshot::Sender<Response>>>>>;
+
+// A conversation with an SFTP server. The server can answer requests in any order, so each answer
+// is passed to what's waiting for it by its request's id.
+struct Session {
+    writer: tokio::sync::Mutex<Box<dyn AsyncWrite + Send + Unpin>>,
+    // None once the server's stopped answering
+    waiting: Waiting,
+    next_id: AtomicU32,
+}
+
+impl Session {
+    async fn start(
+        mut reader: impl AsyncRead + Send + Unpin + 'static,
+        mut writer: impl AsyncWrite + Send + Unpin + 'static,
+    ) -> io::Result<Session> {
+        writer
+            .write_all(&packet(FXP_INIT, None, Fields::default().u32(VERSION)))
+            .await?;
+        writer.flush().await?;
+        let version = read_packet(&mut reader).await?;
+        if version.first() != Some(&FXP_VERSION) {
+            return Err(invalid_data("The server didn't start SFTP"));
+        }
+        let waiting: Waiting = Arc::new(Mutex::new(Some(HashMap::new())));
+        tokio::spawn({
+            let waiting = waiting.clone();
+            async move {
+                while let Ok(packet) = read_packet(&mut reader).await {
+                    let Some(id) = packet.get(1..5) else {
+                        break;
+                    };
+                    let id = u32::from_be_bytes(id.try_into().unwrap());
+                    let answer_tx = waiting
+                        .lock()
+                        .unwrap()
+                        .as_mut()
+                        .and_then(|waiting| waiting.remove(&id));
+                    if let Some(answer_tx) = answer_tx {
+                        let _ = answer_tx.send(Response {
+                            kind: packet[0],
+                            body: packet[5..].to_vec(),
+                        });
+                    }
+                }
+                // What's still waiting is told the connection's closed
+                waiting.lock().unwrap().take();
+            }
+        });
+        Ok(Session {
+            writer: tokio::sync::Mutex::new(Box::new(writer)),
+            waiting,
+            next_id: AtomicU32::new(0),
+        })
+    }
+
+    async fn request(&self, kind: u8, fields: Fields) -> io::Result<Response> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (answer_tx, answer_rx) = oneshot::channel();
+        self.waiting
+            .lock()
+            .unwrap()
+            .as_mut()
+            .ok_or_else(disconnected)?
+            .insert(id, answer_tx);
+        let mut writer = self.writer.lock().await;
+        let written = match writer.write_all(&packet(kind, Some(id), fields)).await {
+            Ok(()) => writer.flush().await,
+            Err(error) => Err(error),
+        };
+        drop(writer);
+        if let Err(error) = written {
+            if let Some(waiting) = self.waiting.lock().unwrap().as_mut() {
+                waiting.remove(&id);
+            }
+            return Err(error);
+        }
+        answer_rx.await.map_err(|_| disconnected())
+    }
+
+    // Follows links
+    async fn stat(&self, path: &str) -> io::Result<Attributes> {
+        self.request(FXP_STAT, Fields::default().string(path.as_bytes()))
+            .await?
+            .fields(FXP_ATTRS)?
+            .attributes()
+    }
+
+    async fn open(&self, kind: u8, fields: Fields) -> io::Result<Vec<u8>> {
+        Ok(self
+            .request(kind, fields)
+            .await?
+            .fields(FXP_HANDLE)?
+            .string()?
+            .to_vec())
+    }
+
+    async fn close(&self, handle: &[u8]) {
+        let _ = self
+            .request(FXP_CLOSE, Fields::default().string(handle))
+            .await;
+    }
+
+    // The names of the entries in a directory, other than . and .., and their attributes, which
+    // are a link's own
+    async fn read_dir(&self, path: &str) -> io::Result<Vec<(String, Attributes)>> {
+        let handle = self
+            .open(FXP_OPENDIR, Fields::default().string(path.as_bytes()))
+            .await?;
+        let entries = self.read_dir_entries(&handle).await;
+        self.close(&handle).await;
+        entries
+    }
+
+    async fn read_dir_entries(&self, handle: &[u8]) -> io::Result<Vec<(String, Attributes)>> {
+        let mut entries = vec![];
+        loop {
+            let response = self
+                .request(FXP_READDIR, Fields::default().string(handle))
+                .await?;
+            if response.is_end() {
+                return Ok(entries);
+            }
+            let mut fields = response.fields(FXP_NAME)?;
+            for _ in 0..fields.u32()? {
+                let name = String::from_utf8_lossy(fields.string()?).to_string();
+                // The long name is what ls -l would show
+                fields.string()?;
+                let attributes = fields.attributes()?;
+                if name != "." && name != ".." {
+                    entries.push((name, attributes));
+                }
+            }
+        }
+    }
+
+    // Writes a file's contents, or as much of the start of them as the maximum size
+    async fn read(
+        &self,
+        path: &str,
+        max_size: Option<u64>,
+        writer: &mut (impl AsyncWrite + Unpin),
+    ) -> io::Result<()> {
+        let fields = Fields::default()
+            .string(path.as_bytes())
+            .u32(OPEN_READ)
+            .u32(0);
+        let handle = self.open(FXP_OPEN, fields).await?;
+        let read = self.read_contents(&handle, max_size, writer).await;
+        self.close(&handle).await;
+        read
+    }
+
+    async fn read_contents(
+        &self,
+        handle: &[u8],
+        max_size: Option<u64>,
+        writer: &mut (impl AsyncWrite + Unpin),
+    ) -> io::Result<()> {
+        let mut offset = 0;
+        while max_size.is_none_or(|max_size| offset < max_size) {
+            let len = max_size.map_or(READ_SIZE, |max_size| {
+                (max_size - offset).min(READ_SIZE.into()) as u32
+            });
+            let fields = Fields::default().string(handle).u64(offset).u32(len);
+            let response = self.request(FXP_READ, fields).await?;
+            if response.is_end() {
+                break;
+            }
+            let data = response.fields(FXP_DATA)?.string()?;
+            if data.is_empty() {
+                break;
+            }
+            writer.write_all(data).await?;
+            offset += data.len() as u64;
+        }
+        writer.flush().await
+    }
+}
+
+// Another computer's file system, reached over SFTP and shown under a local directory. Only its
+// directories are made there, as they're found, so the app can go into them. Its files are read
+// over the connection, and downloaded when something needs one on disk.
+pub(crate) struct SftpFs {
+    session: Session,
+    mount: PathBuf,
+    // The program the connection goes through, which is stopped when the connection's done with
+    _ssh: Option<Mutex<Child>>,
+}
+
+impl SftpFs {
+    // Connects through ssh, which asks for a password or a passphrase on the terminal if it needs
+    // one
+    pub async fn connect(
+        destination: &str,
+        port: Option<&str>,
+        mount: &Path,
+    ) -> io::Result<SftpFs> {
+        let mut command = tokio::process::Command::new("ssh");
+        if let Some(port) = port {
+            command.args(["-p", port]);
+        }
+        let mut ssh = command
+            .args(["-s", "--", destination, "sftp"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|error| match error.kind() {
+                io::ErrorKind::NotFound => {
+                    io::Error::new(io::ErrorKind::NotFound, "sftp:// locations need ssh")
+                }
+                _ => error,
+            })?;
+        let (Some(stdin), Some(stdout), Some(mut stderr)) =
+            (ssh.stdin.take(), ssh.stdout.take(), ssh.stderr.take())
+        else {
+            return Err(disconnected());
+        };
+        // What ssh says is kept for when it can't connect, like a password that's wrong
+        let messages = tokio::spawn(async move {
+            let mut messages = String::new();
+            let _ = stderr.read_to_string(&mut messages).await;
+            messages
+        });
+        match Session::start(stdout, stdin).await {
+            Ok(session) => Ok(SftpFs {
+                session,
+                mount: mount.to_path_buf(),
+                _ssh: Some(Mutex::new(ssh)),
+            }),
+            Err(error) => {
+                let _ = ssh.kill().await;
+                let messages = messages.await.unwrap_or_default();
+                match messages.trim() {
+                    "" => Err(error),
+                    messages => Err(io::Error::new(io::ErrorKind::ConnectionRefused, messages)),
+                }
+            }
+        }
+    }
+
+    // The path on the other computer of a path under the mount
+    fn remote_path(&self, path: &Path) -> io::Result<String> {
+        let relative = path.strip_prefix(&self.mount).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} isn't on the other computer", path.display()),
+            )
+        })?;
+        let mut remote_path = String::new();
+        for component in relative.components() {
+            if let Component::Normal(name) = component {
+                remote_path.push('/');
+                remote_path.push_str(&name.to_string_lossy());
+            }
+        }
+        if remote_path.is_empty() {
+            remote_path.push('/');
+        }
+        Ok(remote_path)
+    }
+}
+
+impl FsProvider for SftpFs {
+    // Links are followed, like they are on the computer's own file system
+    fn read_dir<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<Vec<ListingEntry>>> {
+        async move {
+            let remote_path = self.remote_path(path)?;
+            let mut entries = vec![];
+            for (name, attributes) in self.session.read_dir(&remote_path).await? {
+                let entry = path.join(&name);
+                let attributes = if attributes.is_symlink() {
+                    let target = format!("{}/{name}", remote_path.trim_end_matches('/'));
+                    self.session.stat(&target).await.ok()
+                } else {
+                    Some(attributes)
+                };
+                let metadata = attributes.map(|attributes| attributes.entry_metadata(&entry));
+                if metadata.is_some_and(|metadata| metadata.is_dir) {
+                    tokio::fs::create_dir_all(&entry).await?;
+                }
+                entries.push((entry, metadata));
+            }
+            Ok(entries)
+        }
+        .boxed()
+    }
+
+    // Looking up an entry makes the directory it is, or is in, so it can be gone to
+    fn metadata<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<EntryMetadata>> {
+        async move {
+            let metadata = self
+                .session
+                .stat(&self.remote_path(path)?)
+                .await?
+                .entry_metadata(path);
+            let directory = if metadata.is_dir {
+                Some(path)
+            } else {
+                path.parent()
+            };
+            if let Some(directory) = directory {
+                tokio::fs::create_dir_all(directory).await?;
+            }
+            Ok(metadata)
+        }
+        .boxed()
+    }
+
+    fn read<'a>(
+        &'a self,
+        path: &'a Path,
+        max_size: Option<u64>,
+    ) -> BoxFuture<'a, io::Result<Vec<u8>>> {
+        async move {
+            let mut contents = vec![];
+            self.session
+                .read(&self.remote_path(path)?, max_size, &mut contents)
+                .await?;
+            Ok(contents)
+        }
+        .boxed()
+    }
+
+    // The file's read a part at a time, as it's written
+    fn download<'a>(
+        &'a self,
+        path: &'a Path,
+        file: &'a mut tokio::fs::File,
+    ) -> BoxFuture<'a, io::Result<()>> {
+        async move {
+            self.session
+                .read(&self.remote_path(path)?, None, file)
+                .await
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A server with a few files in memory, which answers what the app asks
+    async fn serve(
+        mut reader: impl AsyncRead + Unpin,
+        mut writer: impl AsyncWrite + Unpin,
+        files: HashMap<&'static str, &'static [u8]>,
+    ) {
+        let mut handles: HashMap<Vec<u8>, (String, bool)> = HashMap::new();
+        while let Ok(request) = read_packet(&mut reader).await {
+            let mut fields = FieldReader(&request[1..]);
+            if request[0] == FXP_INIT {
+                let version = packet(FXP_VERSION, None, Fields::default().u32(VERSION));
+                writer.write_all(&version).await.unwrap();
+                continue;
+            }
+            let id = Some(fields.u32().unwrap());
+            let end = packet(
+                FXP_STATUS,
+                id,
+                Fields::default().u32(FX_EOF).string(b"").string(b""),
+            );
+            let missing = packet(
+                FXP_STATUS,
+                id,
+                Fields::default()
+                    .u32(FX_NO_SUCH_FILE)
+                    .string(b"No such file")
+                    .string(b""),
+            );
+            let file_attributes = |contents: &[u8]| {
+                Fields::default()
+                    .u32(ATTR_SIZE | ATTR_PERMISSIONS)
+                    .u64(contents.len() as u64)
+                    .u32(S_IFREG | 0o644)
+            };
+            let directory_attributes =
+                || Fields::default().u32(ATTR_PERMISSIONS).u32(S_IFDIR | 0o755);
+            let response = match request[0] {
+                FXP_STAT => {
+                    let path = String::from_utf8_lossy(fields.string().unwrap()).to_string();
+                    match files.get(path.as_str()) {
+                        Some(contents) => packet(FXP_ATTRS, id, file_attributes(contents)),
+                        None if files
+                            .keys()
+                            .any(|file| file.starts_with(&format!("{path}/"))) =>
+                        {
+                            packet(FXP_ATTRS, id, directory_attributes())
+                        }
+                        None => missing,
+                    }
+                }
+                FXP_OPENDIR | FXP_OPEN => {
+                    let path = String::from_utf8_lossy(fields.string().unwrap()).to_string();
+                    let handle = format!("{}", handles.len()).into_bytes();
+                    handles.insert(handle.clone(), (path, false));
+                    packet(FXP_HANDLE, id, Fields::default().string(&handle))
+                }
+                FXP_READDIR => {
+                    let (path, is_read) = handles.get_mut(fields.string().unwrap()).unwrap();
+                    if *is_read {
+                        end
+                    } else {
+                        *is_read = true;
+                        let mut names: Vec<(String, Option<&[u8]>)> = vec![];
+                        for (file, contents) in &files {
+                            let Some(relative) = file.strip_prefix(&format!("{path}/")) else {
+                                continue;
+                            };
+                            let name = match relative.split_once('/') {
+                                Some((directory, _)) => (directory.to_string(), None),
+                                None => (relative.to_string(), Some(*contents)),
+                            };
+                            if !names.contains(&name) {
+                                names.push(name);
+                            }
+                        }
+                        let mut response = Fields::default()
+                            .u32(names.len() as u32 + 1)
+                            .string(b".")
+                            .string(b"")
+                            .u32(0);
+                        for (name, contents) in names {
+                            response = response.string(name.as_bytes()).string(b"");
+                            let attributes = match contents {
+                                Some(contents) => file_attributes(contents),
+                                None => directory_attributes(),
+                            };
+                            response.0.extend(attributes.0);
+                        }
+                        packet(FXP_NAME, id, response)
+                    }
+                }
+                FXP_READ => {
+                    let (path, _) = &handles[fields.string().unwrap()];
+                    let offset = fields.u64().unwrap() as usize;
+                    let len = fields.u32().unwrap() as usize;
+                    match files.get(path.as_str()) {
+                        Some(contents) if offset >= contents.len() => end,
+                        Some(contents) => {
+                            let data = &contents[offset..contents.len().min(offset + len)];
+                            packet(FXP_DATA, id, Fields::default().string(data))
+                        }
+                        None => missing,
+                    }
+                }
+                _ => packet(
+                    FXP_STATUS,
+                    id,
+                    Fields::default().u32(0).string(b"").string(b""),
+                ),
+            };
+            writer.write_all(&response).await.unwrap();
+        }
+    }
+
+    async fn sftp_fs(mount: &Path) -> SftpFs {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let (server_reader, server_writer) = tokio::io::split(server);
+        let files = HashMap::from([
+            ("/home/me/notes.txt", &b"notes"[..]),
+            ("/home/me/photos/big.raw", &[7; 100_000][..]),
+        ]);
+        tokio::spawn(serve(server_reader, server_writer, files));
+        let (client_reader, client_writer) = tokio::io::split(client);
+        SftpFs {
+            session: Session::start(client_reader, client_writer).await.unwrap(),
+            mount: mount.to_path_buf(),
+            _ssh: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_and_reads_over_sftp() {
+        let mount = std::env::temp_dir().join(format!("rfb-sftp-test-{}", std::process::id()));
+        let sftp_fs = sftp_fs(&mount).await;
+        let home = mount.join("home/me");
+
+        // Directories are made as they're found
+        assert!(sftp_fs.metadata(&home).await.unwrap().is_dir);
+        assert!(home.is_dir());
+        let mut listing: Vec<(String, bool, u64)> = sftp_fs
+            .read_dir(&home)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(entry, metadata)| {
+                let metadata = metadata.unwrap();
+                (util::entry_name(&entry), metadata.is_dir, metadata.len)
+            })
+            .collect();
+        listing.sort();
+        assert_eq!(
+            listing,
+            [
+                ("notes.txt".to_string(), false, 5),
+                ("photos".to_string(), true, 0)
+            ]
+        );
+        assert!(home.join("photos").is_dir());
+        assert_eq!(
+            sftp_fs
+                .metadata(&home.join("missing"))
+                .await
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+
+        // A file that's bigger than what's asked for at a time is read in parts
+        let big = home.join("photos/big.raw");
+        assert_eq!(sftp_fs.read(&big, None).await.unwrap(), [7; 100_000]);
+        assert_eq!(
+            sftp_fs.read(&big, Some(40_000)).await.unwrap().len(),
+            40_000
+        );
+        assert!(!big.exists());
+        sftp_fs.local_path(&home).await.unwrap();
+        assert_eq!(std::fs::read(&big).unwrap(), [7; 100_000]);
+        assert_eq!(
+            std::fs::read_to_string(home.join("notes.txt")).unwrap(),
+            "notes"
+        );
+
+        let _ = std::fs::remove_dir_all(&mount);
+    }
+
+    #[test]
+    fn reads_attributes() {
+        let fields = Fields::default()
+            .u32(ATTR_SIZE | ATTR_UIDGID | ATTR_PERMISSIONS | ATTR_ACMODTIME | ATTR_EXTENDED)
+            .u64(1234)
+            .u32(1000)
+            .u32(1000)
+            .u32(S_IFREG | 0o755)
+            .u32(1)
+            .u32(60)
+            .u32(1)
+            .string(b"name")
+            .string(b"value");
+        let attributes = FieldReader(&fields.0).attributes().unwrap();
+        let metadata = attributes.entry_metadata(Path::new("/tmp/.script"));
+        assert_eq!(
+            metadata,
+            EntryMetadata {
+                is_dir: false,
+                is_file: true,
+                is_executable: true,
+                is_hidden: true,
+                len: 1234,
+                modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(60)),
+            }
+        );
+    }
+}
@@ -3,10 +3,37 @@
  * Created 2024-03-18
  */
 
-use ratatui::prelude::{Color, Style};
+use ratatui::prelude::{Color, Modifier, Style};
 
 pub(crate) const OTHER_FILE_STYLE: Style = Style::new().fg(Color::Black).bg(Color::Yellow);
 pub(crate) const OVERSIZE_FILE_STYLE: Style = Style::new().fg(Color::Black).bg(Color::Yellow);
-pub(crate) const BINARY_FILE_STYLE: Style = Style::new().fg(Color::Black).bg(Color::Yellow);
 pub(crate) const ERROR_STYLE: Style = Style::new().fg(Color::Red);
+pub(crate) const KEY_HINT_KEY_STYLE: Style = Style::new().fg(Color::Black).bg(Color::Gray);
+pub(crate) const KEY_HINT_STYLE: Style = Style::new().fg(Color::DarkGray);
+pub(crate) const MATCH_STYLE: Style = Style::new().fg(Color::LightYellow);
+pub(crate) const CURRENT_MATCH_STYLE: Style = Style::new().fg(Color::Black).bg(Color::LightYellow);
+pub(crate) const SELECTION_STYLE: Style = Style::new().bg(Color::Blue);
+pub(crate) const LINE_NUMBER_STYLE: Style = Style::new().fg(Color::DarkGray);
+pub(crate) const MARKDOWN_HEADING_STYLE: Style = Style::new()
+    .fg(Color::LightCyan)
+    .add_modifier(Modifier::BOLD);
+pub(crate) const MARKDOWN_CODE_STYLE: Style = Style::new().fg(Color::LightGreen);
+pub(crate) const MARKDOWN_LINK_STYLE: Style = Style::new()
+    .fg(Color::LightBlue)
+    .add_modifier(Modifier::UNDERLINED);
+pub(crate) const MARKDOWN_MARKUP_STYLE: Style = Style::new().fg(Color::DarkGray);
+pub(crate) const JSON_KEY_STYLE: Style = Style::new().fg(Color::LightBlue);
+pub(crate) const JSON_STRING_STYLE: Style = Style::new().fg(Color::LightGreen);
+pub(crate) const JSON_NUMBER_STYLE: Style = Style::new().fg(Color::LightCyan);
+pub(crate) const JSON_LITERAL_STYLE: Style = Style::new().fg(Color::LightMagenta);
+pub(crate) const JSON_SUMMARY_STYLE: Style = Style::new().fg(Color::DarkGray);
+pub(crate) const PROPERTY_NAME_STYLE: Style = Style::new().fg(Color::LightBlue);
+pub(crate) const MARKED_STYLE: Style = Style::new()
+    .fg(Color::LightYellow)
+    .add_modifier(Modifier::BOLD);
+pub(crate) const CHECKSUM_PASSED_STYLE: Style = Style::new().fg(Color::LightGreen);
+pub(crate) const CHECKSUM_FAILED_STYLE: Style = Style::new().fg(Color::LightRed);
+pub(crate) const DIFF_ADDED_STYLE: Style = Style::new().fg(Color::LightGreen);
+pub(crate) const DIFF_REMOVED_STYLE: Style = Style::new().fg(Color::LightRed);
+pub(crate) const EXECUTABLE_STYLE: Style = Style::new().fg(Color::LightGreen);
 pub(crate) const LIST_HIGHLIGHT_STYLE: Style = Style::new().fg(Color::Black).bg(Color::Gray);
@@ -0,0 +1,121 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::path::{Path, PathBuf};
+
+use crate::app::components::directory::Directory;
+use crate::app::components::preview::Preview;
+
+// How many directories going back can go back through
+const MAX_HISTORY: usize = 100;
+
+// A tab's own directory and preview panes, its other directory pane in dual-pane mode, where it's
+// been, and the directory it's in. The showing tab's panes and history are the app's, so its tab
+// holds empty ones until another tab is shown.
+#[derive(Default)]
+pub struct Tab<'a> {
+    pub directory: Directory,
+    pub preview: Preview<'a>,
+    pub other_pane: Option<OtherPane>,
+    pub history: History,
+    pub cwd: PathBuf,
+}
+
+impl Tab<'_> {
+    // The tab's name in the tab bar: the name of the directory it's in
+    pub fn name(cwd: &std::path::Path) -> String {
+        match cwd.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => cwd.to_string_lossy().to_string(),
+        }
+    }
+}
+
+// The directory pane that isn't active in dual-pane mode, the directory it's in, where it's been,
+// and which side it's on
+pub struct OtherPane {
+    pub directory: Directory,
+    pub cwd: PathBuf,
+    pub history: History,
+    pub is_left: bool,
+}
+
+// The directories a pane has been in, to go back and forward through like a web browser's
+#[derive(Default)]
+pub struct History {
+    back: Vec<PathBuf>,
+    forward: Vec<PathBuf>,
+    current: Option<PathBuf>,
+}
+
+impl History {
+    // Records going to a directory. Going somewhere new forgets where going back came from.
+    pub fn visit(&mut self, directory: &Path) {
+        if self.current.as_deref() == Some(directory) {
+            return;
+        }
+        if let Some(current) = self.current.replace(directory.to_path_buf()) {
+            self.back.push(current);
+            if self.back.len() > MAX_HISTORY {
+                self.back.remove(0);
+            }
+            self.forward.clear();
+        }
+    }
+
+    // The directory before the current one, which becomes the current one
+    pub fn back(&mut self) -> Option<PathBuf> {
+        let previous = self.back.pop()?;
+        self.forward.extend(self.current.replace(previous.clone()));
+        Some(previous)
+    }
+
+    // The directory that was gone back from, which becomes the current one
+    pub fn forward(&mut self) -> Option<PathBuf> {
+        let next = self.forward.pop()?;
+        self.back.extend(self.current.replace(next.clone()));
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visited(directories: &[&str]) -> History {
+        let mut history = History::default();
+        for directory in directories {
+            history.visit(Path::new(directory));
+        }
+        history
+    }
+
+    #[test]
+    fn goes_back_and_forward() {
+        let mut history = visited(&["/a", "/b", "/c"]);
+        assert_eq!(history.back(), Some(PathBuf::from("/b")));
+        assert_eq!(history.back(), Some(PathBuf::from("/a")));
+        assert_eq!(history.back(), None);
+        assert_eq!(history.forward(), Some(PathBuf::from("/b")));
+        assert_eq!(history.forward(), Some(PathBuf::from("/c")));
+        assert_eq!(history.forward(), None);
+    }
+
+    #[test]
+    fn going_somewhere_new_forgets_the_way_forward() {
+        let mut history = visited(&["/a", "/b", "/c"]);
+        history.back();
+        history.visit(Path::new("/d"));
+        assert_eq!(history.forward(), None);
+        assert_eq!(history.back(), Some(PathBuf::from("/b")));
+    }
+
+    #[test]
+    fn staying_in_a_directory_isnt_a_visit() {
+        let mut history = visited(&["/a", "/b", "/b"]);
+        assert_eq!(history.back(), Some(PathBuf::from("/a")));
+        assert_eq!(history.back(), None);
+    }
+}
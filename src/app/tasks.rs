@@ -0,0 +1,28 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// How many background tasks have been started and haven't finished, which the debug overlay shows
+static PENDING: AtomicUsize = AtomicUsize::new(0);
+
+// Counts as a pending task until it's dropped. It's made before a task is spawned and moved into
+// it, so a task that's waiting for a thread counts too.
+pub struct Task;
+
+pub fn start() -> Task {
+    PENDING.fetch_add(1, Ordering::Relaxed);
+    Task
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        PENDING.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+pub fn pending() -> usize {
+    PENDING.load(Ordering::Relaxed)
+}
@@ -0,0 +1,215 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
+use crate::app::file_ops;
+
+const INFO_EXTENSION: &str = "trashinfo";
+
+// Something in the trash: where it is in the trash, where it was deleted from, and when
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrashedItem {
+    pub path: PathBuf,
+    pub original_path: PathBuf,
+    pub deletion_date: String,
+}
+
+// Moves a file or directory to the trash, the way the freedesktop.org trash specification says
+// to: it goes into the trash's files directory, under a name nothing else there has, and a file
+// with the same name in its info directory says where it came from and when
+pub fn trash(path: &Path) -> io::Result<()> {
+    let trash = trash_directory()?;
+    let files = trash.join("files");
+    let info = trash.join("info");
+    std::fs::create_dir_all(&files)?;
+    std::fs::create_dir_all(&info)?;
+    let Some(name) = path.file_name() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Can't move {} to the trash", path.display()),
+        ));
+    };
+    let path = std::path::absolute(path)?;
+    let info_contents = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        encode(&path),
+        Local::now().format("%Y-%m-%dT%H:%M:%S")
+    );
+    // Creating the info file claims the name
+    for number in 1.. {
+        let mut trash_name = name.to_os_string();
+        if number > 1 {
+            trash_name.push(format!(".{number}"));
+        }
+        let mut info_name = trash_name.clone();
+        info_name.push(format!(".{INFO_EXTENSION}"));
+        let info_path = info.join(info_name);
+        let trashed_path = files.join(&trash_name);
+        if trashed_path.symlink_metadata().is_ok() {
+            continue;
+        }
+        let mut info_file = match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info_path)
+        {
+            Ok(info_file) => info_file,
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        };
+        info_file.write_all(info_contents.as_bytes())?;
+        if let Err(error) = file_ops::move_to(&path, &trashed_path) {
+            let _ = std::fs::remove_file(&info_path);
+            return Err(error);
+        }
+        break;
+    }
+    Ok(())
+}
+
+// What's in the trash, most recently deleted first
+pub fn list() -> io::Result<Vec<TrashedItem>> {
+    let trash = trash_directory()?;
+    let read_dir = match std::fs::read_dir(trash.join("info")) {
+        Ok(read_dir) => read_dir,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(error) => return Err(error),
+    };
+    let mut items = vec![];
+    for entry in read_dir.flatten() {
+        let info_path = entry.path();
+        if info_path
+            .extension()
+            .is_none_or(|extension| extension != INFO_EXTENSION)
+        {
+            continue;
+        }
+        let (Some(name), Ok(contents)) =
+            (info_path.file_stem(), std::fs::read_to_string(&info_path))
+        else {
+            continue;
+        };
+        let mut original_path = None;
+        let mut deletion_date = String::new();
+        for line in contents.lines() {
+            if let Some(path) = line.strip_prefix("Path=") {
+                original_path = Some(decode(path));
+            } else if let Some(date) = line.strip_prefix("DeletionDate=") {
+                deletion_date = date.replace('T', " ");
+            }
+        }
+        let path = trash.join("files").join(name);
+        if let Some(original_path) = original_path.filter(|_| path.symlink_metadata().is_ok()) {
+            items.push(TrashedItem {
+                path,
+                original_path,
+                deletion_date,
+            });
+        }
+    }
+    items.sort_by(|lhs, rhs| rhs.deletion_date.cmp(&lhs.deletion_date));
+    Ok(items)
+}
+
+// Puts something in the trash back where it was deleted from, as long as nothing's been put there
+// since
+pub fn restore(item: &TrashedItem) -> io::Result<()> {
+    if item.original_path.symlink_metadata().is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", item.original_path.display()),
+        ));
+    }
+    if let Some(parent) = item.original_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    file_ops::move_to(&item.path, &item.original_path)?;
+    std::fs::remove_file(info_path(item)?)
+}
+
+// Deletes everything in the trash for good. What can't be deleted is left, and returned by where
+// it was deleted from, with why, after everything else is.
+pub fn empty() -> io::Result<Vec<(PathBuf, io::Error)>> {
+    let mut failures = vec![];
+    for item in list()? {
+        let result = if item.path.is_dir() && !item.path.is_symlink() {
+            std::fs::remove_dir_all(&item.path)
+        } else {
+            std::fs::remove_file(&item.path)
+        }
+        .and_then(|_| std::fs::remove_file(info_path(&item)?));
+        if let Err(error) = result {
+            failures.push((item.original_path, error));
+        }
+    }
+    Ok(failures)
+}
+
+fn info_path(item: &TrashedItem) -> io::Result<PathBuf> {
+    let mut info_name = item.path.file_name().unwrap_or_default().to_os_string();
+    info_name.push(format!(".{INFO_EXTENSION}"));
+    Ok(trash_directory()?.join("info").join(info_name))
+}
+
+// Only the trash in the user's home directory is used, not the ones at the top of other file
+// systems. macOS and Windows keep their trash differently.
+#[cfg(not(any(target_os = "macos", windows)))]
+fn trash_directory() -> io::Result<PathBuf> {
+    dirs::data_dir()
+        .map(|data| data.join("Trash"))
+        .ok_or(io::Error::new(
+            io::ErrorKind::NotFound,
+            "Can't find the trash directory",
+        ))
+}
+
+#[cfg(any(target_os = "macos", windows))]
+fn trash_directory() -> io::Result<PathBuf> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "The trash isn't supported on this system",
+    ))
+}
+
+// The path, with the characters that aren't allowed in a URL percent-encoded
+fn encode(path: &Path) -> String {
+    let mut encoded = String::new();
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+fn decode(encoded: &str) -> PathBuf {
+    let mut bytes = vec![];
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let code = tail
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match code {
+            Some(code) if byte == b'%' => {
+                bytes.push(code);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    PathBuf::from(String::from_utf8_lossy(&bytes).to_string())
+}
@@ -0,0 +1,123 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::app::components::{ListingOptions, SortKey};
+use crate::constants;
+
+const VIEWS_FILE: &str = "views";
+
+// How a directory's entries are shown: how they're sorted, whether hidden ones are, and what
+// they're filtered by
+#[derive(Clone, Debug, PartialEq)]
+pub struct View {
+    pub listing_options: ListingOptions,
+    pub filter: Option<String>,
+}
+
+// Every directory pane shares the same memory, so a directory looks the same in any tab
+pub type SharedViewMemory = Arc<Mutex<ViewMemory>>;
+
+// The views of the directories whose view has been changed, least recently used first. When
+// they're saved, they're kept in a file in the config directory with a line for each: the sort
+// key, whether hidden entries are shown, the filter and the path, separated by tabs.
+#[derive(Default)]
+pub struct ViewMemory {
+    views: Vec<(PathBuf, View)>,
+    is_saved: bool,
+}
+
+impl ViewMemory {
+    // Reads the views file, and saves the views whenever they change from then on. Nothing's
+    // remembered if it doesn't exist yet, and lines that can't be read are skipped.
+    pub fn load() -> io::Result<ViewMemory> {
+        let contents = match std::fs::read_to_string(Self::file()?) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+        let views = contents
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, '\t');
+                let listing_options = ListingOptions {
+                    sort_key: SortKey::from_name(fields.next()?)?,
+                    show_hidden: fields.next()?.parse().ok()?,
+                };
+                let filter = Some(fields.next()?.to_string()).filter(|filter| !filter.is_empty());
+                let path = PathBuf::from(fields.next()?);
+                Some((
+                    path,
+                    View {
+                        listing_options,
+                        filter,
+                    },
+                ))
+            })
+            .collect();
+        Ok(ViewMemory {
+            views,
+            is_saved: true,
+        })
+    }
+
+    // The directory's view, if it's remembered, which makes it the most recently used
+    pub fn get(&mut self, path: &Path) -> Option<View> {
+        let index = self
+            .views
+            .iter()
+            .position(|(view_path, _)| view_path == path)?;
+        let entry = self.views.remove(index);
+        let view = entry.1.clone();
+        self.views.push(entry);
+        Some(view)
+    }
+
+    // Remembers the directory's view, or forgets it if it's no different from the default view,
+    // forgetting the least recently used ones if there are too many
+    pub fn remember(&mut self, path: &Path, view: View, default_view: &View) -> io::Result<()> {
+        self.views.retain(|(view_path, _)| view_path != path);
+        if view != *default_view {
+            self.views.push((path.to_path_buf(), view));
+        }
+        let excess = self.views.len().saturating_sub(constants::VIEW_MEMORY_SIZE);
+        self.views.drain(..excess);
+        if self.is_saved {
+            self.save()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let file = Self::file()?;
+        if let Some(directory) = file.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        let mut contents = String::new();
+        for (path, view) in &self.views {
+            contents.push_str(&format!(
+                "{}\t{}\t{}\t{}\n",
+                view.listing_options.sort_key.name(),
+                view.listing_options.show_hidden,
+                view.filter.as_deref().unwrap_or_default(),
+                path.to_string_lossy()
+            ));
+        }
+        std::fs::write(file, contents)
+    }
+
+    fn file() -> io::Result<PathBuf> {
+        dirs::config_dir()
+            .map(|config| config.join(constants::CONFIG_DIRECTORY).join(VIEWS_FILE))
+            .ok_or(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Can't find config directory",
+            ))
+    }
+}
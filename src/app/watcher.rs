@@ -0,0 +1,73 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use notify::event::ModifyKind;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tracing::trace;
+
+use crate::tui::Event;
+
+// How long changes are collected before they're reported, so a burst of them causes one refresh
+const DEBOUNCE_TIME: Duration = Duration::from_millis(250);
+
+// Watches directories for entries being created, deleted or renamed, and sends an FsChanged
+// event with the paths that changed
+pub struct Watcher {
+    watcher: RecommendedWatcher,
+    watched: Vec<PathBuf>,
+}
+
+impl Watcher {
+    pub fn new(event_tx: UnboundedSender<Event>) -> notify::Result<Self> {
+        let (path_tx, mut path_rx) = mpsc::unbounded_channel::<Vec<PathBuf>>();
+        let watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+            if let Ok(event) = result {
+                // Files being read or written don't change the listing
+                if let EventKind::Create(_)
+                | EventKind::Remove(_)
+                | EventKind::Modify(ModifyKind::Name(_)) = event.kind
+                {
+                    let _ = path_tx.send(event.paths);
+                }
+            }
+        })?;
+        tokio::spawn(async move {
+            while let Some(paths) = path_rx.recv().await {
+                tokio::time::sleep(DEBOUNCE_TIME).await;
+                let mut changed: BTreeSet<PathBuf> = paths.into_iter().collect();
+                while let Ok(paths) = path_rx.try_recv() {
+                    changed.extend(paths);
+                }
+                trace!(count = changed.len(), "file system changed");
+                // The receiver is only gone if the app is shutting down
+                let _ = event_tx.send(Event::FsChanged(changed.into_iter().collect()));
+            }
+        });
+        Ok(Self {
+            watcher,
+            watched: vec![],
+        })
+    }
+
+    // Watches the directories, and stops watching any others
+    pub fn watch(&mut self, directories: Vec<PathBuf>) {
+        if directories == self.watched {
+            return;
+        }
+        for directory in &self.watched {
+            let _ = self.watcher.unwatch(directory);
+        }
+        // A directory that can't be watched just won't be refreshed
+        for directory in &directories {
+            let _ = self.watcher.watch(directory, RecursiveMode::NonRecursive);
+        }
+        self.watched = directories;
+    }
+}
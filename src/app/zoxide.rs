@@ -0,0 +1,50 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+// Asks zoxide for the directories in its database, highest ranked first
+pub async fn query() -> io::Result<Vec<PathBuf>> {
+    let output = Command::new("zoxide")
+        .args(["query", "--list"])
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|error| match error.kind() {
+            io::ErrorKind::NotFound => {
+                io::Error::new(io::ErrorKind::NotFound, "zoxide isn't installed")
+            }
+            _ => error,
+        })?;
+    // zoxide fails when it has no directories that match, which is the same as having none
+    if !output.status.success() {
+        return Ok(vec![]);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+// Tells zoxide the directory was visited, in the background. If zoxide isn't installed, this does
+// nothing.
+pub fn add(path: &Path) {
+    let path = path.to_path_buf();
+    tokio::spawn(async move {
+        let _ = Command::new("zoxide")
+            .arg("add")
+            .arg(path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await;
+    });
+}
@@ -3,7 +3,65 @@
  * Created 2024-03-17
  */
 
+use std::time::Duration;
+
 pub const PARENT_DIRECTORY: &str = "..";
 pub const DIRECTORY_ICON: char = '📁';
 pub const DOCUMENT_ICON: char = '📄';
 pub const UNKNOWN_ICON: char = '❔';
+
+// Text files at least this many bytes long aren't read all at once, unless told otherwise
+pub const DEFAULT_MAX_TEXT_SIZE: u64 = 50_000;
+
+// How long the selection has to stay on an entry before the entry is previewed
+pub const PREVIEW_DELAY: Duration = Duration::from_millis(75);
+
+// How long a previewer program has to preview a file before it's stopped, unless the settings say
+pub const DEFAULT_PREVIEWER_TIMEOUT: Duration = Duration::from_secs(5);
+
+// How many recently loaded previews are kept
+pub const PREVIEW_CACHE_SIZE: usize = 16;
+
+// How many text files' scroll positions are remembered. The least recently viewed are forgotten
+// first.
+pub const SCROLL_MEMORY_SIZE: usize = 200;
+
+// How long loading a directory waits for it to be read before it shows what's been read so far
+pub const DIRECTORY_READ_WAIT: Duration = Duration::from_millis(100);
+
+// How long something has to take to load before it's shown as loading
+pub const LOADING_INDICATOR_DELAY: Duration = Duration::from_millis(100);
+
+// How long type-ahead waits for the next character before it starts a new prefix
+pub const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+// How many lines a mouse wheel event scrolls to begin with, and the most it can be set to
+pub const DEFAULT_WHEEL_LINES: usize = 3;
+pub const MAX_WHEEL_LINES: usize = 20;
+
+// Up to this many marked files are added up right away. More than that, or any directories, are
+// added up in the background.
+pub const MAX_MARKED_SIZE_WAIT: usize = 100;
+
+// The directory under the user's config directory that the app keeps its files in
+pub const CONFIG_DIRECTORY: &str = "rfb";
+
+// How much of the main area's width, in percent, the directory pane takes to begin with, how
+// little either pane can be left with, and how far Ctrl+Left/Right moves the divider
+pub const DEFAULT_SPLIT_PERCENT: u16 = 40;
+pub const MIN_SPLIT_PERCENT: u16 = 10;
+pub const SPLIT_PERCENT_STEP: u16 = 5;
+
+// How many directories' views are remembered. The least recently used are forgotten first.
+pub const VIEW_MEMORY_SIZE: usize = 200;
+
+// Terminals narrower than this have the preview below the directory instead of beside it
+pub const STACKED_LAYOUT_WIDTH: u16 = 80;
+
+// Terminals smaller than this just say so, until they're made bigger
+pub const MIN_TERMINAL_WIDTH: u16 = 40;
+pub const MIN_TERMINAL_HEIGHT: u16 = 10;
+
+// How much of the main area's width, in percent, the parent directory's entries take in the
+// Miller columns layout
+pub const PARENT_LIST_PERCENT: u16 = 20;
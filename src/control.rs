@@ -0,0 +1,248 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::tui::Event;
+
+// Set for the programs the app runs, like an editor, so they can find the socket
+pub const SOCKET_VARIABLE: &str = "RFB_SOCKET";
+
+// How long connections get to be answered when the app quits
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+// A command from another process, one per line on the socket. Relative paths are relative to the
+// app's current directory.
+#[derive(Clone, Debug)]
+pub enum ControlCommand {
+    // "cd <directory>"
+    Navigate(PathBuf),
+    // "select <path>", which goes to the directory it's in
+    Select(PathBuf),
+    // "quit", which is answered with the marked entries, or the selected one, one per line
+    Quit,
+}
+
+impl ControlCommand {
+    pub fn parse(line: &str) -> Result<ControlCommand, String> {
+        let line = line.trim();
+        let (name, argument) = line.split_once(' ').unwrap_or((line, ""));
+        let argument = argument.trim();
+        match (name, argument) {
+            ("cd", "") | ("select", "") => Err(format!("Usage: {name} <path>")),
+            ("cd", path) => Ok(ControlCommand::Navigate(PathBuf::from(path))),
+            ("select", path) => Ok(ControlCommand::Select(PathBuf::from(path))),
+            ("quit", "") => Ok(ControlCommand::Quit),
+            _ => Err(format!("Unknown command: {line}")),
+        }
+    }
+}
+
+// A Unix socket other processes, like an editor or a window manager's scripts, control the app
+// through. Each command is sent to the app as an event with where to send its answer, which is
+// "ok", "error: <why>", or for quit, the paths it reports.
+pub struct ControlSocket {
+    path: PathBuf,
+    task: JoinHandle<()>,
+    cancellation_token: CancellationToken,
+}
+
+impl ControlSocket {
+    #[cfg(unix)]
+    pub fn listen(path: &Path, event_tx: UnboundedSender<Event>) -> io::Result<ControlSocket> {
+        let path = std::path::absolute(path)?;
+        // A socket that's left over from an app that didn't quit is replaced, but not one that's
+        // in use, or anything that isn't a socket
+        if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+            use std::os::unix::fs::FileTypeExt;
+
+            if !metadata.file_type().is_socket() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} already exists, and isn't a socket", path.display()),
+                ));
+            }
+            if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("Something is already listening on {}", path.display()),
+                ));
+            }
+            std::fs::remove_file(&path)?;
+        }
+        let listener = tokio::net::UnixListener::bind(&path)?;
+        tracing::info!(path = %path.display(), "listening for commands");
+        let cancellation_token = CancellationToken::new();
+        let task = tokio::spawn(accept(listener, event_tx, cancellation_token.clone()));
+        Ok(ControlSocket {
+            path,
+            task,
+            cancellation_token,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn listen(_path: &Path, _event_tx: UnboundedSender<Event>) -> io::Result<ControlSocket> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "The control socket is only available on Unix",
+        ))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Stops listening, gives the commands that are being answered a moment to be, and removes the
+    // socket
+    pub async fn close(self) {
+        self.cancellation_token.cancel();
+        let _ = tokio::time::timeout(SHUTDOWN_TIMEOUT, self.task).await;
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(unix)]
+async fn accept(
+    listener: tokio::net::UnixListener,
+    event_tx: UnboundedSender<Event>,
+    cancellation_token: CancellationToken,
+) {
+    let mut connections = tokio::task::JoinSet::new();
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    // The connections that have been closed are done with
+                    while connections.try_join_next().is_some() {}
+                    connections.spawn(serve(stream, event_tx.clone(), cancellation_token.clone()));
+                }
+                Err(error) => tracing::warn!(%error, "couldn't accept a connection"),
+            },
+        }
+    }
+    while connections.join_next().await.is_some() {}
+}
+
+// Reads a connection's commands and writes their answers, until it's closed or the app quits
+#[cfg(unix)]
+async fn serve(
+    stream: tokio::net::UnixStream,
+    event_tx: UnboundedSender<Event>,
+    cancellation_token: CancellationToken,
+) {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    loop {
+        let line = tokio::select! {
+            _ = cancellation_token.cancelled() => break,
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => line,
+                _ => break,
+            },
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        tracing::debug!(line, "control command");
+        let command = ControlCommand::parse(&line);
+        let is_quit = matches!(command, Ok(ControlCommand::Quit));
+        let answer = match command {
+            Ok(command) => {
+                let (answer_tx, mut answer_rx) = mpsc::unbounded_channel();
+                if event_tx.send(Event::Control(command, answer_tx)).is_err() {
+                    break;
+                }
+                answer_rx.recv().await.unwrap_or_default()
+            }
+            Err(message) => format!("error: {message}"),
+        };
+        // Quitting with nothing to report answers with nothing
+        let answer = if answer.is_empty() {
+            answer
+        } else {
+            answer + "\n"
+        };
+        if writer.write_all(answer.as_bytes()).await.is_err() || is_quit {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert!(matches!(
+            ControlCommand::parse("cd /tmp"),
+            Ok(ControlCommand::Navigate(path)) if path == Path::new("/tmp")
+        ));
+        assert!(matches!(
+            ControlCommand::parse("  select  notes with spaces.txt \n"),
+            Ok(ControlCommand::Select(path)) if path == Path::new("notes with spaces.txt")
+        ));
+        assert!(matches!(
+            ControlCommand::parse("quit"),
+            Ok(ControlCommand::Quit)
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_commands() {
+        assert_eq!(ControlCommand::parse("cd").unwrap_err(), "Usage: cd <path>");
+        assert_eq!(
+            ControlCommand::parse("select   ").unwrap_err(),
+            "Usage: select <path>"
+        );
+        assert_eq!(
+            ControlCommand::parse("quit now").unwrap_err(),
+            "Unknown command: quit now"
+        );
+        assert_eq!(
+            ControlCommand::parse("open x").unwrap_err(),
+            "Unknown command: open x"
+        );
+    }
+
+    // A path that's only used by one test
+    fn socket_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rfb-{name}-{}", std::process::id()))
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn wont_replace_what_isnt_a_socket() {
+        let path = socket_path("not-a-socket");
+        std::fs::write(&path, "notes").unwrap();
+        let (event_tx, _event_rx) = mpsc::unbounded_channel();
+        let error = ControlSocket::listen(&path, event_tx).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "notes");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn replaces_a_socket_thats_left_over() {
+        let path = socket_path("left-over-socket");
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        let (event_tx, _event_rx) = mpsc::unbounded_channel();
+        let control_socket = ControlSocket::listen(&path, event_tx).unwrap();
+        assert!(std::os::unix::net::UnixStream::connect(&path).is_ok());
+        control_socket.close().await;
+        assert!(!path.exists());
+    }
+}
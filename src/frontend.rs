@@ -0,0 +1,175 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+//! What the `rfb` command does with its command line, for other frontends, and tests, to do the
+//! same.
+
+use std::path::PathBuf;
+use std::time::Instant;
+
+use color_eyre::eyre::{eyre, Result};
+
+use crate::app::event_output::EventOutput;
+use crate::app::{self, App};
+use crate::control::ControlSocket;
+use crate::graphics::Protocol;
+use crate::logging;
+use crate::options::Options;
+use crate::tui::{self, Event};
+
+/// Runs the browser on the terminal the way the command line says, until it quits: the events it
+/// writes and what it logs are set up, it's started with [`start`], and it's given the terminal's
+/// events, and the control socket's commands if it listens on one. What it's extracted and
+/// downloaded is removed when it quits.
+///
+/// An option or a path on the command line that can't be used is an error, which is returned
+/// before the terminal's taken over.
+pub async fn run(options: Options) -> Result<()> {
+    // The descriptor is checked before the log file is opened, which could be given its number
+    let event_output = options.events_json.map(EventOutput::from_fd).transpose()?;
+    // Nothing's logged if the log file can't be written, but a filter that can't be parsed is a
+    // mistake on the command line
+    if let Err(error) = logging::init(options.log_level.as_deref()) {
+        if error.kind() == std::io::ErrorKind::InvalidInput {
+            return Err(error.into());
+        }
+    }
+    let listen = options.listen.clone();
+    let mut app = start(options)?;
+    if let Some(event_output) = event_output {
+        app.set_event_output(event_output);
+    }
+    let result = run_app(app, listen).await;
+    // Archives that were opened were extracted to temporary directories
+    app::archive::remove_extracted();
+    // Other computers and buckets that were browsed were mounted on temporary directories
+    app::remote::remove_downloaded();
+    match &result {
+        Ok(()) => tracing::info!("quit"),
+        Err(error) => tracing::error!(%error, "quit with an error"),
+    }
+    result
+}
+
+/// Makes the app the command line says to: it goes to the first path, selecting it if it's a
+/// file, and opens the others in tabs, or restores the last session if there aren't any and it's
+/// asked to. It only goes there when it's given [`Event::Init`], so the current directory isn't
+/// changed before the app runs.
+///
+/// A path that doesn't exist is an error.
+pub fn start(options: Options) -> Result<App<'static>> {
+    let mut initial_selection = None;
+    // The paths given on the command line are opened instead of the last session. Otherwise
+    // whether it's restored is up to the settings, unless --restore says to.
+    let restore_session = if !options.init_paths.is_empty() {
+        Some(false)
+    } else if options.restore {
+        Some(true)
+    } else {
+        None
+    };
+    // The paths are made absolute before the app starts, since it changes the current directory
+    let mut init_paths = vec![];
+    for init_path in &options.init_paths {
+        let init_path = std::path::absolute(init_path)?;
+        if !init_path.exists() {
+            return Err(eyre!("{} doesn't exist", init_path.display()));
+        }
+        init_paths.push(init_path);
+    }
+    let mut init_paths = init_paths.into_iter();
+    // A file is selected in the directory it's in
+    let initial_directory = init_paths.next().map(|init_path| match init_path.parent() {
+        Some(directory) if init_path.is_file() => {
+            let directory = directory.to_path_buf();
+            initial_selection = Some(init_path);
+            directory
+        }
+        _ => init_path,
+    });
+    // The other paths are opened in tabs after the app starts
+    let initial_tabs = init_paths.collect();
+    let graphics_protocol = options.image_protocol.unwrap_or_else(Protocol::detect);
+    tracing::info!(
+        version = env!("CARGO_PKG_VERSION"),
+        cwd = ?std::env::current_dir().ok(),
+        ?initial_directory,
+        ?graphics_protocol,
+        "started"
+    );
+    let mut app = App::default();
+    app.set_graphics_protocol(graphics_protocol);
+    app.set_max_text_size(options.max_text_size);
+    if let Some(initial_directory) = initial_directory {
+        app.set_initial_directory(initial_directory);
+    }
+    if let Some(initial_selection) = initial_selection {
+        app.set_initial_selection(initial_selection);
+    }
+    app.set_initial_tabs(initial_tabs);
+    app.set_restore_session(restore_session);
+    app.is_mouse_released = options.no_mouse;
+    Ok(app)
+}
+
+// Runs the app, which has been set up from the command line, until it quits
+async fn run_app(mut app: App<'_>, listen: Option<PathBuf>) -> Result<()> {
+    let mut tui = tui::Tui::new()?
+        .tick_rate(10.0)
+        .frame_rate(30.0)
+        .mouse(!app.is_mouse_released)
+        .paste(true)
+        .focus(true)
+        .keyboard_enhancement(true);
+    tui.enter()?;
+    let control_socket = listen
+        .map(|path| ControlSocket::listen(&path, tui.event_tx.clone()))
+        .transpose()?;
+    if let Some(control_socket) = &control_socket {
+        app.set_control_socket(control_socket.path().to_path_buf());
+    }
+    app.set_event_tx(Some(tui.event_tx.clone()));
+
+    loop {
+        let event = tui.next().await?; // blocks until next event
+
+        if let Event::Render = event {
+            let start = Instant::now();
+            tui.draw(|f| {
+                app.render(f.size(), f);
+            })?;
+            app.record_frame_time(start.elapsed());
+            if let Some(update) = app.graphics_update() {
+                tui.update_graphics(app.graphics_protocol(), update)?;
+            }
+        }
+        // The app was continued after something else stopped it, so it takes the terminal over
+        // again, in case the shell changed it in the meantime
+        if let Event::Resume = event {
+            tui.resume()?;
+        }
+        app.handle_event(event).await;
+        if app.should_quit {
+            break;
+        }
+        if app.should_suspend {
+            app.should_suspend = false;
+            tui.suspend()?;
+        }
+        if let Some(program) = app.program_to_run.take() {
+            let name = program.get_program().to_string_lossy().to_string();
+            let status = tui.run_program(program).await?;
+            app.program_finished(&name, status).await;
+        }
+        if let Some(location) = app.location_to_connect.take() {
+            tui.with_terminal(app.connect_to_location(location)).await?;
+        }
+        tui.set_mouse_capture(!app.is_mouse_released)?;
+    }
+    if let Some(control_socket) = control_socket {
+        control_socket.close().await;
+    }
+    Ok(())
+}
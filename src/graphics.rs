@@ -0,0 +1,218 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::collections::BTreeMap;
+use std::io::Cursor;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use clap::ValueEnum;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use ratatui::layout::Rect;
+
+// The cell size to assume when the terminal doesn't report its size in pixels
+const DEFAULT_CELL_SIZE: (u32, u32) = (8, 16);
+
+// The most base64 bytes the kitty protocol allows in one escape sequence
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// The ways a terminal can be asked to draw an image
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum Protocol {
+    /// Images aren't drawn
+    #[default]
+    None,
+    /// The kitty graphics protocol
+    Kitty,
+    /// iTerm2's inline images
+    Iterm2,
+    /// Sixel graphics
+    Sixel,
+}
+
+impl Protocol {
+    /// Guesses which protocol the terminal understands from its environment variables
+    pub fn detect() -> Protocol {
+        let term = std::env::var("TERM").unwrap_or_default();
+        let term_program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+        if std::env::var_os("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || term.contains("ghostty")
+        {
+            Protocol::Kitty
+        } else if term_program == "iTerm.app"
+            || term_program == "WezTerm"
+            || std::env::var_os("WEZTERM_EXECUTABLE").is_some()
+        {
+            Protocol::Iterm2
+        } else if term.contains("sixel") || term.starts_with("foot") || term == "mlterm" {
+            Protocol::Sixel
+        } else {
+            Protocol::None
+        }
+    }
+}
+
+/// What has to be written to the terminal after a frame is drawn
+pub enum GraphicsUpdate {
+    /// Take the image that's showing off the screen
+    Erase,
+    /// Draw an image
+    Draw(String),
+}
+
+// Returns the escape sequence that deletes the images the protocol has drawn, if it has one.
+// Images drawn any other way are part of the screen, and go when it's cleared.
+pub(crate) fn erase_sequence(protocol: Protocol) -> Option<&'static str> {
+    match protocol {
+        Protocol::Kitty => Some("\x1b_Ga=d,q=2\x1b\\"),
+        _ => None,
+    }
+}
+
+// Returns the escape sequences that draw the image scaled to fit in the area, at its top left
+pub(crate) fn encode(protocol: Protocol, image: &DynamicImage, area: Rect) -> Option<String> {
+    if area.width == 0 || area.height == 0 {
+        return None;
+    }
+    let (cell_width, cell_height) = cell_size();
+    let mut max_height = area.height as u32 * cell_height;
+    if protocol == Protocol::Sixel {
+        // Sixel images are drawn six pixel rows at a time, so keep the last band inside the area
+        max_height -= max_height % 6;
+    }
+    let image = fit_image(image, area.width as u32 * cell_width, max_height);
+
+    let payload = match protocol {
+        Protocol::None => return None,
+        Protocol::Kitty => kitty(&image),
+        Protocol::Iterm2 => iterm2(&image)?,
+        Protocol::Sixel => sixel(&image),
+    };
+    // Move the cursor to the top left of the area first
+    Some(format!("\x1b[{};{}H{payload}", area.y + 1, area.x + 1))
+}
+
+// The size of a terminal cell in pixels
+fn cell_size() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns) as u32,
+            (size.height / size.rows) as u32,
+        ),
+        _ => DEFAULT_CELL_SIZE,
+    }
+}
+
+// Shrinks the image to fit, keeping its aspect ratio. Images that already fit aren't enlarged.
+pub(crate) fn fit_image(image: &DynamicImage, max_width: u32, max_height: u32) -> RgbaImage {
+    if image.width() > max_width || image.height() > max_height {
+        image
+            .resize(max_width.max(1), max_height.max(1), FilterType::Triangle)
+            .to_rgba8()
+    } else {
+        image.to_rgba8()
+    }
+}
+
+// The kitty graphics protocol: raw RGBA pixels in base64, sent in chunks
+fn kitty(image: &RgbaImage) -> String {
+    let data = BASE64.encode(image.as_raw());
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    let mut payload = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = if index + 1 < chunks.len() { 1 } else { 0 };
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if index == 0 {
+            // Transmit and show, quietly, without moving the cursor
+            payload.push_str(&format!(
+                "\x1b_Ga=T,f=32,s={},v={},q=2,C=1,m={more};{chunk}\x1b\\",
+                image.width(),
+                image.height()
+            ));
+        } else {
+            payload.push_str(&format!("\x1b_Gm={more};{chunk}\x1b\\"));
+        }
+    }
+    payload
+}
+
+// iTerm2's inline images: the image as a PNG file in base64
+fn iterm2(image: &RgbaImage) -> Option<String> {
+    let mut png = Cursor::new(vec![]);
+    image.write_to(&mut png, ImageFormat::Png).ok()?;
+    let png = png.into_inner();
+    Some(format!(
+        "\x1b]1337;File=inline=1;size={};width={}px;height={}px;preserveAspectRatio=1:{}\x07",
+        png.len(),
+        image.width(),
+        image.height(),
+        BASE64.encode(&png)
+    ))
+}
+
+// Sixel graphics, with the colors reduced to a 6x6x6 cube. Transparent pixels aren't drawn.
+fn sixel(image: &RgbaImage) -> String {
+    let (width, height) = image.dimensions();
+    let color_index = |pixel: &image::Rgba<u8>| {
+        let [r, g, b, _] = pixel.0.map(|c| ((c as u16 * 5 + 127) / 255) as u8);
+        r * 36 + g * 6 + b
+    };
+
+    let mut payload = format!("\x1bP0;1;0q\"1;1;{width};{height}");
+    for index in 0..216u16 {
+        let level = |value: u16| value * 100 / 5;
+        payload.push_str(&format!(
+            "#{index};2;{};{};{}",
+            level(index / 36),
+            level(index / 6 % 6),
+            level(index % 6)
+        ));
+    }
+    for band in (0..height).step_by(6) {
+        // The six-pixel columns of each color in the band
+        let mut colors: BTreeMap<u8, Vec<u8>> = BTreeMap::new();
+        for y in band..(band + 6).min(height) {
+            for x in 0..width {
+                let pixel = image.get_pixel(x, y);
+                if pixel.0[3] >= 128 {
+                    let columns = colors
+                        .entry(color_index(pixel))
+                        .or_insert_with(|| vec![0; width as usize]);
+                    columns[x as usize] |= 1 << (y - band);
+                }
+            }
+        }
+        for (index, columns) in colors {
+            payload.push_str(&format!("#{index}"));
+            push_sixel_run_lengths(&mut payload, &columns);
+            // Back to the start of the band for the next color
+            payload.push('$');
+        }
+        payload.push('-');
+    }
+    payload.push_str("\x1b\\");
+    payload
+}
+
+// Appends a color's columns, with repeats run-length encoded
+fn push_sixel_run_lengths(payload: &mut String, columns: &[u8]) {
+    let mut index = 0;
+    while index < columns.len() {
+        let bits = columns[index];
+        let run = columns[index..]
+            .iter()
+            .take_while(|other| **other == bits)
+            .count();
+        let c = (0x3f + bits) as char;
+        if run > 3 {
+            payload.push_str(&format!("!{run}{c}"));
+        } else {
+            (0..run).for_each(|_| payload.push(c));
+        }
+        index += run;
+    }
+}
@@ -0,0 +1,41 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+//! The Ratatui File Browser as a library, for other ratatui applications to embed.
+//!
+//! [`App`] is the whole browser: it's given the terminal's [`Event`]s, which a [`Tui`] reads and
+//! sends, and renders itself into a frame. Its panes are [`Component`]s, like the
+//! [`Directory`] list and the [`Preview`], which can be used on their own, and lists of entries
+//! are kept in [`StatefulList`]s.
+//!
+//! [`run`] is the `rfb` command, given its parsed [`Options`], and [`start`] makes the app it
+//! runs without taking over the terminal.
+
+#![warn(missing_docs)]
+
+/// The browser, and its panes
+pub mod app;
+mod constants;
+mod control;
+pub mod frontend;
+/// Drawing images on terminals that can
+pub mod graphics;
+mod logging;
+/// The command line
+pub mod options;
+/// Lists that keep which item is selected
+pub mod stateful_list;
+/// The terminal, and the events the app handles
+pub mod tui;
+mod util;
+
+pub use app::components::directory::Directory;
+pub use app::components::preview::{Preview, PreviewContent};
+pub use app::components::Component;
+pub use app::App;
+pub use frontend::{run, start};
+pub use options::Options;
+pub use stateful_list::StatefulList;
+pub use tui::{Event, Tui};
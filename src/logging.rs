@@ -0,0 +1,50 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+use std::fs::File;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use tracing_subscriber::EnvFilter;
+
+use crate::constants;
+
+const LOG_FILE: &str = "rfb.log";
+
+// What's logged when neither --log-level nor RUST_LOG says
+const DEFAULT_LOG_LEVEL: &str = "warn";
+
+// Logs to a file in the data directory, which is started over each run, since anything written to
+// the terminal would end up in the middle of the app. The level is a tracing filter, like "debug"
+// or "rfb=trace", from --log-level, or from RUST_LOG if that isn't given.
+pub fn init(log_level: Option<&str>) -> io::Result<PathBuf> {
+    let filter = match log_level {
+        Some(log_level) => EnvFilter::try_new(log_level),
+        None => {
+            EnvFilter::try_from_default_env().or_else(|_| EnvFilter::try_new(DEFAULT_LOG_LEVEL))
+        }
+    }
+    .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+
+    let directory = dirs::data_dir()
+        .map(|data| data.join(constants::CONFIG_DIRECTORY))
+        .ok_or(io::Error::new(
+            io::ErrorKind::NotFound,
+            "Can't find data directory",
+        ))?;
+    std::fs::create_dir_all(&directory)?;
+    let path = directory.join(LOG_FILE);
+    let file = File::create(&path)?;
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_thread_ids(true)
+        .try_init()
+        .map_err(io::Error::other)?;
+    Ok(path)
+}
@@ -5,8 +5,34 @@
 
 use clap::Parser;
 
+/// The rfb command's command line
 #[derive(Parser)]
-#[command(version, long_about("A simple TUI File Browser"))]
+#[command(version, about = None, long_about("A simple TUI File Browser"))]
 pub struct Options {
-    pub(super) init_path: Option<std::path::PathBuf>,
+    /// Directories or files to open, each in its own tab
+    pub init_paths: Vec<std::path::PathBuf>,
+    /// Start in the directory the last session quit in, with the same selection and view
+    #[arg(long)]
+    pub restore: bool,
+    /// How images are drawn (detected from the terminal if not given)
+    #[arg(long, value_enum)]
+    pub image_protocol: Option<crate::graphics::Protocol>,
+    /// Text files at least this many bytes long aren't read all at once
+    #[arg(long, value_name = "BYTES", default_value_t = crate::constants::DEFAULT_MAX_TEXT_SIZE)]
+    pub max_text_size: u64,
+    /// Leave the mouse to the terminal, so text can be selected on the screen (F9 captures it)
+    #[arg(long)]
+    pub no_mouse: bool,
+    /// What's logged to rfb.log in the data directory, like "debug" or "rfb=trace" (RUST_LOG if
+    /// not given, otherwise warnings and errors)
+    #[arg(long, value_name = "FILTER")]
+    pub log_level: Option<String>,
+    /// Listen on a Unix socket at PATH for commands from other programs: "cd <dir>", "select
+    /// <path>" and "quit", which answers with the marked entries or the selected one
+    #[arg(long, value_name = "PATH")]
+    pub listen: Option<std::path::PathBuf>,
+    /// Write the directory and selection changes and the files that are opened to file descriptor
+    /// FD as JSON lines, like "--events-json 3 3>events.jsonl"
+    #[arg(long, value_name = "FD")]
+    pub events_json: Option<i32>,
 }
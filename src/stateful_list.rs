@@ -2,19 +2,33 @@
  * Copyright (c) 2023 Paul Sobolik
  * Created 2023-12-23
  */
+use std::ops::Range;
+
 use ratatui::widgets::ListState;
 
-#[derive(Default)]
+/// A list of items and the [`ListState`] a ratatui `List` of them is drawn with, which says which
+/// one is selected and where the list is scrolled to
 pub struct StatefulList<T> {
     pub(crate) state: ListState,
     items: Vec<T>,
 }
 
+// Implemented by hand so the items don't have to implement Default
+impl<T> Default for StatefulList<T> {
+    fn default() -> Self {
+        StatefulList {
+            state: ListState::default(),
+            items: vec![],
+        }
+    }
+}
+
 #[allow(dead_code)]
 impl<T> StatefulList<T>
 where
     T: PartialEq + std::fmt::Debug,
 {
+    /// A list of the items, with nothing selected
     pub fn with_items(items: Vec<T>) -> StatefulList<T> {
         StatefulList {
             state: ListState::default(),
@@ -22,12 +36,19 @@ where
         }
     }
 
+    /// How many items there are
     pub fn len(&self) -> usize {
         self.items.len()
     }
+    /// Whether there aren't any items
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+    /// The first item's index
     pub fn lower_bound(&self) -> usize {
         0
     }
+    /// The last item's index, or 0 if there aren't any items
     pub fn upper_bound(&self) -> usize {
         let len = self.len();
         if len > 0 {
@@ -37,28 +58,35 @@ where
         }
     }
 
+    /// The index of the first item shown, where the list is scrolled to
     pub fn offset(&self) -> usize {
         self.state.offset()
     }
+    /// Scrolls the list so the item is the first one shown, and selects it
     pub fn set_offset(&mut self, offset: usize) {
         self.set_selected(Some(offset));
         *self.state.offset_mut() = offset;
     }
 
+    /// Whether the list is scrolled to the top
     pub fn at_offset_first(&self) -> bool {
         self.state.offset() == self.lower_bound()
     }
+    /// Scrolls the list to the top
     pub fn offset_first(&mut self) {
         self.set_offset(self.lower_bound())
     }
 
+    /// Whether the list is scrolled as far as it goes, with the last item first
     pub fn at_offset_last(&mut self) -> bool {
         self.state.offset() == self.upper_bound()
     }
+    /// Scrolls the list as far as it goes, so the last item is first
     pub fn offset_last(&mut self) {
         self.set_offset(self.upper_bound())
     }
 
+    /// Scrolls the list down by the distance, or as far as it goes
     pub fn advance_offset(&mut self, distance: usize) {
         let new = self.offset() + distance;
         if new < self.upper_bound() {
@@ -68,6 +96,8 @@ where
         }
     }
 
+    /// Scrolls the list up by the distance, or to the top. The item it's scrolled to is selected,
+    /// and the list widget scrolls up to it when it's drawn.
     pub fn retreat_offset(&mut self, distance: usize) {
         let offset = self.offset();
         if offset < distance {
@@ -77,25 +107,62 @@ where
         }
     }
 
+    /// Scrolls the list down by an item
     pub fn next_offset(&mut self) {
         self.advance_offset(1)
     }
 
+    /// Scrolls the list up by an item
     pub fn previous_offset(&mut self) {
         self.retreat_offset(1)
     }
 
+    /// Scrolls the way the list widget would so the selected item is in a window of the given
+    /// height, and returns the range of items in the window
+    pub fn visible_range(&mut self, height: usize) -> Range<usize> {
+        let len = self.len();
+        let mut offset = self.offset().min(len.saturating_sub(1));
+        if let Some(selected) = self.selected() {
+            let selected = selected.min(len.saturating_sub(1));
+            if selected < offset {
+                offset = selected;
+            } else if height > 0 && selected >= offset + height {
+                offset = selected + 1 - height;
+            }
+        }
+        *self.state.offset_mut() = offset;
+        offset..(offset + height).min(len)
+    }
+
+    /// The state of a list widget that's only given the items in the range
+    pub fn window_state(&self, range: &Range<usize>) -> ListState {
+        let selected = self
+            .selected()
+            .filter(|selected| range.contains(selected))
+            .map(|selected| selected - range.start);
+        ListState::default().with_selected(selected)
+    }
+
+    /// The selected item's index, if one's selected
     pub fn selected(&self) -> Option<usize> {
         self.state.selected()
     }
+    /// Selects the item, or nothing. The list isn't scrolled to it until it's drawn.
     pub fn set_selected(&mut self, index: Option<usize>) {
         self.state.select(index);
     }
 
-    pub fn iter(&self) -> std::slice::Iter<T> {
+    /// The items, in order
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
         self.items.iter()
     }
 
+    /// The items in the range, which has to be in the list
+    pub fn slice(&self, range: Range<usize>) -> &[T] {
+        &self.items[range]
+    }
+
+    /// Selects the first item. Returns false if it was already selected.
     pub fn first(&mut self) -> bool {
         if self.is_first() {
             return false;
@@ -103,6 +170,7 @@ where
         self.set_selected(Some(self.lower_bound()));
         true
     }
+    /// Whether the first item is selected
     pub fn is_first(&self) -> bool {
         match self.selected() {
             Some(selected) => selected == self.lower_bound(),
@@ -110,6 +178,7 @@ where
         }
     }
 
+    /// Selects the last item. Returns false if it was already selected.
     pub fn last(&mut self) -> bool {
         if self.is_last() {
             return false;
@@ -117,6 +186,7 @@ where
         self.set_selected(Some(self.upper_bound()));
         true
     }
+    /// Whether the last item is selected
     pub fn is_last(&self) -> bool {
         match self.selected() {
             Some(selected) => selected == self.upper_bound(),
@@ -124,6 +194,8 @@ where
         }
     }
 
+    /// Selects the item the distance after the selected one, or the last one. Returns false if the
+    /// last one was already selected.
     pub fn advance(&mut self, distance: usize) -> bool {
         if self.is_last() {
             return false;
@@ -138,6 +210,8 @@ where
         true
     }
 
+    /// Selects the item the distance before the selected one, or the first one. Returns false if
+    /// the first one was already selected.
     pub fn retreat(&mut self, distance: usize) -> bool {
         if self.is_first() {
             return false;
@@ -151,18 +225,25 @@ where
         true
     }
 
+    /// Selects the item after the selected one. Returns false if the last one was already selected.
+    // Like previous, it moves the selection; it isn't an iterator
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> bool {
         self.advance(1)
     }
 
+    /// Selects the item before the selected one. Returns false if the first one was already
+    /// selected.
     pub fn previous(&mut self) -> bool {
         self.retreat(1)
     }
 
+    /// Selects nothing
     pub fn unselect(&mut self) {
         self.set_selected(None);
     }
 
+    /// The index of the first item that's equal to the needle, if there is one
     pub fn index_of(&self, needle: &T) -> Option<usize> {
         for (index, item) in self.items.iter().enumerate() {
             if item == needle {
@@ -2,55 +2,162 @@ use color_eyre::eyre::Result;
 use crossterm::{
     cursor,
     event::{
-        DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
-        Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent,
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture, Event as CrosstermEvent, KeyEvent, KeyEventKind,
+        KeyboardEnhancementFlags, MouseEvent, PopKeyboardEnhancementFlags,
+        PushKeyboardEnhancementFlags,
     },
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures::{FutureExt, StreamExt};
 use ratatui::backend::CrosstermBackend as Backend;
+use std::future::Future;
+use std::io::Write;
 use std::ops::{Deref, DerefMut};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
+use tokio::signal::unix::{Signal, SignalKind};
 use tokio::{
     sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
     task::JoinHandle,
 };
 use tokio_util::sync::CancellationToken;
 
+use crate::app::checksum::Checksums;
+use crate::app::components::preview::PreviewContent;
+use crate::app::components::ListingBatch;
+use crate::app::disk_usage::DiskUsage;
+use crate::app::search::SearchResult;
+use crate::control::ControlCommand;
+use crate::graphics;
+use crate::graphics::{GraphicsUpdate, Protocol};
+
 // pub type Frame<'a> = ratatui::Frame<'a>;
 
+// What's asked of terminals that support the kitty keyboard protocol: keys that are ambiguous
+// otherwise, like Esc, Ctrl+I and Shift+Enter, are reported differently, and so is whether a key
+// was pressed, repeated or released
+const KEYBOARD_ENHANCEMENT_FLAGS: KeyboardEnhancementFlags =
+    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+        .union(KeyboardEnhancementFlags::REPORT_EVENT_TYPES);
+
+// Whether the keyboard enhancement flags were pushed, so they're only popped if they were
+static IS_KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+
+// Whether the app stopped itself, so the SIGCONT it's continued with isn't taken for something
+// else having stopped it, since it's already taken the terminal back by then
+#[cfg(unix)]
+static IS_SELF_SUSPENDED: AtomicBool = AtomicBool::new(false);
+
+/// What the app handles: the terminal's input, ticks and renders from the [`Tui`], and the
+/// results of the work the app does in the background
 #[derive(Clone, Debug)]
 pub enum Event {
+    /// The terminal's size when the event task starts, which the app sets itself up for
     Init(u16, u16),
+    /// A directory pane's selection moved, so what depends on it is updated
     SelectionChanged,
+    /// A directory pane went to another directory
     DirectoryChanged,
+    /// Paths found by the fuzzy finder's directory walk, tagged with the walk's id
+    FinderResults(u32, Vec<PathBuf>),
+    /// The fuzzy finder's directory walk is done
+    FinderFinished(u32),
+    /// What a search found, tagged with the search's id
+    SearchResults(u32, Vec<SearchResult>),
+    /// The search is done
+    SearchFinished(u32),
+    /// A search result was chosen, and is gone to
+    OpenSearchResult(SearchResult),
+    /// What a background read of the current directory found, tagged with the read's id
+    DirectoryBatch(u32, ListingBatch),
+    /// What was read to preview an entry, tagged with the load's id
+    PreviewLoaded(u32, PathBuf, PreviewContent),
+    /// Paths that were created, deleted or renamed in the watched directories
+    FsChanged(Vec<PathBuf>),
+    /// How far the checksums of a file have got, and what they are, tagged with the computation's id
+    ChecksumProgress(u32, u64),
+    /// A file's checksums, or why they couldn't be computed, tagged with the computation's id
+    ChecksumsFinished(u32, Result<Checksums, String>),
+    /// How many files and bytes the disk usage scan has found, and what it came up with, tagged
+    /// with the scan's id
+    DiskUsageProgress(u32, u64, u64),
+    /// What the disk usage scan came up with, tagged with the scan's id
+    DiskUsageFinished(u32, DiskUsage),
+    /// How many files there are under the directory in the details popup and their total size, so
+    /// far and in the end, tagged with the computation's id
+    DirectorySizeProgress(u32, u64, u64),
+    /// How many files there are under the directory in the details popup and their total size,
+    /// once they've all been counted, tagged with the computation's id
+    DirectorySizeFinished(u32, u64, u64),
+    /// Whether a file listed in a checksum manifest has the checksum it should, tagged with the
+    /// verification's id and the file's place in the manifest
+    ChecksumVerified(u32, usize, Result<bool, String>),
+    /// The total size of the entries marked in a directory pane, tagged with the computation's id
+    MarkedSizeFinished(u32, u64),
+    /// The size of everything in a directory listed in a directory pane, tagged with the
+    /// computation's id
+    DirectorySizeFound(u32, PathBuf, u64),
+    /// Enter was pressed on a file, which is opened however the settings say
+    OpenFile(PathBuf),
+    /// A command from the control socket, and where its answer goes
+    Control(ControlCommand, UnboundedSender<String>),
     // Quit,
+    /// The terminal's input couldn't be read
     Error,
     // Closed,
+    /// Sent at the tick rate, for what's animated or polled
     Tick,
+    /// Sent at the frame rate, for the app to be drawn
     Render,
+    /// The terminal got focus
     FocusGained,
+    /// The terminal lost focus
     FocusLost,
+    /// Text was pasted into the terminal
     Paste(String),
+    /// A key was pressed, or repeated
     Key(KeyEvent),
+    /// The mouse was used, while it's captured
     Mouse(MouseEvent),
+    /// The terminal's new size
     Resize(u16, u16),
+    /// The app was asked to stop until it's continued, with SIGTSTP, or was continued after being
+    /// stopped, with SIGCONT
+    Suspend,
+    /// See [`Event::Suspend`]
+    Resume,
 }
 
+/// The terminal the app is drawn on, and the task that reads its input and sends it, with ticks and
+/// renders at their rates, as [`Event`]s
 #[derive(Debug)]
 pub struct Tui {
-    pub terminal: ratatui::Terminal<Backend<std::io::Stderr>>,
-    pub task: JoinHandle<()>,
-    pub cancellation_token: CancellationToken,
-    pub event_rx: UnboundedReceiver<Event>,
+    terminal: ratatui::Terminal<Backend<std::io::Stderr>>,
+    task: JoinHandle<()>,
+    cancellation_token: CancellationToken,
+    event_rx: UnboundedReceiver<Event>,
+    /// Where the events [`next`](Tui::next) returns are sent, for the app to send its own
     pub event_tx: UnboundedSender<Event>,
-    pub frame_rate: f64,
-    pub tick_rate: f64,
-    pub mouse: bool,
-    pub paste: bool,
+    frame_rate: f64,
+    tick_rate: f64,
+    mouse: bool,
+    paste: bool,
+    focus: bool,
+    keyboard_enhancement: bool,
+    // Whether the terminal supports the kitty keyboard protocol, once it's been asked, which is
+    // only done once, since the answer would be read by the event task if it's running
+    supports_keyboard_enhancement: Option<bool>,
+    // Whether the event task has been started before, so it's only initialized once
+    started: bool,
 }
 
 #[allow(dead_code)]
 impl Tui {
+    /// A terminal drawn on through standard error, with ticks 4 times a second and renders 60
+    /// times a second, and without the mouse, pasting, focus changes or the kitty keyboard
+    /// protocol. Nothing's done to the terminal until it's [`enter`](Tui::enter)ed.
     pub fn new() -> Result<Self> {
         let tick_rate = 4.0;
         let frame_rate = 60.0;
@@ -60,6 +167,8 @@ impl Tui {
         let task = tokio::spawn(async {});
         let mouse = false;
         let paste = false;
+        let focus = false;
+        let keyboard_enhancement = false;
         Ok(Self {
             terminal,
             task,
@@ -70,42 +179,84 @@ impl Tui {
             tick_rate,
             mouse,
             paste,
+            focus,
+            keyboard_enhancement,
+            supports_keyboard_enhancement: None,
+            started: false,
         })
     }
 
+    /// How many times a second [`Event::Tick`] is sent
     pub fn tick_rate(mut self, tick_rate: f64) -> Self {
         self.tick_rate = tick_rate;
         self
     }
 
+    /// How many times a second [`Event::Render`] is sent
     pub fn frame_rate(mut self, frame_rate: f64) -> Self {
         self.frame_rate = frame_rate;
         self
     }
 
+    /// Whether the mouse is captured, so its events are sent
     pub fn mouse(mut self, mouse: bool) -> Self {
         self.mouse = mouse;
         self
     }
 
+    /// Captures the mouse, or leaves it to the terminal, while the app is running
+    pub fn set_mouse_capture(&mut self, mouse: bool) -> Result<()> {
+        if mouse != self.mouse {
+            if mouse {
+                crossterm::execute!(std::io::stderr(), EnableMouseCapture)?;
+            } else {
+                crossterm::execute!(std::io::stderr(), DisableMouseCapture)?;
+            }
+            self.mouse = mouse;
+        }
+        Ok(())
+    }
+
+    /// Whether bracketed paste is used, so pasted text is sent all at once instead of as keys
     pub fn paste(mut self, paste: bool) -> Self {
         self.paste = paste;
         self
     }
 
-    pub fn start(&mut self) {
+    /// Whether the terminal says when it gains and loses focus, if it can
+    pub fn focus(mut self, focus: bool) -> Self {
+        self.focus = focus;
+        self
+    }
+
+    /// Whether the kitty keyboard protocol is used, if the terminal supports it. Other terminals
+    /// report keys the usual way.
+    pub fn keyboard_enhancement(mut self, keyboard_enhancement: bool) -> Self {
+        self.keyboard_enhancement = keyboard_enhancement;
+        self
+    }
+
+    fn start(&mut self) {
         let tick_delay = std::time::Duration::from_secs_f64(1.0 / self.tick_rate);
         let render_delay = std::time::Duration::from_secs_f64(1.0 / self.frame_rate);
         self.cancel();
         self.cancellation_token = CancellationToken::new();
         let _cancellation_token = self.cancellation_token.clone();
         let _event_tx = self.event_tx.clone();
+        let is_restart = std::mem::replace(&mut self.started, true);
         self.task = tokio::spawn(async move {
             let mut reader = crossterm::event::EventStream::new();
             let mut tick_interval = tokio::time::interval(tick_delay);
             let mut render_interval = tokio::time::interval(render_delay);
+            let mut job_signals = JobSignals::new();
             let (columns, rows) = crossterm::terminal::size().unwrap();
-            _event_tx.send(Event::Init(columns, rows)).unwrap();
+            // When it's restarted after the app was suspended, the terminal may have been resized
+            // in the meantime
+            if is_restart {
+                _event_tx.send(Event::Resize(columns, rows)).unwrap();
+            } else {
+                _event_tx.send(Event::Init(columns, rows)).unwrap();
+            }
 
             loop {
                 let tick_delay = tick_interval.tick();
@@ -120,7 +271,9 @@ impl Tui {
                             Some(Ok(evt)) => {
                                 match evt {
                                     CrosstermEvent::Key(key) => {
-                                        if key.kind == KeyEventKind::Press {
+                                        // Releases are only reported with the kitty keyboard
+                                        // protocol, and nothing's done when a key is released
+                                        if key.kind != KeyEventKind::Release {
                                             _event_tx.send(Event::Key(key)).unwrap();
                                         }
                                     },
@@ -153,11 +306,16 @@ impl Tui {
                     _ = render_delay => {
                         _event_tx.send(Event::Render).unwrap();
                     },
+                    event = job_signals.recv() => {
+                        _event_tx.send(event).unwrap();
+                    },
                 }
             }
         });
     }
 
+    /// Takes the terminal over: it's put in raw mode and the alternate screen, with what the
+    /// builders asked for, and the task that sends the events is started
     pub fn enter(&mut self) -> Result<()> {
         crossterm::terminal::enable_raw_mode()?;
         crossterm::execute!(std::io::stderr(), EnterAlternateScreen, cursor::Hide)?;
@@ -168,14 +326,38 @@ impl Tui {
         if self.paste {
             crossterm::execute!(std::io::stderr(), EnableBracketedPaste)?;
         }
+        if self.focus {
+            crossterm::execute!(std::io::stderr(), EnableFocusChange)?;
+        }
+        // They're pushed on a stack, so they aren't pushed again if they already were
+        if self.keyboard_enhancement
+            && !IS_KEYBOARD_ENHANCED.load(Ordering::Relaxed)
+            && *self.supports_keyboard_enhancement.get_or_insert_with(|| {
+                crossterm::terminal::supports_keyboard_enhancement().unwrap_or(false)
+            })
+        {
+            crossterm::execute!(
+                std::io::stderr(),
+                PushKeyboardEnhancementFlags(KEYBOARD_ENHANCEMENT_FLAGS)
+            )?;
+            IS_KEYBOARD_ENHANCED.store(true, Ordering::Relaxed);
+        }
 
         self.start();
         Ok(())
     }
 
+    /// Puts the terminal back the way it was before it was entered, which is also done when the
+    /// `Tui` is dropped
     pub fn exit(&mut self) -> Result<()> {
         if crossterm::terminal::is_raw_mode_enabled()? {
             self.flush()?;
+            if IS_KEYBOARD_ENHANCED.swap(false, Ordering::Relaxed) {
+                crossterm::execute!(std::io::stderr(), PopKeyboardEnhancementFlags)?;
+            }
+            if self.focus {
+                crossterm::execute!(std::io::stderr(), DisableFocusChange)?;
+            }
             if self.paste {
                 crossterm::execute!(std::io::stderr(), DisableBracketedPaste)?;
             }
@@ -188,21 +370,169 @@ impl Tui {
         Ok(())
     }
 
-    pub fn cancel(&self) {
+    /// Writes an image update after a frame is drawn
+    pub fn update_graphics(&mut self, protocol: Protocol, update: GraphicsUpdate) -> Result<()> {
+        let mut stderr = std::io::stderr();
+        match update {
+            GraphicsUpdate::Erase => match graphics::erase_sequence(protocol) {
+                Some(sequence) => stderr.write_all(sequence.as_bytes())?,
+                // The image is part of the screen, so the whole screen is drawn again
+                None => self.terminal.clear()?,
+            },
+            GraphicsUpdate::Draw(payload) => stderr.write_all(payload.as_bytes())?,
+        }
+        stderr.flush()?;
+        Ok(())
+    }
+
+    fn cancel(&self) {
         self.cancellation_token.cancel();
     }
 
+    /// Puts the terminal back the way it was and stops the process, as Ctrl+Z would if the terminal
+    /// weren't in raw mode, then takes the terminal over again when the process is continued. It's
+    /// stopped with SIGSTOP, since SIGTSTP is caught to suspend it this way.
+    pub fn suspend(&mut self) -> Result<()> {
+        #[cfg(unix)]
+        {
+            self.exit()?;
+            IS_SELF_SUSPENDED.store(true, Ordering::Relaxed);
+            // SAFETY: raise only sends a signal to this process
+            unsafe {
+                libc::raise(libc::SIGSTOP);
+            }
+            self.resume()?;
+        }
+        Ok(())
+    }
+
+    /// Gives the terminal to another program, like an editor, until it's done, then takes it over
+    /// again
+    pub async fn run_program(
+        &mut self,
+        command: std::process::Command,
+    ) -> Result<std::io::Result<std::process::ExitStatus>> {
+        self.with_terminal(tokio::process::Command::from(command).status())
+            .await
+    }
+
+    /// Gives the terminal to something, like a program or a connection that asks for a password,
+    /// until it's done, then takes it over again. Events aren't read in the meantime, so it gets
+    /// the keys.
+    pub async fn with_terminal<T>(&mut self, future: impl Future<Output = T>) -> Result<T> {
+        self.cancel();
+        // The task has to stop first, so it doesn't read the keys
+        let _ = (&mut self.task).await;
+        self.exit()?;
+        let output = future.await;
+        self.resume()?;
+        Ok(output)
+    }
+
+    /// Takes the terminal over again, and clears it so the whole screen is drawn again
     pub fn resume(&mut self) -> Result<()> {
         self.enter()?;
+        self.terminal.clear()?;
         Ok(())
     }
 
+    /// Waits for the next event
     pub async fn next(&mut self) -> Result<Event> {
         self.event_rx
             .recv()
             .await
-            .ok_or(color_eyre::eyre::eyre!("Unable to get event"))
+            .ok_or_else(|| color_eyre::eyre::eyre!("Unable to get event"))
+    }
+}
+
+// The job control signals that suspend the app and say it's been continued, which only unix has
+#[cfg(unix)]
+struct JobSignals {
+    signals: Option<(Signal, Signal)>,
+}
+
+#[cfg(unix)]
+impl JobSignals {
+    // Without the signals, the app is stopped and continued without knowing it
+    fn new() -> Self {
+        let signal = |signal| tokio::signal::unix::signal(SignalKind::from_raw(signal));
+        JobSignals {
+            signals: signal(libc::SIGTSTP)
+                .and_then(|stop| Ok((stop, signal(libc::SIGCONT)?)))
+                .ok(),
+        }
+    }
+
+    async fn recv(&mut self) -> Event {
+        let Some((stop, resume)) = self.signals.as_mut() else {
+            return std::future::pending().await;
+        };
+        loop {
+            tokio::select! {
+                _ = stop.recv() => return Event::Suspend,
+                _ = resume.recv() => {
+                    if !IS_SELF_SUSPENDED.swap(false, Ordering::Relaxed) {
+                        return Event::Resume;
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct JobSignals;
+
+#[cfg(not(unix))]
+impl JobSignals {
+    fn new() -> Self {
+        JobSignals
+    }
+
+    async fn recv(&mut self) -> Event {
+        std::future::pending().await
+    }
+}
+
+/// Makes panics and errors returned from main put the terminal back the way it was before they're
+/// printed, so they can be read and the shell still works. A panic in a background task ends the
+/// app too, since the terminal can't be drawn on once it's been put back. The error hook runs
+/// whenever an error report is made, so one should only be made for an error that ends the app.
+pub fn install_hooks() -> Result<()> {
+    let (panic_hook, eyre_hook) = color_eyre::config::HookBuilder::default().into_hooks();
+    let panic_hook = panic_hook.into_panic_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        restore();
+        tracing::error!(%panic_info, "panicked");
+        panic_hook(panic_info);
+        std::process::exit(101);
+    }));
+    let eyre_hook = eyre_hook.into_eyre_hook();
+    color_eyre::eyre::set_hook(Box::new(move |error| {
+        restore();
+        eyre_hook(error)
+    }))?;
+    Ok(())
+}
+
+// Leaves the alternate screen and raw mode, and turns off everything Tui::enter turned on. Errors
+// are ignored, since this is done when something's already gone wrong.
+fn restore() {
+    if !crossterm::terminal::is_raw_mode_enabled().unwrap_or(true) {
+        return;
+    }
+    if IS_KEYBOARD_ENHANCED.swap(false, Ordering::Relaxed) {
+        let _ = crossterm::execute!(std::io::stderr(), PopKeyboardEnhancementFlags);
     }
+    let _ = crossterm::execute!(
+        std::io::stderr(),
+        DisableFocusChange,
+        DisableBracketedPaste,
+        DisableMouseCapture,
+        LeaveAlternateScreen,
+        cursor::Show
+    );
+    let _ = crossterm::terminal::disable_raw_mode();
 }
 
 impl Deref for Tui {
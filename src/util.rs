@@ -3,25 +3,63 @@
  * Created 2024-03-17
  */
 
+use std::collections::HashMap;
+use std::fs::Metadata;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local};
 
 use crossterm::{
     event::KeyCode::Char,
     event::{KeyCode, KeyEvent, KeyModifiers},
 };
-use ratatui::{prelude::Line, widgets::ListItem};
+use number_prefix::NumberPrefix;
+use ratatui::{
+    prelude::Line,
+    widgets::{ListItem, ListState},
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
+use crate::app::fs_provider::{EntryMetadata, LocalFs};
+use crate::app::styles;
 use crate::{constants, stateful_list::StatefulList};
 
+// Clips the beginning of a string so it fits in a width, marking it with an ellipsis.
+// Widths are in terminal columns, so wide characters count double, and characters are never split.
 pub fn clip_string(string: &String, width: usize) -> String {
-    if string.len() > width {
-        let start = string.len() - width + 1;
+    if display_width(string) > width {
+        let mut clipped_width = 1; // For the ellipsis
+        let mut start = string.len();
+        for (index, grapheme) in string.grapheme_indices(true).rev() {
+            clipped_width += grapheme.width();
+            if clipped_width > width {
+                break;
+            }
+            start = index;
+        }
         format!("…{}", &string[start..])
     } else {
         string.to_string()
     }
 }
 
+// Pasted text as it's typed into a one-line input: its first line, without control characters
+pub fn pasted_line(text: &str) -> String {
+    text.lines()
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect()
+}
+
+// The number of terminal columns a string takes up
+pub fn display_width(string: &str) -> usize {
+    string.width()
+}
+
 pub fn entry_path(path: &Path) -> String {
     if path.ends_with(constants::PARENT_DIRECTORY) {
         let mut pb = path.to_path_buf();
@@ -36,23 +74,78 @@ pub fn entry_path(path: &Path) -> String {
     }
 }
 
-pub fn list_items<'a>(paths: &StatefulList<PathBuf>, height: usize) -> Vec<ListItem<'a>> {
-    let offset = paths.state.offset();
-    paths
+// The metadata of the entries that have been listed or shown, which their icons come from, so it's
+// only looked up once. What's listed is known from the listing, on whatever file system it's on,
+// and anything else is looked up when it's shown. It's cleared when the entries are reloaded.
+pub type KnownMetadata = HashMap<PathBuf, Option<EntryMetadata>>;
+
+// Builds list items for just the entries that fit in the height, and the state to render them with
+pub fn list_items<'a>(
+    paths: &mut StatefulList<PathBuf>,
+    height: usize,
+    known_metadata: &mut KnownMetadata,
+) -> (Vec<ListItem<'a>>, ListState) {
+    list_items_with_prefix(paths, height, known_metadata, |_, _| String::new())
+}
+
+// Like list_items, with text in front of each entry's icon, given the entry and its icon
+pub fn list_items_with_prefix<'a>(
+    paths: &mut StatefulList<PathBuf>,
+    height: usize,
+    known_metadata: &mut KnownMetadata,
+    prefix: impl Fn(&Path, char) -> String,
+) -> (Vec<ListItem<'a>>, ListState) {
+    list_items_with_columns(paths, height, known_metadata, 0, prefix, |_, _| {
+        String::new()
+    })
+}
+
+// Like list_items_with_prefix, with a column after each entry's name, given the entry and what's
+// known about it, that's lined up on the right of the width. The column is at least two spaces
+// from the name.
+pub fn list_items_with_columns<'a>(
+    paths: &mut StatefulList<PathBuf>,
+    height: usize,
+    known_metadata: &mut KnownMetadata,
+    width: usize,
+    prefix: impl Fn(&Path, char) -> String,
+    column: impl Fn(&Path, Option<&EntryMetadata>) -> String,
+) -> (Vec<ListItem<'a>>, ListState) {
+    let range = paths.visible_range(height);
+    let state = paths.window_state(&range);
+    let items = paths
+        .slice(range)
         .iter()
-        .enumerate()
-        .map(|(index, entry)| {
-            if index < offset || index > offset + height {
-                ListItem::new("") // Off screen
+        .map(|entry| {
+            let metadata = known_metadata
+                .entry(entry.clone())
+                .or_insert_with(|| LocalFs::entry_metadata(entry));
+            let (icon, is_executable) = entry_icon(metadata.as_ref());
+            // Executable files stand out, and have a * after their names like ls -F shows
+            let marker = if is_executable { "*" } else { "" };
+            let mut text = format!(
+                "{}{} {}{marker}",
+                prefix(entry, icon),
+                icon,
+                entry_name(entry)
+            );
+            let column = column(entry, metadata.as_ref());
+            if !column.is_empty() {
+                let gap = width
+                    .saturating_sub(display_width(&text) + display_width(&column))
+                    .max(2);
+                text.push_str(&" ".repeat(gap));
+                text.push_str(&column);
+            }
+            let item = ListItem::new(Line::from(text));
+            if is_executable {
+                item.style(styles::EXECUTABLE_STYLE)
             } else {
-                ListItem::new(Line::from(format!(
-                    "{} {}",
-                    path_icon(entry),
-                    entry_name(entry)
-                )))
+                item
             }
         })
-        .collect()
+        .collect();
+    (items, state)
 }
 
 pub(crate) fn entry_name(entry: &Path) -> String {
@@ -69,13 +162,27 @@ pub(crate) fn entry_name(entry: &Path) -> String {
     }
 }
 
-fn path_icon(entry: &Path) -> char {
-    if entry.is_dir() {
-        constants::DIRECTORY_ICON
-    } else if entry.is_file() {
-        constants::DOCUMENT_ICON
-    } else {
-        constants::UNKNOWN_ICON
+// An entry's icon, and whether it's an executable file
+fn entry_icon(metadata: Option<&EntryMetadata>) -> (char, bool) {
+    match metadata {
+        Some(metadata) if metadata.is_dir => (constants::DIRECTORY_ICON, false),
+        Some(metadata) if metadata.is_file => (constants::DOCUMENT_ICON, metadata.is_executable),
+        _ => (constants::UNKNOWN_ICON, false),
+    }
+}
+
+// Whether it's a file anyone can execute
+pub fn is_executable(metadata: &Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        false
     }
 }
 
@@ -89,39 +196,124 @@ pub fn is_down_key(key_event: KeyEvent) -> bool {
         || (Char('n') == key_event.code && key_event.modifiers == KeyModifiers::CONTROL)
 }
 
-pub fn find_match_by_char<T>(
-    list: &[T],
-    ch: char,
-    selected: usize,
-    match_char: fn(entry: &T) -> Option<char>,
-) -> Option<usize> {
-    // First, try to find a matching item that's after the selected item
-    if let Some(idx) = find_match_by_char_from(list, ch, selected + 1, match_char) {
-        Some(idx)
-    } else {
-        // If there's no matching item after the selected item, try to find one starting from the top
-        find_match_by_char_from(list, ch, 0, match_char)
+// Returns the value of a digit key that can be part of a count prefix. A zero only counts
+// if there's already a count pending, since a count can't start with zero.
+pub fn count_digit(key_event: KeyEvent, count_pending: bool) -> Option<usize> {
+    if key_event.modifiers != KeyModifiers::NONE {
+        return None;
+    }
+    match key_event.code {
+        Char(c @ '1'..='9') => c.to_digit(10).map(|d| d as usize),
+        Char('0') if count_pending => Some(0),
+        _ => None,
     }
 }
 
-fn find_match_by_char_from<T>(
+pub fn push_count_digit(count: Option<usize>, digit: usize) -> Option<usize> {
+    Some(count.unwrap_or(0).saturating_mul(10).saturating_add(digit))
+}
+
+// Finds the first item whose name starts with the prefix, or if none does, the first with a word
+// in its name that does. A prefix that's all lowercase ignores case, and one with capitals
+// doesn't. The search starts at `from` and wraps around to the top.
+pub fn find_match_by_prefix<T>(
     list: &[T],
-    ch: char,
+    prefix: &str,
     from: usize,
-    match_char: fn(entry: &T) -> Option<char>,
+    item_name: fn(item: &T) -> Option<&str>,
 ) -> Option<usize> {
-    let ch = ch.to_ascii_lowercase();
-    list[from..]
-        .iter()
-        .enumerate()
-        .find(|(_index, entry)| {
-            if let Some(first_char) = match_char(entry) {
-                first_char.to_ascii_lowercase() == ch
-            } else {
-                false
-            }
+    let is_case_sensitive = prefix.chars().any(char::is_uppercase);
+    let starts_with = |name: &str| {
+        if is_case_sensitive {
+            name.starts_with(prefix)
+        } else {
+            name.to_lowercase().starts_with(prefix)
+        }
+    };
+    let from = from.min(list.len());
+    let items = || {
+        list[from..]
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (from + index, item))
+            .chain(list[..from].iter().enumerate())
+    };
+    items()
+        .find(|(_index, item)| item_name(item).is_some_and(starts_with))
+        .or_else(|| {
+            items().find(|(_index, item)| {
+                item_name(item).is_some_and(|name| {
+                    word_starts(name).any(|word_start| starts_with(&name[word_start..]))
+                })
+            })
         })
-        .map(|(index, _)| from + index)
+        .map(|(index, _)| index)
+}
+
+// Where the words in a name after the first one start: after anything that isn't a letter or
+// digit, like "_" or "-", and at capitals in camelCase
+fn word_starts(name: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut previous: Option<char> = None;
+    name.char_indices().filter_map(move |(index, c)| {
+        let is_word_start = previous.is_some_and(|previous| {
+            c.is_alphanumeric()
+                && (!previous.is_alphanumeric() || (previous.is_lowercase() && c.is_uppercase()))
+        });
+        previous = Some(c);
+        is_word_start.then_some(index)
+    })
+}
+
+#[cfg(windows)]
+pub fn is_hidden(path: &Path, metadata: Option<&Metadata>) -> bool {
+    use std::os::windows::fs::MetadataExt;
+
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+    let has_hidden_attribute = metadata
+        .map(|metadata| metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0)
+        .unwrap_or(false);
+    has_hidden_attribute || is_dot_file(path)
+}
+
+#[cfg(not(windows))]
+pub fn is_hidden(path: &Path, _metadata: Option<&Metadata>) -> bool {
+    is_dot_file(path)
+}
+
+fn is_dot_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|file_name| file_name.to_str())
+        .map(|file_name| file_name.starts_with('.'))
+        .unwrap_or(false)
+}
+
+// Replaces a leading ~ with the user's home directory
+pub fn expand_tilde(path: &str) -> PathBuf {
+    if path == "~" || path.starts_with("~/") || path.starts_with("~\\") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(path[1..].trim_start_matches(['/', '\\']));
+        }
+    }
+    PathBuf::from(path)
+}
+
+// Returns the longest prefix that all of the strings share
+pub fn common_prefix(strings: &[String]) -> String {
+    let Some(first) = strings.first() else {
+        return String::new();
+    };
+    let mut prefix_len = first.len();
+    for string in &strings[1..] {
+        prefix_len = first
+            .char_indices()
+            .zip(string.chars())
+            .take_while(|((_, lhs), rhs)| lhs == rhs)
+            .map(|((index, lhs), _)| index + lhs.len_utf8())
+            .last()
+            .unwrap_or(0)
+            .min(prefix_len);
+    }
+    first[..prefix_len].to_string()
 }
 
 pub fn file_size(path: &Path) -> u64 {
@@ -131,3 +323,76 @@ pub fn file_size(path: &Path) -> u64 {
         0
     }
 }
+
+pub fn metadata_modified_string(metadata: &Metadata) -> String {
+    match modified_datetime(metadata) {
+        Some(modified) => {
+            format!("{}", modified.format("%Y-%m-%d %H:%M"))
+        }
+        _ => "".to_string(),
+    }
+}
+
+fn modified_datetime(metadata: &Metadata) -> Option<DateTime<Local>> {
+    match metadata.modified() {
+        Ok(modified) => {
+            let dur = modified.duration_since(SystemTime::UNIX_EPOCH).unwrap();
+            Some::<DateTime<Local>>(
+                chrono::DateTime::from_timestamp(dur.as_secs() as i64, 0)
+                    .unwrap()
+                    .into(),
+            )
+        }
+        _ => None, // No modified value
+    }
+}
+
+pub fn metadata_size_string(metadata: &Metadata) -> String {
+    // Not meant to be precise...
+    match NumberPrefix::decimal(metadata.len() as f64) {
+        NumberPrefix::Standalone(_) => "1 kB".into(),
+        NumberPrefix::Prefixed(prefix, n) => {
+            format!("{:.0} {}B", n, prefix.symbol())
+        }
+    }
+}
+
+pub fn size_string(size: u64) -> String {
+    match NumberPrefix::binary(size as f64) {
+        NumberPrefix::Standalone(n) => format!("{} B", n),
+        NumberPrefix::Prefixed(prefix, n) => {
+            format!("{:.1} {}B", n, prefix.symbol())
+        }
+    }
+}
+
+#[cfg(unix)]
+pub fn permissions_string(metadata: &Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = metadata.permissions().mode();
+    let file_type = if metadata.is_dir() {
+        'd'
+    } else if metadata.is_symlink() {
+        'l'
+    } else {
+        '-'
+    };
+    let mut permissions = String::from(file_type);
+    for shift in [6, 3, 0] {
+        let bits = (mode >> shift) & 0o7;
+        permissions.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        permissions.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        permissions.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+    permissions
+}
+
+#[cfg(not(unix))]
+pub fn permissions_string(metadata: &Metadata) -> String {
+    if metadata.permissions().readonly() {
+        "read-only".to_string()
+    } else {
+        "read-write".to_string()
+    }
+}
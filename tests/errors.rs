@@ -0,0 +1,54 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+mod support;
+
+use crossterm::event::KeyCode;
+use support::{Fixture, Harness};
+
+fn fixture() -> Fixture {
+    Fixture::new().dir("sub").file("notes.txt", "first line\n")
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn going_to_a_missing_directory_shows_an_error() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.type_text(":cd missing").await;
+    harness.press(KeyCode::Enter).await;
+    harness.assert_screen_contains("missing doesn't exist");
+    assert_eq!(harness.cwd(), harness.fixture().root());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn any_key_dismisses_an_error() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.type_text(":cd missing").await;
+    harness.press(KeyCode::Enter).await;
+    harness.assert_screen_contains("doesn't exist");
+
+    harness.press(KeyCode::Char('x')).await;
+    harness.assert_screen_lacks("doesn't exist");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn an_unknown_command_shows_an_error() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.type_text(":frobnicate").await;
+    harness.press(KeyCode::Enter).await;
+    harness.assert_screen_contains("Unknown command: frobnicate");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn making_a_directory_that_exists_shows_an_error() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.type_text(":mkdir sub").await;
+    harness.press(KeyCode::Enter).await;
+    harness.assert_screen_contains("Error creating sub: File exists");
+
+    // The listing is left as it was
+    harness.press(KeyCode::Esc).await;
+    harness.assert_screen_lacks("Error creating");
+    harness.assert_directory_title("[2 items]");
+}
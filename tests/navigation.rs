@@ -0,0 +1,180 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+mod support;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rfb::Event;
+use support::{Fixture, Harness};
+
+fn fixture() -> Fixture {
+    Fixture::new()
+        .dir("sub")
+        .file("sub/inner.txt", "inside")
+        .file("notes.txt", "first line\nsecond line\n")
+        .file("data.json", "{\"name\": \"rfb\"}")
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn lists_the_starting_directory() {
+    let mut harness = Harness::start(fixture()).await;
+    let root = harness.fixture().root().display().to_string();
+    harness.assert_screen_contains(&format!("[{root}]"));
+    harness.assert_directory_title("[3 items]");
+    for name in ["sub", "data.json", "notes.txt"] {
+        harness.assert_screen_contains(name);
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn enter_opens_a_directory_and_backspace_goes_back() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.press(KeyCode::Down).await;
+    harness.press(KeyCode::Enter).await;
+    assert_eq!(harness.cwd(), harness.fixture().path("sub"));
+    harness.assert_directory_title("[1 items]");
+    harness.assert_selected("..");
+    harness.assert_screen_contains("inner.txt");
+
+    harness.press(KeyCode::Backspace).await;
+    assert_eq!(harness.cwd(), harness.fixture().root());
+    // The directory that was left is selected
+    harness.assert_selected("sub");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn a_letter_jumps_to_the_entry_it_starts() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.type_text("d").await;
+    harness.assert_selected("data.json");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn scripted_keys_move_through_the_listing() {
+    let mut harness = Harness::start(fixture()).await;
+    let down = Event::Key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    harness
+        .send_all([down.clone(), down.clone(), down.clone()])
+        .await;
+    harness.assert_selected("notes.txt");
+    harness.press(KeyCode::Home).await;
+    harness.assert_selected("..");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn the_filter_hides_entries_that_dont_match() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.press(KeyCode::Char('/')).await;
+    harness.type_text("json").await;
+    harness.press(KeyCode::Enter).await;
+    harness.assert_directory_title("[1 of 3 items] /json");
+    harness.assert_selected("data.json");
+    harness.assert_screen_lacks("notes.txt");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn digits_followed_by_a_movement_key_are_a_count() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.type_text("2j").await;
+    harness.assert_selected("data.json");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn digits_followed_by_anything_else_are_typed_ahead() {
+    let fixture = fixture().file("2024-report.txt", "").file("2023-report.txt", "");
+    let mut harness = Harness::start(fixture).await;
+    harness.type_text("2024-").await;
+    harness.assert_selected("2024-report.txt");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn letters_typed_ahead_go_to_the_directory_before_the_app_keys() {
+    let fixture = fixture().file("fig.txt", "").file("find.txt", "");
+    let mut harness = Harness::start(fixture).await;
+    harness.type_text("fi").await;
+    harness.assert_selected("fig.txt");
+    harness.assert_screen_lacks("[Details]");
+    harness.type_text("n").await;
+    harness.assert_selected("find.txt");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn a_letter_jumps_even_if_it_opens_something_with_alt() {
+    let fixture = fixture().file("index.html", "");
+    let mut harness = Harness::start(fixture).await;
+    harness.type_text("i").await;
+    harness.assert_selected("index.html");
+    harness.assert_screen_lacks("[Details]");
+    harness
+        .press_with(KeyCode::Char('i'), KeyModifiers::ALT)
+        .await;
+    harness.assert_screen_contains("[Details]");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn sizes_are_shown_with_the_directories_added_up() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.press(KeyCode::Down).await;
+    harness
+        .press_with(KeyCode::Char('S'), KeyModifiers::ALT | KeyModifiers::SHIFT)
+        .await;
+    let screen = harness.screen();
+    let row = |name: &str| {
+        screen
+            .iter()
+            .find(|line| line.contains(&format!(" {name} ")))
+            .cloned()
+            .unwrap_or_default()
+    };
+    assert!(row("notes.txt").contains("23 B"), "{screen:#?}");
+    assert!(row("sub").contains("6 B"), "{screen:#?}");
+    harness.assert_selected("sub");
+
+    harness
+        .press_with(KeyCode::Char('S'), KeyModifiers::ALT | KeyModifiers::SHIFT)
+        .await;
+    harness.assert_screen_lacks("23 B");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn ctrl_n_and_ctrl_p_move_down_and_up() {
+    let mut harness = Harness::start(fixture()).await;
+    harness
+        .press_with(KeyCode::Char('n'), KeyModifiers::CONTROL)
+        .await;
+    harness
+        .press_with(KeyCode::Char('n'), KeyModifiers::CONTROL)
+        .await;
+    harness.assert_selected("data.json");
+    harness
+        .press_with(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .await;
+    harness.assert_selected("sub");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn b_jumps_instead_of_bookmarking() {
+    let fixture = fixture().dir("build");
+    let mut harness = Harness::start(fixture).await;
+    harness.type_text("b").await;
+    harness.assert_selected("build");
+    harness
+        .press_with(KeyCode::Char('B'), KeyModifiers::ALT | KeyModifiers::SHIFT)
+        .await;
+    harness.assert_screen_contains("No bookmarks");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn alt_left_and_alt_right_go_back_and_forward() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.press(KeyCode::Down).await;
+    harness.press(KeyCode::Enter).await;
+    assert_eq!(harness.cwd(), harness.fixture().path("sub"));
+
+    harness.press_with(KeyCode::Left, KeyModifiers::ALT).await;
+    assert_eq!(harness.cwd(), harness.fixture().root());
+    harness.press_with(KeyCode::Right, KeyModifiers::ALT).await;
+    assert_eq!(harness.cwd(), harness.fixture().path("sub"));
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+mod support;
+
+use crossterm::event::KeyCode;
+use support::{Fixture, Harness};
+
+fn fixture() -> Fixture {
+    Fixture::new()
+        .dir("sub")
+        .file("sub/inner.txt", "inside")
+        .file("notes.txt", "first line\nsecond line\n")
+        .file("data.json", "{\"name\": \"rfb\"}")
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn a_text_file_shows_its_lines() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.type_text("n").await;
+    harness.assert_selected("notes.txt");
+    harness.assert_screen_contains("first line");
+    harness.assert_screen_contains("second line");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn a_directory_shows_its_entries() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.type_text("s").await;
+    harness.assert_screen_contains("inner.txt");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn the_preview_follows_the_selection() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.type_text("n").await;
+    harness.assert_screen_contains("first line");
+
+    harness.press(KeyCode::Up).await;
+    harness.assert_screen_lacks("first line");
+    harness.assert_screen_contains("name: \"rfb\"");
+
+    harness.press(KeyCode::Up).await;
+    harness.assert_screen_lacks("name: \"rfb\"");
+    harness.assert_screen_contains("inner.txt");
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "current_thread")]
+async fn a_previewer_from_the_settings_shows_its_output() {
+    let fixture = fixture()
+        .file("long.log", "one\ntwo\nthree\n")
+        .settings("previewer.*.log = head -n 1\n");
+    let mut harness = Harness::start(fixture).await;
+    harness.type_text("l").await;
+    harness.assert_screen_contains("via head");
+    harness.assert_screen_contains("one");
+    harness.assert_screen_lacks("three");
+}
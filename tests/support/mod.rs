@@ -0,0 +1,278 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+// Each test file uses what it needs of this
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::style::Color;
+use ratatui::Terminal;
+use rfb::{App, Event, Options};
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+const WIDTH: u16 = 120;
+const HEIGHT: u16 = 40;
+
+// The app is done with an event when nothing's come back from the background for this long
+const QUIET_TIME: Duration = Duration::from_millis(150);
+// No event's results take longer than this to come back
+const SETTLE_LIMIT: Duration = Duration::from_secs(5);
+
+// The selected entry's row is drawn on this, like every list's
+const HIGHLIGHT_BACKGROUND: Color = Color::Gray;
+
+// The app works in the process's current directory, and reads its settings from the config
+// directory in the environment, so only one test can drive it at a time
+static APP_LOCK: Mutex<()> = Mutex::new(());
+
+static NEXT_FIXTURE_ID: AtomicU32 = AtomicU32::new(0);
+
+// A temporary directory of files for the app to browse, with config and data directories of its
+// own beside it, so the user's settings and session aren't read or written. It's removed when
+// it's dropped.
+pub struct Fixture {
+    base: PathBuf,
+    root: PathBuf,
+}
+
+impl Fixture {
+    pub fn new() -> Fixture {
+        let base = std::env::temp_dir().join(format!(
+            "rfb-test-{}-{}",
+            std::process::id(),
+            NEXT_FIXTURE_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        for directory in ["files", "config", "data"] {
+            std::fs::create_dir_all(base.join(directory)).unwrap();
+        }
+        // The app's current directory is the real path, which the temporary directory may not be
+        let base = base.canonicalize().unwrap();
+        let root = base.join("files");
+        Fixture { base, root }
+    }
+
+    pub fn file(self, path: &str, contents: &str) -> Fixture {
+        let path = self.root.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, contents).unwrap();
+        self
+    }
+
+    pub fn dir(self, path: &str) -> Fixture {
+        std::fs::create_dir_all(self.root.join(path)).unwrap();
+        self
+    }
+
+    // The settings the app starts with, as the lines of its settings file
+    pub fn settings(self, settings: &str) -> Fixture {
+        let directory = self.base.join("config").join("rfb");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("settings"), settings).unwrap();
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn path(&self, path: &str) -> PathBuf {
+        self.root.join(path)
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.base);
+    }
+}
+
+// Drives the app without a terminal: events are handed to it like the event loop does, along with
+// everything it sends itself from the background, and it's drawn on a test backend whose buffer
+// the tests read
+pub struct Harness {
+    app: App<'static>,
+    terminal: Terminal<TestBackend>,
+    event_rx: UnboundedReceiver<Event>,
+    fixture: Fixture,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Harness {
+    // Starts the app in the fixture's root. The tests run on a current_thread runtime, like
+    // #[tokio::test]'s default, so nothing the app spawns runs while the lock's held but the test.
+    pub async fn start(fixture: Fixture) -> Harness {
+        assert_eq!(
+            Handle::current().runtime_flavor(),
+            RuntimeFlavor::CurrentThread,
+            "the harness needs a current_thread runtime"
+        );
+        // A test that failed while it held the lock doesn't stop the others
+        let lock = APP_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+        std::env::set_var("XDG_CONFIG_HOME", fixture.base.join("config"));
+        std::env::set_var("XDG_DATA_HOME", fixture.base.join("data"));
+        // The directories the app visits are reported to zoxide, if it's installed, which would
+        // add them to the user's database otherwise
+        std::env::set_var("_ZO_DATADIR", fixture.base.join("data").join("zoxide"));
+
+        // Started like the command starts it, in the fixture's root, instead of the last session
+        let options = Options::parse_from([Path::new("rfb"), fixture.root()]);
+        let mut app = rfb::start(options).unwrap();
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        app.set_event_tx(Some(event_tx));
+        let mut harness = Harness {
+            app,
+            terminal: Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap(),
+            event_rx,
+            fixture,
+            _lock: lock,
+        };
+        harness.send(Event::Init(WIDTH, HEIGHT)).await;
+        harness
+    }
+
+    pub fn fixture(&self) -> &Fixture {
+        &self.fixture
+    }
+
+    pub fn app(&self) -> &App<'static> {
+        &self.app
+    }
+
+    // Hands the app an event, then everything that comes back from what it started
+    pub async fn send(&mut self, event: Event) {
+        self.app.handle_event(event).await;
+        self.settle().await;
+    }
+
+    // Hands the app the events in order, waiting for each one's results before the next
+    pub async fn send_all(&mut self, events: impl IntoIterator<Item = Event>) {
+        for event in events {
+            self.send(event).await;
+        }
+    }
+
+    pub async fn press(&mut self, code: KeyCode) {
+        self.press_with(code, KeyModifiers::NONE).await;
+    }
+
+    pub async fn press_with(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        self.send(Event::Key(KeyEvent::new(code, modifiers))).await;
+    }
+
+    // Types the text a key at a time
+    pub async fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.press(KeyCode::Char(c)).await;
+        }
+    }
+
+    // Hands the app what it's sent itself until it's been quiet for a while
+    pub async fn settle(&mut self) {
+        let deadline = Instant::now() + SETTLE_LIMIT;
+        while Instant::now() < deadline {
+            match tokio::time::timeout(QUIET_TIME, self.event_rx.recv()).await {
+                Ok(Some(event)) => self.app.handle_event(event).await,
+                _ => break,
+            }
+        }
+    }
+
+    // Draws the app and returns the screen's lines
+    pub fn screen(&mut self) -> Vec<String> {
+        let app = &mut self.app;
+        self.terminal
+            .draw(|frame| app.render(frame.size(), frame))
+            .unwrap();
+        let buffer = self.terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer.get(x, y).symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    // The directory pane's title, like "[3 items]"
+    pub fn directory_title(&mut self) -> String {
+        let screen = self.screen();
+        let border = &screen[1];
+        let end = border.find(['╗', '┐']).unwrap_or(border.len());
+        border[..end].trim_matches(['╔', '┌', '═', '─']).to_string()
+    }
+
+    // The name of the entry that's selected in the directory pane, which is on the left, without
+    // its icon
+    pub fn selected_name(&mut self) -> Option<String> {
+        self.screen();
+        let buffer = self.terminal.backend().buffer();
+        (2..buffer.area.height).find_map(|y| {
+            let row: Vec<_> = (1..buffer.area.width)
+                .map(|x| buffer.get(x, y))
+                .take_while(|cell| !matches!(cell.symbol(), "║" | "│"))
+                .filter(|cell| cell.bg == HIGHLIGHT_BACKGROUND)
+                .map(|cell| cell.symbol())
+                .collect();
+            let row = row.concat();
+            let (_icon, name) = row.trim().split_once(' ')?;
+            Some(name.trim_start().split("  ").next()?.to_string())
+        })
+    }
+
+    #[track_caller]
+    pub fn assert_selected(&mut self, name: &str) {
+        let selected = self.selected_name();
+        assert_eq!(
+            selected.as_deref(),
+            Some(name),
+            "{name:?} isn't selected:\n{}",
+            self.screen().join("\n")
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_directory_title(&mut self, title: &str) {
+        assert_eq!(self.directory_title(), title);
+    }
+
+    pub fn screen_contains(&mut self, text: &str) -> bool {
+        self.screen().iter().any(|line| line.contains(text))
+    }
+
+    // Fails the test with the screen, so it's clear what was there instead
+    #[track_caller]
+    pub fn assert_screen_contains(&mut self, text: &str) {
+        let screen = self.screen();
+        assert!(
+            screen.iter().any(|line| line.contains(text)),
+            "{text:?} isn't on the screen:\n{}",
+            screen.join("\n")
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_screen_lacks(&mut self, text: &str) {
+        let screen = self.screen();
+        assert!(
+            !screen.iter().any(|line| line.contains(text)),
+            "{text:?} is on the screen:\n{}",
+            screen.join("\n")
+        );
+    }
+
+    pub fn cwd(&self) -> PathBuf {
+        std::env::current_dir().unwrap()
+    }
+}
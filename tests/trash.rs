@@ -0,0 +1,51 @@
+/*
+ * Copyright (c) 2026 Paul Sobolik
+ * Created 2026-10-16
+ */
+
+mod support;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use support::{Fixture, Harness};
+
+fn fixture() -> Fixture {
+    Fixture::new().file("notes.txt", "first line\n")
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn delete_asks_before_trashing() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.type_text("n").await;
+    harness.press(KeyCode::Delete).await;
+    harness.assert_screen_contains("Move notes.txt to the trash? (y/n)");
+
+    harness.press(KeyCode::Char('n')).await;
+    harness.assert_screen_lacks("(y/n)");
+    assert!(harness.fixture().path("notes.txt").exists());
+
+    harness.press(KeyCode::Delete).await;
+    harness.press(KeyCode::Char('y')).await;
+    assert!(!harness.fixture().path("notes.txt").exists());
+    harness.assert_directory_title("[0 items]");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn emptying_the_trash_asks_first() {
+    let mut harness = Harness::start(fixture()).await;
+    harness.type_text("n").await;
+    harness.press(KeyCode::Delete).await;
+    harness.press(KeyCode::Char('y')).await;
+
+    harness
+        .press_with(KeyCode::Char('X'), KeyModifiers::ALT | KeyModifiers::SHIFT)
+        .await;
+    harness.assert_screen_contains("notes.txt");
+    harness.type_text("E").await;
+    harness.assert_screen_contains("Delete everything in the trash for good? (y/n)");
+    harness.press(KeyCode::Char('y')).await;
+
+    harness
+        .press_with(KeyCode::Char('X'), KeyModifiers::ALT | KeyModifiers::SHIFT)
+        .await;
+    harness.assert_screen_contains("The trash is empty.");
+}